@@ -0,0 +1,109 @@
+//! Compares the default broad-phase with the spatial-hash broad-phase on a large
+//! uniform grid of small colliders.
+//!
+//! Run with `cargo run --release --bin broad_phase3`.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rapier3d::prelude::*;
+use std::time::{Duration, Instant};
+
+const NUM_STEPS: u32 = 50;
+
+struct Timings {
+    insertion: Duration,
+    update: Duration,
+    num_events: usize,
+}
+
+fn run(mut broad_phase: BroadPhase, motion_amplitude: Real) -> Timings {
+    let bodies = RigidBodySet::new();
+    let mut colliders = ColliderSet::new();
+    let mut handles = vec![];
+
+    let num = 80;
+    let num_layers = 4;
+    let rad = 0.5;
+    let shift = rad * 2.0 + 0.1;
+
+    for i in 0..num {
+        for j in 0..num_layers {
+            for k in 0..num {
+                let collider = ColliderBuilder::ball(rad).translation(vector![
+                    i as Real * shift,
+                    j as Real * shift,
+                    k as Real * shift
+                ]);
+                handles.push(colliders.insert(collider));
+            }
+        }
+    }
+
+    let prediction_distance = IntegrationParameters::default().prediction_distance;
+    let mut events = vec![];
+    let t0 = Instant::now();
+    broad_phase.update(
+        0.0,
+        prediction_distance,
+        &mut colliders,
+        &bodies,
+        &handles,
+        &[],
+        &mut events,
+    );
+    let insertion = t0.elapsed();
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut update = Duration::ZERO;
+    let mut num_events = 0;
+
+    for _ in 0..NUM_STEPS {
+        for handle in &handles {
+            let shift = vector![
+                rng.gen_range(-0.5..0.5),
+                rng.gen_range(-0.5..0.5),
+                rng.gen_range(-0.5..0.5)
+            ] * motion_amplitude;
+            let new_translation = colliders[*handle].translation() + shift;
+            colliders[*handle].set_translation(new_translation);
+        }
+
+        events.clear();
+        let t0 = Instant::now();
+        broad_phase.update(
+            0.0,
+            prediction_distance,
+            &mut colliders,
+            &bodies,
+            &handles,
+            &[],
+            &mut events,
+        );
+        update += t0.elapsed();
+        num_events += events.len();
+    }
+
+    Timings {
+        insertion,
+        update: update / NUM_STEPS,
+        num_events,
+    }
+}
+
+fn main() {
+    println!("| Motion amplitude | Broad-phase | Insertion | Update (avg.) | Pair events |");
+    println!("|---|---|---|---|---|");
+
+    for motion_amplitude in [0.001, 0.05, 0.3] {
+        for (name, broad_phase) in [
+            ("Hierarchical SAP", BroadPhase::new()),
+            ("Spatial hash", BroadPhase::with_spatial_hash(2.0)),
+        ] {
+            let timings = run(broad_phase, motion_amplitude);
+            println!(
+                "| {} | {} | {:.2?} | {:.2?} | {} |",
+                motion_amplitude, name, timings.insertion, timings.update, timings.num_events
+            );
+        }
+    }
+}
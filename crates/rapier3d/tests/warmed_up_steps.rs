@@ -0,0 +1,116 @@
+//! Checks the allocations made by the physics pipeline at each timestep.
+//!
+//! This is an integration test because it replaces the global allocator of its test binary.
+
+// The parallel pipeline allocates on the threads of the thread pool.
+#![cfg(not(feature = "parallel"))]
+
+use rapier3d::prelude::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// Counts the allocations made by each thread.
+struct CountingAllocator;
+
+std::thread_local! {
+    static NUM_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        NUM_ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        NUM_ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The number of allocations made by each of the first `num_steps` steps of a stack of boxes
+/// that can't sleep, so all the stages of the step are running.
+///
+/// If `capacity` is set, it is given as a capacity hint to every structure supporting one.
+fn num_allocations_per_step(capacity: Option<usize>, num_steps: usize) -> Vec<usize> {
+    let (mut pipeline, mut broad_phase, mut narrow_phase, mut bodies, mut colliders, mut islands) =
+        match capacity {
+            Some(n) => (
+                PhysicsPipeline::with_capacity(n),
+                BroadPhase::with_capacity(n),
+                NarrowPhase::with_capacity(n, n),
+                RigidBodySet::with_capacity(n),
+                ColliderSet::with_capacity(n),
+                IslandManager::with_capacity(n),
+            ),
+            None => (
+                PhysicsPipeline::new(),
+                BroadPhase::new(),
+                NarrowPhase::new(),
+                RigidBodySet::new(),
+                ColliderSet::new(),
+                IslandManager::new(),
+            ),
+        };
+    let mut impulse_joints = ImpulseJointSet::new();
+    let mut multibody_joints = MultibodyJointSet::new();
+    let mut ccd_solver = CCDSolver::new();
+
+    let ground_half_extents = Vector::repeat(10.0) - Vector::y() * 9.5;
+    let ground = ColliderBuilder::new(SharedShape::new(Cuboid::new(ground_half_extents)))
+        .translation(-Vector::y() * 0.5);
+    colliders.insert(ground);
+    for i in 0..8 {
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * (0.5 + i as Real))
+            .can_sleep(false);
+        let body = bodies.insert(body);
+        let collider = ColliderBuilder::new(SharedShape::new(Cuboid::new(Vector::repeat(0.5))));
+        colliders.insert_with_parent(collider, body, &mut bodies);
+    }
+
+    let gravity = Vector::y() * -9.81;
+    let params = IntegrationParameters::default();
+    (0..num_steps)
+        .map(|_| {
+            let num_allocations_before = NUM_ALLOCATIONS.with(|n| n.get());
+            pipeline.step(
+                &gravity,
+                &params,
+                &mut islands,
+                &mut broad_phase,
+                &mut narrow_phase,
+                &mut bodies,
+                &mut colliders,
+                &mut impulse_joints,
+                &mut multibody_joints,
+                &mut ccd_solver,
+                None,
+                &(),
+                &(),
+            );
+            NUM_ALLOCATIONS.with(|n| n.get()) - num_allocations_before
+        })
+        .collect()
+}
+
+#[test]
+fn warmed_up_steps_dont_allocate() {
+    let hinted = num_allocations_per_step(Some(16), 60);
+    let unhinted = num_allocations_per_step(None, 60);
+
+    // The capacity hints save some of the allocations of the first step.
+    assert!(hinted[0] > 0);
+    assert!(hinted[0] < unhinted[0]);
+
+    // Once warmed up, neither pipeline allocates.
+    assert_eq!(hinted[50..], [0; 10]);
+    assert_eq!(unhinted[50..], [0; 10]);
+}
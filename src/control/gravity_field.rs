@@ -0,0 +1,140 @@
+use crate::dynamics::{IslandManager, RigidBodySet};
+use crate::math::{Point, Real, Vector};
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// A localized gravity field pulling rigid-bodies toward a point (e.g. a planet).
+///
+/// The gravity field is applied on top of the global gravity given to
+/// [`PhysicsPipeline::step`](crate::pipeline::PhysicsPipeline::step) by calling
+/// [`GravityField::apply`] before each step. Just like the global gravity, the acceleration
+/// it generates is multiplied by each rigid-body’s gravity scale.
+pub struct GravityField {
+    /// The point all the affected rigid-bodies are attracted to.
+    pub center: Point<Real>,
+    /// The magnitude of the acceleration generated by this field.
+    ///
+    /// If `inverse_square` is `true`, this is the acceleration magnitude at a unit distance
+    /// from the `center`. Negative values make the field repulsive.
+    pub strength: Real,
+    /// Rigid-bodies with a center-of-mass farther than this distance from the `center` are not
+    /// affected by this field.
+    pub max_distance: Real,
+    /// If `true`, the acceleration decreases with the square of the distance to the `center`.
+    /// Otherwise, it is constant within `max_distance`.
+    pub inverse_square: bool,
+}
+
+impl GravityField {
+    /// A gravity field with an acceleration of constant magnitude within `max_distance` of `center`.
+    pub fn constant(center: Point<Real>, strength: Real, max_distance: Real) -> Self {
+        Self {
+            center,
+            strength,
+            max_distance,
+            inverse_square: false,
+        }
+    }
+
+    /// A gravity field with an acceleration decreasing with the square of the distance to `center`.
+    ///
+    /// Here, `strength` is the acceleration magnitude at a unit distance from `center`.
+    pub fn inverse_square(center: Point<Real>, strength: Real, max_distance: Real) -> Self {
+        Self {
+            center,
+            strength,
+            max_distance,
+            inverse_square: true,
+        }
+    }
+
+    /// The gravitational acceleration generated by this field at the given world-space point.
+    ///
+    /// This is zero if the point is farther than `self.max_distance` from `self.center`, or
+    /// if it coincides with `self.center`.
+    pub fn acceleration_at(&self, point: &Point<Real>) -> Vector<Real> {
+        let dir = self.center - point;
+        let dist = dir.norm();
+
+        if dist > self.max_distance || dist <= Real::EPSILON {
+            return Vector::zeros();
+        }
+
+        let magnitude = if self.inverse_square {
+            self.strength / (dist * dist)
+        } else {
+            self.strength
+        };
+
+        dir * (magnitude / dist)
+    }
+
+    /// Applies the acceleration of this field to all the active dynamic rigid-bodies during
+    /// a timestep of length `dt`.
+    ///
+    /// This should be called right before each call to
+    /// [`PhysicsPipeline::step`](crate::pipeline::PhysicsPipeline::step) with the same `dt`
+    /// as the integration parameters. Sleeping rigid-bodies are not affected: since the field
+    /// is stationary, they were already at rest within it. If the field is moved or modified,
+    /// the rigid-bodies in its range should be woken up by the user.
+    pub fn apply(&self, dt: Real, bodies: &mut RigidBodySet, islands: &IslandManager) {
+        for handle in islands.active_dynamic_bodies() {
+            if let Some(rb) = bodies.get_mut_internal(*handle) {
+                let acceleration = self.acceleration_at(rb.center_of_mass());
+                let impulse = acceleration * (rb.mass() * rb.gravity_scale() * dt);
+                rb.apply_impulse(impulse, false);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GravityField;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn gravity_field_is_applied_per_body() {
+        let mut world = TestWorld::new();
+        let params = world.params;
+
+        let mut insert =
+            |builder: RigidBodyBuilder| world.insert(builder, ColliderBuilder::ball(0.5)).0;
+        let near = insert(RigidBodyBuilder::dynamic().translation(Vector::x() * 3.0));
+        let scaled = insert(
+            RigidBodyBuilder::dynamic()
+                .translation(-Vector::x() * 3.0)
+                .gravity_scale(2.0),
+        );
+        let far = insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 20.0));
+        let asleep = insert(
+            RigidBodyBuilder::dynamic()
+                .translation(-Vector::y() * 3.0)
+                .sleeping(true),
+        );
+
+        // A field with an acceleration of 1 at a distance of 3 from the origin.
+        let field = GravityField::inverse_square(Point::origin(), 9.0, 10.0);
+        let acceleration = field.acceleration_at(&(Point::origin() + Vector::x() * 3.0));
+        assert!((acceleration + Vector::x()).norm() < 1.0e-6);
+
+        world.gravity = Vector::zeros();
+        for i in 0..2 {
+            if i > 0 {
+                field.apply(params.dt, &mut world.bodies, &world.islands);
+            }
+
+            world.step();
+        }
+
+        let dv: Real = params.dt;
+        assert!((world.bodies[near].linvel() + Vector::x() * dv).norm() < 1.0e-6);
+        assert!((world.bodies[scaled].linvel() - Vector::x() * dv * 2.0).norm() < 1.0e-6);
+        assert_eq!(*world.bodies[far].linvel(), Vector::zeros());
+        assert_eq!(*world.bodies[asleep].linvel(), Vector::zeros());
+        assert!(world.bodies[asleep].is_sleeping());
+    }
+}
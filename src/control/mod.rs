@@ -4,11 +4,15 @@ pub use self::character_controller::{
     CharacterAutostep, CharacterCollision, CharacterLength, EffectiveCharacterMovement,
     KinematicCharacterController,
 };
+pub use self::gravity_field::GravityField;
+pub use self::slide::{slide, slide_on_planes};
 
 #[cfg(feature = "dim3")]
 pub use self::ray_cast_vehicle_controller::{DynamicRayCastVehicleController, Wheel, WheelTuning};
 
 mod character_controller;
+mod gravity_field;
+mod slide;
 
 #[cfg(feature = "dim3")]
 mod ray_cast_vehicle_controller;
@@ -7,6 +7,12 @@ use crate::pipeline::{QueryFilter, QueryPipeline};
 use crate::utils::{WCross, WDot};
 
 /// A character controller to simulate vehicles using ray-casting for the wheels.
+///
+/// Each wheel is a ray-cast suspension: a ray is cast from its attachment point on the chassis
+/// along its suspension direction to find the ground, and a spring-damper pushes the chassis
+/// away from the ground, at the contact point, depending on the compression of the
+/// suspension. The tire friction (engine, brake, and side grip) is applied at the same contact
+/// point. Call [`DynamicRayCastVehicleController::update_vehicle`] before each simulation step.
 pub struct DynamicRayCastVehicleController {
     wheels: Vec<Wheel>,
     forward_ws: Vec<Vector<Real>>,
@@ -239,6 +245,16 @@ impl DynamicRayCastVehicleController {
     // basically most of the code is general for 2 or 4 wheel vehicles, but some of it needs to be reviewed
     //
     /// Adds a wheel to this vehicle.
+    ///
+    /// # Parameters
+    /// * `chassis_connection_cs` - The point where the suspension is attached, in the local-space
+    ///   of the chassis.
+    /// * `direction_cs` - The direction of the suspension (usually pointing down), in the
+    ///   local-space of the chassis. The ground is detected by casting a ray in this direction.
+    /// * `axle_cs` - The axle of the wheel, in the local-space of the chassis.
+    /// * `suspension_rest_length` - The length of the suspension when it applies no force.
+    /// * `radius` - The radius of the wheel.
+    /// * `tuning` - The stiffness, damping, and friction of the suspension and tire.
     pub fn add_wheel(
         &mut self,
         chassis_connection_cs: Point<Real>,
@@ -419,11 +435,18 @@ impl DynamicRayCastVehicleController {
             .get_mut_internal_with_modification_tracking(self.chassis)
             .unwrap();
 
-        for wheel in &mut self.wheels {
-            if wheel.engine_force > 0.0 {
-                chassis.wake_up(true);
-            }
+        if self.wheels.iter().any(|wheel| wheel.engine_force != 0.0) {
+            chassis.wake_up(true);
+        }
 
+        if chassis.is_sleeping() {
+            // The sleeping chassis isn’t affected by gravity, so the suspension and tires must not
+            // push it either. Otherwise, their impulses would accumulate into its velocity and
+            // make it jump as soon as it wakes up.
+            return;
+        }
+
+        for wheel in &mut self.wheels {
             // apply suspension force
             let mut suspension_force = wheel.wheel_suspension_force;
 
@@ -806,3 +829,60 @@ fn resolve_single_unilateral(body1: &RigidBody, pt1: &Point<Real>, normal: &Vect
     let contact_damping = 0.2;
     -contact_damping * rel_vel * jac_diag_ab_inv
 }
+
+#[cfg(test)]
+mod test {
+    use super::{DynamicRayCastVehicleController, WheelTuning};
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use crate::pipeline::{QueryFilter, QueryPipeline};
+
+    #[test]
+    fn sleeping_chassis_are_only_pushed_once_woken_up() {
+        let mut world = TestWorld::new();
+        world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+        let chassis = RigidBodyBuilder::dynamic()
+            .translation(Vector::y())
+            .sleeping(true);
+        let (chassis, _) = world.insert(chassis, ColliderBuilder::cuboid(1.0, 0.25, 0.5));
+
+        // The suspensions are compressed by 0.1.
+        let mut vehicle = DynamicRayCastVehicleController::new(chassis);
+        for (x, z) in [(0.8, 0.4), (0.8, -0.4), (-0.8, 0.4), (-0.8, -0.4)] {
+            vehicle.add_wheel(
+                Point::new(x, 0.0, z),
+                -Vector::y(),
+                -Vector::z(),
+                0.8,
+                0.3,
+                &WheelTuning::default(),
+            );
+        }
+
+        let mut queries = QueryPipeline::new();
+        queries.update(&world.bodies, &world.colliders);
+        let update = |world: &mut TestWorld, vehicle: &mut DynamicRayCastVehicleController| {
+            vehicle.update_vehicle(
+                world.params.dt,
+                &mut world.bodies,
+                &world.colliders,
+                &queries,
+                QueryFilter::exclude_dynamic().exclude_rigid_body(chassis),
+            );
+        };
+
+        update(&mut world, &mut vehicle);
+        assert!(world.bodies[chassis].is_sleeping());
+        assert_eq!(*world.bodies[chassis].linvel(), Vector::zeros());
+
+        // Any engine force, even backward, wakes the chassis up.
+        vehicle.wheels_mut()[0].engine_force = -1.0;
+        update(&mut world, &mut vehicle);
+        assert!(!world.bodies[chassis].is_sleeping());
+        assert!(world.bodies[chassis].linvel().y > 0.0);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::math::{Real, UnitVector, Vector};
+
+/// Tolerance used to decide if a displacement points into a plane.
+const SLIDE_TOLERANCE: Real = 1.0e-6;
+
+/// Removes the part of `displacement` moving toward a plane with the given `normal`.
+///
+/// The `normal` is the contact normal pointing away from the obstacle, toward the moving
+/// object. If `displacement` points into the plane, the result is its projection onto the plane
+/// (the object slides along it). Otherwise, it is returned unchanged.
+pub fn slide(displacement: &Vector<Real>, normal: &UnitVector<Real>) -> Vector<Real> {
+    let normal_part = displacement.dot(normal);
+
+    if normal_part < 0.0 {
+        displacement - **normal * normal_part
+    } else {
+        *displacement
+    }
+}
+
+/// Removes the parts of `displacement` moving toward any of the planes with the given `normals`.
+///
+/// This is the multi-plane version of [`slide`]. The result doesn’t point into any of the planes:
+/// - If sliding along one of the planes is enough, the result is the projection of
+///   `displacement` onto that plane.
+/// - In 3D, if the object is stuck in the crease between two planes, the result is the
+///   projection of `displacement` onto the crease.
+/// - If the object is wedged in a corner (i.e. no sliding direction satisfies all the planes),
+///   the result is zero.
+pub fn slide_on_planes(displacement: &Vector<Real>, normals: &[UnitVector<Real>]) -> Vector<Real> {
+    let satisfies_planes = |candidate: &Vector<Real>, skipped: &[usize]| {
+        normals
+            .iter()
+            .enumerate()
+            .all(|(k, normal)| skipped.contains(&k) || candidate.dot(normal) >= -SLIDE_TOLERANCE)
+    };
+
+    if satisfies_planes(displacement, &[]) {
+        return *displacement;
+    }
+
+    for (i, normal) in normals.iter().enumerate() {
+        let candidate = slide(displacement, normal);
+
+        if satisfies_planes(&candidate, &[i]) {
+            return candidate;
+        }
+    }
+
+    #[cfg(feature = "dim3")]
+    for (i, normal1) in normals.iter().enumerate() {
+        for (j, normal2) in normals.iter().enumerate().skip(i + 1) {
+            if let Some(crease) = normal1.cross(normal2).try_normalize(SLIDE_TOLERANCE) {
+                let candidate = crease * crease.dot(displacement);
+
+                if satisfies_planes(&candidate, &[i, j]) {
+                    return candidate;
+                }
+            }
+        }
+    }
+
+    // Wedged in a corner.
+    Vector::zeros()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{slide, slide_on_planes};
+    use crate::math::Vector;
+
+    #[test]
+    fn sliding_displacements_never_point_into_the_planes() {
+        let floor = Vector::y_axis();
+        let wall = -Vector::x_axis();
+        let displacement = Vector::x() - Vector::y();
+
+        assert_eq!(slide(&displacement, &floor), Vector::x());
+        assert_eq!(slide(&-displacement, &floor), -displacement);
+        assert_eq!(slide_on_planes(&displacement, &[floor]), Vector::x());
+
+        // Wedged between the floor and the wall.
+        assert_eq!(
+            slide_on_planes(&displacement, &[floor, wall]),
+            Vector::zeros()
+        );
+        assert_eq!(slide_on_planes(&-Vector::x(), &[floor, wall]), -Vector::x());
+
+        // Two slanted walls meeting at a crease along the y axis.
+        #[cfg(feature = "dim3")]
+        {
+            use crate::math::UnitVector;
+
+            let left = UnitVector::new_normalize(Vector::new(1.0, 0.0, -1.0));
+            let right = UnitVector::new_normalize(Vector::new(-1.0, 0.0, -1.0));
+            let forward = Vector::new(0.0, 1.0, 1.0);
+            let result = slide_on_planes(&forward, &[left, right]);
+            assert!((result - Vector::y()).norm() < 1.0e-5);
+            // Adding a ceiling wedges it in the corner.
+            let ceiling = -Vector::y_axis();
+            assert_eq!(
+                slide_on_planes(&forward, &[left, right, ceiling]),
+                Vector::zeros()
+            );
+        }
+    }
+}
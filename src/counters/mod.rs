@@ -4,12 +4,14 @@ use std::fmt::{Display, Formatter, Result};
 
 pub use self::ccd_counters::CCDCounters;
 pub use self::collision_detection_counters::CollisionDetectionCounters;
+pub use self::physics_timings::PhysicsTimings;
 pub use self::solver_counters::SolverCounters;
 pub use self::stages_counters::StagesCounters;
 pub use self::timer::Timer;
 
 mod ccd_counters;
 mod collision_detection_counters;
+mod physics_timings;
 mod solver_counters;
 mod stages_counters;
 mod timer;
@@ -115,6 +117,34 @@ impl Counters {
         self.cd.ncontact_pairs = n;
     }
 
+    /// The time spent by each phase of the last timestep.
+    ///
+    /// All the timings are zero if the `profiler` feature isn’t enabled, or if these counters
+    /// are disabled.
+    pub fn timings(&self) -> PhysicsTimings {
+        if !self.enabled {
+            return PhysicsTimings::default();
+        }
+
+        PhysicsTimings {
+            step: self.step_time(),
+            broad_phase: self.broad_phase_time(),
+            narrow_phase: self.narrow_phase_time(),
+            island_construction: self.island_construction_time(),
+            update: self.update_time(),
+            solver: self.solver_time(),
+            ccd: self.ccd_time(),
+        }
+    }
+
+    /// Notify that the CCD resumed, without resetting the CCD time measured since the
+    /// beginning of the timestep.
+    pub fn ccd_resumed(&mut self) {
+        if self.enabled {
+            self.stages.ccd_time.resume();
+        }
+    }
+
     /// Resets all the counters and timers.
     pub fn reset(&mut self) {
         if self.enabled {
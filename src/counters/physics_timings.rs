@@ -0,0 +1,40 @@
+use std::fmt::{Display, Formatter, Result};
+
+/// The time spent by each phase of a single timestep.
+///
+/// All the times are expressed in milliseconds. They are only measured if the
+/// `profiler` feature is enabled, and are always zero otherwise.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct PhysicsTimings {
+    /// Total time spent for the whole timestep.
+    pub step: f64,
+    /// Time spent by the broad-phase.
+    pub broad_phase: f64,
+    /// Time spent by the narrow-phase.
+    pub narrow_phase: f64,
+    /// Time spent for the computation of islands and body activation/deactivation (sleeping).
+    pub island_construction: f64,
+    /// Time spent for updating the kinematic and dynamics of every body.
+    pub update: f64,
+    /// Time spent for the constraints resolution and position update.
+    pub solver: f64,
+    /// Time spent for CCD and CCD resolution.
+    pub ccd: f64,
+}
+
+impl Display for PhysicsTimings {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        writeln!(f, "Step time: {}ms", self.step)?;
+        writeln!(f, "Broad-phase time: {}ms", self.broad_phase)?;
+        writeln!(f, "Narrow-phase time: {}ms", self.narrow_phase)?;
+        writeln!(
+            f,
+            "Island construction time: {}ms",
+            self.island_construction
+        )?;
+        writeln!(f, "Update time: {}ms", self.update)?;
+        writeln!(f, "Solver time: {}ms", self.solver)?;
+        writeln!(f, "CCD time: {}ms", self.ccd)
+    }
+}
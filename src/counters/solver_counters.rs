@@ -8,6 +8,9 @@ pub struct SolverCounters {
     pub nconstraints: usize,
     /// Number of contacts found.
     pub ncontacts: usize,
+    /// Number of rigid-bodies skipped by the force computation and integration because they
+    /// aren’t active, i.e., sleeping bodies, fixed bodies, and kinematic bodies that don’t move.
+    pub nskipped_bodies: usize,
     /// Time spent for the resolution of the constraints (force computation).
     pub velocity_resolution_time: Timer,
     /// Time spent for the assembly of all the velocity constraints.
@@ -26,6 +29,7 @@ impl SolverCounters {
         SolverCounters {
             nconstraints: 0,
             ncontacts: 0,
+            nskipped_bodies: 0,
             velocity_assembly_time: Timer::new(),
             velocity_resolution_time: Timer::new(),
             velocity_update_time: Timer::new(),
@@ -38,6 +42,7 @@ impl SolverCounters {
     pub fn reset(&mut self) {
         self.nconstraints = 0;
         self.ncontacts = 0;
+        self.nskipped_bodies = 0;
         self.velocity_resolution_time.reset();
         self.velocity_assembly_time.reset();
         self.velocity_update_time.reset();
@@ -50,6 +55,7 @@ impl Display for SolverCounters {
     fn fmt(&self, f: &mut Formatter) -> Result {
         writeln!(f, "Number of contacts: {}", self.ncontacts)?;
         writeln!(f, "Number of constraints: {}", self.nconstraints)?;
+        writeln!(f, "Number of skipped bodies: {}", self.nskipped_bodies)?;
         writeln!(f, "Velocity assembly time: {}", self.velocity_assembly_time)?;
         writeln!(
             f,
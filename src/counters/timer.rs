@@ -1,11 +1,15 @@
 use std::fmt::{Display, Error, Formatter};
 
-/// A timer.
+/// A timer.
+///
+/// The elapsed time is measured with a monotonic clock, in milliseconds. If the `profiler`
+/// feature isn’t enabled, all the timer operations are no-ops and the measured time
+/// is always zero.
 #[derive(Copy, Clone, Debug, Default)]
 pub struct Timer {
     time: f64,
-    #[allow(dead_code)] // The field isn’t used if the `profiler` feature isn’t enabled.
-    start: Option<f64>,
+    #[cfg(feature = "profiler")]
+    start: Option<instant::Instant>,
 }
 
 impl Timer {
@@ -13,6 +17,7 @@ impl Timer {
     pub fn new() -> Self {
         Timer {
             time: 0.0,
+            #[cfg(feature = "profiler")]
             start: None,
         }
     }
@@ -27,7 +32,7 @@ impl Timer {
         #[cfg(feature = "profiler")]
         {
             self.time = 0.0;
-            self.start = Some(instant::now());
+            self.start = Some(instant::Instant::now());
         }
     }
 
@@ -36,7 +41,7 @@ impl Timer {
         #[cfg(feature = "profiler")]
         {
             if let Some(start) = self.start {
-                self.time += instant::now() - start;
+                self.time += start.elapsed().as_secs_f64() * 1000.0;
             }
             self.start = None;
         }
@@ -46,11 +51,11 @@ impl Timer {
     pub fn resume(&mut self) {
         #[cfg(feature = "profiler")]
         {
-            self.start = Some(instant::now());
+            self.start = Some(instant::Instant::now());
         }
     }
 
-    /// The measured time between the last `.start()` and `.pause()` calls.
+    /// The measured time between the last `.start()` and `.pause()` calls, in milliseconds.
     pub fn time(&self) -> f64 {
         self.time
     }
@@ -58,6 +63,6 @@ impl Timer {
 
 impl Display for Timer {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-        write!(f, "{}s", self.time)
+        write!(f, "{}ms", self.time)
     }
 }
@@ -22,13 +22,22 @@ pub struct Arena<T> {
     generation: u32,
     free_list_head: Option<u32>,
     len: usize,
+    // The insertion stamp that will be given to the next inserted element.
+    next_stamp: u64,
 }
 
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 enum Entry<T> {
-    Free { next_free: Option<u32> },
-    Occupied { generation: u32, value: T },
+    Free {
+        next_free: Option<u32>,
+    },
+    Occupied {
+        generation: u32,
+        // Strictly increasing with the insertion order, and never reused.
+        stamp: u64,
+        value: T,
+    },
 }
 
 /// An index (and generation) into an `Arena`.
@@ -141,6 +150,7 @@ impl<T> Arena<T> {
             generation: 0,
             free_list_head: None,
             len: 0,
+            next_stamp: 0,
         };
         arena.reserve(n);
         arena
@@ -211,8 +221,10 @@ impl<T> Arena<T> {
             Some(index) => {
                 self.items[index.index as usize] = Entry::Occupied {
                     generation: self.generation,
+                    stamp: self.next_stamp,
                     value,
                 };
+                self.next_stamp += 1;
                 Ok(index)
             }
         }
@@ -252,8 +264,10 @@ impl<T> Arena<T> {
             Some(index) => {
                 self.items[index.index as usize] = Entry::Occupied {
                     generation: self.generation,
+                    stamp: self.next_stamp,
                     value: create(index),
                 };
+                self.next_stamp += 1;
                 Ok(index)
             }
         }
@@ -375,10 +389,7 @@ impl<T> Arena<T> {
                 self.len -= 1;
 
                 match entry {
-                    Entry::Occupied {
-                        generation: _,
-                        value,
-                    } => Some(value),
+                    Entry::Occupied { value, .. } => Some(value),
                     _ => unreachable!(),
                 }
             }
@@ -407,7 +418,9 @@ impl<T> Arena<T> {
     pub fn retain(&mut self, mut predicate: impl FnMut(Index, &mut T) -> bool) {
         for i in 0..self.capacity() as u32 {
             let remove = match &mut self.items[i as usize] {
-                Entry::Occupied { generation, value } => {
+                Entry::Occupied {
+                    generation, value, ..
+                } => {
                     let index = Index {
                         index: i,
                         generation: *generation,
@@ -466,9 +479,9 @@ impl<T> Arena<T> {
     /// ```
     pub fn get(&self, i: Index) -> Option<&T> {
         match self.items.get(i.index as usize) {
-            Some(Entry::Occupied { generation, value }) if *generation == i.generation => {
-                Some(value)
-            }
+            Some(Entry::Occupied {
+                generation, value, ..
+            }) if *generation == i.generation => Some(value),
             _ => None,
         }
     }
@@ -492,13 +505,44 @@ impl<T> Arena<T> {
     /// ```
     pub fn get_mut(&mut self, i: Index) -> Option<&mut T> {
         match self.items.get_mut(i.index as usize) {
-            Some(Entry::Occupied { generation, value }) if *generation == i.generation => {
-                Some(value)
-            }
+            Some(Entry::Occupied {
+                generation, value, ..
+            }) if *generation == i.generation => Some(value),
             _ => None,
         }
     }
 
+    /// Iterates mutably through the elements at the given indices.
+    ///
+    /// The indices must be sorted by increasing slot: an index pointing to the same slot as,
+    /// or to a slot before, the previously visited one is skipped, and so are the indices of
+    /// elements that are no longer in the arena. This walks through the arena only once.
+    pub fn iter_sorted_mut<'a>(
+        &'a mut self,
+        sorted_indices: impl IntoIterator<Item = Index> + 'a,
+    ) -> impl Iterator<Item = (Index, &'a mut T)> + 'a {
+        let mut items = self.items.iter_mut();
+        let mut next_slot = 0;
+
+        sorted_indices.into_iter().filter_map(move |i| {
+            let slot = i.index as usize;
+
+            if slot < next_slot {
+                return None;
+            }
+
+            let entry = items.nth(slot - next_slot)?;
+            next_slot = slot + 1;
+
+            match entry {
+                Entry::Occupied {
+                    generation, value, ..
+                } if *generation == i.generation => Some((i, value)),
+                _ => None,
+            }
+        })
+    }
+
     /// Get a pair of exclusive references to the elements at index `i1` and `i2` if it is in the
     /// arena.
     ///
@@ -558,12 +602,16 @@ impl<T> Arena<T> {
         };
 
         let item1 = match raw_item1 {
-            Entry::Occupied { generation, value } if *generation == i1.generation => Some(value),
+            Entry::Occupied {
+                generation, value, ..
+            } if *generation == i1.generation => Some(value),
             _ => None,
         };
 
         let item2 = match raw_item2 {
-            Entry::Occupied { generation, value } if *generation == i2.generation => Some(value),
+            Entry::Occupied {
+                generation, value, ..
+            } if *generation == i2.generation => Some(value),
             _ => None,
         };
 
@@ -705,6 +753,53 @@ impl<T> Arena<T> {
         }
     }
 
+    /// Iterate over shared references to the elements in this arena, in the order they were
+    /// inserted.
+    ///
+    /// Yields pairs of `(Index, &T)` items.
+    ///
+    /// Unlike [`Arena::iter`], this order doesn't depend on the slots freed by previous removals:
+    /// two arenas containing the same elements inserted in the same order yield them in the same
+    /// order, regardless of the elements inserted and removed in-between.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rapier::data::arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let idx_1 = arena.insert(1);
+    /// let idx_2 = arena.insert(2);
+    /// arena.remove(idx_1);
+    /// let idx_3 = arena.insert(3); // Reuses the slot of `idx_1`.
+    ///
+    /// let indices: Vec<_> = arena.iter_in_insertion_order().map(|(idx, _)| idx).collect();
+    /// assert_eq!(indices, [idx_2, idx_3]);
+    /// ```
+    pub fn iter_in_insertion_order(&self) -> impl Iterator<Item = (Index, &T)> {
+        let mut elements: Vec<_> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| match entry {
+                Entry::Occupied {
+                    generation,
+                    stamp,
+                    value,
+                } => {
+                    let idx = Index {
+                        index: index as u32,
+                        generation: *generation,
+                    };
+                    Some((*stamp, idx, value))
+                }
+                Entry::Free { .. } => None,
+            })
+            .collect();
+        elements.sort_unstable_by_key(|(stamp, _, _)| *stamp);
+        elements.into_iter().map(|(_, idx, value)| (idx, value))
+    }
+
     /// Iterate over exclusive references to the elements in this arena.
     ///
     /// Yields pairs of `(Index, &mut T)` items.
@@ -775,7 +870,9 @@ impl<T> Arena<T> {
     /// You should use the `get` method instead most of the time.
     pub fn get_unknown_gen(&self, i: u32) -> Option<(&T, Index)> {
         match self.items.get(i as usize) {
-            Some(Entry::Occupied { generation, value }) => Some((
+            Some(Entry::Occupied {
+                generation, value, ..
+            }) => Some((
                 value,
                 Index {
                     generation: *generation,
@@ -798,7 +895,9 @@ impl<T> Arena<T> {
     /// You should use the `get_mut` method instead most of the time.
     pub fn get_unknown_gen_mut(&mut self, i: u32) -> Option<(&mut T, Index)> {
         match self.items.get_mut(i as usize) {
-            Some(Entry::Occupied { generation, value }) => Some((
+            Some(Entry::Occupied {
+                generation, value, ..
+            }) => Some((
                 value,
                 Index {
                     generation: *generation,
@@ -943,6 +1042,7 @@ impl<'a, T> Iterator for Iter<'a, T> {
                     &Entry::Occupied {
                         generation,
                         ref value,
+                        ..
                     },
                 )) => {
                     self.len -= 1;
@@ -975,6 +1075,7 @@ impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
                     &Entry::Occupied {
                         generation,
                         ref value,
+                        ..
                     },
                 )) => {
                     self.len -= 1;
@@ -1047,6 +1148,7 @@ impl<'a, T> Iterator for IterMut<'a, T> {
                     &mut Entry::Occupied {
                         generation,
                         ref mut value,
+                        ..
                     },
                 )) => {
                     self.len -= 1;
@@ -1079,6 +1181,7 @@ impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
                     &mut Entry::Occupied {
                         generation,
                         ref mut value,
+                        ..
                     },
                 )) => {
                     self.len -= 1;
@@ -1142,7 +1245,12 @@ impl<'a, T> Iterator for Drain<'a, T> {
         loop {
             match self.inner.next() {
                 Some((_, Entry::Free { .. })) => continue,
-                Some((index, Entry::Occupied { generation, value })) => {
+                Some((
+                    index,
+                    Entry::Occupied {
+                        generation, value, ..
+                    },
+                )) => {
                     let idx = Index {
                         index: index as u32,
                         generation,
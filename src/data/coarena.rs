@@ -13,6 +13,13 @@ impl<T> Coarena<T> {
         Self { data: Vec::new() }
     }
 
+    /// A coarena with no element, with room for elements with indices up to `capacity`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
     /// Iterates through all the elements of this coarena.
     pub fn iter(&self) -> impl Iterator<Item = (Index, &T)> {
         self.data
@@ -182,8 +182,9 @@ impl CCDSolver {
                                 if bh1 == bh2                                                       // Ignore self-intersection.
                                     || (co1.is_sensor() || co2.is_sensor())                         // Ignore sensors.
                                     || !co1.flags.collision_groups.test(co2.flags.collision_groups) // Apply collision groups.
-                                    || !co1.flags.solver_groups.test(co2.flags.solver_groups)
-                                // Apply solver groups.
+                                    || !co1.flags.solver_groups.test(co2.flags.solver_groups)       // Apply solver groups.
+                                    || !co1.flags.ccd_groups.test(co2.flags.ccd_groups)
+                                // Apply CCD groups.
                                 {
                                     return true;
                                 }
@@ -301,6 +302,7 @@ impl CCDSolver {
                                 // Ignore self-intersections and apply groups filter.
                                 if bh1 == bh2
                                     || !co1.flags.collision_groups.test(co2.flags.collision_groups)
+                                    || !co1.flags.ccd_groups.test(co2.flags.ccd_groups)
                                 {
                                     return true;
                                 }
@@ -423,6 +425,7 @@ impl CCDSolver {
                         // Ignore self-intersection and apply groups filter.
                         if bh1 == bh2
                             || !co1.flags.collision_groups.test(co2.flags.collision_groups)
+                            || !co1.flags.ccd_groups.test(co2.flags.ccd_groups)
                         {
                             return true;
                         }
@@ -43,7 +43,29 @@ pub struct IntegrationParameters {
     pub max_penetration_correction: Real,
     /// The maximal distance separating two objects that will generate predictive contacts (default: `0.002`).
     pub prediction_distance: Real,
+    /// Additional distance beyond `prediction_distance` up to which contact points are kept
+    /// between timesteps (default: `0.0`).
+    ///
+    /// Contact points farther than `prediction_distance` aren't given to the constraints solver
+    /// and don't apply any impulse. However, if this is non-zero, a contact point that applied
+    /// an impulse is no longer treated as a new (and bouncy) contact until it moves farther than
+    /// `prediction_distance + contact_persistence_distance`. This prevents objects vibrating near
+    /// rest from bouncing each time they briefly separate and touch again.
+    pub contact_persistence_distance: Real,
+    /// The distance under which the broad-phase reports two colliders as a potential contact
+    /// pair (default: `None`).
+    ///
+    /// The Aabbs of the colliders are enlarged by half this distance. If `None`, this is
+    /// `prediction_distance + contact_persistence_distance`. Smaller values reduce the number of
+    /// candidate pairs, e.g. for mostly static scenes, but then no contact is generated for
+    /// colliders separated by more than this distance. Larger values keep the pairs of fast moving
+    /// colliders alive for longer, at the cost of more candidate pairs.
+    pub broad_phase_margin: Option<Real>,
     /// Maximum number of iterations performed to solve non-penetration and joint constraints (default: `4`).
+    ///
+    /// The solver isn't warmstarted: the iterations of each timestep start from zero impulses.
+    /// The impulses stored in the contact manifolds and joints after a step are only reported
+    /// results and don't influence the next timestep.
     pub max_velocity_iterations: usize,
     /// Maximum number of iterations performed to solve friction constraints (default: `8`).
     pub max_velocity_friction_iterations: usize,
@@ -54,8 +76,67 @@ pub struct IntegrationParameters {
     pub interleave_restitution_and_friction_resolution: bool,
     /// Minimum number of dynamic bodies in each active island (default: `128`).
     pub min_island_size: usize,
+    /// Maximum number of active islands simulated at each timestep (default: `usize::MAX`).
+    ///
+    /// If there are more active islands than this, the islands deferred for the largest number of
+    /// timesteps, then the islands with the largest kinetic energy, are simulated first. The bodies
+    /// of the other islands keep their position and velocity during this timestep, but still fall
+    /// asleep as usual. They are listed by
+    /// [`PhysicsPipeline::deferred_bodies`](crate::pipeline::PhysicsPipeline::deferred_bodies).
+    ///
+    /// Note that small islands are merged until they contain at least `min_island_size` bodies.
+    pub max_solved_islands: usize,
     /// Maximum number of substeps performed by the  solver (default: `1`).
     pub max_ccd_substeps: usize,
+    /// Hysteresis coefficient applied to the sleep velocity thresholds (default: `1.0`).
+    ///
+    /// A rigid-body starts counting the time it can fall asleep once its velocity goes below its
+    /// sleep thresholds, but this count is only reset once its velocity exceeds these thresholds
+    /// multiplied by this coefficient. In-between, the count is paused. Setting this to a value
+    /// greater than `1.0` prevents bodies moving at velocities close to the thresholds from
+    /// flickering between the awake and sleeping states.
+    pub sleep_hysteresis: Real,
+    /// The model used by the contact solver to bound the friction impulses (default:
+    /// `FrictionModel::Cone`).
+    ///
+    /// This has no effect in 2D, where there is only one friction direction.
+    pub friction_model: FrictionModel,
+    /// If `true`, the constraints of each island are sorted by the handles of their rigid-bodies
+    /// before being grouped for parallel resolution (default: `false`).
+    ///
+    /// This makes the groups, hence the results, independent from the order the constraints are
+    /// given in, at the cost of sorting them at each timestep. With the `enhanced-determinism`
+    /// feature, the constraints are sorted the same way without the `parallel` feature, so the
+    /// results remain identical with and without it.
+    pub sort_parallel_constraints: bool,
+}
+
+/// The model used by the contact solver to bound the friction impulse applied at each contact.
+///
+/// In 3D, the friction impulse at a contact point has two components, along two directions
+/// orthogonal to the contact normal. Both models limit this impulse based on the friction
+/// coefficient `μ` and the normal impulse `λ`, but differ in the shape of the allowed region.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum FrictionModel {
+    /// The two friction components are solved together and the norm of the friction impulse is
+    /// limited to `μ * λ`.
+    ///
+    /// This is isotropic: a body at the friction limit slides in the direction it is pushed to,
+    /// independently from the orientation of the friction directions.
+    Cone,
+    /// Each friction component is limited independently to `[-μ * λ, μ * λ]`.
+    ///
+    /// This approximates the friction cone with a pyramid (a square in the tangent plane), which
+    /// is anisotropic: along the diagonals of the friction directions, up to `√2 * μ * λ` can be
+    /// applied, and bodies at the friction limit tend to slide along these directions.
+    Pyramid,
+}
+
+impl Default for FrictionModel {
+    fn default() -> Self {
+        FrictionModel::Cone
+    }
 }
 
 impl IntegrationParameters {
@@ -71,6 +152,16 @@ impl IntegrationParameters {
         }
     }
 
+    /// The distance under which the broad-phase reports two colliders as a potential contact pair.
+    ///
+    /// This is `self.broad_phase_margin` if it is set, and
+    /// `self.prediction_distance + self.contact_persistence_distance` otherwise.
+    #[inline]
+    pub fn effective_broad_phase_margin(&self) -> Real {
+        self.broad_phase_margin
+            .unwrap_or(self.prediction_distance + self.contact_persistence_distance)
+    }
+
     /// Sets the time-stepping length.
     #[inline]
     #[deprecated = "You can just set the `IntegrationParams::dt` value directly"]
@@ -158,6 +249,8 @@ impl Default for IntegrationParameters {
             allowed_linear_error: 0.001, // 0.005
             max_penetration_correction: Real::MAX,
             prediction_distance: 0.002,
+            contact_persistence_distance: 0.0,
+            broad_phase_margin: None,
             max_velocity_iterations: 4,
             max_velocity_friction_iterations: 8,
             max_stabilization_iterations: 1,
@@ -168,7 +261,11 @@ impl Default for IntegrationParameters {
             // However we don't want it to be too small and end up with
             // tons of islands, reducing SIMD parallelism opportunities.
             min_island_size: 128,
+            max_solved_islands: usize::MAX,
             max_ccd_substeps: 1,
+            sleep_hysteresis: 1.0,
+            friction_model: FrictionModel::Cone,
+            sort_parallel_constraints: false,
         }
     }
 }
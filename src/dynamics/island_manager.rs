@@ -1,6 +1,6 @@
 use crate::dynamics::{
     ImpulseJointSet, MultibodyJointSet, RigidBodyActivation, RigidBodyChanges, RigidBodyColliders,
-    RigidBodyHandle, RigidBodyIds, RigidBodySet, RigidBodyType, RigidBodyVelocity,
+    RigidBodyHandle, RigidBodyIds, RigidBodySet, RigidBodyVelocity,
 };
 use crate::geometry::{ColliderSet, NarrowPhase};
 use crate::math::Real;
@@ -34,6 +34,20 @@ impl IslandManager {
         }
     }
 
+    /// Creates a new empty island manager, with room for `num_bodies` active rigid-bodies.
+    ///
+    /// The island manager grows as usual (without panicking) if more rigid-bodies are active.
+    pub fn with_capacity(num_bodies: usize) -> Self {
+        Self {
+            active_dynamic_set: Vec::with_capacity(num_bodies),
+            active_kinematic_set: Vec::with_capacity(num_bodies),
+            active_islands: Vec::with_capacity(num_bodies + 1),
+            active_set_timestamp: 0,
+            can_sleep: Vec::with_capacity(num_bodies),
+            stack: Vec::with_capacity(num_bodies),
+        }
+    }
+
     pub(crate) fn num_islands(&self) -> usize {
         self.active_islands.len() - 1
     }
@@ -64,6 +78,57 @@ impl IslandManager {
         }
     }
 
+    /// Recomputes the active sets and the islands from scratch, in a single pass over `bodies`.
+    ///
+    /// This is useful when this island manager may be out-of-sync with `bodies`, e.g., after
+    /// deserializing the bodies without their island manager. The enabled dynamic rigid-bodies
+    /// that aren’t sleeping are made active, along with any sleeping rigid-body interacting with
+    /// them, or with a moving kinematic body, through contacts or joints. Each group of interacting
+    /// rigid-bodies is put in its own island. The other rigid-bodies are left asleep.
+    pub fn rebuild(
+        &mut self,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+        narrow_phase: &NarrowPhase,
+        impulse_joints: &ImpulseJointSet,
+        multibody_joints: &MultibodyJointSet,
+    ) {
+        self.active_dynamic_set.clear();
+        self.active_kinematic_set.clear();
+        self.can_sleep.clear();
+        self.stack.clear();
+
+        let handles: Vec<_> = bodies.iter().map(|(handle, _)| handle).collect();
+
+        for handle in handles {
+            let rb = bodies.index_mut_internal(handle);
+            // NOTE: reset the timestamps so that the bodies aren’t considered as already visited
+            //       by the graph traversal, whatever island manager updated them before.
+            rb.ids.active_set_timestamp = 0;
+
+            if !rb.is_enabled() {
+                continue;
+            }
+
+            if rb.is_kinematic() {
+                rb.ids.active_set_id = self.active_kinematic_set.len();
+                self.active_kinematic_set.push(handle);
+            } else if rb.is_dynamic() && !rb.activation.sleeping {
+                self.stack.push(handle);
+            }
+        }
+
+        self.active_set_timestamp = 1;
+        self.propagate_activation(
+            bodies,
+            colliders,
+            narrow_phase,
+            impulse_joints,
+            multibody_joints,
+            1,
+        );
+    }
+
     pub(crate) fn rigid_body_removed(
         &mut self,
         removed_handle: RigidBodyHandle,
@@ -86,7 +151,7 @@ impl IslandManager {
         }
     }
 
-    /// Forces the specified rigid-body to wake up if it is dynamic.
+    /// Forces the specified rigid-body to wake up if it is dynamic and enabled.
     ///
     /// If `strong` is `true` then it is assured that the rigid-body will
     /// remain awake during multiple subsequent timesteps.
@@ -94,7 +159,12 @@ impl IslandManager {
         // NOTE: the use an Option here because there are many legitimate cases (like when
         //       deleting a joint attached to an already-removed body) where we could be
         //       attempting to wake-up a rigid-body that has already been deleted.
-        if bodies.get(handle).map(|rb| rb.body_type()) == Some(RigidBodyType::Dynamic) {
+        //       Disabled rigid-bodies are left untouched so they resume their motion where they
+        //       left off once re-enabled.
+        if bodies
+            .get(handle)
+            .map_or(false, |rb| rb.is_dynamic() && rb.is_enabled())
+        {
             let rb = bodies.index_mut_internal(handle);
 
             // Check that the user didn’t change the sleeping state explicitly, in which
@@ -102,9 +172,7 @@ impl IslandManager {
             if !rb.changes.contains(RigidBodyChanges::SLEEP) {
                 rb.activation.wake_up(strong);
 
-                if rb.is_enabled()
-                    && self.active_dynamic_set.get(rb.ids.active_set_id) != Some(&handle)
-                {
+                if self.active_dynamic_set.get(rb.ids.active_set_id) != Some(&handle) {
                     rb.ids.active_set_id = self.active_dynamic_set.len();
                     self.active_dynamic_set.push(handle);
                 }
@@ -122,6 +190,34 @@ impl IslandManager {
         &self.active_dynamic_set[..]
     }
 
+    /// The sum of the kinetic energies of the rigid-bodies of the active island containing `body`.
+    ///
+    /// An island is a group of dynamic rigid-bodies interacting through contacts or joints. The
+    /// islands are computed at each timestep. Note that islands with less than
+    /// [`IntegrationParameters::min_island_size`](crate::dynamics::IntegrationParameters::min_island_size)
+    /// rigid-bodies are merged together: set it to `1` to get one island per group of interacting
+    /// rigid-bodies. The kinetic energy of each rigid-body is given by
+    /// [`RigidBody::kinetic_energy`](crate::dynamics::RigidBody::kinetic_energy).
+    ///
+    /// Returns `None` if `body` isn’t part of any active island, e.g., because it is sleeping,
+    /// isn’t dynamic, or was woken up after the last timestep.
+    pub fn island_kinetic_energy(
+        &self,
+        bodies: &RigidBodySet,
+        body: RigidBodyHandle,
+    ) -> Option<Real> {
+        let ids = &bodies.get(body)?.ids;
+        let island_start = *self.active_islands.get(ids.active_island_id)?;
+        let island_end = *self.active_islands.get(ids.active_island_id + 1)?;
+        let island = self.active_dynamic_set.get(island_start..island_end)?;
+
+        if island.get(ids.active_set_id.wrapping_sub(island_start)) != Some(&body) {
+            return None;
+        }
+
+        Some(island.iter().map(|h| bodies[*h].kinetic_energy()).sum())
+    }
+
     pub(crate) fn active_island(&self, island_id: usize) -> &[RigidBodyHandle] {
         let island_range = self.active_islands[island_id]..self.active_islands[island_id + 1];
         &self.active_dynamic_set[island_range]
@@ -149,6 +245,7 @@ impl IslandManager {
         impulse_joints: &ImpulseJointSet,
         multibody_joints: &MultibodyJointSet,
         min_island_size: usize,
+        sleep_hysteresis: Real,
     ) {
         assert!(
             min_island_size > 0,
@@ -174,7 +271,13 @@ impl IslandManager {
             let sq_linvel = rb.vels.linvel.norm_squared();
             let sq_angvel = rb.vels.angvel.gdot(rb.vels.angvel);
 
-            update_energy(&mut rb.activation, sq_linvel, sq_angvel, dt);
+            update_energy(
+                &mut rb.activation,
+                sq_linvel,
+                sq_angvel,
+                dt,
+                sleep_hysteresis,
+            );
 
             if rb.activation.time_since_can_sleep >= RigidBodyActivation::default_time_until_sleep()
             {
@@ -188,32 +291,36 @@ impl IslandManager {
             }
         }
 
-        // Read all the contacts and push objects touching touching this rigid-body.
-        #[inline(always)]
-        fn push_contacting_bodies(
-            rb_colliders: &RigidBodyColliders,
-            colliders: &ColliderSet,
-            narrow_phase: &NarrowPhase,
-            stack: &mut Vec<RigidBodyHandle>,
-        ) {
-            for collider_handle in &rb_colliders.0 {
-                for inter in narrow_phase.contacts_with(*collider_handle) {
-                    for manifold in &inter.manifolds {
-                        if !manifold.data.solver_contacts.is_empty() {
-                            let other = crate::utils::select_other(
-                                (inter.collider1, inter.collider2),
-                                *collider_handle,
-                            );
-                            if let Some(other_body) = colliders[other].parent {
-                                stack.push(other_body.handle);
-                            }
-                            break;
-                        }
-                    }
-                }
+        self.propagate_activation(
+            bodies,
+            colliders,
+            narrow_phase,
+            impulse_joints,
+            multibody_joints,
+            min_island_size,
+        );
+
+        // Actually put to sleep bodies which have not been detected as awake.
+        for handle in &self.can_sleep {
+            let rb = bodies.index_mut_internal(*handle);
+            if rb.activation.sleeping {
+                rb.vels = RigidBodyVelocity::zero();
+                rb.activation.sleep();
             }
         }
+    }
 
+    /// Wakes up the bodies interacting with the bodies on `self.stack` or with moving kinematic
+    /// bodies, and sorts all these bodies into the active dynamic set and its islands.
+    fn propagate_activation(
+        &mut self,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+        narrow_phase: &NarrowPhase,
+        impulse_joints: &ImpulseJointSet,
+        multibody_joints: &MultibodyJointSet,
+        min_island_size: usize,
+    ) {
         // Now iterate on all active kinematic bodies and push all the bodies
         // touching them to the stack so they can be woken up.
         for h in self.active_kinematic_set.iter() {
@@ -242,9 +349,12 @@ impl IslandManager {
         while let Some(handle) = self.stack.pop() {
             let rb = bodies.index_mut_internal(handle);
 
-            if rb.ids.active_set_timestamp == self.active_set_timestamp || !rb.is_dynamic() {
+            if rb.ids.active_set_timestamp == self.active_set_timestamp
+                || !rb.is_dynamic()
+                || !rb.is_enabled()
+            {
                 // We already visited this body and its neighbors.
-                // Also, we don't propagate awake state through fixed bodies.
+                // Also, we don't propagate awake state through fixed or disabled bodies.
                 continue;
             }
 
@@ -288,24 +398,192 @@ impl IslandManager {
         //            instant::now() - t,
         //            self.active_islands.len() - 1
         //        );
+    }
+}
 
-        // Actually put to sleep bodies which have not been detected as awake.
-        for handle in &self.can_sleep {
-            let rb = bodies.index_mut_internal(*handle);
-            if rb.activation.sleeping {
-                rb.vels = RigidBodyVelocity::zero();
-                rb.activation.sleep();
+// Read all the contacts and push objects touching touching this rigid-body.
+#[inline(always)]
+fn push_contacting_bodies(
+    rb_colliders: &RigidBodyColliders,
+    colliders: &ColliderSet,
+    narrow_phase: &NarrowPhase,
+    stack: &mut Vec<RigidBodyHandle>,
+) {
+    for collider_handle in &rb_colliders.0 {
+        for inter in narrow_phase.contacts_with(*collider_handle) {
+            for manifold in &inter.manifolds {
+                if !manifold.data.solver_contacts.is_empty() {
+                    let other = crate::utils::select_other(
+                        (inter.collider1, inter.collider2),
+                        *collider_handle,
+                    );
+                    if let Some(other_body) = colliders[other].parent {
+                        stack.push(other_body.handle);
+                    }
+                    break;
+                }
             }
         }
     }
 }
 
-fn update_energy(activation: &mut RigidBodyActivation, sq_linvel: Real, sq_angvel: Real, dt: Real) {
-    if sq_linvel < activation.linear_threshold * activation.linear_threshold.abs()
-        && sq_angvel < activation.angular_threshold * activation.angular_threshold.abs()
-    {
+fn update_energy(
+    activation: &mut RigidBodyActivation,
+    sq_linvel: Real,
+    sq_angvel: Real,
+    dt: Real,
+    hysteresis: Real,
+) {
+    // NOTE: scaling the squared velocities by `1 / coeff²` is equivalent to scaling the
+    //       thresholds by `coeff`.
+    let below_thresholds = |coeff: Real| {
+        let inv_sq_coeff = 1.0 / (coeff * coeff);
+        activation.is_below_linear_threshold(sq_linvel * inv_sq_coeff)
+            && activation.is_below_angular_threshold(sq_angvel * inv_sq_coeff)
+    };
+
+    if below_thresholds(1.0) {
         activation.time_since_can_sleep += dt;
-    } else {
+    } else if !below_thresholds(hysteresis.max(1.0)) {
         activation.time_since_can_sleep = 0.0;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::update_energy;
+    use crate::dynamics::{
+        FixedJointBuilder, IslandManager, RigidBodyActivation, RigidBodyBuilder,
+    };
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn rebuilt_islands_only_contain_awake_bodies() {
+        let mut world = TestWorld::new();
+
+        let awake = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 5.0));
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), awake, &mut world.bodies);
+        let asleep = world.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 10.0)
+                .sleeping(true),
+        );
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), asleep, &mut world.bodies);
+
+        for _ in 0..2 {
+            world.step();
+        }
+
+        // Simulate an island manager lost during serialization.
+        world.islands = IslandManager::new();
+        world.islands.rebuild(
+            &mut world.bodies,
+            &world.colliders,
+            &world.narrow_phase,
+            &world.impulse_joints,
+            &world.multibody_joints,
+        );
+        assert_eq!(world.islands.active_dynamic_bodies(), &[awake]);
+
+        let awake_y = world.bodies[awake].translation().y;
+        for _ in 0..2 {
+            world.step();
+        }
+        assert!(world.bodies[awake].translation().y < awake_y);
+        assert_eq!(world.bodies[asleep].translation(), &(Vector::x() * 10.0));
+        assert!(world.bodies[asleep].is_sleeping());
+    }
+
+    #[test]
+    fn disabled_bodies_are_never_woken_up() {
+        let mut world = TestWorld::new();
+        world.gravity = Vector::zeros();
+
+        let body = RigidBodyBuilder::dynamic().sleeping(true);
+        let (disabled, _) = world.insert(body, ColliderBuilder::ball(0.5));
+        let body = RigidBodyBuilder::dynamic().translation(Vector::x() * 3.0);
+        let (other, _) = world.insert(body, ColliderBuilder::ball(0.5));
+        world.bodies[disabled].set_enabled(false);
+        let joint = world
+            .impulse_joints
+            .insert(disabled, other, FixedJointBuilder::new(), true);
+        world.step();
+
+        // Neither the joints nor an explicit wake-up touch the activation of a disabled body.
+        world.impulse_joints.remove(joint, true);
+        world.islands.wake_up(&mut world.bodies, disabled, true);
+        world.step();
+        assert!(world.bodies[disabled].is_sleeping());
+        assert!(!world.islands.active_dynamic_bodies().contains(&disabled));
+
+        // Once re-enabled, it can be woken up again.
+        world.bodies[disabled].set_enabled(true);
+        world.step();
+        assert!(world.bodies[disabled].is_sleeping());
+        world.islands.wake_up(&mut world.bodies, disabled, true);
+        assert!(!world.bodies[disabled].is_sleeping());
+        world.step();
+        assert!(world.islands.active_dynamic_bodies().contains(&disabled));
+    }
+
+    #[test]
+    fn sleep_hysteresis_pauses_the_sleep_timer_near_the_thresholds() {
+        let mut activation = RigidBodyActivation::active();
+        let threshold = activation.linear_threshold;
+        let sq_linvel = |coeff: Real| (threshold * coeff) * (threshold * coeff);
+        let dt = 0.5;
+
+        update_energy(&mut activation, sq_linvel(0.5), 0.0, dt, 2.0);
+        assert_eq!(activation.time_since_can_sleep, dt);
+        // Between the thresholds and the thresholds multiplied by the hysteresis, the timer is
+        // paused.
+        update_energy(&mut activation, sq_linvel(1.5), 0.0, dt, 2.0);
+        assert_eq!(activation.time_since_can_sleep, dt);
+        update_energy(&mut activation, sq_linvel(0.5), 0.0, dt, 2.0);
+        assert_eq!(activation.time_since_can_sleep, 2.0 * dt);
+        update_energy(&mut activation, sq_linvel(2.5), 0.0, dt, 2.0);
+        assert_eq!(activation.time_since_can_sleep, 0.0);
+
+        // Without hysteresis, any velocity above the thresholds resets the timer.
+        update_energy(&mut activation, sq_linvel(0.5), 0.0, dt, 1.0);
+        update_energy(&mut activation, sq_linvel(1.5), 0.0, dt, 1.0);
+        assert_eq!(activation.time_since_can_sleep, 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "dim3")]
+    fn spinning_body_sleeps_only_if_angular_velocity_is_ignored() {
+        let mut world = TestWorld::new();
+
+        let mut insert_spinning_body = |x, ignore_angvel| {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * x)
+                .angvel(Vector::y() * 5.0)
+                .sleep_ignores_angular_velocity(ignore_angvel)
+                .build();
+            let body = world.bodies.insert(body);
+            world
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut world.bodies);
+            body
+        };
+        let top = insert_spinning_body(0.0, false);
+        let linear_only = insert_spinning_body(5.0, true);
+
+        world.gravity = Vector::zeros();
+        for _ in 0..200 {
+            world.step();
+        }
+
+        assert!(!world.bodies[top].is_sleeping());
+        assert!(world.bodies[linear_only].is_sleeping());
+    }
+}
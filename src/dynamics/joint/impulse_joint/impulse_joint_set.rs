@@ -104,6 +104,21 @@ impl ImpulseJointSet {
             .map(|inter| (inter.0, inter.1, inter.2.handle, inter.2))
     }
 
+    /// Iterates through the handles of all the impulse joints attached to the given rigid-body,
+    /// together with the handle of the other rigid-body attached to each joint.
+    ///
+    /// This only goes through the joints attached to `body`, not the whole set.
+    pub fn joints_with(
+        &self,
+        body: RigidBodyHandle,
+    ) -> impl Iterator<Item = (ImpulseJointHandle, RigidBodyHandle)> + '_ {
+        self.attached_joints(body)
+            .map(move |(body1, body2, handle, _)| {
+                let other = if body1 == body { body2 } else { body1 };
+                (handle, other)
+            })
+    }
+
     /// Iterates through all the impulse joints attached to the given rigid-body.
     pub fn map_attached_joints_mut<'a>(
         &'a mut self,
@@ -196,6 +211,21 @@ impl ImpulseJointSet {
             .map(|e| (e.weight.handle, &e.weight))
     }
 
+    /// Iterates through all the joints on this set, in the order they were inserted.
+    ///
+    /// Unlike [`ImpulseJointSet::iter`], this order doesn't depend on the removals that happened
+    /// before the insertion of the joints.
+    pub fn iter_in_insertion_order(
+        &self,
+    ) -> impl Iterator<Item = (ImpulseJointHandle, &ImpulseJoint)> {
+        self.joint_ids.iter_in_insertion_order().map(|(h, id)| {
+            (
+                ImpulseJointHandle(h),
+                &self.joint_graph.graph.edges[id.index()].weight,
+            )
+        })
+    }
+
     /// Iterates mutably through all the joint on this set.
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (ImpulseJointHandle, &mut ImpulseJoint)> {
         self.joint_graph
@@ -345,6 +375,7 @@ impl ImpulseJointSet {
         handle: RigidBodyHandle,
     ) -> Vec<ImpulseJointHandle> {
         let mut deleted = vec![];
+        let to_delete: Vec<_> = self.joints_with(handle).collect();
 
         if let Some(deleted_id) = self
             .rb_graph_ids
@@ -355,12 +386,7 @@ impl ImpulseJointSet {
                 // - Wake-up the attached bodies.
                 // - Update our Handle -> graph edge mapping.
                 // Delete the node.
-                let to_delete: Vec<_> = self
-                    .joint_graph
-                    .interactions_with(deleted_id)
-                    .map(|e| (e.0, e.1, e.2.handle))
-                    .collect();
-                for (h1, h2, to_delete_handle) in to_delete {
+                for (to_delete_handle, other) in to_delete {
                     deleted.push(to_delete_handle);
                     let to_delete_edge_id = self.joint_ids.remove(to_delete_handle.0).unwrap();
                     self.joint_graph.graph.remove_edge(to_delete_edge_id);
@@ -371,8 +397,8 @@ impl ImpulseJointSet {
                     }
 
                     // Wake up the attached bodies.
-                    self.to_wake_up.push(h1);
-                    self.to_wake_up.push(h2);
+                    self.to_wake_up.push(handle);
+                    self.to_wake_up.push(other);
                 }
 
                 if let Some(other) = self.joint_graph.remove_node(deleted_id) {
@@ -386,3 +412,56 @@ impl ImpulseJointSet {
         deleted
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::ImpulseJointSet;
+    use crate::dynamics::{
+        FixedJointBuilder, IslandManager, MultibodyJointSet, RigidBodyBuilder, RigidBodySet,
+    };
+    use crate::geometry::ColliderSet;
+
+    #[test]
+    fn joints_with_follows_joint_and_body_removals() {
+        let mut islands = IslandManager::new();
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+
+        let a = bodies.insert(RigidBodyBuilder::dynamic());
+        let b = bodies.insert(RigidBodyBuilder::dynamic());
+        let c = bodies.insert(RigidBodyBuilder::dynamic());
+        let ab = impulse_joints.insert(a, b, FixedJointBuilder::new(), true);
+        let bc = impulse_joints.insert(b, c, FixedJointBuilder::new(), true);
+        let ca = impulse_joints.insert(c, a, FixedJointBuilder::new(), true);
+
+        let mut with_a: Vec<_> = impulse_joints.joints_with(a).collect();
+        with_a.sort_by_key(|(joint, _)| joint.0);
+        assert_eq!(with_a, vec![(ab, b), (ca, c)]);
+
+        impulse_joints.remove(ab, true);
+        assert_eq!(
+            impulse_joints.joints_with(a).collect::<Vec<_>>(),
+            vec![(ca, c)]
+        );
+        assert_eq!(
+            impulse_joints.joints_with(b).collect::<Vec<_>>(),
+            vec![(bc, c)]
+        );
+
+        // Removing a rigid-body removes its joints, and updates the joints of the other bodies.
+        bodies.remove(
+            c,
+            &mut islands,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            true,
+        );
+        assert_eq!(impulse_joints.joints_with(a).count(), 0);
+        assert_eq!(impulse_joints.joints_with(b).count(), 0);
+        assert!(!impulse_joints.contains(bc) && !impulse_joints.contains(ca));
+        assert!(impulse_joints.is_empty());
+    }
+}
@@ -3,6 +3,8 @@ pub use self::generic_joint::*;
 pub use self::impulse_joint::*;
 pub use self::motor_model::MotorModel;
 pub use self::multibody_joint::*;
+pub use self::orientation_joint::*;
+pub use self::point_joint::*;
 pub use self::prismatic_joint::*;
 pub use self::revolute_joint::*;
 pub use self::rope_joint::*;
@@ -15,6 +17,8 @@ mod generic_joint;
 mod impulse_joint;
 mod motor_model;
 mod multibody_joint;
+mod orientation_joint;
+mod point_joint;
 mod prismatic_joint;
 mod revolute_joint;
 mod rope_joint;
@@ -83,6 +83,9 @@ pub struct MultibodyJointSet {
     //       that any more in the future when we improve our island builder.
     pub(crate) connectivity_graph: InteractionGraph<RigidBodyHandle, ()>,
     pub(crate) to_wake_up: Vec<RigidBodyHandle>,
+    // The insertion stamp of each multibody joint, indexed by joint handle.
+    insertion_stamps: Coarena<u64>,
+    next_insertion_stamp: u64,
 }
 
 impl MultibodyJointSet {
@@ -93,6 +96,8 @@ impl MultibodyJointSet {
             rb2mb: Coarena::new(),
             connectivity_graph: InteractionGraph::new(),
             to_wake_up: vec![],
+            insertion_stamps: Coarena::new(),
+            next_insertion_stamp: 0,
         }
     }
 
@@ -107,6 +112,18 @@ impl MultibodyJointSet {
             })
     }
 
+    /// Iterates through all the multibody joints from this set, in the order they were inserted.
+    ///
+    /// Unlike [`MultibodyJointSet::iter`], this order doesn't depend on the removals that
+    /// happened before the insertion of the joints.
+    pub fn iter_in_insertion_order(
+        &self,
+    ) -> impl Iterator<Item = (MultibodyJointHandle, &Multibody, &MultibodyLink)> {
+        let mut joints: Vec<_> = self.iter().collect();
+        joints.sort_unstable_by_key(|(handle, _, _)| self.insertion_stamps.get(handle.0).copied());
+        joints.into_iter()
+    }
+
     /// Inserts a new multibody_joint into this set.
     pub fn insert(
         &mut self,
@@ -155,6 +172,9 @@ impl MultibodyJointSet {
         }
 
         multibody1.append(mb2, link1.id, MultibodyJoint::new(data));
+        self.insertion_stamps
+            .insert(body2.0, self.next_insertion_stamp);
+        self.next_insertion_stamp += 1;
 
         if wake_up {
             self.to_wake_up.push(body1);
@@ -405,6 +425,9 @@ impl MultibodyJointSet {
     }
 }
 
+/// Panics if there is no multibody with the given index.
+///
+/// Use `MultibodyJointSet::get_multibody` for a non-panicking alternative.
 impl std::ops::Index<MultibodyIndex> for MultibodyJointSet {
     type Output = Multibody;
 
@@ -0,0 +1,116 @@
+use crate::dynamics::{GenericJoint, GenericJointBuilder, JointAxesMask};
+use crate::math::{Isometry, Real};
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(transparent)]
+/// An orientation joint, locks all relative rotations between two bodies.
+///
+/// The bodies remain free to translate relative to each other. The orientation of the joint’s
+/// frame attached to the first body is kept equal to the orientation of the joint’s frame attached
+/// to the second body.
+pub struct OrientationJoint {
+    /// The underlying joint data.
+    pub data: GenericJoint,
+}
+
+impl Default for OrientationJoint {
+    fn default() -> Self {
+        OrientationJoint::new()
+    }
+}
+
+impl OrientationJoint {
+    /// Creates a new orientation joint locking all relative rotations between two bodies.
+    #[must_use]
+    pub fn new() -> Self {
+        let data = GenericJointBuilder::new(JointAxesMask::ANG_AXES).build();
+        Self { data }
+    }
+
+    /// Are contacts between the attached rigid-bodies enabled?
+    pub fn contacts_enabled(&self) -> bool {
+        self.data.contacts_enabled
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    pub fn set_contacts_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.data.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// The joint’s frame, expressed in the first rigid-body’s local-space.
+    #[must_use]
+    pub fn local_frame1(&self) -> &Isometry<Real> {
+        &self.data.local_frame1
+    }
+
+    /// Sets the joint’s frame, expressed in the first rigid-body’s local-space.
+    pub fn set_local_frame1(&mut self, local_frame: Isometry<Real>) -> &mut Self {
+        self.data.set_local_frame1(local_frame);
+        self
+    }
+
+    /// The joint’s frame, expressed in the second rigid-body’s local-space.
+    #[must_use]
+    pub fn local_frame2(&self) -> &Isometry<Real> {
+        &self.data.local_frame2
+    }
+
+    /// Sets joint’s frame, expressed in the second rigid-body’s local-space.
+    pub fn set_local_frame2(&mut self, local_frame: Isometry<Real>) -> &mut Self {
+        self.data.set_local_frame2(local_frame);
+        self
+    }
+}
+
+impl Into<GenericJoint> for OrientationJoint {
+    fn into(self) -> GenericJoint {
+        self.data
+    }
+}
+
+/// Create orientation joints using the builder pattern.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct OrientationJointBuilder(pub OrientationJoint);
+
+impl OrientationJointBuilder {
+    /// Creates a new builder for orientation joints.
+    pub fn new() -> Self {
+        Self(OrientationJoint::new())
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    #[must_use]
+    pub fn contacts_enabled(mut self, enabled: bool) -> Self {
+        self.0.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// Sets the joint’s frame, expressed in the first rigid-body’s local-space.
+    #[must_use]
+    pub fn local_frame1(mut self, local_frame: Isometry<Real>) -> Self {
+        self.0.set_local_frame1(local_frame);
+        self
+    }
+
+    /// Sets joint’s frame, expressed in the second rigid-body’s local-space.
+    #[must_use]
+    pub fn local_frame2(mut self, local_frame: Isometry<Real>) -> Self {
+        self.0.set_local_frame2(local_frame);
+        self
+    }
+
+    /// Build the orientation joint.
+    #[must_use]
+    pub fn build(self) -> OrientationJoint {
+        self.0
+    }
+}
+
+impl Into<GenericJoint> for OrientationJointBuilder {
+    fn into(self) -> GenericJoint {
+        self.0.into()
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(feature = "dim2")]
+use crate::dynamics::{RevoluteJoint, RevoluteJointBuilder};
+#[cfg(feature = "dim3")]
+use crate::dynamics::{SphericalJoint, SphericalJointBuilder};
+
+/// A point joint, locks all relative translations between two bodies.
+///
+/// The anchors of both bodies are kept at the same position, while the bodies remain free to
+/// rotate relative to each other. This is the same as a [`SphericalJoint`].
+#[cfg(feature = "dim3")]
+pub type PointJoint = SphericalJoint;
+
+/// A point joint, locks all relative translations between two bodies.
+///
+/// The anchors of both bodies are kept at the same position, while the bodies remain free to
+/// rotate relative to each other. This is the same as a [`RevoluteJoint`].
+#[cfg(feature = "dim2")]
+pub type PointJoint = RevoluteJoint;
+
+/// Create point joints using the builder pattern.
+#[cfg(feature = "dim3")]
+pub type PointJointBuilder = SphericalJointBuilder;
+
+/// Create point joints using the builder pattern.
+#[cfg(feature = "dim2")]
+pub type PointJointBuilder = RevoluteJointBuilder;
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{OrientationJointBuilder, PointJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Isometry, Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    fn simulate_pendulum(lock_orientation: bool) -> Isometry<Real> {
+        let mut world = TestWorld::new();
+
+        let anchor = world.bodies.insert(RigidBodyBuilder::fixed());
+        let hanging = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::x() * 2.0));
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), hanging, &mut world.bodies);
+
+        let point = PointJointBuilder::new().local_anchor2(Point::origin() - Vector::x() * 2.0);
+        world.impulse_joints.insert(anchor, hanging, point, true);
+        if lock_orientation {
+            world
+                .impulse_joints
+                .insert(anchor, hanging, OrientationJointBuilder::new(), true);
+        }
+
+        for _ in 0..120 {
+            world.step();
+        }
+
+        *world.bodies[hanging].position()
+    }
+
+    #[test]
+    fn point_joints_only_lock_relative_translations() {
+        let anchor2 = Point::origin() - Vector::x() * 2.0;
+
+        // The point joint lets the body swing around its anchor.
+        let pos = simulate_pendulum(false);
+        assert!((pos * anchor2).coords.norm() < 1.0e-2);
+        assert!(pos.rotation.angle().abs() > 0.1);
+
+        // Adding an orientation joint locks the remaining relative rotations. The joints are
+        // soft, so they sag a bit under the weight of the body.
+        let pos = simulate_pendulum(true);
+        assert!((pos * anchor2).coords.norm() < 5.0e-2);
+        assert!(pos.rotation.angle().abs() < 5.0e-2);
+    }
+}
@@ -2,16 +2,19 @@
 
 pub use self::ccd::CCDSolver;
 pub use self::coefficient_combine_rule::CoefficientCombineRule;
-pub use self::integration_parameters::IntegrationParameters;
+pub use self::integration_parameters::{FrictionModel, IntegrationParameters};
 pub use self::island_manager::IslandManager;
 pub(crate) use self::joint::JointGraphEdge;
 pub(crate) use self::joint::JointIndex;
 pub use self::joint::*;
 pub use self::rigid_body_components::*;
+pub use self::solver::contact_effective_mass;
 #[cfg(not(feature = "parallel"))]
 pub(crate) use self::solver::IslandSolver;
 #[cfg(feature = "parallel")]
 pub(crate) use self::solver::ParallelIslandSolver;
+#[cfg(all(feature = "debug-solver-residuals", not(feature = "parallel")))]
+pub use self::solver::SolverResiduals;
 pub use parry::mass_properties::MassProperties;
 
 pub use self::rigid_body::{RigidBody, RigidBodyBuilder};
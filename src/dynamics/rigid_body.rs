@@ -1,13 +1,14 @@
 use crate::dynamics::{
     LockedAxes, MassProperties, RigidBodyActivation, RigidBodyAdditionalMassProps, RigidBodyCcd,
     RigidBodyChanges, RigidBodyColliders, RigidBodyDamping, RigidBodyDominance, RigidBodyForces,
-    RigidBodyIds, RigidBodyMassProps, RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
+    RigidBodyIds, RigidBodyMassProps, RigidBodyPosition, RigidBodyPositionTarget, RigidBodyType,
+    RigidBodyVelocity,
 };
 use crate::geometry::{
     ColliderHandle, ColliderMassProps, ColliderParent, ColliderPosition, ColliderSet, ColliderShape,
 };
 use crate::math::{AngVector, Isometry, Point, Real, Rotation, Vector};
-use crate::utils::WCross;
+use crate::utils::{WAngularInertia, WCross};
 use num::Zero;
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -23,6 +24,9 @@ pub struct RigidBody {
     //       should remove this field.
     pub(crate) integrated_vels: RigidBodyVelocity,
     pub(crate) vels: RigidBodyVelocity,
+    /// The velocity this rigid-body had right before it was last made fixed.
+    pub(crate) vels_before_fixed: RigidBodyVelocity,
+    pub(crate) position_correction: Isometry<Real>,
     pub(crate) damping: RigidBodyDamping,
     pub(crate) forces: RigidBodyForces,
     pub(crate) ccd: RigidBodyCcd,
@@ -35,6 +39,14 @@ pub struct RigidBody {
     pub(crate) body_type: RigidBodyType,
     /// The dominance group this rigid-body is part of.
     pub(crate) dominance: RigidBodyDominance,
+    /// Overrides the global maximum penetration correction for this rigid-body.
+    pub(crate) max_penetration_correction: Real,
+    /// The minimum number of velocity iterations of the island containing this rigid-body.
+    pub(crate) min_solver_iterations: usize,
+    /// The angular impulse discarded by the rotation locking at the last timestep.
+    pub(crate) locked_angular_impulse: AngVector<Real>,
+    /// The pose this rigid-body is pulled toward, if it is dynamic.
+    pub(crate) position_target: Option<RigidBodyPositionTarget>,
     pub(crate) enabled: bool,
     /// User-defined data associated to this rigid-body.
     pub user_data: u128,
@@ -53,6 +65,8 @@ impl RigidBody {
             mprops: RigidBodyMassProps::default(),
             integrated_vels: RigidBodyVelocity::default(),
             vels: RigidBodyVelocity::default(),
+            vels_before_fixed: RigidBodyVelocity::default(),
+            position_correction: Isometry::identity(),
             damping: RigidBodyDamping::default(),
             forces: RigidBodyForces::default(),
             ccd: RigidBodyCcd::default(),
@@ -62,6 +76,10 @@ impl RigidBody {
             changes: RigidBodyChanges::all(),
             body_type: RigidBodyType::Dynamic,
             dominance: RigidBodyDominance::default(),
+            max_penetration_correction: Real::MAX,
+            min_solver_iterations: 0,
+            locked_angular_impulse: AngVector::zero(),
+            position_target: None,
             enabled: true,
             user_data: 0,
         }
@@ -72,6 +90,20 @@ impl RigidBody {
         self.ids = Default::default();
     }
 
+    /// The user-data of this rigid-body, unpacked as two `u64`.
+    ///
+    /// See [`crate::utils::unpack_u64s`] for details.
+    pub fn user_data_u64s(&self) -> [u64; 2] {
+        crate::utils::unpack_u64s(self.user_data)
+    }
+
+    /// Sets the user-data of this rigid-body by packing two `u64`.
+    ///
+    /// See [`crate::utils::pack_u64s`] for details.
+    pub fn set_user_data_u64s(&mut self, data: [u64; 2]) {
+        self.user_data = crate::utils::pack_u64s(data);
+    }
+
     /// The activation status of this rigid-body.
     pub fn activation(&self) -> &RigidBodyActivation {
         &self.activation
@@ -89,6 +121,14 @@ impl RigidBody {
     }
 
     /// Sets whether this rigid-body is enabled or not.
+    ///
+    /// A disabled rigid-body is excluded from the simulation: it isn’t part of any island, its
+    /// colliders no longer generate contacts, and the impulse joints attached to it are disabled.
+    /// Unlike a sleeping rigid-body, a disabled rigid-body is never woken up automatically
+    /// (by contacts, joints, or [`IslandManager::wake_up`](crate::dynamics::IslandManager::wake_up)).
+    ///
+    /// Its position and velocities are left untouched while it is disabled so that re-enabling it
+    /// resumes its motion where it left off.
     pub fn set_enabled(&mut self, enabled: bool) {
         if enabled != self.enabled {
             if enabled {
@@ -135,15 +175,41 @@ impl RigidBody {
     }
 
     /// Sets the type of this rigid-body.
+    ///
+    /// The change is applied to the island manager, the contacts, and the solver at the next
+    /// timestep: a rigid-body that is no longer dynamic acts as if it had an infinite mass from
+    /// then on. Switching to [`RigidBodyType::Fixed`] sets the velocity to zero, so it remains
+    /// zero if the rigid-body becomes dynamic again later. Use
+    /// [`RigidBody::set_body_type_with_velocity`] to resume the previous motion instead.
+    ///
+    /// If `wake_up` is `true` and the rigid-body becomes dynamic, it is woken up.
     pub fn set_body_type(&mut self, status: RigidBodyType, wake_up: bool) {
+        self.set_body_type_with_velocity(status, wake_up, false)
+    }
+
+    /// Sets the type of this rigid-body, and chooses the velocity it gets when it stops being fixed.
+    ///
+    /// This is the same as [`RigidBody::set_body_type`], except that if `restore_velocity` is
+    /// `true` and this rigid-body was [`RigidBodyType::Fixed`], it gets back the velocity it had
+    /// right before it was made fixed. If `restore_velocity` is `false`, its velocity remains zero.
+    pub fn set_body_type_with_velocity(
+        &mut self,
+        status: RigidBodyType,
+        wake_up: bool,
+        restore_velocity: bool,
+    ) {
         if status != self.body_type {
             self.changes.insert(RigidBodyChanges::TYPE);
-            self.body_type = status;
 
             if status == RigidBodyType::Fixed {
+                self.vels_before_fixed = self.vels;
                 self.vels = RigidBodyVelocity::zero();
+            } else if self.body_type == RigidBodyType::Fixed && restore_velocity {
+                self.vels = self.vels_before_fixed;
             }
 
+            self.body_type = status;
+
             if self.is_dynamic() && wake_up {
                 self.wake_up(true);
             }
@@ -172,6 +238,15 @@ impl RigidBody {
     }
 
     /// Sets the axes along which this rigid-body cannot translate or rotate.
+    ///
+    /// If this rigid-body is dynamic, the components of its velocity along the locked axes
+    /// are set to zero so it doesn’t drift along these axes. For example, use
+    /// `LockedAxes::PLANAR_XY` to restrict the motion of a 3D rigid-body to the plane
+    /// orthogonal to the `Z` axis and containing its current position. The velocities set
+    /// afterwards are projected onto the unlocked axes too.
+    ///
+    /// The axes are the axes of the world frame, so only planes orthogonal to one of these
+    /// axes are supported.
     #[inline]
     pub fn set_locked_axes(&mut self, locked_axes: LockedAxes, wake_up: bool) {
         if locked_axes != self.mprops.flags {
@@ -179,6 +254,10 @@ impl RigidBody {
                 self.wake_up(true);
             }
 
+            if self.is_dynamic() {
+                self.vels.remove_locked_components(locked_axes);
+            }
+
             self.mprops.flags = locked_axes;
             self.update_world_mass_properties();
         }
@@ -192,21 +271,18 @@ impl RigidBody {
 
     #[inline]
     /// Locks or unlocks all the rotations of this rigid-body.
+    ///
+    /// See [`Self::set_locked_axes`] for the effect on the velocity of this rigid-body.
     pub fn lock_rotations(&mut self, locked: bool, wake_up: bool) {
-        if !self.mprops.flags.contains(LockedAxes::ROTATION_LOCKED) {
-            if self.is_dynamic() && wake_up {
-                self.wake_up(true);
-            }
-
-            self.mprops.flags.set(LockedAxes::ROTATION_LOCKED_X, locked);
-            self.mprops.flags.set(LockedAxes::ROTATION_LOCKED_Y, locked);
-            self.mprops.flags.set(LockedAxes::ROTATION_LOCKED_Z, locked);
-            self.update_world_mass_properties();
-        }
+        let mut locked_axes = self.mprops.flags;
+        locked_axes.set(LockedAxes::ROTATION_LOCKED, locked);
+        self.set_locked_axes(locked_axes, wake_up);
     }
 
     #[inline]
     /// Locks or unlocks rotations of this rigid-body along each cartesian axes.
+    ///
+    /// See [`Self::set_locked_axes`] for the effect on the velocity of this rigid-body.
     pub fn set_enabled_rotations(
         &mut self,
         allow_rotations_x: bool,
@@ -214,25 +290,11 @@ impl RigidBody {
         allow_rotations_z: bool,
         wake_up: bool,
     ) {
-        if self.mprops.flags.contains(LockedAxes::ROTATION_LOCKED_X) != !allow_rotations_x
-            || self.mprops.flags.contains(LockedAxes::ROTATION_LOCKED_Y) != !allow_rotations_y
-            || self.mprops.flags.contains(LockedAxes::ROTATION_LOCKED_Z) != !allow_rotations_z
-        {
-            if self.is_dynamic() && wake_up {
-                self.wake_up(true);
-            }
-
-            self.mprops
-                .flags
-                .set(LockedAxes::ROTATION_LOCKED_X, !allow_rotations_x);
-            self.mprops
-                .flags
-                .set(LockedAxes::ROTATION_LOCKED_Y, !allow_rotations_y);
-            self.mprops
-                .flags
-                .set(LockedAxes::ROTATION_LOCKED_Z, !allow_rotations_z);
-            self.update_world_mass_properties();
-        }
+        let mut locked_axes = self.mprops.flags;
+        locked_axes.set(LockedAxes::ROTATION_LOCKED_X, !allow_rotations_x);
+        locked_axes.set(LockedAxes::ROTATION_LOCKED_Y, !allow_rotations_y);
+        locked_axes.set(LockedAxes::ROTATION_LOCKED_Z, !allow_rotations_z);
+        self.set_locked_axes(locked_axes, wake_up);
     }
 
     /// Locks or unlocks rotations of this rigid-body along each cartesian axes.
@@ -253,22 +315,19 @@ impl RigidBody {
     }
 
     #[inline]
-    /// Locks or unlocks all the rotations of this rigid-body.
+    /// Locks or unlocks all the translations of this rigid-body.
+    ///
+    /// See [`Self::set_locked_axes`] for the effect on the velocity of this rigid-body.
     pub fn lock_translations(&mut self, locked: bool, wake_up: bool) {
-        if !self.mprops.flags.contains(LockedAxes::TRANSLATION_LOCKED) {
-            if self.is_dynamic() && wake_up {
-                self.wake_up(true);
-            }
-
-            self.mprops
-                .flags
-                .set(LockedAxes::TRANSLATION_LOCKED, locked);
-            self.update_world_mass_properties();
-        }
+        let mut locked_axes = self.mprops.flags;
+        locked_axes.set(LockedAxes::TRANSLATION_LOCKED, locked);
+        self.set_locked_axes(locked_axes, wake_up);
     }
 
     #[inline]
-    /// Locks or unlocks rotations of this rigid-body along each cartesian axes.
+    /// Locks or unlocks translations of this rigid-body along each cartesian axes.
+    ///
+    /// See [`Self::set_locked_axes`] for the effect on the velocity of this rigid-body.
     pub fn set_enabled_translations(
         &mut self,
         allow_translation_x: bool,
@@ -276,37 +335,12 @@ impl RigidBody {
         #[cfg(feature = "dim3")] allow_translation_z: bool,
         wake_up: bool,
     ) {
-        #[cfg(feature = "dim2")]
-        if self.mprops.flags.contains(LockedAxes::TRANSLATION_LOCKED_X) == !allow_translation_x
-            && self.mprops.flags.contains(LockedAxes::TRANSLATION_LOCKED_Y) == !allow_translation_y
-        {
-            // Nothing to change.
-            return;
-        }
-        #[cfg(feature = "dim3")]
-        if self.mprops.flags.contains(LockedAxes::TRANSLATION_LOCKED_X) == !allow_translation_x
-            && self.mprops.flags.contains(LockedAxes::TRANSLATION_LOCKED_Y) == !allow_translation_y
-            && self.mprops.flags.contains(LockedAxes::TRANSLATION_LOCKED_Z) == !allow_translation_z
-        {
-            // Nothing to change.
-            return;
-        }
-
-        if self.is_dynamic() && wake_up {
-            self.wake_up(true);
-        }
-
-        self.mprops
-            .flags
-            .set(LockedAxes::TRANSLATION_LOCKED_X, !allow_translation_x);
-        self.mprops
-            .flags
-            .set(LockedAxes::TRANSLATION_LOCKED_Y, !allow_translation_y);
+        let mut locked_axes = self.mprops.flags;
+        locked_axes.set(LockedAxes::TRANSLATION_LOCKED_X, !allow_translation_x);
+        locked_axes.set(LockedAxes::TRANSLATION_LOCKED_Y, !allow_translation_y);
         #[cfg(feature = "dim3")]
-        self.mprops
-            .flags
-            .set(LockedAxes::TRANSLATION_LOCKED_Z, !allow_translation_z);
-        self.update_world_mass_properties();
+        locked_axes.set(LockedAxes::TRANSLATION_LOCKED_Z, !allow_translation_z);
+        self.set_locked_axes(locked_axes, wake_up);
     }
 
     #[inline]
@@ -385,6 +419,23 @@ impl RigidBody {
         self.ccd.ccd_active
     }
 
+    /// Is this rigid-body moving fast enough to have CCD run during a timestep of length `dt`?
+    ///
+    /// This performs the same test as the physics pipeline when it decides which rigid-bodies
+    /// must have their CCD resolved: the rigid-body must be awake, dynamic, with CCD enabled, and
+    /// the motion of its colliders predicted from its last solver velocities and the forces
+    /// applied to it must exceed a threshold based on its CCD thickness. Once the timestep is
+    /// done, [`Self::is_ccd_active`] reports the final decision, based on the velocities computed
+    /// by the constraints solver during that timestep.
+    pub fn is_moving_fast_for_ccd(&self, dt: Real) -> bool {
+        self.is_dynamic()
+            && !self.is_sleeping()
+            && self.ccd.ccd_enabled
+            && self
+                .ccd
+                .is_moving_fast(dt, &self.integrated_vels, Some(&self.forces))
+    }
+
     /// Recompute the mass-properties of this rigid-bodies based on its currently attached colliders.
     pub fn recompute_mass_properties_from_colliders(&mut self, colliders: &ColliderSet) {
         self.mprops.recompute_mass_properties_from_colliders(
@@ -464,6 +515,10 @@ impl RigidBody {
     }
 
     /// The handles of colliders attached to this rigid body.
+    ///
+    /// This list is stored by the rigid-body and kept in sync as colliders are attached to it,
+    /// detached from it, or removed through the [`ColliderSet`], so reading it doesn’t require
+    /// going through the whole collider set.
     pub fn colliders(&self) -> &[ColliderHandle] {
         &self.colliders.0[..]
     }
@@ -505,6 +560,33 @@ impl RigidBody {
         &self.pos.next_position
     }
 
+    /// The motion applied by the constraints solver to this rigid-body during the last timestep
+    /// in order to correct penetrations and joint drift.
+    ///
+    /// This is the difference between the position computed by the solver, and the position
+    /// that would have been reached by integrating the final velocity of the rigid-body. Its
+    /// translational part is the displacement of the center-of-mass, and its rotational part
+    /// is the rotation applied around the center-of-mass.
+    ///
+    /// This is reset to the identity at the beginning of each timestep, and is only modified
+    /// for the dynamic rigid-bodies handled by the solver (i.e. neither sleeping, nor part
+    /// of a multibody).
+    pub fn position_correction(&self) -> &Isometry<Real> {
+        &self.position_correction
+    }
+
+    /// Accumulates into `self.position_correction` the difference between `self.pos.next_position`
+    /// and the position reached by integrating the current velocities during `dt`.
+    pub(crate) fn accumulate_position_correction(&mut self, dt: Real) {
+        let local_com = &self.mprops.local_mprops.local_com;
+        let uncorrected = self.vels.integrate(dt, &self.pos.position, local_com);
+        let translation = self.pos.next_position * local_com - uncorrected * local_com;
+        let rotation = self.pos.next_position.rotation * uncorrected.rotation.inverse();
+
+        self.position_correction.translation.vector += translation;
+        self.position_correction.rotation = rotation * self.position_correction.rotation;
+    }
+
     /// The scale factor applied to the gravity affecting this rigid-body.
     pub fn gravity_scale(&self) -> Real {
         self.forces.gravity_scale
@@ -535,6 +617,67 @@ impl RigidBody {
         }
     }
 
+    /// The maximum amount of penetration the constraints solver will attempt to resolve in one
+    /// timestep for the contacts involving this rigid-body.
+    ///
+    /// The value actually used for a contact is the smallest between this value, the one of the
+    /// other rigid-body involved, and [`IntegrationParameters::max_penetration_correction`].
+    ///
+    /// [`IntegrationParameters::max_penetration_correction`]: crate::dynamics::IntegrationParameters::max_penetration_correction
+    pub fn max_penetration_correction(&self) -> Real {
+        self.max_penetration_correction
+    }
+
+    /// Sets the maximum amount of penetration the constraints solver will attempt to resolve in
+    /// one timestep for the contacts involving this rigid-body.
+    ///
+    /// A small value makes this rigid-body ease out of deep penetrations (e.g. if it was spawned
+    /// inside of another collider) instead of being ejected violently. Contacts with a
+    /// penetration smaller than this value are not affected. Set it to `Real::MAX` (the default)
+    /// to only rely on [`IntegrationParameters::max_penetration_correction`].
+    ///
+    /// [`IntegrationParameters::max_penetration_correction`]: crate::dynamics::IntegrationParameters::max_penetration_correction
+    pub fn set_max_penetration_correction(&mut self, max_correction: Real) {
+        self.max_penetration_correction = max_correction;
+    }
+
+    /// The minimum number of velocity iterations the constraints solver runs for the island
+    /// containing this rigid-body.
+    pub fn min_solver_iterations(&self) -> usize {
+        self.min_solver_iterations
+    }
+
+    /// Sets the minimum number of velocity iterations the constraints solver runs for the island
+    /// containing this rigid-body.
+    ///
+    /// Each island is solved with the largest number of iterations between
+    /// [`IntegrationParameters::max_velocity_iterations`](crate::dynamics::IntegrationParameters::max_velocity_iterations)
+    /// and the minimum number of iterations of all its rigid-bodies. This allows spending more
+    /// iterations on the islands needing them (e.g. a tall stack) without slowing down the
+    /// other ones. The default, zero, only relies on the global number of iterations.
+    pub fn set_min_solver_iterations(&mut self, min_iterations: usize) {
+        self.min_solver_iterations = min_iterations;
+    }
+
+    /// The pose this rigid-body is pulled toward, if any.
+    pub fn position_target(&self) -> Option<&RigidBodyPositionTarget> {
+        self.position_target.as_ref()
+    }
+
+    /// Sets the pose this rigid-body is pulled toward, or `None` to stop pulling it.
+    ///
+    /// This only affects dynamic rigid-bodies: the forces pulling the rigid-body toward its
+    /// target are recomputed at each timestep, and added to its other forces for that timestep
+    /// only. See [`RigidBodyPositionTarget`] for details. Because a rigid-body resting at its
+    /// target may fall asleep, set `wake_up` to `true` when moving the target.
+    pub fn set_position_target(&mut self, target: Option<RigidBodyPositionTarget>, wake_up: bool) {
+        self.position_target = target;
+
+        if wake_up && target.is_some() {
+            self.wake_up(true);
+        }
+    }
+
     /// Adds a collider to this rigid-body.
     // TODO ECS: we keep this public for now just to simply our experiments on bevy_rapier.
     pub fn add_collider(
@@ -588,6 +731,25 @@ impl RigidBody {
         self.activation.wake_up(strong);
     }
 
+    /// Can this rigid-body fall asleep when it reaches a dynamic equilibrium?
+    pub fn can_sleep(&self) -> bool {
+        self.activation.can_sleep()
+    }
+
+    /// Sets whether this rigid-body can fall asleep when it reaches a dynamic equilibrium.
+    ///
+    /// A rigid-body that can’t sleep is woken up if it is sleeping. It also keeps awake the
+    /// rigid-bodies it is in contact with or attached to by a joint (since they interact with
+    /// it), but the other rigid-bodies can still fall asleep. Sleeping can also be prevented
+    /// right from the creation of the rigid-body with [`RigidBodyBuilder::can_sleep`].
+    pub fn set_can_sleep(&mut self, can_sleep: bool) {
+        self.activation.set_can_sleep(can_sleep);
+
+        if !can_sleep {
+            self.wake_up(true);
+        }
+    }
+
     /// Is this rigid body sleeping?
     pub fn is_sleeping(&self) -> bool {
         // TODO: should we:
@@ -602,6 +764,36 @@ impl RigidBody {
         !self.vels.linvel.is_zero() || !self.vels.angvel.is_zero()
     }
 
+    /// The linear and angular velocities of this rigid-body.
+    pub fn velocity(&self) -> RigidBodyVelocity {
+        self.vels
+    }
+
+    /// Sets the linear and angular velocities of this rigid-body.
+    ///
+    /// This does nothing on fixed and position-based kinematic bodies. On dynamic bodies, the
+    /// components along the locked axes are ignored.
+    ///
+    /// If `wake_up` is `true` then the rigid-body will be woken up if it was
+    /// put to sleep because it did not move for a while.
+    pub fn set_velocity(&mut self, vels: RigidBodyVelocity, wake_up: bool) {
+        if self.vels != vels {
+            match self.body_type {
+                RigidBodyType::Dynamic => {
+                    self.vels = vels;
+                    self.vels.remove_locked_components(self.mprops.flags);
+                    if wake_up {
+                        self.wake_up(true)
+                    }
+                }
+                RigidBodyType::KinematicVelocityBased => {
+                    self.vels = vels;
+                }
+                RigidBodyType::Fixed | RigidBodyType::KinematicPositionBased => {}
+            }
+        }
+    }
+
     /// The linear velocity of this rigid-body.
     pub fn linvel(&self) -> &Vector<Real> {
         &self.vels.linvel
@@ -621,6 +813,8 @@ impl RigidBody {
 
     /// The linear velocity of this rigid-body.
     ///
+    /// On dynamic bodies, the components along the locked axes are ignored.
+    ///
     /// If `wake_up` is `true` then the rigid-body will be woken up if it was
     /// put to sleep because it did not move for a while.
     pub fn set_linvel(&mut self, linvel: Vector<Real>, wake_up: bool) {
@@ -628,6 +822,7 @@ impl RigidBody {
             match self.body_type {
                 RigidBodyType::Dynamic => {
                     self.vels.linvel = linvel;
+                    self.vels.remove_locked_components(self.mprops.flags);
                     if wake_up {
                         self.wake_up(true)
                     }
@@ -642,6 +837,8 @@ impl RigidBody {
 
     /// The angular velocity of this rigid-body.
     ///
+    /// On dynamic bodies, the components along the locked axes are ignored.
+    ///
     /// If `wake_up` is `true` then the rigid-body will be woken up if it was
     /// put to sleep because it did not move for a while.
     #[cfg(feature = "dim2")]
@@ -650,6 +847,7 @@ impl RigidBody {
             match self.body_type {
                 RigidBodyType::Dynamic => {
                     self.vels.angvel = angvel;
+                    self.vels.remove_locked_components(self.mprops.flags);
                     if wake_up {
                         self.wake_up(true)
                     }
@@ -664,6 +862,8 @@ impl RigidBody {
 
     /// The angular velocity of this rigid-body.
     ///
+    /// On dynamic bodies, the components along the locked axes are ignored.
+    ///
     /// If `wake_up` is `true` then the rigid-body will be woken up if it was
     /// put to sleep because it did not move for a while.
     #[cfg(feature = "dim3")]
@@ -672,6 +872,7 @@ impl RigidBody {
             match self.body_type {
                 RigidBodyType::Dynamic => {
                     self.vels.angvel = angvel;
+                    self.vels.remove_locked_components(self.mprops.flags);
                     if wake_up {
                         self.wake_up(true)
                     }
@@ -765,6 +966,29 @@ impl RigidBody {
         }
     }
 
+    /// Teleports this rigid-body to the given position.
+    ///
+    /// This sets the position and `next_kinematic_position` of this rigid-body like
+    /// [`Self::set_position`]. In addition, the contacts of this rigid-body and the impulses of
+    /// the impulse joints attached to it are reset during the next timestep, so the rigid-body
+    /// doesn’t inherit contacts or impulses computed at its previous location. The multibody
+    /// joints aren’t reset though: teleporting a link of a multibody isn’t supported.
+    ///
+    /// If `reset_velocity` is `true` then the linear and angular velocities of this rigid-body
+    /// are set to zero.
+    ///
+    /// If `wake_up` is `true` then the rigid-body will be woken up if it was
+    /// put to sleep because it did not move for a while.
+    pub fn teleport(&mut self, pos: Isometry<Real>, reset_velocity: bool, wake_up: bool) {
+        self.set_position(pos, wake_up);
+        self.changes
+            .insert(RigidBodyChanges::POSITION | RigidBodyChanges::TELEPORTED);
+
+        if reset_velocity {
+            self.vels = RigidBodyVelocity::zero();
+        }
+    }
+
     /// If this rigid body is kinematic, sets its future translation after the next timestep integration.
     pub fn set_next_kinematic_rotation(&mut self, rotation: Rotation<Real>) {
         if self.is_kinematic() {
@@ -780,12 +1004,34 @@ impl RigidBody {
     }
 
     /// If this rigid body is kinematic, sets its future position after the next timestep integration.
+    ///
+    /// This is the way to drive a body from an external source (e.g. an animation system): push
+    /// its target position before each step and the physics pipeline derives the velocity needed
+    /// to reach it during that step. For position-based kinematic bodies, this velocity is used by
+    /// the contacts, so dynamic bodies resting on it are carried along (through friction) while
+    /// the kinematic body itself is never pushed back.
     pub fn set_next_kinematic_position(&mut self, pos: Isometry<Real>) {
         if self.is_kinematic() {
             self.pos.next_position = pos;
         }
     }
 
+    /// Predicts the position of this rigid-body after a time of `dt`, by integrating its current
+    /// velocity, ignoring forces, collisions, and joints.
+    ///
+    /// This uses the same integration as the physics pipeline: the damping is applied to the
+    /// velocity first, then the rotation is integrated around the center-of-mass with the
+    /// exponential map of `angvel * dt` (not a first-order approximation). As a result, the
+    /// position predicted for a free rigid-body without any force matches exactly the position
+    /// it reaches after a timestep of length `dt`.
+    pub fn predict_position_using_velocity(&self, dt: Real) -> Isometry<Real> {
+        self.vels.apply_damping(dt, &self.damping).integrate(
+            dt,
+            &self.pos.position,
+            &self.mprops.local_mprops.local_com,
+        )
+    }
+
     /// Predicts the next position of this rigid-body, by integrating its velocity and forces
     /// by a time of `dt`.
     pub fn predict_position_using_velocity_and_forces(&self, dt: Real) -> Isometry<Real> {
@@ -945,6 +1191,71 @@ impl RigidBody {
         self.apply_torque_impulse(torque_impulse, wake_up);
     }
 
+    /// Adds an impulse of an external constraint, applied at the center-of-mass of this
+    /// rigid-body by the constraints solver during the next timestep.
+    ///
+    /// Unlike [`Self::apply_impulse`], the velocity isn’t modified right away: the impulse is
+    /// converted into a force of `impulse / dt`, added to gravity and the other forces the next
+    /// time this rigid-body is simulated. The contacts and joints are then solved with this
+    /// force already applied, so they can push back against it during the same timestep, and
+    /// restitution isn’t triggered by it. This is useful for constraints implemented outside of
+    /// Rapier (e.g. inverse kinematics). The impulses added during the same timestep are
+    /// accumulated, and are cleared once applied. This does nothing on non-dynamic bodies.
+    pub fn add_constraint_impulse(&mut self, impulse: Vector<Real>, wake_up: bool) {
+        if !impulse.is_zero() && self.body_type == RigidBodyType::Dynamic {
+            self.forces.constraint_impulse += impulse;
+
+            if wake_up {
+                self.wake_up(true);
+            }
+        }
+    }
+
+    /// Adds an angular impulse of an external constraint, applied by the constraints solver
+    /// during the next timestep.
+    ///
+    /// See [`Self::add_constraint_impulse`] for details.
+    #[cfg(feature = "dim2")]
+    pub fn add_constraint_torque_impulse(&mut self, torque_impulse: Real, wake_up: bool) {
+        if !torque_impulse.is_zero() && self.body_type == RigidBodyType::Dynamic {
+            self.forces.constraint_torque_impulse += torque_impulse;
+
+            if wake_up {
+                self.wake_up(true);
+            }
+        }
+    }
+
+    /// Adds an angular impulse of an external constraint, applied by the constraints solver
+    /// during the next timestep.
+    ///
+    /// See [`Self::add_constraint_impulse`] for details.
+    #[cfg(feature = "dim3")]
+    pub fn add_constraint_torque_impulse(&mut self, torque_impulse: Vector<Real>, wake_up: bool) {
+        if !torque_impulse.is_zero() && self.body_type == RigidBodyType::Dynamic {
+            self.forces.constraint_torque_impulse += torque_impulse;
+
+            if wake_up {
+                self.wake_up(true);
+            }
+        }
+    }
+
+    /// Adds an impulse of an external constraint, applied at the given world-space point of this
+    /// rigid-body by the constraints solver during the next timestep.
+    ///
+    /// See [`Self::add_constraint_impulse`] for details.
+    pub fn add_constraint_impulse_at_point(
+        &mut self,
+        impulse: Vector<Real>,
+        point: Point<Real>,
+        wake_up: bool,
+    ) {
+        let torque_impulse = (point - self.mprops.world_com).gcross(impulse);
+        self.add_constraint_impulse(impulse, wake_up);
+        self.add_constraint_torque_impulse(torque_impulse, wake_up);
+    }
+
     /// Retrieves the constant force(s) that the user has added to the body.
     ///
     /// Returns zero if the rigid-body isn’t dynamic.
@@ -966,15 +1277,98 @@ impl RigidBody {
             AngVector::zero()
         }
     }
+
+    /// The total external force applied to this rigid-body at the next timestep, if the
+    /// `gravity` given to the physics pipeline doesn’t change.
+    ///
+    /// This is the sum of the forces added by the user and of the gravity force (taking the
+    /// gravity scale and the locked translations into account). It doesn’t include the forces
+    /// applied by the contacts and joints. Returns zero if the rigid-body isn’t dynamic.
+    pub fn external_force(&self, gravity: &Vector<Real>) -> Vector<Real> {
+        if self.body_type == RigidBodyType::Dynamic {
+            self.forces.user_force
+                + gravity.component_mul(&self.mprops.effective_mass()) * self.forces.gravity_scale
+        } else {
+            Vector::zeros()
+        }
+    }
+
+    /// The total external torque applied to this rigid-body at the next timestep.
+    ///
+    /// This doesn’t include the torques applied by the contacts and joints. Returns zero if
+    /// the rigid-body isn’t dynamic.
+    pub fn external_torque(&self) -> AngVector<Real> {
+        self.user_torque()
+    }
+
+    /// The angular impulse discarded by the rotation locking of this rigid-body during the last
+    /// timestep.
+    ///
+    /// This is the angular impulse, relative to the center of mass, that the contacts and the user
+    /// torques applied to this rigid-body during the last timestep along its locked rotation axes.
+    /// The rotation locking is exact: this impulse is entirely discarded by the solver. It is zero
+    /// along the rotation axes that aren’t locked, and doesn’t include the impulses of joints.
+    pub fn locked_angular_impulse(&self) -> AngVector<Real> {
+        self.locked_angular_impulse
+    }
+
+    /// The angular velocity this rigid-body would have had at the end of the last timestep if its
+    /// rotations weren’t locked.
+    ///
+    /// This is the current angular velocity, plus the angular velocity change that
+    /// [`RigidBody::locked_angular_impulse`] would have caused based on the angular inertia of the
+    /// rigid-body ignoring its rotation locking. This can be used to drive a cosmetic spin of a
+    /// rigid-body with locked rotations. This equals its angular velocity if its rotations aren’t
+    /// locked.
+    pub fn unlocked_angvel(&self) -> AngVector<Real> {
+        let inv_inertia_sqrt = self
+            .mprops
+            .local_mprops
+            .world_inv_inertia_sqrt(&self.pos.position.rotation);
+        self.vels.angvel
+            + inv_inertia_sqrt
+                .squared()
+                .transform_vector(self.locked_angular_impulse)
+    }
+
+    /// Are the external forces applied to this rigid-body integrated by the user instead of the
+    /// physics pipeline?
+    pub fn is_force_integration_external(&self) -> bool {
+        self.forces.integrated_by_user
+    }
+
+    /// Sets whether the external forces applied to this rigid-body are integrated by the user
+    /// instead of the physics pipeline.
+    ///
+    /// If `true`, the physics pipeline no longer applies the gravity nor the forces and torques
+    /// added by the user to this rigid-body. The constraints (contacts and joints) are still
+    /// solved. A custom integrator can then read [`Self::external_force`] and
+    /// [`Self::external_torque`], together with [`Self::mass_properties`], integrate them, and
+    /// write the resulting velocity back with [`Self::set_velocity`] before each timestep,
+    /// without the gravity being applied twice. Write the velocity back with `wake_up` set to
+    /// `false` and skip the sleeping rigid-bodies, so they can fall asleep and stay asleep.
+    pub fn set_force_integration_external(&mut self, external: bool) {
+        self.forces.integrated_by_user = external;
+    }
 }
 
 impl RigidBody {
     /// The velocity of the given world-space point on this rigid-body.
+    ///
+    /// This is computed as `linvel + angvel × (point - center_of_mass)` using the current
+    /// world-space center of mass of this rigid-body. In 2D, the angular velocity is a scalar
+    /// and the cross product yields the velocity orthogonal to `point - center_of_mass`.
     pub fn velocity_at_point(&self, point: &Point<Real>) -> Vector<Real> {
         self.vels.velocity_at_point(point, &self.mprops.world_com)
     }
 
     /// The kinetic energy of this body.
+    ///
+    /// This is the sum of its translational energy, computed from the velocity of its center of
+    /// mass, and of its rotational energy, computed from its world-space angular inertia (a scalar
+    /// in 2D, and a tensor in 3D). The angular inertia along locked rotation axes is ignored.
+    ///
+    /// Use [`IslandManager::island_kinetic_energy`](crate::dynamics::IslandManager::island_kinetic_energy) for the kinetic energy of a whole island.
     pub fn kinetic_energy(&self) -> Real {
         self.vels.kinetic_energy(&self.mprops)
     }
@@ -1019,12 +1413,21 @@ pub struct RigidBodyBuilder {
     pub can_sleep: bool,
     /// Whether or not the rigid-body is to be created asleep.
     pub sleeping: bool,
+    /// Whether or not only the linear velocity of the rigid-body to be created is taken into
+    /// account to decide if it can sleep.
+    pub sleep_ignores_angular_velocity: bool,
     /// Whether continuous collision-detection is enabled for the rigid-body to be built.
     ///
     /// CCD prevents tunneling, but may still allow limited interpenetration of colliders.
     pub ccd_enabled: bool,
     /// The dominance group of the rigid-body to be built.
     pub dominance_group: i8,
+    /// The maximum amount of penetration the solver will attempt to resolve in one timestep for
+    /// the contacts of the rigid-body to be built.
+    pub max_penetration_correction: Real,
+    /// The minimum number of velocity iterations of the island containing the rigid-body to
+    /// be built.
+    pub min_solver_iterations: usize,
     /// Will the rigid-body being built be enabled?
     pub enabled: bool,
     /// An arbitrary user-defined 128-bit integer associated to the rigid-bodies built by this builder.
@@ -1046,8 +1449,11 @@ impl RigidBodyBuilder {
             additional_mass_properties: RigidBodyAdditionalMassProps::default(),
             can_sleep: true,
             sleeping: false,
+            sleep_ignores_angular_velocity: false,
             ccd_enabled: false,
             dominance_group: 0,
+            max_penetration_correction: Real::MAX,
+            min_solver_iterations: 0,
             enabled: true,
             user_data: 0,
         }
@@ -1101,6 +1507,23 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets the maximum amount of penetration the solver will attempt to resolve in one timestep
+    /// for the contacts of this rigid-body.
+    ///
+    /// See [`RigidBody::set_max_penetration_correction`] for details.
+    pub fn max_penetration_correction(mut self, max_correction: Real) -> Self {
+        self.max_penetration_correction = max_correction;
+        self
+    }
+
+    /// Sets the minimum number of velocity iterations of the island containing this rigid-body.
+    ///
+    /// See [`RigidBody::set_min_solver_iterations`] for details.
+    pub fn min_solver_iterations(mut self, min_iterations: usize) -> Self {
+        self.min_solver_iterations = min_iterations;
+        self
+    }
+
     /// Sets the initial translation of the rigid-body to be created.
     pub fn translation(mut self, translation: Vector<Real>) -> Self {
         self.position.translation.vector = translation;
@@ -1164,6 +1587,8 @@ impl RigidBodyBuilder {
     }
 
     /// Sets the axes along which this rigid-body cannot translate or rotate.
+    ///
+    /// See [`RigidBody::set_locked_axes`] for details.
     pub fn locked_axes(mut self, locked_axes: LockedAxes) -> Self {
         self.mprops_flags = locked_axes;
         self
@@ -1281,6 +1706,15 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets whether or not only the linear velocity of the rigid-body to be created is taken into
+    /// account to decide if it can sleep.
+    ///
+    /// See [`RigidBodyActivation::ignore_angular_velocity`].
+    pub fn sleep_ignores_angular_velocity(mut self, ignore: bool) -> Self {
+        self.sleep_ignores_angular_velocity = ignore;
+        self
+    }
+
     /// Sets whether or not continuous collision-detection is enabled for this rigid-body.
     ///
     /// CCD prevents tunneling, but may still allow limited interpenetration of colliders.
@@ -1319,20 +1753,28 @@ impl RigidBodyBuilder {
         }
 
         rb.mprops.flags = self.mprops_flags;
+
+        if rb.is_dynamic() {
+            rb.vels.remove_locked_components(self.mprops_flags);
+        }
+
         rb.damping.linear_damping = self.linear_damping;
         rb.damping.angular_damping = self.angular_damping;
         rb.forces.gravity_scale = self.gravity_scale;
         rb.dominance = RigidBodyDominance(self.dominance_group);
+        rb.max_penetration_correction = self.max_penetration_correction;
+        rb.min_solver_iterations = self.min_solver_iterations;
         rb.enabled = self.enabled;
         rb.enable_ccd(self.ccd_enabled);
 
+        rb.activation.ignore_angular_velocity = self.sleep_ignores_angular_velocity;
+
         if self.can_sleep && self.sleeping {
             rb.sleep();
         }
 
         if !self.can_sleep {
-            rb.activation.linear_threshold = -1.0;
-            rb.activation.angular_threshold = -1.0;
+            rb.activation.set_can_sleep(false);
         }
 
         rb
@@ -1344,3 +1786,668 @@ impl Into<RigidBody> for RigidBodyBuilder {
         self.build()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{
+        IntegrationParameters, IslandManager, LockedAxes, RigidBody, RigidBodyBuilder,
+        RigidBodySet, RigidBodyType, RigidBodyVelocity,
+    };
+    use crate::geometry::{ColliderBuilder, ColliderSet};
+    use crate::math::{AngVector, Isometry, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    fn moving_body() -> RigidBody {
+        #[cfg(feature = "dim2")]
+        let angvel = 1.0;
+        #[cfg(feature = "dim3")]
+        let angvel = Vector::repeat(1.0);
+        RigidBodyBuilder::dynamic()
+            .linvel(Vector::repeat(1.0))
+            .angvel(angvel)
+            .build()
+    }
+
+    fn angvel(rb: &RigidBody) -> AngVector<Real> {
+        #[cfg(feature = "dim2")]
+        return rb.angvel();
+        #[cfg(feature = "dim3")]
+        return *rb.angvel();
+    }
+
+    #[test]
+    fn locking_axes_removes_their_velocity() {
+        let mut rb = moving_body();
+        rb.lock_translations(true, true);
+        assert_eq!(rb.linvel(), &Vector::zeros());
+        assert_eq!(angvel(&rb), angvel(&moving_body()));
+        rb.lock_translations(false, true);
+        assert_eq!(rb.locked_axes(), LockedAxes::empty());
+
+        let mut rb = moving_body();
+        rb.lock_rotations(true, true);
+        assert_eq!(rb.linvel(), moving_body().linvel());
+        assert_eq!(angvel(&rb), na::zero::<AngVector<Real>>());
+        rb.lock_rotations(false, true);
+        assert_eq!(rb.locked_axes(), LockedAxes::empty());
+
+        let mut rb = moving_body();
+        #[cfg(feature = "dim2")]
+        rb.set_enabled_translations(false, true, true);
+        #[cfg(feature = "dim3")]
+        rb.set_enabled_translations(false, true, true, true);
+        assert_eq!(rb.linvel().x, 0.0);
+        assert_eq!(rb.linvel().y, 1.0);
+
+        let mut rb = moving_body();
+        rb.set_enabled_rotations(true, true, false, true);
+        #[cfg(feature = "dim2")]
+        assert_eq!(angvel(&rb), 0.0);
+        #[cfg(feature = "dim3")]
+        assert_eq!(angvel(&rb), Vector::new(1.0, 1.0, 0.0));
+
+        let mut rb = moving_body();
+        rb.set_locked_axes(LockedAxes::TRANSLATION_LOCKED_Y, true);
+        assert_eq!(rb.linvel().x, 1.0);
+        assert_eq!(rb.linvel().y, 0.0);
+    }
+
+    #[test]
+    fn unfreezing_a_body_restores_or_zeroes_its_velocity() {
+        for (restore_velocity, new_type) in [
+            (true, RigidBodyType::Dynamic),
+            (true, RigidBodyType::KinematicVelocityBased),
+            (false, RigidBodyType::Dynamic),
+        ] {
+            let mut rb = moving_body();
+            rb.set_body_type_with_velocity(RigidBodyType::Fixed, true, restore_velocity);
+            assert_eq!(rb.linvel(), &Vector::zeros());
+            assert_eq!(angvel(&rb), na::zero::<AngVector<Real>>());
+
+            rb.set_body_type_with_velocity(new_type, true, restore_velocity);
+            assert_eq!(rb.body_type(), new_type);
+
+            if restore_velocity {
+                assert_eq!(rb.linvel(), moving_body().linvel());
+                assert_eq!(angvel(&rb), angvel(&moving_body()));
+            } else {
+                assert_eq!(rb.linvel(), &Vector::zeros());
+                assert_eq!(angvel(&rb), na::zero::<AngVector<Real>>());
+            }
+        }
+
+        // Switching between non-fixed types doesn't change the velocity.
+        let mut rb = moving_body();
+        rb.set_body_type_with_velocity(RigidBodyType::KinematicVelocityBased, true, true);
+        assert_eq!(rb.linvel(), moving_body().linvel());
+    }
+
+    /// Simulates the rigid-bodies of the world, integrating the external forces of the rigid-bodies
+    /// with external force integration like a custom integrator would, unless they sleep.
+    fn step_with_external_integration(world: &mut TestWorld, num_steps: usize) {
+        for _ in 0..num_steps {
+            for (_, rb) in world.bodies.iter_mut() {
+                if rb.is_force_integration_external() && !rb.is_sleeping() {
+                    let linvel = rb.linvel()
+                        + rb.external_force(&world.gravity) * (world.params.dt / rb.mass());
+                    let vels = RigidBodyVelocity::new(linvel, rb.vels.angvel);
+                    rb.set_velocity(vels, false);
+                }
+            }
+
+            world.step();
+        }
+    }
+
+    #[test]
+    fn external_force_integration_doesnt_apply_gravity_twice() {
+        let mut world = TestWorld::new();
+        let mut falling_bodies = vec![];
+
+        for external in [false, true] {
+            let mut rb = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * falling_bodies.len() as Real * 10.0)
+                .build();
+            rb.set_force_integration_external(external);
+            falling_bodies.push(world.insert(rb, ColliderBuilder::ball(0.5)).0);
+        }
+
+        step_with_external_integration(&mut world, 30);
+
+        let bodies = &world.bodies;
+        let (internal, external) = (&bodies[falling_bodies[0]], &bodies[falling_bodies[1]]);
+        assert!(internal.linvel().y < -4.0);
+        assert!((internal.linvel() - external.linvel()).norm() < 1.0e-4);
+        assert!((internal.translation().y - external.translation().y).abs() < 1.0e-4);
+    }
+
+    #[test]
+    fn bodies_with_external_force_integration_fall_asleep() {
+        let mut world = TestWorld::new();
+        world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+
+        let mut rb = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * 0.5)
+            .build();
+        rb.set_force_integration_external(true);
+        let (handle, _) = world.insert(rb, ColliderBuilder::ball(0.5));
+
+        step_with_external_integration(&mut world, 300);
+        assert!(world.bodies[handle].is_sleeping());
+
+        // The sleeping body isn't woken up, nor moved, by its external force.
+        let translation = *world.bodies[handle].translation();
+        assert_ne!(
+            world.bodies[handle].external_force(&world.gravity),
+            Vector::zeros()
+        );
+        step_with_external_integration(&mut world, 100);
+        assert!(world.bodies[handle].is_sleeping());
+        assert_eq!(*world.bodies[handle].translation(), translation);
+        assert!((translation.y - 0.5).abs() < 1.0e-2);
+    }
+
+    #[test]
+    fn teleported_bodies_dont_inherit_their_contacts() {
+        for reset_velocity in [false, true] {
+            let mut world = TestWorld::new();
+            let ground = world
+                .colliders
+                .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+            let body = RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5);
+            let (body, ball) = world.insert(body, ColliderBuilder::ball(0.5));
+
+            for _ in 0..20 {
+                world.step();
+            }
+            assert!(world.narrow_phase.contact_pair(ground, ball).is_some());
+
+            let linvel = *world.bodies[body].linvel();
+            #[cfg(feature = "dim2")]
+            let far_away = Isometry::translation(0.0, 10.0);
+            #[cfg(feature = "dim3")]
+            let far_away = Isometry::translation(0.0, 10.0, 0.0);
+            world.bodies[body].teleport(far_away, reset_velocity, true);
+            world.step();
+
+            // Only gravity is applied, without any contact impulse from the ground.
+            let gravity_dvel = world.gravity * world.params.dt;
+            let expected = if reset_velocity {
+                gravity_dvel
+            } else {
+                linvel + gravity_dvel
+            };
+            assert!((world.bodies[body].linvel() - expected).norm() < 1.0e-5);
+            let pair = world.narrow_phase.contact_pair(ground, ball).unwrap();
+            assert!(!pair.has_any_active_contact);
+            assert!(pair.manifolds.iter().all(|m| m.points.is_empty()));
+        }
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn velocities_are_projected_onto_the_unlocked_axes() {
+        let mut rb = RigidBodyBuilder::dynamic()
+            .locked_axes(LockedAxes::PLANAR_XY)
+            .build();
+        let vels = RigidBodyVelocity {
+            linvel: Vector::new(1.0, 2.0, 3.0),
+            angvel: Vector::new(4.0, 5.0, 6.0),
+        };
+
+        rb.set_velocity(vels, true);
+        assert_eq!(*rb.linvel(), Vector::new(1.0, 2.0, 0.0));
+        assert_eq!(*rb.angvel(), Vector::new(0.0, 0.0, 6.0));
+
+        rb.set_linvel(Vector::new(3.0, 2.0, 1.0), true);
+        rb.set_angvel(Vector::new(6.0, 5.0, 4.0), true);
+        assert_eq!(*rb.linvel(), Vector::new(3.0, 2.0, 0.0));
+        assert_eq!(*rb.angvel(), Vector::new(0.0, 0.0, 4.0));
+
+        // The velocities of kinematic bodies aren't affected by the locked axes.
+        rb.set_body_type(RigidBodyType::KinematicVelocityBased, true);
+        rb.set_velocity(vels, true);
+        assert_eq!(rb.velocity(), vels);
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn kinetic_energy_ignores_locked_rotation_axes() {
+        let mut world = TestWorld::new();
+        world.gravity = Vector::zeros();
+
+        let rb = RigidBodyBuilder::dynamic()
+            .angvel(Vector::new(1.0, 2.0, 0.0))
+            .enabled_rotations(true, false, true);
+        let (handle, _) = world.insert(rb, ColliderBuilder::ball(0.5));
+
+        world.step();
+
+        // Only the rotation around the unlocked X axis contributes to the energy.
+        let rb = &world.bodies[handle];
+        assert_eq!(*rb.angvel(), Vector::x());
+        let inertia = rb.mass_properties().local_mprops.principal_inertia().x;
+        assert!((rb.kinetic_energy() - inertia / 2.0).abs() < 1.0e-6);
+        assert_eq!(
+            world.islands.island_kinetic_energy(&world.bodies, handle),
+            Some(rb.kinetic_energy())
+        );
+    }
+
+    #[test]
+    fn locked_rotations_report_the_discarded_spin() {
+        use crate::math::{AngVector, Rotation};
+        use num::Zero;
+
+        let mut world = TestWorld::new();
+
+        world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()).build());
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * 0.5)
+            .linvel(Vector::x() * 5.0)
+            .lock_rotations();
+        let body = world.bodies.insert(body);
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut world.bodies);
+
+        for _ in 0..5 {
+            world.step();
+        }
+
+        let rb = &world.bodies[body];
+        assert_eq!(rb.rotation(), &Rotation::identity());
+
+        // Friction at the bottom of the ball, opposite to its motion along +x, would make it
+        // roll clockwise around the z axis.
+        #[cfg(feature = "dim2")]
+        let (spin, locked_impulse) = (rb.unlocked_angvel(), rb.locked_angular_impulse());
+        #[cfg(feature = "dim3")]
+        let (spin, locked_impulse) = (rb.unlocked_angvel().z, rb.locked_angular_impulse().z);
+        assert!(locked_impulse < 0.0);
+        assert!(spin < 0.0);
+        assert_eq!(rb.vels.angvel, AngVector::zero());
+    }
+
+    #[test]
+    fn attached_colliders_stay_in_sync() {
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut islands = IslandManager::new();
+
+        let body1 = bodies.insert(RigidBodyBuilder::dynamic());
+        let body2 = bodies.insert(RigidBodyBuilder::dynamic());
+        let co1 = colliders.insert_with_parent(ColliderBuilder::ball(0.5), body1, &mut bodies);
+        let co2 = colliders.insert_with_parent(ColliderBuilder::ball(0.5), body1, &mut bodies);
+        let co3 = colliders.insert(ColliderBuilder::ball(0.5));
+        assert_eq!(bodies[body1].colliders(), &[co1, co2]);
+
+        colliders.set_parent(co3, Some(body1), &mut bodies);
+        colliders.set_parent(co1, Some(body2), &mut bodies);
+        let attached = bodies[body1].colliders();
+        assert!(attached.len() == 2 && attached.contains(&co2) && attached.contains(&co3));
+        assert_eq!(bodies[body2].colliders(), &[co1]);
+
+        colliders.remove(co2, &mut islands, &mut bodies, false);
+        colliders.set_parent(co1, None, &mut bodies);
+        assert_eq!(bodies[body1].colliders(), &[co3]);
+        assert!(bodies[body2].colliders().is_empty());
+    }
+
+    #[test]
+    fn ccd_motion_predicate_matches_the_pipeline() {
+        let mut world = TestWorld::new();
+        let params = IntegrationParameters {
+            max_ccd_substeps: 2,
+            ..IntegrationParameters::default()
+        };
+
+        let handles: Vec<_> = [(100.0, true), (0.01, true), (100.0, false)]
+            .iter()
+            .enumerate()
+            .map(|(i, (speed, ccd_enabled))| {
+                let body = RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * (i as crate::math::Real * 10.0))
+                    .linvel(Vector::x() * *speed)
+                    .gravity_scale(0.0)
+                    .ccd_enabled(*ccd_enabled);
+                let handle = world.bodies.insert(body);
+                world.colliders.insert_with_parent(
+                    ColliderBuilder::ball(0.1),
+                    handle,
+                    &mut world.bodies,
+                );
+                handle
+            })
+            .collect();
+
+        world.gravity = Vector::zeros();
+        world.params = params;
+        for _ in 0..2 {
+            let predicted: Vec<_> = handles
+                .iter()
+                .map(|h| world.bodies[*h].is_moving_fast_for_ccd(params.dt))
+                .collect();
+
+            world.step();
+
+            let active: Vec<_> = handles
+                .iter()
+                .map(|h| world.bodies[*h].is_ccd_active())
+                .collect();
+            // No solver velocities are known before the first step.
+            assert!(predicted == [false; 3] || predicted == active);
+            assert_eq!(active, [true, false, false]);
+        }
+
+        assert!(world.bodies[handles[0]].is_moving_fast_for_ccd(params.dt));
+    }
+
+    #[test]
+    #[cfg(feature = "dim3")]
+    fn driven_kinematic_bodies_carry_dynamic_bodies() {
+        use crate::math::Isometry;
+
+        let mut world = TestWorld::new();
+        let params = world.params;
+
+        let platform = world
+            .bodies
+            .insert(RigidBodyBuilder::kinematic_position_based().build());
+        world.colliders.insert_with_parent(
+            ColliderBuilder::cuboid(5.0, 0.5, 5.0).build(),
+            platform,
+            &mut world.bodies,
+        );
+        let cube = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y()).build());
+        world.colliders.insert_with_parent(
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5).build(),
+            cube,
+            &mut world.bodies,
+        );
+
+        let mut platform_x = 0.0;
+        for i in 0..200 {
+            if i >= 20 {
+                // Drive the platform by its positions only, as an animation system would.
+                platform_x += 0.02;
+                world.bodies[platform]
+                    .set_next_kinematic_position(Isometry::translation(platform_x, 0.0, 0.0));
+            }
+
+            world.step();
+
+            // The driven body is never pushed by the cube resting on it.
+            assert_eq!(
+                *world.bodies[platform].position(),
+                Isometry::translation(platform_x, 0.0, 0.0)
+            );
+        }
+
+        let expected_vel = 0.02 / params.dt;
+        assert!((world.bodies[platform].linvel().x - expected_vel).abs() < 1.0e-3);
+        // The cube is carried along with the platform.
+        assert!((world.bodies[cube].linvel().x - expected_vel).abs() < 1.0e-2);
+        assert!((world.bodies[cube].translation().x - platform_x).abs() < 0.2);
+        assert!((world.bodies[cube].translation().y - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn position_targets_pull_dynamic_bodies() {
+        use crate::dynamics::RigidBodyPositionTarget;
+        use crate::math::Isometry;
+
+        let mut world = TestWorld::new();
+
+        let held = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 2.0));
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), held, &mut world.bodies);
+        let obstacle = world.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 1.5 + Vector::y() * 2.1)
+                .gravity_scale(0.0),
+        );
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), obstacle, &mut world.bodies);
+        world
+            .colliders
+            .insert(ColliderBuilder::ball(1.0).translation(Vector::x() * 6.0 + Vector::y() * 2.0));
+
+        let run = |world: &mut TestWorld| {
+            for _ in 0..120 {
+                world.step();
+            }
+        };
+
+        // The target compensates the gravity.
+        let target = RigidBodyPositionTarget::new(Isometry::from(Vector::y() * 2.0));
+        world.bodies[held].set_position_target(Some(target), true);
+        run(&mut world);
+        assert!((world.bodies[held].translation() - Vector::y() * 2.0).norm() < 1.0e-2);
+
+        // The obstacle on the way is pushed aside.
+        let target = RigidBodyPositionTarget {
+            max_force: 20.0,
+            ..RigidBodyPositionTarget::new(Isometry::from(Vector::x() * 3.0 + Vector::y() * 2.0))
+        };
+        world.bodies[held].set_position_target(Some(target), true);
+        run(&mut world);
+        assert!(
+            (world.bodies[held].translation() - target.position.translation.vector).norm() < 1.0e-2
+        );
+        assert!(
+            (world.bodies[obstacle].translation() - Vector::x() * 1.5 - Vector::y() * 2.1).norm()
+                > 0.5
+        );
+
+        // A target inside of a fixed collider can’t be reached, and the body rests against it.
+        let target = RigidBodyPositionTarget {
+            max_force: 50.0,
+            ..RigidBodyPositionTarget::new(Isometry::from(Vector::x() * 6.0 + Vector::y() * 2.0))
+        };
+        world.bodies[held].set_position_target(Some(target), true);
+        run(&mut world);
+        let distance =
+            (world.bodies[held].translation() - target.position.translation.vector).norm();
+        assert!(distance > 1.4 && distance < 1.6);
+        assert!(world.bodies[held].linvel().norm() < 0.1);
+
+        world.bodies[held].set_position_target(None, true);
+        run(&mut world);
+        assert!(world.bodies[held].translation().y < 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "dim2")]
+    fn position_targets_handle_aligned_and_locked_rotations() {
+        use crate::dynamics::RigidBodyPositionTarget;
+        use crate::math::Isometry;
+
+        let mut world = TestWorld::new();
+
+        // Already aligned with its target: the angle error is zero.
+        let aligned = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 2.0));
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), aligned, &mut world.bodies);
+        // The angular inertia of a body with locked rotations is zero.
+        let locked = world.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 3.0)
+                .lock_rotations(),
+        );
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), locked, &mut world.bodies);
+        let free = world.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * -3.0)
+                .gravity_scale(0.0),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::cuboid(0.5, 0.5),
+            free,
+            &mut world.bodies,
+        );
+
+        let target = |x, y, angle| {
+            Some(RigidBodyPositionTarget::new(Isometry::new(
+                Vector::new(x, y),
+                angle,
+            )))
+        };
+        world.bodies[aligned].set_position_target(target(0.0, 2.0, 0.0), true);
+        world.bodies[locked].set_position_target(target(3.0, 2.0, 1.0), true);
+        world.bodies[free].set_position_target(target(-3.0, 0.0, 1.0), true);
+
+        for _ in 0..120 {
+            world.step();
+        }
+
+        assert!((world.bodies[aligned].translation() - Vector::y() * 2.0).norm() < 1.0e-2);
+        assert_eq!(world.bodies[aligned].rotation().angle(), 0.0);
+        assert!((world.bodies[locked].translation() - Vector::new(3.0, 2.0)).norm() < 1.0e-2);
+        assert_eq!(world.bodies[locked].rotation().angle(), 0.0);
+        assert!((world.bodies[free].rotation().angle() - 1.0).abs() < 1.0e-2);
+    }
+
+    #[test]
+    fn predicted_positions_match_the_integrator() {
+        let mut world = TestWorld::new();
+        let params = world.params;
+
+        #[cfg(feature = "dim2")]
+        let angvel = 3.0;
+        #[cfg(feature = "dim3")]
+        let angvel = Vector::new(1.0, -3.0, 2.0);
+        let body = RigidBodyBuilder::dynamic()
+            .linvel(Vector::x() * 2.0)
+            .angvel(angvel)
+            .linear_damping(0.5)
+            .angular_damping(0.2);
+        let body = world.bodies.insert(body);
+        // Offset the collider so the center-of-mass doesn’t match the body’s origin.
+        let collider = ColliderBuilder::ball(0.5).translation(Vector::y() * 1.0);
+        world
+            .colliders
+            .insert_with_parent(collider, body, &mut world.bodies);
+
+        world.gravity = Vector::zeros();
+        for _ in 0..10 {
+            let predicted = world.bodies[body].predict_position_using_velocity(params.dt);
+            world.step();
+            assert_eq!(world.bodies[body].position(), &predicted);
+        }
+    }
+
+    #[test]
+    fn bodies_that_cant_sleep_only_keep_their_neighbors_awake() {
+        let mut world = TestWorld::new();
+
+        world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+        let mut ball = |x, y| {
+            let body = RigidBodyBuilder::dynamic().translation(Vector::x() * x + Vector::y() * y);
+            let body = world.bodies.insert(body);
+            world
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut world.bodies);
+            body
+        };
+        let polled = ball(0.0, 0.5);
+        let stacked = ball(0.0, 1.5);
+        let alone = ball(10.0, 0.5);
+        world.bodies[polled].set_can_sleep(false);
+        assert!(!world.bodies[polled].can_sleep());
+
+        let step = |world: &mut TestWorld| {
+            for _ in 0..300 {
+                world.step();
+            }
+        };
+
+        step(&mut world);
+        assert!(!world.bodies[polled].is_sleeping());
+        assert!(!world.bodies[stacked].is_sleeping());
+        assert!(world.bodies[alone].is_sleeping());
+
+        world.bodies[polled].set_can_sleep(true);
+        step(&mut world);
+        assert!(world.bodies[polled].is_sleeping());
+        assert!(world.bodies[stacked].is_sleeping());
+    }
+
+    #[test]
+    fn position_corrections_are_reset_at_each_step() {
+        let mut world = TestWorld::new();
+        world.gravity = Vector::zeros();
+
+        world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+        let body = RigidBodyBuilder::dynamic().translation(Vector::y() * 0.4);
+        let (penetrating, _) = world.insert(body, ColliderBuilder::ball(0.5));
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::x() * 10.0 + Vector::y() * 5.0)
+            .linvel(Vector::x());
+        let (free, _) = world.insert(body, ColliderBuilder::ball(0.5));
+
+        world.step();
+        // Only the penetrating body is pushed out of the ground by the solver.
+        let correction = *world.bodies[penetrating].position_correction();
+        assert!(correction.translation.vector.y > 0.0);
+        assert!(correction.translation.vector.x.abs() < 1.0e-5);
+        assert_eq!(
+            world.bodies[free].position_correction(),
+            &Isometry::identity()
+        );
+
+        world.bodies[penetrating].set_translation(Vector::y() * 5.0, true);
+        world.bodies[penetrating].set_linvel(Vector::zeros(), true);
+        world.step();
+        assert_eq!(
+            world.bodies[penetrating].position_correction(),
+            &Isometry::identity()
+        );
+    }
+
+    #[test]
+    fn max_penetration_correction_only_slows_down_deep_recoveries() {
+        // The height gained by a ball spawned with the given penetration into the ground.
+        fn recovery(penetration: Real, max_correction: Real) -> Real {
+            let mut world = TestWorld::new();
+            world.gravity = Vector::zeros();
+            world
+                .colliders
+                .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * (0.5 - penetration))
+                .max_penetration_correction(max_correction);
+            let (body, _) = world.insert(body, ColliderBuilder::ball(0.5));
+
+            world.step();
+            world.bodies[body].translation().y - (0.5 - penetration)
+        }
+
+        let default = recovery(0.3, Real::MAX);
+        let capped = recovery(0.3, 0.01);
+        assert!(capped > 0.0);
+        assert!(capped < default / 10.0);
+
+        // Shallow penetrations aren't affected.
+        assert_eq!(recovery(0.005, 0.01), recovery(0.005, Real::MAX));
+    }
+}
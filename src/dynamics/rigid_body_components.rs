@@ -114,6 +114,9 @@ bitflags::bitflags! {
         const LOCAL_MASS_PROPERTIES = 1 << 6;
         /// Flag indicating that the rigid-body was enabled or disabled.
         const ENABLED_OR_DISABLED = 1 << 7;
+        /// Flag indicating that the rigid-body was teleported and that its contacts and the
+        /// impulses of its joints must be reset.
+        const TELEPORTED = 1 << 8;
     }
 }
 
@@ -223,6 +226,21 @@ bitflags::bitflags! {
         const ROTATION_LOCKED_Z = 1 << 5;
         /// Combination of flags indicating that the rigid-body cannot rotate along any axis.
         const ROTATION_LOCKED = Self::ROTATION_LOCKED_X.bits | Self::ROTATION_LOCKED_Y.bits | Self::ROTATION_LOCKED_Z.bits;
+        /// Combination of flags restricting the motion of the rigid-body to a plane orthogonal to the `X` axis.
+        ///
+        /// The rigid-body can only translate along the `Y` and `Z` axes, and rotate along the `X` axis.
+        #[cfg(feature = "dim3")]
+        const PLANAR_YZ = Self::TRANSLATION_LOCKED_X.bits | Self::ROTATION_LOCKED_Y.bits | Self::ROTATION_LOCKED_Z.bits;
+        /// Combination of flags restricting the motion of the rigid-body to a plane orthogonal to the `Y` axis.
+        ///
+        /// The rigid-body can only translate along the `X` and `Z` axes, and rotate along the `Y` axis.
+        #[cfg(feature = "dim3")]
+        const PLANAR_XZ = Self::TRANSLATION_LOCKED_Y.bits | Self::ROTATION_LOCKED_X.bits | Self::ROTATION_LOCKED_Z.bits;
+        /// Combination of flags restricting the motion of the rigid-body to a plane orthogonal to the `Z` axis.
+        ///
+        /// The rigid-body can only translate along the `X` and `Y` axes, and rotate along the `Z` axis.
+        #[cfg(feature = "dim3")]
+        const PLANAR_XY = Self::TRANSLATION_LOCKED_Z.bits | Self::ROTATION_LOCKED_X.bits | Self::ROTATION_LOCKED_Y.bits;
     }
 }
 
@@ -454,6 +472,41 @@ impl RigidBodyVelocity {
         }
     }
 
+    /// Sets to zero the components of these velocities along the given locked axes.
+    pub(crate) fn remove_locked_components(&mut self, locked_axes: LockedAxes) {
+        if locked_axes.contains(LockedAxes::TRANSLATION_LOCKED_X) {
+            self.linvel.x = 0.0;
+        }
+
+        if locked_axes.contains(LockedAxes::TRANSLATION_LOCKED_Y) {
+            self.linvel.y = 0.0;
+        }
+
+        #[cfg(feature = "dim2")]
+        if locked_axes.contains(LockedAxes::ROTATION_LOCKED_Z) {
+            self.angvel = 0.0;
+        }
+
+        #[cfg(feature = "dim3")]
+        {
+            if locked_axes.contains(LockedAxes::TRANSLATION_LOCKED_Z) {
+                self.linvel.z = 0.0;
+            }
+
+            if locked_axes.contains(LockedAxes::ROTATION_LOCKED_X) {
+                self.angvel.x = 0.0;
+            }
+
+            if locked_axes.contains(LockedAxes::ROTATION_LOCKED_Y) {
+                self.angvel.y = 0.0;
+            }
+
+            if locked_axes.contains(LockedAxes::ROTATION_LOCKED_Z) {
+                self.angvel.z = 0.0;
+            }
+        }
+    }
+
     /// Velocities set to zero.
     #[must_use]
     pub fn zero() -> Self {
@@ -587,11 +640,23 @@ impl RigidBodyVelocity {
         }
 
         #[cfg(feature = "dim3")]
-        if !rb_mprops.effective_world_inv_inertia_sqrt.is_zero() {
-            let inertia_sqrt = rb_mprops
-                .effective_world_inv_inertia_sqrt
-                .inverse_unchecked();
-            energy += (inertia_sqrt * self.angvel).norm_squared() / 2.0;
+        {
+            // The rows and columns of the locked rotation axes are zero, so the angular velocity
+            // along these axes doesn’t contribute to the energy. Only invert the other block.
+            let mut inv_inertia_sqrt =
+                rb_mprops.effective_world_inv_inertia_sqrt * na::Matrix3::identity();
+            let mut angvel = self.angvel;
+
+            for i in 0..3 {
+                if inv_inertia_sqrt[(i, i)] == 0.0 {
+                    inv_inertia_sqrt[(i, i)] = 1.0;
+                    angvel[i] = 0.0;
+                }
+            }
+
+            if let Some(inertia_sqrt) = inv_inertia_sqrt.try_inverse() {
+                energy += (inertia_sqrt * angvel).norm_squared() / 2.0;
+            }
         }
 
         energy
@@ -707,6 +772,15 @@ pub struct RigidBodyForces {
     pub user_force: Vector<Real>,
     /// Torque applied by the user.
     pub user_torque: AngVector<Real>,
+    /// If `true`, the external forces (including gravity) are not integrated by the physics
+    /// pipeline because the user integrates them.
+    pub integrated_by_user: bool,
+    /// Impulse of the external constraints, applied by the constraints solver at the next
+    /// timestep (only for dynamic bodies).
+    pub constraint_impulse: Vector<Real>,
+    /// Angular impulse of the external constraints, applied by the constraints solver at the
+    /// next timestep (only for dynamic bodies).
+    pub constraint_torque_impulse: AngVector<Real>,
 }
 
 impl Default for RigidBodyForces {
@@ -717,6 +791,9 @@ impl Default for RigidBodyForces {
             gravity_scale: 1.0,
             user_force: na::zero(),
             user_torque: na::zero(),
+            integrated_by_user: false,
+            constraint_impulse: na::zero(),
+            constraint_torque_impulse: na::zero(),
         }
     }
 }
@@ -742,13 +819,33 @@ impl RigidBodyForces {
 
     /// Adds to `self` the gravitational force that would result in a gravitational acceleration
     /// equal to `gravity`.
+    ///
+    /// Both the force and torque are set to zero if [`Self::integrated_by_user`] is `true`.
     pub fn compute_effective_force_and_torque(
         &mut self,
         gravity: &Vector<Real>,
         mass: &Vector<Real>,
     ) {
-        self.force = self.user_force + gravity.component_mul(&mass) * self.gravity_scale;
-        self.torque = self.user_torque;
+        if self.integrated_by_user {
+            self.force = na::zero();
+            self.torque = na::zero();
+        } else {
+            self.force = self.user_force + gravity.component_mul(&mass) * self.gravity_scale;
+            self.torque = self.user_torque;
+        }
+    }
+
+    /// Adds to `self.force` and `self.torque` the forces resulting in the constraint impulses
+    /// after a time `dt`, and resets the constraint impulses to zero.
+    ///
+    /// This lets the constraints solver apply the constraint impulses at the same time as the
+    /// external forces, i.e., before the resolution of the contacts and joints.
+    pub(crate) fn apply_constraint_impulses(&mut self, dt: Real) {
+        let inv_dt = crate::utils::inv(dt);
+        self.force += self.constraint_impulse * inv_dt;
+        self.torque += self.constraint_torque_impulse * inv_dt;
+        self.constraint_impulse = na::zero();
+        self.constraint_torque_impulse = na::zero();
     }
 
     /// Applies a force at the given world-space point of the rigid-body with the given mass properties.
@@ -763,6 +860,108 @@ impl RigidBodyForces {
     }
 }
 
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, PartialEq)]
+/// A pose a dynamic rigid-body is pulled toward by forces computed at each timestep.
+///
+/// Unlike a kinematic body, a rigid-body with a position target remains dynamic: it is still
+/// affected by gravity and contacts, and pushes the other dynamic bodies depending on its mass.
+/// The forces pulling it toward its target are limited by `max_force` and `max_torque`, so the
+/// rigid-body lags behind, or stops, when it is blocked instead of being pushed violently.
+pub struct RigidBodyPositionTarget {
+    /// The target world-space position of the rigid-body.
+    pub position: Isometry<Real>,
+    /// The fraction, between 0 and 1, of the remaining distance to the target the rigid-body
+    /// tries to cover at each timestep.
+    ///
+    /// A value of 1 makes the rigid-body reach its target in one timestep, if the force and
+    /// torque limits allow it. Smaller values result in smoother motions.
+    pub stiffness: Real,
+    /// The maximum magnitude of the force applied to pull the rigid-body toward its target.
+    ///
+    /// This includes the force needed to compensate the gravity and the other forces applied
+    /// to the rigid-body.
+    pub max_force: Real,
+    /// The maximum magnitude of the torque applied to rotate the rigid-body toward its target.
+    pub max_torque: Real,
+}
+
+impl RigidBodyPositionTarget {
+    /// A position target with the given pose, a stiffness of `0.5`, and no limit on the
+    /// force and torque.
+    pub fn new(position: Isometry<Real>) -> Self {
+        Self {
+            position,
+            stiffness: 0.5,
+            max_force: Real::MAX,
+            max_torque: Real::MAX,
+        }
+    }
+
+    /// Adds to `forces` the force and torque pulling the rigid-body toward this target
+    /// during the next timestep of length `dt`.
+    ///
+    /// The external forces of the rigid-body must have been computed already.
+    pub(crate) fn apply(
+        &self,
+        dt: Real,
+        pos: &RigidBodyPosition,
+        vels: &RigidBodyVelocity,
+        mprops: &RigidBodyMassProps,
+        forces: &mut RigidBodyForces,
+    ) {
+        // NOTE: the desired velocities are limited so that the rigid-body can still stop at
+        //       its target with the maximum force and torque. Otherwise, it would overshoot
+        //       and oscillate around its target.
+        let inv_dt = crate::utils::inv(dt);
+        let target_com = self.position * mprops.local_mprops.local_com;
+        let translation_error = target_com - mprops.world_com;
+        let max_linvel =
+            (self.max_force * mprops.local_mprops.inv_mass * translation_error.norm()).sqrt();
+        let desired_linvel =
+            (translation_error * self.stiffness * inv_dt).cap_magnitude(max_linvel);
+        let force = (desired_linvel - vels.linvel).component_mul(&mprops.effective_mass()) * inv_dt
+            - forces.force;
+        forces.force += force.cap_magnitude(self.max_force);
+
+        let angular_inertia = mprops.effective_angular_inertia();
+        let rotation_error = self.position.rotation * pos.position.rotation.inverse();
+        #[cfg(feature = "dim2")]
+        let desired_angvel = {
+            let angle = rotation_error.angle();
+            // NOTE: the rotation can’t be controlled if it is locked, and the limit below is NaN
+            //       for a zero angle if the angular inertia is zero or tiny.
+            if angle == 0.0 || mprops.effective_world_inv_inertia_sqrt == 0.0 {
+                0.0
+            } else {
+                let max_angvel = (self.max_torque / angular_inertia * angle.abs()).sqrt();
+                (angle * self.stiffness * inv_dt)
+                    .max(-max_angvel)
+                    .min(max_angvel)
+            }
+        };
+        #[cfg(feature = "dim3")]
+        let desired_angvel = match rotation_error.axis_angle() {
+            Some((axis, angle)) => {
+                let inertia = axis.dot(&angular_inertia.transform_vector(*axis));
+                let max_angvel = (self.max_torque / inertia * angle).sqrt();
+                (*axis * angle * self.stiffness * inv_dt).cap_magnitude(max_angvel)
+            }
+            None => AngVector::zeros(),
+        };
+        let torque = angular_inertia.transform_vector((desired_angvel - vels.angvel) * inv_dt)
+            - forces.torque;
+        #[cfg(feature = "dim2")]
+        {
+            forces.torque += torque.max(-self.max_torque).min(self.max_torque);
+        }
+        #[cfg(feature = "dim3")]
+        {
+            forces.torque += torque.cap_magnitude(self.max_torque);
+        }
+    }
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Copy, PartialEq)]
 /// Information used for Continuous-Collision-Detection.
@@ -978,6 +1177,11 @@ pub struct RigidBodyActivation {
     pub linear_threshold: Real,
     /// The angular linear velocity bellow which the body can fall asleep.
     pub angular_threshold: Real,
+    /// If `true`, only the linear velocity is compared to its threshold to decide if the body
+    /// can fall asleep, i.e., `angular_threshold` is ignored.
+    ///
+    /// This is `false` by default so that, e.g., a top spinning in place doesn’t fall asleep.
+    pub ignore_angular_velocity: bool,
     /// Since how much time can this body sleep?
     pub time_since_can_sleep: Real,
     /// Is this body sleeping?
@@ -1012,6 +1216,7 @@ impl RigidBodyActivation {
         RigidBodyActivation {
             linear_threshold: Self::default_linear_threshold(),
             angular_threshold: Self::default_angular_threshold(),
+            ignore_angular_velocity: false,
             time_since_can_sleep: 0.0,
             sleeping: false,
         }
@@ -1022,6 +1227,7 @@ impl RigidBodyActivation {
         RigidBodyActivation {
             linear_threshold: Self::default_linear_threshold(),
             angular_threshold: Self::default_angular_threshold(),
+            ignore_angular_velocity: false,
             sleeping: true,
             time_since_can_sleep: Self::default_time_until_sleep(),
         }
@@ -1036,6 +1242,38 @@ impl RigidBodyActivation {
         }
     }
 
+    /// Is the given squared linear velocity norm below the linear threshold?
+    #[inline]
+    pub fn is_below_linear_threshold(&self, sq_linvel: Real) -> bool {
+        sq_linvel < self.linear_threshold * self.linear_threshold.abs()
+    }
+
+    /// Is the given squared angular velocity norm below the angular threshold?
+    ///
+    /// This is always `true` if [`Self::ignore_angular_velocity`] is set.
+    #[inline]
+    pub fn is_below_angular_threshold(&self, sq_angvel: Real) -> bool {
+        self.ignore_angular_velocity
+            || sq_angvel < self.angular_threshold * self.angular_threshold.abs()
+    }
+
+    /// Can the rigid-body fall asleep, i.e., are its thresholds non-negative?
+    #[inline]
+    pub fn can_sleep(&self) -> bool {
+        self.linear_threshold.is_sign_positive() && self.angular_threshold.is_sign_positive()
+    }
+
+    /// Allows or prevents the rigid-body from falling asleep.
+    ///
+    /// This changes the sign of the thresholds, so their magnitude is kept when sleeping is
+    /// allowed again.
+    #[inline]
+    pub fn set_can_sleep(&mut self, can_sleep: bool) {
+        let sign: Real = if can_sleep { 1.0 } else { -1.0 };
+        self.linear_threshold = self.linear_threshold.abs() * sign;
+        self.angular_threshold = self.angular_threshold.abs() * sign;
+    }
+
     /// Returns `true` if the body is not asleep.
     #[inline]
     pub fn is_active(&self) -> bool {
@@ -3,6 +3,7 @@ use crate::dynamics::{
     ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBody, RigidBodyChanges, RigidBodyHandle,
 };
 use crate::geometry::ColliderSet;
+use crate::math::{Isometry, Real};
 use std::ops::{Index, IndexMut};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -32,6 +33,8 @@ pub struct RigidBodySet {
     // Could we avoid this?
     pub(crate) bodies: Arena<RigidBody>,
     pub(crate) modified_bodies: Vec<RigidBodyHandle>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    sorted_active_bodies: Vec<crate::data::Index>, // Workspace.
 }
 
 impl RigidBodySet {
@@ -40,6 +43,18 @@ impl RigidBodySet {
         RigidBodySet {
             bodies: Arena::new(),
             modified_bodies: Vec::new(),
+            sorted_active_bodies: Vec::new(),
+        }
+    }
+
+    /// Create a new empty set of rigid bodies, with room for `capacity` rigid-bodies.
+    ///
+    /// The set grows as usual (without panicking) if more rigid-bodies are inserted.
+    pub fn with_capacity(capacity: usize) -> Self {
+        RigidBodySet {
+            bodies: Arena::with_capacity(capacity),
+            modified_bodies: Vec::new(),
+            sorted_active_bodies: Vec::new(),
         }
     }
 
@@ -195,6 +210,17 @@ impl RigidBodySet {
         self.bodies.iter().map(|(h, b)| (RigidBodyHandle(h), b))
     }
 
+    /// Iterates through all the rigid-bodies on this set, in the order they were inserted.
+    ///
+    /// Unlike [`RigidBodySet::iter`], this order doesn't depend on the removals that happened
+    /// before the insertion of the rigid-bodies (which can make new rigid-bodies reuse the
+    /// storage of removed ones). This is useful to serialize, or compare, sets built differently.
+    pub fn iter_in_insertion_order(&self) -> impl Iterator<Item = (RigidBodyHandle, &RigidBody)> {
+        self.bodies
+            .iter_in_insertion_order()
+            .map(|(h, b)| (RigidBodyHandle(h), b))
+    }
+
     /// Iterates mutably through all the rigid-bodies on this set.
     #[cfg(not(feature = "dev-remove-slow-accessors"))]
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (RigidBodyHandle, &mut RigidBody)> {
@@ -206,6 +232,64 @@ impl RigidBodySet {
         })
     }
 
+    /// Iterates mutably through all the active (i.e. non-sleeping) dynamic and kinematic
+    /// rigid-bodies on this set.
+    ///
+    /// Only the rigid-bodies that were active at the end of the last timestep are visited,
+    /// without iterating through the sleeping or fixed rigid-bodies. They are visited in the
+    /// order of their handles rather than in the order of the active sets of the island manager.
+    /// Each visited rigid-body is marked as modified, just like with [`RigidBodySet::get_mut`].
+    #[cfg(not(feature = "dev-remove-slow-accessors"))]
+    pub fn iter_active_mut<'a>(
+        &'a mut self,
+        islands: &'a IslandManager,
+    ) -> impl Iterator<Item = (RigidBodyHandle, &'a mut RigidBody)> {
+        // NOTE: the sorted handles are stored in a workspace so that no allocation is needed once
+        //       it is large enough.
+        let active = &mut self.sorted_active_bodies;
+        active.clear();
+        active.extend(islands.iter_active_bodies().map(|h| h.0));
+        active.sort_unstable_by_key(|h| h.into_raw_parts().0);
+
+        let modified_bodies = &mut self.modified_bodies;
+        self.bodies
+            .iter_sorted_mut(active.iter().copied())
+            .map(move |(h, rb)| {
+                let handle = RigidBodyHandle(h);
+                Self::mark_as_modified(handle, rb, modified_bodies);
+                (handle, rb)
+            })
+    }
+
+    /// Copies the positions of the given rigid-bodies into `out`, in the same order.
+    ///
+    /// The position of the rigid-body `handles[i]` is written into `out[i]`, so `handles` acts as
+    /// a stable mapping between the rigid-bodies and the elements of a user buffer (e.g. the
+    /// transforms to render). If `only_awake` is `true`, the elements of `out` matching sleeping
+    /// rigid-bodies are left unchanged. The elements matching handles that don’t exist in this set
+    /// are left unchanged too, as well as any element beyond `handles.len()`.
+    ///
+    /// Returns the number of positions actually written.
+    pub fn copy_positions_into(
+        &self,
+        handles: &[RigidBodyHandle],
+        out: &mut [Isometry<Real>],
+        only_awake: bool,
+    ) -> usize {
+        let mut num_copied = 0;
+
+        for (handle, position) in handles.iter().zip(out.iter_mut()) {
+            if let Some(rb) = self.bodies.get(handle.0) {
+                if !only_awake || !rb.is_sleeping() {
+                    *position = rb.pos.position;
+                    num_copied += 1;
+                }
+            }
+        }
+
+        num_copied
+    }
+
     /// Update colliders positions after rigid-bodies moved.
     ///
     /// When a rigid-body moves, the positions of the colliders attached to it need to be updated.
@@ -227,6 +311,9 @@ impl RigidBodySet {
     }
 }
 
+/// Panics if there is no rigid-body with the given handle.
+///
+/// Use `RigidBodySet::get` for a non-panicking alternative.
 impl Index<RigidBodyHandle> for RigidBodySet {
     type Output = RigidBody;
 
@@ -235,6 +322,9 @@ impl Index<RigidBodyHandle> for RigidBodySet {
     }
 }
 
+/// Panics if there is no rigid-body with the given index.
+///
+/// Use `RigidBodySet::get` with `RigidBodyHandle(index)` for a non-panicking alternative.
 impl Index<crate::data::Index> for RigidBodySet {
     type Output = RigidBody;
 
@@ -244,6 +334,9 @@ impl Index<crate::data::Index> for RigidBodySet {
 }
 
 #[cfg(not(feature = "dev-remove-slow-accessors"))]
+/// Panics if there is no rigid-body with the given handle.
+///
+/// Use `RigidBodySet::get_mut` for a non-panicking alternative.
 impl IndexMut<RigidBodyHandle> for RigidBodySet {
     fn index_mut(&mut self, handle: RigidBodyHandle) -> &mut RigidBody {
         let rb = &mut self.bodies[handle.0];
@@ -251,3 +344,138 @@ impl IndexMut<RigidBodyHandle> for RigidBodySet {
         rb
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{
+        ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBodyBuilder, RigidBodySet,
+    };
+    use crate::geometry::ColliderSet;
+    use crate::math::{Isometry, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn iter_active_mut_only_visits_the_awake_bodies() {
+        let mut world = TestWorld::new();
+        world.gravity = Vector::zeros();
+
+        let awake1 = world.bodies.insert(RigidBodyBuilder::dynamic());
+        let _asleep = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().sleeping(true));
+        let _fixed = world.bodies.insert(RigidBodyBuilder::fixed());
+        let awake2 = world.bodies.insert(RigidBodyBuilder::dynamic());
+
+        world.step();
+        assert!(world.bodies.modified_bodies.is_empty());
+
+        let mut visited = vec![];
+        for (handle, rb) in world.bodies.iter_active_mut(&world.islands) {
+            rb.set_linvel(Vector::repeat(1.0), false);
+            visited.push(handle);
+        }
+
+        assert_eq!(visited, vec![awake1, awake2]);
+        assert_eq!(world.bodies.modified_bodies, visited);
+
+        world.step();
+        for handle in visited {
+            assert_eq!(*world.bodies[handle].linvel(), Vector::repeat(1.0));
+            assert!(world.bodies[handle].translation().x > 0.0);
+        }
+    }
+
+    #[test]
+    fn insertion_order_iteration_ignores_removal_history() {
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut islands = IslandManager::new();
+
+        let body = |y| RigidBodyBuilder::dynamic().translation(Vector::y() * y);
+
+        // Insert 0, 1, 2, remove 0 and 1, then insert 3 (which reuses a freed slot).
+        let mut bodies1 = RigidBodySet::new();
+        let h0 = bodies1.insert(body(0.0));
+        let h1 = bodies1.insert(body(1.0));
+        bodies1.insert(body(2.0));
+
+        for handle in [h1, h0] {
+            bodies1.remove(
+                handle,
+                &mut islands,
+                &mut colliders,
+                &mut impulse_joints,
+                &mut multibody_joints,
+                true,
+            );
+        }
+
+        bodies1.insert(body(3.0));
+
+        // Insert 2 and 3 directly.
+        let mut bodies2 = RigidBodySet::new();
+        bodies2.insert(body(2.0));
+        bodies2.insert(body(3.0));
+
+        let heights = |bodies: &RigidBodySet| -> Vec<_> {
+            bodies
+                .iter_in_insertion_order()
+                .map(|(_, rb)| rb.translation().y)
+                .collect()
+        };
+        assert_ne!(
+            bodies1
+                .iter()
+                .map(|(_, rb)| rb.translation().y)
+                .collect::<Vec<_>>(),
+            heights(&bodies1)
+        );
+        assert_eq!(heights(&bodies1), [2.0, 3.0]);
+        assert_eq!(heights(&bodies2), [2.0, 3.0]);
+    }
+
+    #[test]
+    fn copied_positions_follow_the_order_of_the_handles() {
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut islands = IslandManager::new();
+
+        let body = |x| RigidBodyBuilder::dynamic().translation(Vector::x() * x);
+        let awake = bodies.insert(body(1.0));
+        let asleep = bodies.insert(body(2.0).sleeping(true));
+        let removed = bodies.insert(body(3.0));
+        bodies.remove(
+            removed,
+            &mut islands,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            true,
+        );
+
+        let handles = [asleep, removed, awake];
+        let unchanged = Isometry::from(-Vector::repeat(1.0));
+        let mut out = [unchanged; 4];
+        assert_eq!(bodies.copy_positions_into(&handles, &mut out, false), 2);
+        assert_eq!(
+            out,
+            [
+                Isometry::from(Vector::x() * 2.0),
+                unchanged,
+                Isometry::from(Vector::x()),
+                unchanged
+            ]
+        );
+
+        let mut out = [unchanged; 3];
+        assert_eq!(bodies.copy_positions_into(&handles, &mut out, true), 1);
+        assert_eq!(out, [unchanged, unchanged, Isometry::from(Vector::x())]);
+
+        // The handles beyond the size of the output buffer are ignored.
+        let mut out = [unchanged; 1];
+        assert_eq!(bodies.copy_positions_into(&handles, &mut out, true), 0);
+    }
+}
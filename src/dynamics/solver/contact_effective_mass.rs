@@ -0,0 +1,261 @@
+//! The effective mass of contacts, as seen by the contact solver.
+
+use crate::dynamics::{RigidBody, RigidBodyDominance, RigidBodyMassProps, RigidBodyType};
+use crate::math::{AngVector, Point, Real, Vector};
+use crate::utils::{self, WAngularInertia, WCross, WDot, WReal};
+
+/// The effective mass of a contact between two rigid-bodies, along the contact normal.
+///
+/// This is the mass `1 / (n · K · n)` the contact solver uses to convert the relative velocity
+/// of the bodies at `point` along `normal` into a normal impulse, where `K` is the inverse mass
+/// matrix of the bodies at the contact point. The contact normal points from the first body
+/// toward the second body but its sign doesn’t affect the result.
+///
+/// A body set to `None` is treated like a fixed body, e.g., for contacts with a collider that
+/// isn’t attached to any rigid-body. Non-dynamic bodies, and bodies dominated by the other one
+/// (see [`RigidBody::set_dominance_group`]), have an infinite mass so the result only depends on
+/// the other body. Returns zero if neither body is dynamic.
+///
+/// This uses the current world-space mass properties of the bodies, so it matches the solver
+/// exactly for contacts computed at the same positions. Bodies attached to multibodies are
+/// treated like free rigid-bodies.
+pub fn contact_effective_mass(
+    body1: Option<&RigidBody>,
+    body2: Option<&RigidBody>,
+    point: &Point<Real>,
+    normal: &Vector<Real>,
+) -> Real {
+    let dominance = |body: Option<&RigidBody>| {
+        body.map(|rb| rb.effective_dominance_group())
+            .unwrap_or_else(|| RigidBodyDominance(0).effective_group(&RigidBodyType::Fixed))
+    };
+    let relative_dominance = dominance(body1) - dominance(body2);
+    let mprops1 = body1.map(|rb| &rb.mprops);
+    let mprops2 = body2.map(|rb| &rb.mprops);
+
+    // Same as the contact solver: if a body dominates the other, only the dominated one
+    // contributes to the effective mass.
+    match (relative_dominance, mprops1, mprops2) {
+        (0, Some(mprops1), Some(mprops2)) => {
+            projected_contact_mass(Some(mprops1), mprops2, point, &-normal)
+        }
+        (d, _, Some(mprops2)) if d > 0 => projected_contact_mass(None, mprops2, point, &-normal),
+        (d, Some(mprops1), _) if d < 0 => projected_contact_mass(None, mprops1, point, normal),
+        _ => 0.0,
+    }
+}
+
+/// The mass of a contact along `force_dir1`, i.e., `1 / (force_dir1 · K · force_dir1)`.
+///
+/// If `mprops1` is `None`, the first body is considered to have an infinite mass, as for
+/// ground contact constraints.
+#[inline]
+pub(crate) fn projected_contact_mass(
+    mprops1: Option<&RigidBodyMassProps>,
+    mprops2: &RigidBodyMassProps,
+    point: &Point<Real>,
+    force_dir1: &Vector<Real>,
+) -> Real {
+    let dp2 = point - mprops2.world_com;
+    let gcross2 = mprops2
+        .effective_world_inv_inertia_sqrt
+        .transform_vector(dp2.gcross(-force_dir1));
+
+    if let Some(mprops1) = mprops1 {
+        let dp1 = point - mprops1.world_com;
+        let gcross1 = mprops1
+            .effective_world_inv_inertia_sqrt
+            .transform_vector(dp1.gcross(*force_dir1));
+
+        let imsum = mprops1.effective_inv_mass + mprops2.effective_inv_mass;
+        projected_contact_mass_from_gcross(&imsum, force_dir1, Some(gcross1), gcross2)
+    } else {
+        projected_contact_mass_from_gcross(&mprops2.effective_inv_mass, force_dir1, None, gcross2)
+    }
+}
+
+/// The mass of a contact along `force_dir1`, from the angular terms already computed by the
+/// contact constraint builders.
+///
+/// Here `gcross1` and `gcross2` are the contact lever arms crossed with the force direction and
+/// transformed by the square root of the world-space inverse angular inertia of each body, and
+/// `im_sum` is the sum of the inverse masses of the bodies. A `None` `gcross1` stands for a body
+/// with an infinite mass. This is shared by all the contact constraints, including the SIMD
+/// ones, so the mass they use always matches [`contact_effective_mass`].
+#[inline]
+pub(crate) fn projected_contact_mass_from_gcross<N: WReal>(
+    im_sum: &Vector<N>,
+    force_dir1: &Vector<N>,
+    gcross1: Option<AngVector<N>>,
+    gcross2: AngVector<N>,
+) -> N {
+    let mut inv_mass = force_dir1.dot(&im_sum.component_mul(force_dir1));
+    if let Some(gcross1) = gcross1 {
+        inv_mass += gcross1.gdot(gcross1);
+    }
+    utils::simd_inv(inv_mass + gcross2.gdot(gcross2))
+}
+
+#[cfg(test)]
+mod test {
+    use super::contact_effective_mass;
+    use crate::dynamics::solver::{
+        AnyVelocityConstraint, VelocityConstraint, VelocityGroundConstraint,
+    };
+    #[cfg(feature = "simd-is-enabled")]
+    use crate::dynamics::solver::{WVelocityConstraint, WVelocityGroundConstraint};
+    use crate::dynamics::{IntegrationParameters, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    #[cfg(feature = "simd-is-enabled")]
+    use crate::math::SIMD_WIDTH;
+    use crate::math::{Isometry, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    #[cfg(feature = "simd-is-enabled")]
+    use simba::simd::SimdValue;
+
+    fn constraint_masses(constraints: &[AnyVelocityConstraint]) -> Vec<Real> {
+        let mut masses = vec![];
+        for constraint in constraints {
+            match constraint {
+                AnyVelocityConstraint::Nongrouped(c) => masses.extend(
+                    c.elements[..c.num_contacts as usize]
+                        .iter()
+                        .map(|e| e.normal_part.r),
+                ),
+                AnyVelocityConstraint::NongroupedGround(c) => masses.extend(
+                    c.elements[..c.num_contacts as usize]
+                        .iter()
+                        .map(|e| e.normal_part.r),
+                ),
+                // All the lanes hold the same manifold, check the first one.
+                #[cfg(feature = "simd-is-enabled")]
+                AnyVelocityConstraint::Grouped(c) => masses.extend(
+                    c.elements[..c.num_contacts as usize]
+                        .iter()
+                        .map(|e| e.normal_part.r.extract(0)),
+                ),
+                #[cfg(feature = "simd-is-enabled")]
+                AnyVelocityConstraint::GroupedGround(c) => masses.extend(
+                    c.elements[..c.num_contacts as usize]
+                        .iter()
+                        .map(|e| e.normal_part.r.extract(0)),
+                ),
+                _ => unreachable!(),
+            }
+        }
+        masses
+    }
+
+    #[test]
+    fn contact_effective_mass_matches_the_solver_constraints() {
+        let mut world = TestWorld::new();
+
+        // A light box on a heavier tilted box, itself resting on a parentless ground, so we get
+        // both two-body and ground contacts with lever arms that aren't aligned with the normal.
+        world
+            .colliders
+            .insert(ColliderBuilder::cuboid(10.0, 0.5, 10.0).translation(-Vector::y() * 0.5));
+        #[cfg(feature = "dim2")]
+        let tilt = 0.1;
+        #[cfg(feature = "dim3")]
+        let tilt = Vector::new(0.1, 0.2, 0.05);
+        for (i, density) in [5.0, 1.0].into_iter().enumerate() {
+            let body = RigidBodyBuilder::dynamic().position(Isometry::new(
+                Vector::y() * (0.45 + i as Real * 0.9),
+                tilt * i as Real,
+            ));
+            let body = world.bodies.insert(body);
+            let collider = ColliderBuilder::cuboid(0.5, 0.5, 0.5).density(density);
+            world
+                .colliders
+                .insert_with_parent(collider, body, &mut world.bodies);
+        }
+
+        world.gravity = Vector::zeros();
+        world.step();
+
+        let params = IntegrationParameters::default();
+        let manifolds = world
+            .narrow_phase
+            .contact_pairs()
+            .flat_map(|pair| pair.manifolds.iter())
+            .filter(|manifold| !manifold.data.solver_contacts.is_empty());
+        let mut num_checked = [0; 2];
+
+        for (manifold_id, manifold) in manifolds.enumerate() {
+            let body1 = manifold
+                .data
+                .rigid_body1
+                .map(|handle| &world.bodies[handle]);
+            let body2 = manifold
+                .data
+                .rigid_body2
+                .map(|handle| &world.bodies[handle]);
+            let expected: Vec<_> = manifold
+                .data
+                .solver_contacts
+                .iter()
+                .map(|contact| {
+                    contact_effective_mass(body1, body2, &contact.point, &manifold.data.normal)
+                })
+                .collect();
+
+            let mut constraints = vec![];
+            #[cfg(feature = "simd-is-enabled")]
+            let mut wide_constraints = vec![];
+            let is_ground = manifold.data.relative_dominance != 0;
+
+            if is_ground {
+                VelocityGroundConstraint::generate(
+                    &params,
+                    manifold_id,
+                    manifold,
+                    &world.bodies,
+                    &mut constraints,
+                    None,
+                );
+                #[cfg(feature = "simd-is-enabled")]
+                WVelocityGroundConstraint::generate(
+                    &params,
+                    [manifold_id; SIMD_WIDTH],
+                    [manifold; SIMD_WIDTH],
+                    &world.bodies,
+                    &mut wide_constraints,
+                    None,
+                );
+            } else {
+                VelocityConstraint::generate(
+                    &params,
+                    manifold_id,
+                    manifold,
+                    &world.bodies,
+                    &mut constraints,
+                    None,
+                );
+                #[cfg(feature = "simd-is-enabled")]
+                WVelocityConstraint::generate(
+                    &params,
+                    [manifold_id; SIMD_WIDTH],
+                    [manifold; SIMD_WIDTH],
+                    &world.bodies,
+                    &mut wide_constraints,
+                    None,
+                );
+            }
+
+            assert!(expected.iter().all(|mass| *mass > 0.0));
+            assert_eq!(constraint_masses(&constraints), expected);
+            #[cfg(feature = "simd-is-enabled")]
+            {
+                let wide_masses = constraint_masses(&wide_constraints);
+                assert_eq!(wide_masses.len(), expected.len());
+                for (wide_mass, mass) in wide_masses.iter().zip(expected.iter()) {
+                    assert!((wide_mass - mass).abs() <= mass * 1.0e-5);
+                }
+            }
+            num_checked[is_ground as usize] += 1;
+        }
+
+        assert_eq!(num_checked, [1, 1]);
+    }
+}
@@ -34,15 +34,21 @@ impl GenericVelocityConstraint {
         jacobian_id: &mut usize,
         insert_at: Option<usize>,
     ) {
-        let cfm_factor = params.cfm_factor();
+        let (erp_inv_dt, cfm_factor) = manifold
+            .data
+            .contact_softness
+            .erp_inv_dt_and_cfm_factor(params);
         let inv_dt = params.inv_dt();
-        let erp_inv_dt = params.erp_inv_dt();
 
         let handle1 = manifold.data.rigid_body1.unwrap();
         let handle2 = manifold.data.rigid_body2.unwrap();
 
         let rb1 = &bodies[handle1];
         let rb2 = &bodies[handle2];
+        let max_penetration_correction = params
+            .max_penetration_correction
+            .min(rb1.max_penetration_correction)
+            .min(rb2.max_penetration_correction);
 
         let (vels1, mprops1, type1) = (&rb1.vels, &rb1.mprops, &rb1.body_type);
         let (vels2, mprops2, type2) = (&rb2.vels, &rb2.mprops, &rb2.body_type);
@@ -73,8 +79,11 @@ impl GenericVelocityConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let (tangents1, friction_scales) = super::anisotropic_friction_directions(
+            manifold,
+            &force_dir1,
+            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel),
+        );
 
         let multibodies_ndof = multibody1.map(|m| m.0.ndofs()).unwrap_or(0)
             + multibody2.map(|m| m.0.ndofs()).unwrap_or(0);
@@ -112,6 +121,10 @@ impl GenericVelocityConstraint {
                 },
                 cfm_factor,
                 limit: 0.0,
+                #[cfg(feature = "dim3")]
+                friction_model: params.friction_model,
+                #[cfg(feature = "dim3")]
+                friction_scales,
                 mj_lambda1,
                 mj_lambda2,
                 manifold_id,
@@ -198,7 +211,7 @@ impl GenericVelocityConstraint {
                     rhs_wo_bias += manifold_point.dist.max(0.0) * inv_dt;
                     rhs_wo_bias *= is_bouncy + is_resting;
                     let rhs_bias =
-                        /* is_resting * */ erp_inv_dt * manifold_point.dist.clamp(-params.max_penetration_correction, 0.0);
+                        /* is_resting * */ erp_inv_dt * manifold_point.dist.clamp(-max_penetration_correction, 0.0);
 
                     let rhs = rhs_wo_bias + rhs_bias;
                     is_fast_contact = is_fast_contact || (-rhs * params.dt > ccd_thickness * 0.5);
@@ -210,6 +223,8 @@ impl GenericVelocityConstraint {
                         rhs_wo_bias,
                         impulse: na::zero(),
                         r,
+                        max_impulse: manifold.data.max_contact_impulse,
+                        unclamped_impulse: na::zero(),
                     };
                 }
 
@@ -349,6 +364,10 @@ impl GenericVelocityConstraint {
             &self.velocity_constraint.im1,
             &self.velocity_constraint.im2,
             self.velocity_constraint.limit,
+            #[cfg(feature = "dim3")]
+            self.velocity_constraint.friction_model,
+            #[cfg(feature = "dim3")]
+            self.velocity_constraint.friction_scales.as_ref(),
             self.ndofs1,
             self.ndofs2,
             self.j_id,
@@ -1,7 +1,11 @@
 use super::DeltaVel;
+#[cfg(feature = "dim3")]
+use crate::dynamics::solver::project_anisotropic_friction_impulse;
 use crate::dynamics::solver::{
     VelocityConstraintElement, VelocityConstraintNormalPart, VelocityConstraintTangentPart,
 };
+#[cfg(feature = "dim3")]
+use crate::dynamics::FrictionModel;
 use crate::math::{AngVector, Real, Vector, DIM};
 use crate::utils::WDot;
 use na::DVector;
@@ -99,6 +103,8 @@ impl VelocityConstraintTangentPart<Real> {
         ndofs1: usize,
         ndofs2: usize,
         limit: Real,
+        #[cfg(feature = "dim3")] friction_model: FrictionModel,
+        #[cfg(feature = "dim3")] friction_scales: Option<&[Real; 2]>,
         mj_lambda1: &mut GenericRhs,
         mj_lambda2: &mut GenericRhs,
         mj_lambdas: &mut DVector<Real>,
@@ -189,7 +195,15 @@ impl VelocityConstraintTangentPart<Real> {
                 self.impulse[0] - self.r[0] * dvel_0,
                 self.impulse[1] - self.r[1] * dvel_1,
             );
-            let new_impulse = new_impulse.cap_magnitude(limit);
+            let new_impulse =
+                project_anisotropic_friction_impulse(new_impulse, friction_scales, |impulse| {
+                    match friction_model {
+                        FrictionModel::Cone => impulse.cap_magnitude(limit),
+                        FrictionModel::Pyramid => {
+                            impulse.map(|impulse| impulse.clamp(-limit, limit))
+                        }
+                    }
+                });
 
             let dlambda = new_impulse - self.impulse;
             self.impulse = new_impulse;
@@ -262,7 +276,9 @@ impl VelocityConstraintNormalPart<Real> {
             + mj_lambda2.dvel(j_id2, ndofs2, jacobians, &-dir1, &self.gcross2, mj_lambdas)
             + self.rhs;
 
-        let new_impulse = cfm_factor * (self.impulse - self.r * dvel).max(0.0);
+        let unclamped_impulse = cfm_factor * (self.impulse - self.r * dvel).max(0.0);
+        let new_impulse = unclamped_impulse.min(self.max_impulse);
+        self.unclamped_impulse = unclamped_impulse;
         let dlambda = new_impulse - self.impulse;
         self.impulse = new_impulse;
 
@@ -300,6 +316,8 @@ impl VelocityConstraintElement<Real> {
         im1: &Vector<Real>,
         im2: &Vector<Real>,
         limit: Real,
+        #[cfg(feature = "dim3")] friction_model: FrictionModel,
+        #[cfg(feature = "dim3")] friction_scales: Option<&[Real; 2]>,
         // ndofs is 0 for a non-multibody body, or a multibody with zero
         // degrees of freedom.
         ndofs1: usize,
@@ -339,8 +357,21 @@ impl VelocityConstraintElement<Real> {
                 let limit = limit * element.normal_part.impulse;
                 let part = &mut element.tangent_part;
                 part.generic_solve(
-                    tng_j_id, jacobians, tangents1, im1, im2, ndofs1, ndofs2, limit, mj_lambda1,
-                    mj_lambda2, mj_lambdas,
+                    tng_j_id,
+                    jacobians,
+                    tangents1,
+                    im1,
+                    im2,
+                    ndofs1,
+                    ndofs2,
+                    limit,
+                    #[cfg(feature = "dim3")]
+                    friction_model,
+                    #[cfg(feature = "dim3")]
+                    friction_scales,
+                    mj_lambda1,
+                    mj_lambda2,
+                    mj_lambdas,
                 );
                 tng_j_id += j_step;
             }
@@ -32,9 +32,11 @@ impl GenericVelocityGroundConstraint {
         jacobian_id: &mut usize,
         insert_at: Option<usize>,
     ) {
-        let cfm_factor = params.cfm_factor();
+        let (erp_inv_dt, cfm_factor) = manifold
+            .data
+            .contact_softness
+            .erp_inv_dt_and_cfm_factor(params);
         let inv_dt = params.inv_dt();
-        let erp_inv_dt = params.erp_inv_dt();
 
         let mut handle1 = manifold.data.rigid_body1;
         let mut handle2 = manifold.data.rigid_body2;
@@ -56,6 +58,9 @@ impl GenericVelocityGroundConstraint {
 
         let rb2 = &bodies[handle2.unwrap()];
         let (vels2, mprops2) = (&rb2.vels, &rb2.mprops);
+        let max_penetration_correction = params
+            .max_penetration_correction
+            .min(rb2.max_penetration_correction);
 
         let (mb2, link_id2) = handle2
             .and_then(|h| multibodies.rigid_body_link(h))
@@ -66,8 +71,11 @@ impl GenericVelocityGroundConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let (tangents1, friction_scales) = super::anisotropic_friction_directions(
+            manifold,
+            &force_dir1,
+            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel),
+        );
 
         let multibodies_ndof = mb2.ndofs();
         // For each solver contact we generate DIM constraints, and each constraints appends
@@ -95,6 +103,10 @@ impl GenericVelocityGroundConstraint {
                 im2: mprops2.effective_inv_mass,
                 cfm_factor,
                 limit: 0.0,
+                #[cfg(feature = "dim3")]
+                friction_model: params.friction_model,
+                #[cfg(feature = "dim3")]
+                friction_scales,
                 mj_lambda2,
                 manifold_id,
                 manifold_contact_id: [0; MAX_MANIFOLD_POINTS],
@@ -138,7 +150,7 @@ impl GenericVelocityGroundConstraint {
                     rhs_wo_bias += manifold_point.dist.max(0.0) * inv_dt;
                     rhs_wo_bias *= is_bouncy + is_resting;
                     let rhs_bias =
-                        /* is_resting * */ erp_inv_dt * manifold_point.dist.clamp(-params.max_penetration_correction, 0.0);
+                        /* is_resting * */ erp_inv_dt * manifold_point.dist.clamp(-max_penetration_correction, 0.0);
 
                     let rhs = rhs_wo_bias + rhs_bias;
                     is_fast_contact =
@@ -150,6 +162,8 @@ impl GenericVelocityGroundConstraint {
                         rhs_wo_bias,
                         impulse: na::zero(),
                         r,
+                        max_impulse: manifold.data.max_contact_impulse,
+                        unclamped_impulse: na::zero(),
                     };
                 }
 
@@ -221,6 +235,10 @@ impl GenericVelocityGroundConstraint {
             elements,
             jacobians,
             self.velocity_constraint.limit,
+            #[cfg(feature = "dim3")]
+            self.velocity_constraint.friction_model,
+            #[cfg(feature = "dim3")]
+            self.velocity_constraint.friction_scales.as_ref(),
             self.ndofs2,
             self.j_id,
             mj_lambda2,
@@ -1,7 +1,11 @@
+#[cfg(feature = "dim3")]
+use crate::dynamics::solver::project_anisotropic_friction_impulse;
 use crate::dynamics::solver::{
     VelocityGroundConstraintElement, VelocityGroundConstraintNormalPart,
     VelocityGroundConstraintTangentPart,
 };
+#[cfg(feature = "dim3")]
+use crate::dynamics::FrictionModel;
 use crate::math::{Real, DIM};
 use na::DVector;
 #[cfg(feature = "dim2")]
@@ -15,6 +19,8 @@ impl VelocityGroundConstraintTangentPart<Real> {
         jacobians: &DVector<Real>,
         ndofs2: usize,
         limit: Real,
+        #[cfg(feature = "dim3")] friction_model: FrictionModel,
+        #[cfg(feature = "dim3")] friction_scales: Option<&[Real; 2]>,
         mj_lambda2: usize,
         mj_lambdas: &mut DVector<Real>,
     ) {
@@ -52,7 +58,15 @@ impl VelocityGroundConstraintTangentPart<Real> {
                 self.impulse[0] - self.r[0] * dvel_0,
                 self.impulse[1] - self.r[1] * dvel_1,
             );
-            let new_impulse = new_impulse.cap_magnitude(limit);
+            let new_impulse =
+                project_anisotropic_friction_impulse(new_impulse, friction_scales, |impulse| {
+                    match friction_model {
+                        FrictionModel::Cone => impulse.cap_magnitude(limit),
+                        FrictionModel::Pyramid => {
+                            impulse.map(|impulse| impulse.clamp(-limit, limit))
+                        }
+                    }
+                });
 
             let dlambda = new_impulse - self.impulse;
             self.impulse = new_impulse;
@@ -87,7 +101,9 @@ impl VelocityGroundConstraintNormalPart<Real> {
             .dot(&mj_lambdas.rows(mj_lambda2, ndofs2))
             + self.rhs;
 
-        let new_impulse = cfm_factor * (self.impulse - self.r * dvel).max(0.0);
+        let unclamped_impulse = cfm_factor * (self.impulse - self.r * dvel).max(0.0);
+        let new_impulse = unclamped_impulse.min(self.max_impulse);
+        self.unclamped_impulse = unclamped_impulse;
         let dlambda = new_impulse - self.impulse;
         self.impulse = new_impulse;
 
@@ -106,6 +122,8 @@ impl VelocityGroundConstraintElement<Real> {
         elements: &mut [Self],
         jacobians: &DVector<Real>,
         limit: Real,
+        #[cfg(feature = "dim3")] friction_model: FrictionModel,
+        #[cfg(feature = "dim3")] friction_scales: Option<&[Real; 2]>,
         ndofs2: usize,
         // Jacobian index of the first constraint.
         j_id: usize,
@@ -135,7 +153,18 @@ impl VelocityGroundConstraintElement<Real> {
             for element in elements.iter_mut() {
                 let limit = limit * element.normal_part.impulse;
                 let part = &mut element.tangent_part;
-                part.generic_solve(tng_j_id, jacobians, ndofs2, limit, mj_lambda2, mj_lambdas);
+                part.generic_solve(
+                    tng_j_id,
+                    jacobians,
+                    ndofs2,
+                    limit,
+                    #[cfg(feature = "dim3")]
+                    friction_model,
+                    #[cfg(feature = "dim3")]
+                    friction_scales,
+                    mj_lambda2,
+                    mj_lambdas,
+                );
                 tng_j_id += j_step;
             }
         }
@@ -8,23 +8,25 @@ use {
 };
 
 #[cfg(feature = "parallel")]
-use crate::dynamics::{MultibodyJointSet, RigidBodyHandle};
+use crate::dynamics::MultibodyJointSet;
+#[cfg(any(feature = "parallel", feature = "enhanced-determinism"))]
+use crate::dynamics::RigidBodyHandle;
 
-#[cfg(feature = "parallel")]
+#[cfg(any(feature = "parallel", feature = "enhanced-determinism"))]
 pub(crate) trait PairInteraction {
     fn body_pair(&self) -> (Option<RigidBodyHandle>, Option<RigidBodyHandle>);
 }
 #[cfg(feature = "simd-is-enabled")]
 use crate::dynamics::RigidBodyType;
 
-#[cfg(feature = "parallel")]
+#[cfg(any(feature = "parallel", feature = "enhanced-determinism"))]
 impl<'a> PairInteraction for &'a mut ContactManifold {
     fn body_pair(&self) -> (Option<RigidBodyHandle>, Option<RigidBodyHandle>) {
         (self.data.rigid_body1, self.data.rigid_body2)
     }
 }
 
-#[cfg(feature = "parallel")]
+#[cfg(any(feature = "parallel", feature = "enhanced-determinism"))]
 impl<'a> PairInteraction for JointGraphEdge {
     fn body_pair(&self) -> (Option<RigidBodyHandle>, Option<RigidBodyHandle>) {
         (Some(self.weight.body1), Some(self.weight.body2))
@@ -33,7 +35,10 @@ impl<'a> PairInteraction for JointGraphEdge {
 
 #[cfg(feature = "parallel")]
 pub(crate) struct ParallelInteractionGroups {
-    bodies_color: Vec<u128>,         // Workspace.
+    #[cfg(not(feature = "enhanced-determinism"))]
+    bodies_color: Vec<u128>, // Workspace.
+    #[cfg(feature = "enhanced-determinism")]
+    body_levels: Vec<usize>, // Workspace.
     interaction_indices: Vec<usize>, // Workspace.
     interaction_colors: Vec<usize>,  // Workspace.
     sorted_interactions: Vec<usize>,
@@ -44,7 +49,10 @@ pub(crate) struct ParallelInteractionGroups {
 impl ParallelInteractionGroups {
     pub fn new() -> Self {
         Self {
+            #[cfg(not(feature = "enhanced-determinism"))]
             bodies_color: Vec::new(),
+            #[cfg(feature = "enhanced-determinism")]
+            body_levels: Vec::new(),
             interaction_indices: Vec::new(),
             interaction_colors: Vec::new(),
             sorted_interactions: Vec::new(),
@@ -52,6 +60,15 @@ impl ParallelInteractionGroups {
         }
     }
 
+    pub fn with_capacity(num_interactions: usize) -> Self {
+        Self {
+            interaction_indices: Vec::with_capacity(num_interactions),
+            interaction_colors: Vec::with_capacity(num_interactions),
+            sorted_interactions: Vec::with_capacity(num_interactions),
+            ..Self::new()
+        }
+    }
+
     pub fn group(&self, i: usize) -> &[usize] {
         let range = self.groups[i]..self.groups[i + 1];
         &self.sorted_interactions[range]
@@ -68,19 +85,41 @@ impl ParallelInteractionGroups {
         multibodies: &MultibodyJointSet,
         interactions: &[Interaction],
         interaction_indices: &[usize],
+        sort_interactions: bool,
     ) {
         let num_island_bodies = islands.active_island(island_id).len();
-        self.bodies_color.clear();
         self.interaction_indices.clear();
         self.groups.clear();
         self.sorted_interactions.clear();
         self.interaction_colors.clear();
 
-        let mut color_len = [0; 128];
-        self.bodies_color.resize(num_island_bodies, 0u128);
         self.interaction_indices
             .extend_from_slice(interaction_indices);
         self.interaction_colors.resize(interaction_indices.len(), 0);
+
+        if sort_interactions {
+            sort_interactions_by_bodies(interactions, &mut self.interaction_indices);
+        }
+
+        #[cfg(not(feature = "enhanced-determinism"))]
+        self.color_interactions(num_island_bodies, bodies, multibodies, interactions);
+        #[cfg(feature = "enhanced-determinism")]
+        self.level_interactions(num_island_bodies, bodies, multibodies, interactions);
+    }
+
+    /// Assigns to each interaction the first color not used yet by the other interactions
+    /// involving the same bodies.
+    #[cfg(not(feature = "enhanced-determinism"))]
+    fn color_interactions<Interaction: PairInteraction>(
+        &mut self,
+        num_island_bodies: usize,
+        bodies: &RigidBodySet,
+        multibodies: &MultibodyJointSet,
+        interactions: &[Interaction],
+    ) {
+        let mut color_len = [0; 128];
+        self.bodies_color.clear();
+        self.bodies_color.resize(num_island_bodies, 0u128);
         let bcolors = &mut self.bodies_color;
 
         for (interaction_id, color) in self
@@ -92,22 +131,9 @@ impl ParallelInteractionGroups {
             let is_fixed1 = body_pair.0.map(|b| bodies[b].is_fixed()).unwrap_or(true);
             let is_fixed2 = body_pair.1.map(|b| bodies[b].is_fixed()).unwrap_or(true);
 
-            let representative = |handle: RigidBodyHandle| {
-                if let Some(link) = multibodies.rigid_body_link(handle).copied() {
-                    let multibody = multibodies.get_multibody(link.multibody).unwrap();
-                    multibody
-                        .link(1) // Use the link 1 to cover the case where the multibody root is fixed.
-                        .or(multibody.link(0)) // TODO: Never happens?
-                        .map(|l| l.rigid_body)
-                        .unwrap()
-                } else {
-                    handle
-                }
-            };
-
             body_pair = (
-                body_pair.0.map(representative),
-                body_pair.1.map(representative),
+                body_pair.0.map(|h| representative(multibodies, h)),
+                body_pair.1.map(|h| representative(multibodies, h)),
             );
 
             match (is_fixed1, is_fixed2) {
@@ -151,9 +177,10 @@ impl ParallelInteractionGroups {
         }
 
         self.sorted_interactions
-            .resize(interaction_indices.len(), 0);
+            .resize(self.interaction_indices.len(), 0);
 
-        for (interaction_id, color) in interaction_indices
+        for (interaction_id, color) in self
+            .interaction_indices
             .iter()
             .zip(self.interaction_colors.iter())
         {
@@ -163,6 +190,105 @@ impl ParallelInteractionGroups {
 
         self.groups.push(self.sorted_interactions.len());
     }
+
+    /// Assigns to each interaction the group following the last group containing an
+    /// interaction involving the same bodies.
+    ///
+    /// The interactions involving a given body are then solved in the order they are given in,
+    /// like the sequential solver does, so the results only depend on that order.
+    #[cfg(feature = "enhanced-determinism")]
+    fn level_interactions<Interaction: PairInteraction>(
+        &mut self,
+        num_island_bodies: usize,
+        bodies: &RigidBodySet,
+        multibodies: &MultibodyJointSet,
+        interactions: &[Interaction],
+    ) {
+        // The number of groups containing an interaction with each body.
+        self.body_levels.clear();
+        self.body_levels.resize(num_island_bodies, 0);
+        let mut num_levels = 0;
+
+        for (interaction_id, level) in self
+            .interaction_indices
+            .iter()
+            .zip(self.interaction_colors.iter_mut())
+        {
+            let (handle1, handle2) = interactions[*interaction_id].body_pair();
+            // Only the velocities of the dynamic bodies are modified by the constraints.
+            let offset = |handle: Option<RigidBodyHandle>| {
+                let rb = &bodies[representative(multibodies, handle?)];
+                rb.is_dynamic().then_some(rb.ids.active_set_offset)
+            };
+            let (offset1, offset2) = (offset(handle1), offset(handle2));
+
+            *level = [offset1, offset2]
+                .into_iter()
+                .flatten()
+                .map(|i| self.body_levels[i])
+                .max()
+                .unwrap_or(0);
+            for i in [offset1, offset2].into_iter().flatten() {
+                self.body_levels[i] = *level + 1;
+            }
+            num_levels = num_levels.max(*level + 1);
+        }
+
+        // Every level contains at least one interaction, so each of them is a group.
+        self.groups.resize(num_levels + 1, 0);
+        for level in &self.interaction_colors {
+            self.groups[*level + 1] += 1;
+        }
+        for i in 0..num_levels {
+            self.groups[i + 1] += self.groups[i];
+        }
+
+        // Reuse the levels buffer for the insertion offset of each group.
+        self.body_levels.clear();
+        self.body_levels
+            .extend_from_slice(&self.groups[..num_levels]);
+        self.sorted_interactions
+            .resize(self.interaction_indices.len(), 0);
+
+        for (interaction_id, level) in self
+            .interaction_indices
+            .iter()
+            .zip(self.interaction_colors.iter())
+        {
+            self.sorted_interactions[self.body_levels[*level]] = *interaction_id;
+            self.body_levels[*level] += 1;
+        }
+    }
+}
+
+/// The rigid-body standing for all the links of the multibody `handle` is part of, if any.
+#[cfg(feature = "parallel")]
+fn representative(multibodies: &MultibodyJointSet, handle: RigidBodyHandle) -> RigidBodyHandle {
+    if let Some(link) = multibodies.rigid_body_link(handle).copied() {
+        let multibody = multibodies.get_multibody(link.multibody).unwrap();
+        multibody
+            .link(1) // Use the link 1 to cover the case where the multibody root is fixed.
+            .or(multibody.link(0)) // TODO: Never happens?
+            .map(|l| l.rigid_body)
+            .unwrap()
+    } else {
+        handle
+    }
+}
+
+/// Sorts the interactions by the handles of the bodies involved, so the resulting constraints
+/// don't depend on the order the interactions were given in.
+///
+/// The sort is stable, so interactions between the same bodies remain in the given order.
+#[cfg(any(feature = "parallel", feature = "enhanced-determinism"))]
+pub(crate) fn sort_interactions_by_bodies<Interaction: PairInteraction>(
+    interactions: &[Interaction],
+    interaction_indices: &mut [usize],
+) {
+    interaction_indices.sort_by_key(|id| {
+        let (handle1, handle2) = interactions[*id].body_pair();
+        (handle1.map(|h| h.0), handle2.map(|h| h.0))
+    });
 }
 
 pub(crate) struct InteractionGroups {
@@ -188,6 +314,15 @@ impl InteractionGroups {
         }
     }
 
+    pub fn with_capacity(num_interactions: usize) -> Self {
+        Self {
+            #[cfg(feature = "simd-is-enabled")]
+            grouped_interactions: Vec::with_capacity(num_interactions),
+            nongrouped_interactions: Vec::with_capacity(num_interactions),
+            ..Self::new()
+        }
+    }
+
     // #[cfg(not(feature = "parallel"))]
     // pub fn clear(&mut self) {
     //     #[cfg(feature = "simd-is-enabled")]
@@ -351,6 +486,15 @@ impl InteractionGroups {
         //        );
     }
 
+    /// The interaction indices packed into each SIMD group, exactly as they are gathered by
+    /// the constraints solver.
+    #[cfg(all(feature = "simd-is-enabled", feature = "debug-simd-groups"))]
+    pub fn simd_groups(&self) -> impl Iterator<Item = [usize; SIMD_WIDTH]> + '_ {
+        self.grouped_interactions
+            .chunks_exact(SIMD_WIDTH)
+            .map(|group| gather![|ii| group[ii]])
+    }
+
     pub fn clear_groups(&mut self) {
         #[cfg(feature = "simd-is-enabled")]
         self.grouped_interactions.clear();
@@ -410,6 +554,13 @@ impl InteractionGroups {
                     continue;
                 }
 
+                // NOTE: the SIMD constraints don't support anisotropic friction.
+                #[cfg(feature = "dim3")]
+                if interaction.data.anisotropic_friction.is_some() {
+                    self.nongrouped_interactions.push(*interaction_i);
+                    continue;
+                }
+
                 let (status1, active_set_offset1) = if let Some(rb1) = interaction.data.rigid_body1
                 {
                     let rb1 = &bodies[rb1];
@@ -1,17 +1,25 @@
 use super::VelocitySolver;
 use crate::counters::Counters;
+#[cfg(feature = "enhanced-determinism")]
+use crate::dynamics::solver::sort_interactions_by_bodies;
 use crate::dynamics::solver::{
     AnyJointVelocityConstraint, AnyVelocityConstraint, SolverConstraints,
 };
 use crate::dynamics::IslandManager;
 use crate::dynamics::{IntegrationParameters, JointGraphEdge, JointIndex, RigidBodySet};
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
+#[cfg(all(feature = "simd-is-enabled", feature = "debug-simd-groups"))]
+use crate::math::SIMD_WIDTH;
 use crate::prelude::MultibodyJointSet;
 
 pub struct IslandSolver {
     contact_constraints: SolverConstraints<AnyVelocityConstraint>,
     joint_constraints: SolverConstraints<AnyJointVelocityConstraint>,
     velocity_solver: VelocitySolver,
+    #[cfg(feature = "enhanced-determinism")]
+    sorted_manifold_indices: Vec<ContactManifoldIndex>, // Workspace.
+    #[cfg(feature = "enhanced-determinism")]
+    sorted_joint_indices: Vec<JointIndex>, // Workspace.
 }
 
 impl Default for IslandSolver {
@@ -26,9 +34,36 @@ impl IslandSolver {
             contact_constraints: SolverConstraints::new(),
             joint_constraints: SolverConstraints::new(),
             velocity_solver: VelocitySolver::new(),
+            #[cfg(feature = "enhanced-determinism")]
+            sorted_manifold_indices: Vec::new(),
+            #[cfg(feature = "enhanced-determinism")]
+            sorted_joint_indices: Vec::new(),
         }
     }
 
+    /// An island solver with room for `num_contact_manifolds` contact manifolds.
+    pub fn with_capacity(num_contact_manifolds: usize) -> Self {
+        Self {
+            contact_constraints: SolverConstraints::with_capacity(num_contact_manifolds),
+            ..Self::new()
+        }
+    }
+
+    #[cfg(all(feature = "simd-is-enabled", feature = "debug-simd-groups"))]
+    pub fn contact_simd_groups(&self) -> Vec<[usize; SIMD_WIDTH]> {
+        self.contact_constraints.simd_groups()
+    }
+
+    #[cfg(all(feature = "simd-is-enabled", feature = "debug-simd-groups"))]
+    pub fn joint_simd_groups(&self) -> Vec<[usize; SIMD_WIDTH]> {
+        self.joint_constraints.simd_groups()
+    }
+
+    #[cfg(feature = "debug-solver-residuals")]
+    pub fn residuals(&self) -> &super::SolverResiduals {
+        &self.velocity_solver.residuals
+    }
+
     pub fn init_and_solve(
         &mut self,
         island_id: usize,
@@ -50,6 +85,25 @@ impl IslandSolver {
             solver_id += multibody.ndofs();
         }
 
+        // With enhanced-determinism, the parallel solver solves the constraints in the same
+        // order as this one, including the optional sorting of the parallel constraints.
+        #[cfg(feature = "enhanced-determinism")]
+        let (manifold_indices, joint_indices) = if params.sort_parallel_constraints {
+            self.sorted_manifold_indices.clear();
+            self.sorted_manifold_indices
+                .extend_from_slice(manifold_indices);
+            sort_interactions_by_bodies(manifolds, &mut self.sorted_manifold_indices);
+            self.sorted_joint_indices.clear();
+            self.sorted_joint_indices.extend_from_slice(joint_indices);
+            sort_interactions_by_bodies(impulse_joints, &mut self.sorted_joint_indices);
+            (
+                &self.sorted_manifold_indices[..],
+                &self.sorted_joint_indices[..],
+            )
+        } else {
+            (manifold_indices, joint_indices)
+        };
+
         counters.solver.velocity_assembly_time.resume();
         self.contact_constraints.init(
             island_id,
@@ -8,7 +8,7 @@ use crate::dynamics::{IntegrationParameters, JointIndex, Multibody};
 use crate::math::{Real, Vector, ANG_DIM, DIM, SPATIAL_DIM};
 use crate::utils::IndexMut2;
 use crate::utils::WDot;
-use na::{DVector, SVector};
+use na::{ComplexField, DVector, SVector};
 
 #[cfg(feature = "dim3")]
 use crate::utils::WAngularInertia;
@@ -354,7 +354,10 @@ impl JointVelocityConstraintBuilder<Real> {
             ang_jac,
         );
 
-        let s_limits = [(limits[0] / 2.0).sin(), (limits[1] / 2.0).sin()];
+        let s_limits = [
+            ComplexField::sin(limits[0] / 2.0),
+            ComplexField::sin(limits[1] / 2.0),
+        ];
         #[cfg(feature = "dim2")]
         let s_ang = self.ang_err.im;
         #[cfg(feature = "dim3")]
@@ -416,7 +419,7 @@ impl JointVelocityConstraintBuilder<Real> {
             let s_ang_dist = self.ang_err.im;
             #[cfg(feature = "dim3")]
             let s_ang_dist = self.ang_err.imag()[_motor_axis];
-            let s_target_ang = motor_params.target_pos.sin();
+            let s_target_ang = ComplexField::sin(motor_params.target_pos);
             rhs_wo_bias += (s_ang_dist - s_target_ang) * motor_params.erp_inv_dt;
         }
 
@@ -735,7 +738,10 @@ impl JointVelocityConstraintBuilder<Real> {
             ang_jac,
         );
 
-        let s_limits = [(limits[0] / 2.0).sin(), (limits[1] / 2.0).sin()];
+        let s_limits = [
+            ComplexField::sin(limits[0] / 2.0),
+            ComplexField::sin(limits[1] / 2.0),
+        ];
         #[cfg(feature = "dim2")]
         let s_ang = self.ang_err.im;
         #[cfg(feature = "dim3")]
@@ -794,7 +800,7 @@ impl JointVelocityConstraintBuilder<Real> {
             let s_ang_dist = self.ang_err.im;
             #[cfg(feature = "dim3")]
             let s_ang_dist = self.ang_err.imag()[_motor_axis];
-            let s_target_ang = motor_params.target_pos.sin();
+            let s_target_ang = ComplexField::sin(motor_params.target_pos);
             rhs += (s_ang_dist - s_target_ang) * motor_params.erp_inv_dt;
         }
 
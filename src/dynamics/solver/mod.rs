@@ -8,8 +8,12 @@ pub(self) use self::parallel_solver_constraints::ParallelSolverConstraints;
 pub(self) use self::parallel_velocity_solver::ParallelVelocitySolver;
 #[cfg(not(feature = "parallel"))]
 pub(self) use self::solver_constraints::SolverConstraints;
+#[cfg(all(feature = "debug-solver-residuals", not(feature = "parallel")))]
+pub use self::velocity_solver::SolverResiduals;
 #[cfg(not(feature = "parallel"))]
 pub(self) use self::velocity_solver::VelocitySolver;
+pub use contact_effective_mass::contact_effective_mass;
+pub(self) use contact_effective_mass::projected_contact_mass_from_gcross;
 pub(self) use delta_vel::DeltaVel;
 pub(self) use generic_velocity_constraint::*;
 pub(self) use generic_velocity_constraint_element::*;
@@ -27,6 +31,7 @@ pub(self) use velocity_ground_constraint_element::*;
 pub(self) use velocity_ground_constraint_wide::*;
 
 mod categorization;
+mod contact_effective_mass;
 mod delta_vel;
 mod generic_velocity_constraint;
 mod generic_velocity_constraint_element;
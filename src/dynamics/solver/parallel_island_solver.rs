@@ -2,6 +2,11 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 
 use rayon::Scope;
 
+#[cfg(feature = "enhanced-determinism")]
+use crate::dynamics::solver::{
+    categorization::{categorize_contacts, categorize_joints},
+    sort_interactions_by_bodies,
+};
 use crate::dynamics::solver::{
     AnyJointVelocityConstraint, AnyVelocityConstraint, ParallelSolverConstraints,
 };
@@ -140,6 +145,12 @@ pub struct ParallelIslandSolver {
     parallel_contact_constraints: ParallelSolverConstraints<AnyVelocityConstraint>,
     parallel_joint_constraints: ParallelSolverConstraints<AnyJointVelocityConstraint>,
     thread: ThreadContext,
+    #[cfg(feature = "enhanced-determinism")]
+    ordered_manifold_indices: Vec<ContactManifoldIndex>, // Workspace.
+    #[cfg(feature = "enhanced-determinism")]
+    ordered_joint_indices: Vec<JointIndex>, // Workspace.
+    #[cfg(feature = "enhanced-determinism")]
+    categories: [Vec<usize>; 4], // Workspace.
 }
 
 impl Default for ParallelIslandSolver {
@@ -157,6 +168,23 @@ impl ParallelIslandSolver {
             parallel_contact_constraints: ParallelSolverConstraints::new(),
             parallel_joint_constraints: ParallelSolverConstraints::new(),
             thread: ThreadContext::new(8),
+            #[cfg(feature = "enhanced-determinism")]
+            ordered_manifold_indices: Vec::new(),
+            #[cfg(feature = "enhanced-determinism")]
+            ordered_joint_indices: Vec::new(),
+            #[cfg(feature = "enhanced-determinism")]
+            categories: Default::default(),
+        }
+    }
+
+    /// An island solver with room for `num_contact_manifolds` contact manifolds.
+    pub fn with_capacity(num_contact_manifolds: usize) -> Self {
+        Self {
+            parallel_groups: ParallelInteractionGroups::with_capacity(num_contact_manifolds),
+            parallel_contact_constraints: ParallelSolverConstraints::with_capacity(
+                num_contact_manifolds,
+            ),
+            ..Self::new()
         }
     }
 
@@ -177,6 +205,72 @@ impl ParallelIslandSolver {
         let num_task_per_island = num_threads; // (num_threads / num_islands).max(1); // TODO: not sure this is the best value. Also, perhaps it is better to interleave tasks of each island?
         self.thread = ThreadContext::new(8); // TODO: could we compute some kind of optimal value here?
 
+        // With enhanced-determinism, the interactions are given to the grouping in the order
+        // the sequential solver solves their constraints, and the groups preserve the order of
+        // the interactions involving each body. Because the constraints of a group never involve
+        // the same body twice, the results are then identical to the sequential solver’s.
+        #[cfg(feature = "enhanced-determinism")]
+        let (manifold_indices, joint_indices, sort_interactions) = {
+            let [ground, not_ground, generic_ground, generic_not_ground] = &mut self.categories;
+
+            self.ordered_manifold_indices.clear();
+            self.ordered_manifold_indices
+                .extend_from_slice(manifold_indices);
+            if params.sort_parallel_constraints {
+                sort_interactions_by_bodies(manifolds, &mut self.ordered_manifold_indices);
+            }
+            ground.clear();
+            not_ground.clear();
+            generic_ground.clear();
+            generic_not_ground.clear();
+            categorize_contacts(
+                bodies,
+                multibodies,
+                manifolds,
+                &self.ordered_manifold_indices,
+                ground,
+                not_ground,
+                generic_ground,
+                generic_not_ground,
+            );
+            self.ordered_manifold_indices.clear();
+            for category in [&*not_ground, generic_not_ground, ground, generic_ground] {
+                self.ordered_manifold_indices.extend_from_slice(category);
+            }
+
+            self.ordered_joint_indices.clear();
+            self.ordered_joint_indices.extend_from_slice(joint_indices);
+            if params.sort_parallel_constraints {
+                sort_interactions_by_bodies(impulse_joints, &mut self.ordered_joint_indices);
+            }
+            ground.clear();
+            not_ground.clear();
+            generic_ground.clear();
+            generic_not_ground.clear();
+            categorize_joints(
+                bodies,
+                multibodies,
+                impulse_joints,
+                &self.ordered_joint_indices,
+                ground,
+                not_ground,
+                generic_ground,
+                generic_not_ground,
+            );
+            self.ordered_joint_indices.clear();
+            for category in [&*not_ground, generic_not_ground, ground, generic_ground] {
+                self.ordered_joint_indices.extend_from_slice(category);
+            }
+
+            (
+                &self.ordered_manifold_indices[..],
+                &self.ordered_joint_indices[..],
+                false,
+            )
+        };
+        #[cfg(not(feature = "enhanced-determinism"))]
+        let sort_interactions = params.sort_parallel_constraints;
+
         // Interactions grouping.
         self.parallel_groups.group_interactions(
             island_id,
@@ -185,6 +279,7 @@ impl ParallelIslandSolver {
             multibodies,
             manifolds,
             manifold_indices,
+            sort_interactions,
         );
         self.parallel_joint_groups.group_interactions(
             island_id,
@@ -193,6 +288,7 @@ impl ParallelIslandSolver {
             multibodies,
             impulse_joints,
             joint_indices,
+            sort_interactions,
         );
 
         let mut contact_j_id = 0;
@@ -75,6 +75,18 @@ impl<VelocityConstraint> ParallelSolverConstraints<VelocityConstraint> {
             parallel_desc_groups: vec![],
         }
     }
+
+    pub fn with_capacity(num_interactions: usize) -> Self {
+        Self {
+            not_ground_interactions: Vec::with_capacity(num_interactions),
+            ground_interactions: Vec::with_capacity(num_interactions),
+            interaction_groups: InteractionGroups::with_capacity(num_interactions),
+            ground_interaction_groups: InteractionGroups::with_capacity(num_interactions),
+            velocity_constraints: Vec::with_capacity(num_interactions),
+            constraint_descs: Vec::with_capacity(num_interactions),
+            ..Self::new()
+        }
+    }
 }
 
 macro_rules! impl_init_constraints_group {
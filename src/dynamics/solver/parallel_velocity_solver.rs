@@ -50,6 +50,16 @@ impl ParallelVelocitySolver {
         // solve. If the batch size is large enough to cross the boundary of
         // a parallel_desc_group, we have to wait util the current group is finished
         // before starting the next one.
+        //
+        // NOTE: there is no concurrent accumulation of impulses here. The constraints
+        //       of a given parallel_desc_group never involve the same body twice, so
+        //       each `mj_lambdas` entry is written by a single constraint per group,
+        //       and the groups are processed in a fixed order. Therefore, the result
+        //       doesn’t depend on the number of threads or on their scheduling.
+        //       It may still differ from the result of the sequential solver (without
+        //       the `parallel` feature) because the constraints aren’t solved in the
+        //       same order, unless the `enhanced-determinism` feature is enabled (see
+        //       `ParallelIslandSolver::init_and_solve`).
         macro_rules! solve {
             ($part: expr, $($solve_args: expr),*) => {
                 for group in $part.parallel_desc_groups.windows(2) {
@@ -131,16 +141,21 @@ impl ParallelVelocitySolver {
                 start_index -= contact_descs.len();
 
                 // Solve generic rigid-body contacts.
-                solve!(
-                    contact_constraints,
-                    &contact_constraints.generic_jacobians,
-                    &mut self.mj_lambdas,
-                    &mut self.generic_mj_lambdas,
-                    true,
-                    false
-                );
-                shift += contact_descs.len();
-                start_index -= contact_descs.len();
+                // NOTE: this solves all the contacts a second time, which the sequential solver
+                //       doesn’t do, so it is skipped to match its results.
+                #[cfg(not(feature = "enhanced-determinism"))]
+                {
+                    solve!(
+                        contact_constraints,
+                        &contact_constraints.generic_jacobians,
+                        &mut self.mj_lambdas,
+                        &mut self.generic_mj_lambdas,
+                        true,
+                        false
+                    );
+                    shift += contact_descs.len();
+                    start_index -= contact_descs.len();
+                }
 
                 if solve_friction {
                     solve!(
@@ -220,6 +235,7 @@ impl ParallelVelocitySolver {
                             &rb.pos.position,
                             &rb.mprops.local_mprops.local_com,
                         );
+                        rb.integrated_vels = new_vels;
                     }
                 }
             }
@@ -310,6 +326,7 @@ impl ParallelVelocitySolver {
                         rb.vels.linvel += dvel.linear;
                         rb.vels.angvel += dangvel;
                         rb.vels = rb.vels.apply_damping(params.dt, &rb.damping);
+                        rb.accumulate_position_correction(params.dt);
                     }
                 }
             }
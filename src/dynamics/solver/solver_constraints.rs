@@ -41,6 +41,27 @@ impl<VelocityConstraint> SolverConstraints<VelocityConstraint> {
         }
     }
 
+    pub fn with_capacity(num_interactions: usize) -> Self {
+        Self {
+            not_ground_interactions: Vec::with_capacity(num_interactions),
+            ground_interactions: Vec::with_capacity(num_interactions),
+            interaction_groups: InteractionGroups::with_capacity(num_interactions),
+            ground_interaction_groups: InteractionGroups::with_capacity(num_interactions),
+            velocity_constraints: Vec::with_capacity(num_interactions),
+            ..Self::new()
+        }
+    }
+
+    /// The interaction indices packed into each SIMD group, for the non-ground interactions
+    /// first, then the ground interactions.
+    #[cfg(all(feature = "simd-is-enabled", feature = "debug-simd-groups"))]
+    pub fn simd_groups(&self) -> Vec<[usize; SIMD_WIDTH]> {
+        self.interaction_groups
+            .simd_groups()
+            .chain(self.ground_interaction_groups.simd_groups())
+            .collect()
+    }
+
     // pub fn clear(&mut self) {
     //     self.not_ground_interactions.clear();
     //     self.ground_interactions.clear();
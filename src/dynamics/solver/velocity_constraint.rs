@@ -3,13 +3,18 @@ use crate::dynamics::solver::{
 };
 #[cfg(feature = "simd-is-enabled")]
 use crate::dynamics::solver::{WVelocityConstraint, WVelocityGroundConstraint};
+#[cfg(feature = "dim3")]
+use crate::dynamics::FrictionModel;
 use crate::dynamics::{IntegrationParameters, RigidBodySet};
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
 use crate::math::{Real, Vector, DIM, MAX_MANIFOLD_POINTS};
 use crate::utils::{self, WAngularInertia, WBasis, WCross, WDot};
 use na::DVector;
 
-use super::{DeltaVel, VelocityConstraintElement, VelocityConstraintNormalPart};
+use super::{
+    projected_contact_mass_from_gcross, DeltaVel, VelocityConstraintElement,
+    VelocityConstraintNormalPart,
+};
 
 //#[repr(align(64))]
 #[derive(Copy, Clone, Debug)]
@@ -123,6 +128,10 @@ pub(crate) struct VelocityConstraint {
     pub im2: Vector<Real>,
     pub cfm_factor: Real,
     pub limit: Real,
+    #[cfg(feature = "dim3")]
+    pub friction_model: FrictionModel,
+    #[cfg(feature = "dim3")]
+    pub friction_scales: Option<[Real; 2]>,
     pub mj_lambda1: usize,
     pub mj_lambda2: usize,
     pub manifold_id: ContactManifoldIndex,
@@ -151,9 +160,11 @@ impl VelocityConstraint {
     ) {
         assert_eq!(manifold.data.relative_dominance, 0);
 
-        let cfm_factor = params.cfm_factor();
+        let (erp_inv_dt, cfm_factor) = manifold
+            .data
+            .contact_softness
+            .erp_inv_dt_and_cfm_factor(params);
         let inv_dt = params.inv_dt();
-        let erp_inv_dt = params.erp_inv_dt();
 
         let handle1 = manifold.data.rigid_body1.unwrap();
         let handle2 = manifold.data.rigid_body2.unwrap();
@@ -163,6 +174,10 @@ impl VelocityConstraint {
         let rb2 = &bodies[handle2];
         let (vels2, mprops2) = (&rb2.vels, &rb2.mprops);
         let ccd_thickness = rb1.ccd.ccd_thickness + rb2.ccd.ccd_thickness;
+        let max_penetration_correction = params
+            .max_penetration_correction
+            .min(rb1.max_penetration_correction)
+            .min(rb2.max_penetration_correction);
 
         let mj_lambda1 = rb1.ids.active_set_offset;
         let mj_lambda2 = rb2.ids.active_set_offset;
@@ -171,8 +186,11 @@ impl VelocityConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let (tangents1, friction_scales) = super::anisotropic_friction_directions(
+            manifold,
+            &force_dir1,
+            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel),
+        );
 
         for (_l, manifold_points) in manifold
             .data
@@ -192,6 +210,10 @@ impl VelocityConstraint {
                 im2: mprops2.effective_inv_mass,
                 cfm_factor,
                 limit: 0.0,
+                #[cfg(feature = "dim3")]
+                friction_model: params.friction_model,
+                #[cfg(feature = "dim3")]
+                friction_scales,
                 mj_lambda1,
                 mj_lambda2,
                 manifold_id,
@@ -241,6 +263,11 @@ impl VelocityConstraint {
                 constraint.im2 = mprops2.effective_inv_mass;
                 constraint.cfm_factor = cfm_factor;
                 constraint.limit = 0.0;
+                #[cfg(feature = "dim3")]
+                {
+                    constraint.friction_model = params.friction_model;
+                    constraint.friction_scales = friction_scales;
+                }
                 constraint.mj_lambda1 = mj_lambda1;
                 constraint.mj_lambda2 = mj_lambda2;
                 constraint.manifold_id = manifold_id;
@@ -269,10 +296,11 @@ impl VelocityConstraint {
                         .transform_vector(dp2.gcross(-force_dir1));
 
                     let imsum = mprops1.effective_inv_mass + mprops2.effective_inv_mass;
-                    let projected_mass = utils::inv(
-                        force_dir1.dot(&imsum.component_mul(&force_dir1))
-                            + gcross1.gdot(gcross1)
-                            + gcross2.gdot(gcross2),
+                    let projected_mass = projected_contact_mass_from_gcross(
+                        &imsum,
+                        &force_dir1,
+                        Some(gcross1),
+                        gcross2,
                     );
 
                     let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
@@ -284,7 +312,7 @@ impl VelocityConstraint {
                     rhs_wo_bias *= is_bouncy + is_resting;
                     let rhs_bias = /* is_resting
                         * */  erp_inv_dt
-                        * (manifold_point.dist + params.allowed_linear_error).clamp(-params.max_penetration_correction, 0.0);
+                        * (manifold_point.dist + params.allowed_linear_error).clamp(-max_penetration_correction, 0.0);
 
                     let rhs = rhs_wo_bias + rhs_bias;
                     is_fast_contact = is_fast_contact || (-rhs * params.dt > ccd_thickness * 0.5);
@@ -296,6 +324,8 @@ impl VelocityConstraint {
                         rhs_wo_bias,
                         impulse: na::zero(),
                         r: projected_mass,
+                        max_impulse: manifold.data.max_contact_impulse,
+                        unclamped_impulse: na::zero(),
                     };
                 }
 
@@ -367,6 +397,10 @@ impl VelocityConstraint {
             &self.im1,
             &self.im2,
             self.limit,
+            #[cfg(feature = "dim3")]
+            self.friction_model,
+            #[cfg(feature = "dim3")]
+            self.friction_scales.as_ref(),
             &mut mj_lambda1,
             &mut mj_lambda2,
             solve_normal,
@@ -379,11 +413,18 @@ impl VelocityConstraint {
 
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
         let manifold = &mut manifolds_all[self.manifold_id];
+        writeback_tangents(
+            manifold,
+            &self.dir1,
+            #[cfg(feature = "dim3")]
+            &self.tangent1,
+        );
 
         for k in 0..self.num_contacts as usize {
             let contact_id = self.manifold_contact_id[k];
             let active_contact = &mut manifold.points[contact_id as usize];
             active_contact.data.impulse = self.elements[k].normal_part.impulse;
+            active_contact.data.unclamped_impulse = self.elements[k].normal_part.unclamped_impulse;
 
             #[cfg(feature = "dim2")]
             {
@@ -404,6 +445,34 @@ impl VelocityConstraint {
     }
 }
 
+/// Stores the friction directions of a contact constraint into `manifold.data.tangents`.
+///
+/// The friction impulses are applied along the tangents to the first body of the constraint,
+/// and along their opposite to its second body. The constraint's bodies are swapped relative
+/// to the colliders of the manifold if `dir1` points along the contact normal.
+#[inline]
+pub(crate) fn writeback_tangents(
+    manifold: &mut ContactManifold,
+    dir1: &Vector<Real>,
+    #[cfg(feature = "dim3")] tangent1: &Vector<Real>,
+) {
+    // Orient the tangents so they give the impulse applied to the body of `collider2`.
+    let sign = if dir1.dot(&manifold.data.normal) < 0.0 {
+        -1.0
+    } else {
+        1.0
+    };
+
+    #[cfg(feature = "dim2")]
+    {
+        manifold.data.tangents = [dir1.orthonormal_vector() * sign];
+    }
+    #[cfg(feature = "dim3")]
+    {
+        manifold.data.tangents = [tangent1 * sign, dir1.cross(tangent1) * sign];
+    }
+}
+
 #[inline(always)]
 #[cfg(feature = "dim3")]
 pub(crate) fn compute_tangent_contact_directions<N>(
@@ -439,3 +508,33 @@ where
 
     [tangent1, bitangent1]
 }
+
+/// Aligns the friction directions `tangents1` of `manifold` with the principal direction of its
+/// anisotropic friction, if any.
+///
+/// Also returns the scale factors applied by the solver to the friction limit along each of the
+/// returned directions, or `None` if the friction is isotropic.
+#[cfg(feature = "dim3")]
+pub(crate) fn anisotropic_friction_directions(
+    manifold: &ContactManifold,
+    force_dir1: &Vector<Real>,
+    tangents1: [Vector<Real>; DIM - 1],
+) -> ([Vector<Real>; DIM - 1], Option<[Real; 2]>) {
+    let friction = match &manifold.data.anisotropic_friction {
+        Some(friction) => friction,
+        None => return (tangents1, None),
+    };
+
+    let inv_max_friction = utils::inv(friction.friction_along.max(friction.friction_across));
+    let scale_along = friction.friction_along * inv_max_friction;
+    let scale_across = friction.friction_across * inv_max_friction;
+    let mut tangent1 = friction.direction - force_dir1 * force_dir1.dot(&friction.direction);
+
+    if tangent1.try_normalize_mut(1.0e-4).is_some() {
+        let bitangent1 = force_dir1.cross(&tangent1);
+        ([tangent1, bitangent1], Some([scale_along, scale_across]))
+    } else {
+        // The principal direction is along the normal, so every tangent direction is across it.
+        (tangents1, Some([scale_across, scale_across]))
+    }
+}
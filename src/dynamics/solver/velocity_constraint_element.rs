@@ -1,7 +1,33 @@
 use super::DeltaVel;
+#[cfg(feature = "dim3")]
+use crate::dynamics::FrictionModel;
 use crate::math::{AngVector, Vector, DIM};
 use crate::utils::{WBasis, WDot, WReal};
 
+/// Projects the friction impulse `impulse` with `project` after dividing each of its components by
+/// the anisotropic `friction_scales`, so that the friction limit enforced by `project` is scaled
+/// independently along each friction direction.
+#[cfg(feature = "dim3")]
+#[inline(always)]
+pub(crate) fn project_anisotropic_friction_impulse<N: WReal>(
+    impulse: na::Vector2<N>,
+    friction_scales: Option<&[N; 2]>,
+    project: impl FnOnce(na::Vector2<N>) -> na::Vector2<N>,
+) -> na::Vector2<N> {
+    match friction_scales {
+        Some(scales) => {
+            // NOTE: a zero scale maps to a zero impulse along its direction.
+            let scaled = na::vector![
+                impulse[0] * crate::utils::simd_inv(scales[0]),
+                impulse[1] * crate::utils::simd_inv(scales[1])
+            ];
+            let projected = project(scaled);
+            na::vector![projected[0] * scales[0], projected[1] * scales[1]]
+        }
+        None => project(impulse),
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct VelocityConstraintTangentPart<N: WReal> {
     pub gcross1: [AngVector<N>; DIM - 1],
@@ -38,6 +64,8 @@ impl<N: WReal> VelocityConstraintTangentPart<N> {
         im1: &Vector<N>,
         im2: &Vector<N>,
         limit: N,
+        #[cfg(feature = "dim3")] friction_model: FrictionModel,
+        #[cfg(feature = "dim3")] friction_scales: Option<&[N; 2]>,
         mj_lambda1: &mut DeltaVel<N>,
         mj_lambda2: &mut DeltaVel<N>,
     ) where
@@ -87,7 +115,15 @@ impl<N: WReal> VelocityConstraintTangentPart<N> {
                 let _disable_fe_except =
                         crate::utils::DisableFloatingPointExceptionsFlags::
                         disable_floating_point_exceptions();
-                new_impulse.simd_cap_magnitude(limit)
+                project_anisotropic_friction_impulse(new_impulse, friction_scales, |impulse| {
+                    match friction_model {
+                        FrictionModel::Cone => impulse.simd_cap_magnitude(limit),
+                        FrictionModel::Pyramid => na::vector![
+                            impulse[0].simd_clamp(-limit, limit),
+                            impulse[1].simd_clamp(-limit, limit)
+                        ],
+                    }
+                })
             };
 
             let dlambda = new_impulse - self.impulse;
@@ -112,6 +148,10 @@ pub(crate) struct VelocityConstraintNormalPart<N: WReal> {
     pub rhs_wo_bias: N,
     pub impulse: N,
     pub r: N,
+    // The impulse is clamped to this value, see `ContactManifoldData::max_contact_impulse`.
+    pub max_impulse: N,
+    // The impulse computed by the last iteration, before being clamped to `max_impulse`.
+    pub unclamped_impulse: N,
 }
 
 impl<N: WReal> VelocityConstraintNormalPart<N> {
@@ -123,6 +163,8 @@ impl<N: WReal> VelocityConstraintNormalPart<N> {
             rhs_wo_bias: na::zero(),
             impulse: na::zero(),
             r: na::zero(),
+            max_impulse: na::zero(),
+            unclamped_impulse: na::zero(),
         }
     }
 
@@ -142,7 +184,9 @@ impl<N: WReal> VelocityConstraintNormalPart<N> {
             - dir1.dot(&mj_lambda2.linear)
             + self.gcross2.gdot(mj_lambda2.angular)
             + self.rhs;
-        let new_impulse = cfm_factor * (self.impulse - self.r * dvel).simd_max(N::zero());
+        let unclamped_impulse = cfm_factor * (self.impulse - self.r * dvel).simd_max(N::zero());
+        let new_impulse = unclamped_impulse.simd_min(self.max_impulse);
+        self.unclamped_impulse = unclamped_impulse;
         let dlambda = new_impulse - self.impulse;
         self.impulse = new_impulse;
 
@@ -177,6 +221,8 @@ impl<N: WReal> VelocityConstraintElement<N> {
         im1: &Vector<N>,
         im2: &Vector<N>,
         limit: N,
+        #[cfg(feature = "dim3")] friction_model: FrictionModel,
+        #[cfg(feature = "dim3")] friction_scales: Option<&[N; 2]>,
         mj_lambda1: &mut DeltaVel<N>,
         mj_lambda2: &mut DeltaVel<N>,
         solve_normal: bool,
@@ -204,7 +250,18 @@ impl<N: WReal> VelocityConstraintElement<N> {
             for element in elements.iter_mut() {
                 let limit = limit * element.normal_part.impulse;
                 let part = &mut element.tangent_part;
-                part.solve(tangents1, im1, im2, limit, mj_lambda1, mj_lambda2);
+                part.solve(
+                    tangents1,
+                    im1,
+                    im2,
+                    limit,
+                    #[cfg(feature = "dim3")]
+                    friction_model,
+                    #[cfg(feature = "dim3")]
+                    friction_scales,
+                    mj_lambda1,
+                    mj_lambda2,
+                );
             }
         }
     }
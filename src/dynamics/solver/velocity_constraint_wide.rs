@@ -1,6 +1,9 @@
 use super::{
-    AnyVelocityConstraint, DeltaVel, VelocityConstraintElement, VelocityConstraintNormalPart,
+    projected_contact_mass_from_gcross, writeback_tangents, AnyVelocityConstraint, DeltaVel,
+    VelocityConstraintElement, VelocityConstraintNormalPart,
 };
+#[cfg(feature = "dim3")]
+use crate::dynamics::FrictionModel;
 use crate::dynamics::{
     IntegrationParameters, RigidBodyIds, RigidBodyMassProps, RigidBodySet, RigidBodyVelocity,
 };
@@ -26,6 +29,8 @@ pub(crate) struct WVelocityConstraint {
     pub im2: Vector<SimdReal>,
     pub cfm_factor: SimdReal,
     pub limit: SimdReal,
+    #[cfg(feature = "dim3")]
+    pub friction_model: FrictionModel,
     pub mj_lambda1: [usize; SIMD_WIDTH],
     pub mj_lambda2: [usize; SIMD_WIDTH],
     pub manifold_id: [ContactManifoldIndex; SIMD_WIDTH],
@@ -45,15 +50,24 @@ impl WVelocityConstraint {
             assert_eq!(manifolds[ii].data.relative_dominance, 0);
         }
 
-        let cfm_factor = SimdReal::splat(params.cfm_factor());
         let dt = SimdReal::splat(params.dt);
         let inv_dt = SimdReal::splat(params.inv_dt());
         let allowed_lin_err = SimdReal::splat(params.allowed_linear_error);
-        let erp_inv_dt = SimdReal::splat(params.erp_inv_dt());
-        let max_penetration_correction = SimdReal::splat(params.max_penetration_correction);
+        let coeffs = gather![|ii| manifolds[ii]
+            .data
+            .contact_softness
+            .erp_inv_dt_and_cfm_factor(params)];
+        let erp_inv_dt = SimdReal::from(gather![|ii| coeffs[ii].0]);
+        let cfm_factor = SimdReal::from(gather![|ii| coeffs[ii].1]);
+        let max_contact_impulse =
+            SimdReal::from(gather![|ii| manifolds[ii].data.max_contact_impulse]);
 
         let handles1 = gather![|ii| manifolds[ii].data.rigid_body1.unwrap()];
         let handles2 = gather![|ii| manifolds[ii].data.rigid_body2.unwrap()];
+        let max_penetration_correction = SimdReal::from(gather![|ii| params
+            .max_penetration_correction
+            .min(bodies[handles1[ii]].max_penetration_correction)
+            .min(bodies[handles2[ii]].max_penetration_correction)]);
 
         let vels1: [&RigidBodyVelocity; SIMD_WIDTH] = gather![|ii| &bodies[handles1[ii]].vels];
         let vels2: [&RigidBodyVelocity; SIMD_WIDTH] = gather![|ii| &bodies[handles2[ii]].vels];
@@ -109,6 +123,8 @@ impl WVelocityConstraint {
                 im2,
                 cfm_factor,
                 limit: SimdReal::splat(0.0),
+                #[cfg(feature = "dim3")]
+                friction_model: params.friction_model,
                 mj_lambda1,
                 mj_lambda2,
                 manifold_id,
@@ -142,10 +158,11 @@ impl WVelocityConstraint {
                     let gcross2 = ii2.transform_vector(dp2.gcross(-force_dir1));
 
                     let imsum = im1 + im2;
-                    let projected_mass = utils::simd_inv(
-                        force_dir1.dot(&imsum.component_mul(&force_dir1))
-                            + gcross1.gdot(gcross1)
-                            + gcross2.gdot(gcross2),
+                    let projected_mass = projected_contact_mass_from_gcross(
+                        &imsum,
+                        &force_dir1,
+                        Some(gcross1),
+                        gcross2,
                     );
 
                     let projected_velocity = (vel1 - vel2).dot(&force_dir1);
@@ -168,6 +185,8 @@ impl WVelocityConstraint {
                         rhs_wo_bias,
                         impulse: SimdReal::splat(0.0),
                         r: projected_mass,
+                        max_impulse: max_contact_impulse,
+                        unclamped_impulse: na::zero(),
                     };
                 }
 
@@ -243,6 +262,10 @@ impl WVelocityConstraint {
             &self.im1,
             &self.im2,
             self.limit,
+            #[cfg(feature = "dim3")]
+            self.friction_model,
+            #[cfg(feature = "dim3")]
+            None,
             &mut mj_lambda1,
             &mut mj_lambda2,
             solve_normal,
@@ -260,8 +283,19 @@ impl WVelocityConstraint {
     }
 
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
+        for ii in 0..SIMD_WIDTH {
+            writeback_tangents(
+                manifolds_all[self.manifold_id[ii]],
+                &self.dir1.extract(ii),
+                #[cfg(feature = "dim3")]
+                &self.tangent1.extract(ii),
+            );
+        }
+
         for k in 0..self.num_contacts as usize {
             let impulses: [_; SIMD_WIDTH] = self.elements[k].normal_part.impulse.into();
+            let unclamped_impulses: [_; SIMD_WIDTH] =
+                self.elements[k].normal_part.unclamped_impulse.into();
             #[cfg(feature = "dim2")]
             let tangent_impulses: [_; SIMD_WIDTH] = self.elements[k].tangent_part.impulse[0].into();
             #[cfg(feature = "dim3")]
@@ -272,6 +306,7 @@ impl WVelocityConstraint {
                 let contact_id = self.manifold_contact_id[k][ii];
                 let active_contact = &mut manifold.points[contact_id as usize];
                 active_contact.data.impulse = impulses[ii];
+                active_contact.data.unclamped_impulse = unclamped_impulses[ii];
 
                 #[cfg(feature = "dim2")]
                 {
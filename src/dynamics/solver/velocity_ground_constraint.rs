@@ -1,12 +1,14 @@
 use super::{
-    AnyVelocityConstraint, DeltaVel, VelocityGroundConstraintElement,
-    VelocityGroundConstraintNormalPart,
+    projected_contact_mass_from_gcross, writeback_tangents, AnyVelocityConstraint, DeltaVel,
+    VelocityGroundConstraintElement, VelocityGroundConstraintNormalPart,
 };
 use crate::math::{Point, Real, Vector, DIM, MAX_MANIFOLD_POINTS};
 #[cfg(feature = "dim2")]
 use crate::utils::WBasis;
 use crate::utils::{self, WAngularInertia, WCross, WDot};
 
+#[cfg(feature = "dim3")]
+use crate::dynamics::FrictionModel;
 use crate::dynamics::{IntegrationParameters, RigidBodySet, RigidBodyVelocity};
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
 
@@ -19,6 +21,10 @@ pub(crate) struct VelocityGroundConstraint {
     pub im2: Vector<Real>,
     pub cfm_factor: Real,
     pub limit: Real,
+    #[cfg(feature = "dim3")]
+    pub friction_model: FrictionModel,
+    #[cfg(feature = "dim3")]
+    pub friction_scales: Option<[Real; 2]>,
     pub elements: [VelocityGroundConstraintElement<Real>; MAX_MANIFOLD_POINTS],
 
     pub manifold_id: ContactManifoldIndex,
@@ -35,9 +41,11 @@ impl VelocityGroundConstraint {
         out_constraints: &mut Vec<AnyVelocityConstraint>,
         insert_at: Option<usize>,
     ) {
-        let cfm_factor = params.cfm_factor();
+        let (erp_inv_dt, cfm_factor) = manifold
+            .data
+            .contact_softness
+            .erp_inv_dt_and_cfm_factor(params);
         let inv_dt = params.inv_dt();
-        let erp_inv_dt = params.erp_inv_dt();
 
         let mut handle1 = manifold.data.rigid_body1;
         let mut handle2 = manifold.data.rigid_body2;
@@ -60,12 +68,18 @@ impl VelocityGroundConstraint {
         let rb2 = &bodies[handle2.unwrap()];
         let vels2 = &rb2.vels;
         let mprops2 = &rb2.mprops;
+        let max_penetration_correction = params
+            .max_penetration_correction
+            .min(rb2.max_penetration_correction);
 
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let (tangents1, friction_scales) = super::anisotropic_friction_directions(
+            manifold,
+            &force_dir1,
+            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel),
+        );
 
         let mj_lambda2 = rb2.ids.active_set_offset;
 
@@ -84,6 +98,10 @@ impl VelocityGroundConstraint {
                 im2: mprops2.effective_inv_mass,
                 cfm_factor,
                 limit: 0.0,
+                #[cfg(feature = "dim3")]
+                friction_model: params.friction_model,
+                #[cfg(feature = "dim3")]
+                friction_scales,
                 mj_lambda2,
                 manifold_id,
                 manifold_contact_id: [0; MAX_MANIFOLD_POINTS],
@@ -131,6 +149,11 @@ impl VelocityGroundConstraint {
                 constraint.im2 = mprops2.effective_inv_mass;
                 constraint.cfm_factor = cfm_factor;
                 constraint.limit = 0.0;
+                #[cfg(feature = "dim3")]
+                {
+                    constraint.friction_model = params.friction_model;
+                    constraint.friction_scales = friction_scales;
+                }
                 constraint.mj_lambda2 = mj_lambda2;
                 constraint.manifold_id = manifold_id;
                 constraint.manifold_contact_id = [0; MAX_MANIFOLD_POINTS];
@@ -155,9 +178,11 @@ impl VelocityGroundConstraint {
                         .effective_world_inv_inertia_sqrt
                         .transform_vector(dp2.gcross(-force_dir1));
 
-                    let projected_mass = utils::inv(
-                        force_dir1.dot(&mprops2.effective_inv_mass.component_mul(&force_dir1))
-                            + gcross2.gdot(gcross2),
+                    let projected_mass = projected_contact_mass_from_gcross(
+                        &mprops2.effective_inv_mass,
+                        &force_dir1,
+                        None,
+                        gcross2,
                     );
 
                     let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
@@ -169,7 +194,7 @@ impl VelocityGroundConstraint {
                     rhs_wo_bias *= is_bouncy + is_resting;
                     let rhs_bias = /* is_resting
                         * */ erp_inv_dt
-                        * (manifold_point.dist + params.allowed_linear_error).clamp(-params.max_penetration_correction, 0.0);
+                        * (manifold_point.dist + params.allowed_linear_error).clamp(-max_penetration_correction, 0.0);
 
                     let rhs = rhs_wo_bias + rhs_bias;
                     is_fast_contact =
@@ -181,6 +206,8 @@ impl VelocityGroundConstraint {
                         rhs_wo_bias,
                         impulse: na::zero(),
                         r: projected_mass,
+                        max_impulse: manifold.data.max_contact_impulse,
+                        unclamped_impulse: na::zero(),
                     };
                 }
 
@@ -244,6 +271,10 @@ impl VelocityGroundConstraint {
             &self.tangent1,
             &self.im2,
             self.limit,
+            #[cfg(feature = "dim3")]
+            self.friction_model,
+            #[cfg(feature = "dim3")]
+            self.friction_scales.as_ref(),
             &mut mj_lambda2,
             solve_normal,
             solve_friction,
@@ -255,11 +286,18 @@ impl VelocityGroundConstraint {
     // FIXME: duplicated code. This is exactly the same as in the non-ground velocity constraint.
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
         let manifold = &mut manifolds_all[self.manifold_id];
+        writeback_tangents(
+            manifold,
+            &self.dir1,
+            #[cfg(feature = "dim3")]
+            &self.tangent1,
+        );
 
         for k in 0..self.num_contacts as usize {
             let contact_id = self.manifold_contact_id[k];
             let active_contact = &mut manifold.points[contact_id as usize];
             active_contact.data.impulse = self.elements[k].normal_part.impulse;
+            active_contact.data.unclamped_impulse = self.elements[k].normal_part.unclamped_impulse;
 
             #[cfg(feature = "dim2")]
             {
@@ -1,4 +1,8 @@
+#[cfg(feature = "dim3")]
+use super::project_anisotropic_friction_impulse;
 use super::DeltaVel;
+#[cfg(feature = "dim3")]
+use crate::dynamics::FrictionModel;
 use crate::math::{AngVector, Vector, DIM};
 use crate::utils::{WBasis, WDot, WReal};
 
@@ -35,6 +39,8 @@ impl<N: WReal> VelocityGroundConstraintTangentPart<N> {
         tangents1: [&Vector<N>; DIM - 1],
         im2: &Vector<N>,
         limit: N,
+        #[cfg(feature = "dim3")] friction_model: FrictionModel,
+        #[cfg(feature = "dim3")] friction_scales: Option<&[N; 2]>,
         mj_lambda2: &mut DeltaVel<N>,
     ) where
         AngVector<N>: WDot<AngVector<N>, Result = N>,
@@ -74,7 +80,15 @@ impl<N: WReal> VelocityGroundConstraintTangentPart<N> {
                 let _disable_fe_except =
                     crate::utils::DisableFloatingPointExceptionsFlags::
                     disable_floating_point_exceptions();
-                new_impulse.simd_cap_magnitude(limit)
+                project_anisotropic_friction_impulse(new_impulse, friction_scales, |impulse| {
+                    match friction_model {
+                        FrictionModel::Cone => impulse.simd_cap_magnitude(limit),
+                        FrictionModel::Pyramid => na::vector![
+                            impulse[0].simd_clamp(-limit, limit),
+                            impulse[1].simd_clamp(-limit, limit)
+                        ],
+                    }
+                })
             };
             let dlambda = new_impulse - self.impulse;
             self.impulse = new_impulse;
@@ -93,6 +107,10 @@ pub(crate) struct VelocityGroundConstraintNormalPart<N: WReal> {
     pub rhs_wo_bias: N,
     pub impulse: N,
     pub r: N,
+    // The impulse is clamped to this value, see `ContactManifoldData::max_contact_impulse`.
+    pub max_impulse: N,
+    // The impulse computed by the last iteration, before being clamped to `max_impulse`.
+    pub unclamped_impulse: N,
 }
 
 impl<N: WReal> VelocityGroundConstraintNormalPart<N> {
@@ -103,6 +121,8 @@ impl<N: WReal> VelocityGroundConstraintNormalPart<N> {
             rhs_wo_bias: na::zero(),
             impulse: na::zero(),
             r: na::zero(),
+            max_impulse: na::zero(),
+            unclamped_impulse: na::zero(),
         }
     }
 
@@ -117,7 +137,9 @@ impl<N: WReal> VelocityGroundConstraintNormalPart<N> {
         AngVector<N>: WDot<AngVector<N>, Result = N>,
     {
         let dvel = -dir1.dot(&mj_lambda2.linear) + self.gcross2.gdot(mj_lambda2.angular) + self.rhs;
-        let new_impulse = cfm_factor * (self.impulse - self.r * dvel).simd_max(N::zero());
+        let unclamped_impulse = cfm_factor * (self.impulse - self.r * dvel).simd_max(N::zero());
+        let new_impulse = unclamped_impulse.simd_min(self.max_impulse);
+        self.unclamped_impulse = unclamped_impulse;
         let dlambda = new_impulse - self.impulse;
         self.impulse = new_impulse;
 
@@ -148,6 +170,8 @@ impl<N: WReal> VelocityGroundConstraintElement<N> {
         #[cfg(feature = "dim3")] tangent1: &Vector<N>,
         im2: &Vector<N>,
         limit: N,
+        #[cfg(feature = "dim3")] friction_model: FrictionModel,
+        #[cfg(feature = "dim3")] friction_scales: Option<&[N; 2]>,
         mj_lambda2: &mut DeltaVel<N>,
         solve_normal: bool,
         solve_friction: bool,
@@ -174,7 +198,16 @@ impl<N: WReal> VelocityGroundConstraintElement<N> {
             for element in elements.iter_mut() {
                 let limit = limit * element.normal_part.impulse;
                 let part = &mut element.tangent_part;
-                part.solve(tangents1, im2, limit, mj_lambda2);
+                part.solve(
+                    tangents1,
+                    im2,
+                    limit,
+                    #[cfg(feature = "dim3")]
+                    friction_model,
+                    #[cfg(feature = "dim3")]
+                    friction_scales,
+                    mj_lambda2,
+                );
             }
         }
     }
@@ -1,7 +1,9 @@
 use super::{
-    AnyVelocityConstraint, DeltaVel, VelocityGroundConstraintElement,
-    VelocityGroundConstraintNormalPart,
+    projected_contact_mass_from_gcross, writeback_tangents, AnyVelocityConstraint, DeltaVel,
+    VelocityGroundConstraintElement, VelocityGroundConstraintNormalPart,
 };
+#[cfg(feature = "dim3")]
+use crate::dynamics::FrictionModel;
 use crate::dynamics::{
     IntegrationParameters, RigidBodyIds, RigidBodyMassProps, RigidBodySet, RigidBodyVelocity,
 };
@@ -26,6 +28,8 @@ pub(crate) struct WVelocityGroundConstraint {
     pub im2: Vector<SimdReal>,
     pub cfm_factor: SimdReal,
     pub limit: SimdReal,
+    #[cfg(feature = "dim3")]
+    pub friction_model: FrictionModel,
     pub mj_lambda2: [usize; SIMD_WIDTH],
     pub manifold_id: [ContactManifoldIndex; SIMD_WIDTH],
     pub manifold_contact_id: [[u8; SIMD_WIDTH]; MAX_MANIFOLD_POINTS],
@@ -40,12 +44,17 @@ impl WVelocityGroundConstraint {
         out_constraints: &mut Vec<AnyVelocityConstraint>,
         insert_at: Option<usize>,
     ) {
-        let cfm_factor = SimdReal::splat(params.cfm_factor());
         let dt = SimdReal::splat(params.dt);
         let inv_dt = SimdReal::splat(params.inv_dt());
         let allowed_lin_err = SimdReal::splat(params.allowed_linear_error);
-        let erp_inv_dt = SimdReal::splat(params.erp_inv_dt());
-        let max_penetration_correction = SimdReal::splat(params.max_penetration_correction);
+        let coeffs = gather![|ii| manifolds[ii]
+            .data
+            .contact_softness
+            .erp_inv_dt_and_cfm_factor(params)];
+        let erp_inv_dt = SimdReal::from(gather![|ii| coeffs[ii].0]);
+        let cfm_factor = SimdReal::from(gather![|ii| coeffs[ii].1]);
+        let max_contact_impulse =
+            SimdReal::from(gather![|ii| manifolds[ii].data.max_contact_impulse]);
 
         let mut handles1 = gather![|ii| manifolds[ii].data.rigid_body1];
         let mut handles2 = gather![|ii| manifolds[ii].data.rigid_body2];
@@ -75,6 +84,9 @@ impl WVelocityGroundConstraint {
         let ids2: [&RigidBodyIds; SIMD_WIDTH] = gather![|ii| &bodies2[ii].ids];
         let mprops2: [&RigidBodyMassProps; SIMD_WIDTH] = gather![|ii| &bodies2[ii].mprops];
         let ccd_thickness = SimdReal::from(gather![|ii| bodies2[ii].ccd.ccd_thickness]);
+        let max_penetration_correction = SimdReal::from(gather![|ii| params
+            .max_penetration_correction
+            .min(bodies2[ii].max_penetration_correction)]);
 
         let flipped_sign = SimdReal::from(flipped);
 
@@ -115,6 +127,8 @@ impl WVelocityGroundConstraint {
                 im2,
                 cfm_factor,
                 limit: SimdReal::splat(0.0),
+                #[cfg(feature = "dim3")]
+                friction_model: params.friction_model,
                 mj_lambda2,
                 manifold_id,
                 manifold_contact_id: [[0; SIMD_WIDTH]; MAX_MANIFOLD_POINTS],
@@ -146,9 +160,8 @@ impl WVelocityGroundConstraint {
                 {
                     let gcross2 = ii2.transform_vector(dp2.gcross(-force_dir1));
 
-                    let projected_mass = utils::simd_inv(
-                        force_dir1.dot(&im2.component_mul(&force_dir1)) + gcross2.gdot(gcross2),
-                    );
+                    let projected_mass =
+                        projected_contact_mass_from_gcross(&im2, &force_dir1, None, gcross2);
 
                     let projected_velocity = (vel1 - vel2).dot(&force_dir1);
                     let mut rhs_wo_bias =
@@ -169,6 +182,8 @@ impl WVelocityGroundConstraint {
                         rhs_wo_bias,
                         impulse: na::zero(),
                         r: projected_mass,
+                        max_impulse: max_contact_impulse,
+                        unclamped_impulse: na::zero(),
                     };
                 }
 
@@ -230,6 +245,10 @@ impl WVelocityGroundConstraint {
             &self.tangent1,
             &self.im2,
             self.limit,
+            #[cfg(feature = "dim3")]
+            self.friction_model,
+            #[cfg(feature = "dim3")]
+            None,
             &mut mj_lambda2,
             solve_normal,
             solve_friction,
@@ -243,8 +262,19 @@ impl WVelocityGroundConstraint {
 
     // FIXME: duplicated code. This is exactly the same as in the non-ground velocity constraint.
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
+        for ii in 0..SIMD_WIDTH {
+            writeback_tangents(
+                manifolds_all[self.manifold_id[ii]],
+                &self.dir1.extract(ii),
+                #[cfg(feature = "dim3")]
+                &self.tangent1.extract(ii),
+            );
+        }
+
         for k in 0..self.num_contacts as usize {
             let impulses: [_; SIMD_WIDTH] = self.elements[k].normal_part.impulse.into();
+            let unclamped_impulses: [_; SIMD_WIDTH] =
+                self.elements[k].normal_part.unclamped_impulse.into();
             #[cfg(feature = "dim2")]
             let tangent_impulses: [_; SIMD_WIDTH] = self.elements[k].tangent_part.impulse[0].into();
             #[cfg(feature = "dim3")]
@@ -255,6 +285,7 @@ impl WVelocityGroundConstraint {
                 let contact_id = self.manifold_contact_id[k][ii];
                 let active_contact = &mut manifold.points[contact_id as usize];
                 active_contact.data.impulse = impulses[ii];
+                active_contact.data.unclamped_impulse = unclamped_impulses[ii];
 
                 #[cfg(feature = "dim2")]
                 {
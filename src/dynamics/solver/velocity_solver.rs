@@ -8,9 +8,33 @@ use crate::math::Real;
 use crate::utils::WAngularInertia;
 use na::DVector;
 
+/// The convergence diagnostics of the constraints solver of an island.
+///
+/// The residual of a solver iteration is the largest velocity correction it applied to a single
+/// body: the maximum of the norms of the changes of linear velocity (in m/s) and of angular
+/// velocity (in rad/s) of every rigid-body, and of the changes of the generalized velocities of
+/// every multibody. It converges to zero as the constraints get satisfied, so a residual that
+/// plateaus above zero indicates that more iterations won’t improve the result.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg(feature = "debug-solver-residuals")]
+pub struct SolverResiduals {
+    /// The residual of each iteration of the velocity resolution, including the iterations
+    /// solving friction only (see [`IntegrationParameters::max_velocity_friction_iterations`]).
+    pub velocity: Vec<Real>,
+    /// The residual of each iteration of the stabilization pass, which corrects the
+    /// penetrations and joint drifts (see [`IntegrationParameters::max_stabilization_iterations`]).
+    pub stabilization: Vec<Real>,
+}
+
 pub(crate) struct VelocitySolver {
     pub mj_lambdas: Vec<DeltaVel<Real>>,
     pub generic_mj_lambdas: DVector<Real>,
+    #[cfg(feature = "debug-solver-residuals")]
+    pub residuals: SolverResiduals,
+    #[cfg(feature = "debug-solver-residuals")]
+    prev_mj_lambdas: Vec<DeltaVel<Real>>, // Workspace
+    #[cfg(feature = "debug-solver-residuals")]
+    prev_generic_mj_lambdas: DVector<Real>, // Workspace
 }
 
 impl VelocitySolver {
@@ -18,9 +42,59 @@ impl VelocitySolver {
         Self {
             mj_lambdas: Vec::new(),
             generic_mj_lambdas: DVector::zeros(0),
+            #[cfg(feature = "debug-solver-residuals")]
+            residuals: SolverResiduals::default(),
+            #[cfg(feature = "debug-solver-residuals")]
+            prev_mj_lambdas: Vec::new(),
+            #[cfg(feature = "debug-solver-residuals")]
+            prev_generic_mj_lambdas: DVector::zeros(0),
         }
     }
 
+    /// Computes the residual of the iteration that just completed, and saves the current
+    /// delta-velocities for computing the residual of the next one.
+    #[cfg(feature = "debug-solver-residuals")]
+    fn record_residual(
+        &mut self,
+        island_id: usize,
+        islands: &IslandManager,
+        bodies: &RigidBodySet,
+        multibodies: &MultibodyJointSet,
+    ) -> Real {
+        let mut residual: Real = 0.0;
+
+        for handle in islands.active_island(island_id) {
+            if multibodies.rigid_body_link(*handle).is_some() {
+                continue;
+            }
+
+            let rb = &bodies[*handle];
+            let i = rb.ids.active_set_offset;
+            let (dvel, prev_dvel) = (self.mj_lambdas[i], self.prev_mj_lambdas[i]);
+            let dangvel = rb
+                .mprops
+                .effective_world_inv_inertia_sqrt
+                .transform_vector(dvel.angular - prev_dvel.angular);
+            #[cfg(feature = "dim2")]
+            let dangvel = dangvel.abs();
+            #[cfg(feature = "dim3")]
+            let dangvel = dangvel.norm();
+            residual = residual
+                .max((dvel.linear - prev_dvel.linear).norm())
+                .max(dangvel);
+        }
+
+        if !self.generic_mj_lambdas.is_empty() {
+            residual =
+                residual.max((&self.generic_mj_lambdas - &self.prev_generic_mj_lambdas).amax());
+        }
+
+        self.prev_mj_lambdas.copy_from_slice(&self.mj_lambdas);
+        self.prev_generic_mj_lambdas
+            .copy_from(&self.generic_mj_lambdas);
+        residual
+    }
+
     pub fn solve(
         &mut self,
         island_id: usize,
@@ -68,6 +142,15 @@ impl VelocitySolver {
             }
         }
 
+        #[cfg(feature = "debug-solver-residuals")]
+        {
+            self.residuals.velocity.clear();
+            self.residuals.stabilization.clear();
+            self.prev_mj_lambdas.clone_from(&self.mj_lambdas);
+            self.prev_generic_mj_lambdas
+                .clone_from(&self.generic_mj_lambdas);
+        }
+
         /*
          * Solve constraints.
          */
@@ -104,6 +187,12 @@ impl VelocitySolver {
                     );
                 }
             }
+
+            #[cfg(feature = "debug-solver-residuals")]
+            {
+                let residual = self.record_residual(island_id, islands, bodies, multibodies);
+                self.residuals.velocity.push(residual);
+            }
         }
 
         let remaining_friction_iterations =
@@ -125,6 +214,12 @@ impl VelocitySolver {
                     true,
                 );
             }
+
+            #[cfg(feature = "debug-solver-residuals")]
+            {
+                let residual = self.record_residual(island_id, islands, bodies, multibodies);
+                self.residuals.velocity.push(residual);
+            }
         }
 
         // Integrate positions.
@@ -204,6 +299,12 @@ impl VelocitySolver {
                     true,
                 );
             }
+
+            #[cfg(feature = "debug-solver-residuals")]
+            {
+                let residual = self.record_residual(island_id, islands, bodies, multibodies);
+                self.residuals.stabilization.push(residual);
+            }
         }
 
         // Update velocities.
@@ -230,6 +331,7 @@ impl VelocitySolver {
                 rb.vels.linvel += dvel.linear;
                 rb.vels.angvel += dangvel;
                 rb.vels = rb.vels.apply_damping(params.dt, &rb.damping);
+                rb.accumulate_position_correction(params.dt);
             }
         }
 
@@ -1,12 +1,13 @@
 use super::{
     BroadPhasePairEvent, ColliderPair, SAPLayer, SAPProxies, SAPProxy, SAPProxyData, SAPRegionPool,
 };
+use crate::dynamics::RigidBodySet;
 use crate::geometry::broad_phase_multi_sap::SAPProxyIndex;
 use crate::geometry::{
-    ColliderBroadPhaseData, ColliderChanges, ColliderHandle, ColliderPosition, ColliderSet,
-    ColliderShape,
+    BroadPhaseSpatialHash, ColliderBroadPhaseData, ColliderChanges, ColliderHandle,
+    ColliderPosition, ColliderSet, ColliderShape,
 };
-use crate::math::Real;
+use crate::math::{Isometry, Real};
 use crate::utils::IndexMut2;
 use parry::bounding_volume::BoundingVolume;
 use parry::utils::hashmap::HashMap;
@@ -72,6 +73,10 @@ use parry::utils::hashmap::HashMap;
 ///   the bounds on the `SAPRegion` containing this `SAPAxis`.
 /// - A set of `SAPProxy` are maintained separately. It contains the Aabbs of all the colliders managed by this
 ///   broad-phase, as well as the Aabbs of all the regions part of this broad-phase.
+///
+/// Alternatively, a broad-phase created with [`BroadPhase::with_spatial_hash`] replaces the
+/// Hierarchical-SAP by a single uniform grid: colliders are inserted into every cell their Aabb
+/// intersects, and pairs are found by testing the Aabbs of the colliders sharing a cell.
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone)]
 pub struct BroadPhase {
@@ -112,6 +117,11 @@ pub struct BroadPhase {
         )
     )]
     reporting: HashMap<(u32, u32), bool>, // Workspace
+    spatial_hash: Option<BroadPhaseSpatialHash>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    added_colliders: Vec<ColliderHandle>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    removed_colliders: Vec<ColliderHandle>,
 }
 
 impl Default for BroadPhase {
@@ -131,6 +141,83 @@ impl BroadPhase {
             region_pool: Vec::new(),
             reporting: HashMap::default(),
             colliders_proxy_ids: HashMap::default(),
+            spatial_hash: None,
+            added_colliders: vec![],
+            removed_colliders: vec![],
+        }
+    }
+
+    /// Create a new empty broad-phase, with room for `num_colliders` colliders.
+    ///
+    /// The broad-phase grows as usual (without panicking) if more colliders are inserted.
+    pub fn with_capacity(num_colliders: usize) -> Self {
+        BroadPhase {
+            proxies: SAPProxies::with_capacity(num_colliders),
+            colliders_proxy_ids: HashMap::with_capacity_and_hasher(
+                num_colliders,
+                Default::default(),
+            ),
+            added_colliders: Vec::with_capacity(num_colliders),
+            ..Self::new()
+        }
+    }
+
+    /// Create a new empty broad-phase based on a uniform grid with cells of size `cell_size`.
+    ///
+    /// This is best suited for scenes where most colliders have roughly the same size. The
+    /// cell size should then be close to the size of these colliders: smaller cells result in
+    /// colliders being inserted into lots of cells, while larger cells result in lots of
+    /// Aabb tests between colliders sharing a cell. Colliders covering more than 64 cells
+    /// (e.g. the ground) are tested against all the other colliders whenever they move.
+    ///
+    /// This produces the same collision pairs as the broad-phase created by [`BroadPhase::new`].
+    /// It is much faster at inserting lots of colliders at once, and when lots of colliders move
+    /// chaotically. However, it may be slightly slower for scenes where most colliders are
+    /// moving coherently or resting (e.g. stacks) because it can’t exploit the temporal
+    /// coherence as well as the Hierarchical-SAP. See the `broad_phase3` benchmark.
+    pub fn with_spatial_hash(cell_size: Real) -> Self {
+        BroadPhase {
+            spatial_hash: Some(BroadPhaseSpatialHash::new(cell_size)),
+            ..Self::new()
+        }
+    }
+
+    /// The cell size of this broad-phase if it was created with [`BroadPhase::with_spatial_hash`].
+    pub fn spatial_hash_cell_size(&self) -> Option<Real> {
+        self.spatial_hash.as_ref().map(|grid| grid.cell_size())
+    }
+
+    /// The colliders that were added to this broad-phase during the last step.
+    ///
+    /// This includes the colliders inserted into the collider set, as well as the colliders
+    /// that were re-enabled. This list is cleared at the beginning of each step of the physics
+    /// and collision pipelines.
+    pub fn added_colliders(&self) -> &[ColliderHandle] {
+        &self.added_colliders
+    }
+
+    /// The colliders that were removed from this broad-phase during the last step.
+    ///
+    /// This includes the colliders removed from the collider set, as well as the colliders
+    /// that were disabled. This list is cleared at the beginning of each step of the physics
+    /// and collision pipelines.
+    pub fn removed_colliders(&self) -> &[ColliderHandle] {
+        &self.removed_colliders
+    }
+
+    pub(crate) fn clear_collider_changes(&mut self) {
+        self.added_colliders.clear();
+        self.removed_colliders.clear();
+    }
+
+    /// Is the given collider currently part of this broad-phase?
+    fn contains_collider(&self, handle: ColliderHandle) -> bool {
+        match &self.spatial_hash {
+            Some(spatial_hash) => spatial_hash.contains(handle),
+            None => self
+                .colliders_proxy_ids
+                .get(&handle)
+                .map_or(false, |proxy_id| *proxy_id != crate::INVALID_U32),
         }
     }
 
@@ -354,12 +441,16 @@ impl BroadPhase {
         handle: ColliderHandle,
         proxy_index: &mut u32,
         collider: (&ColliderPosition, &ColliderShape, &ColliderChanges),
+        next_position: Option<&Isometry<Real>>,
     ) -> bool {
         let (co_pos, co_shape, co_changes) = collider;
 
-        let mut aabb = co_shape
-            .compute_aabb(co_pos)
-            .loosened(prediction_distance / 2.0);
+        let aabb = match next_position {
+            // Cover the whole motion of the collider so the pairs needed by CCD aren't missed.
+            Some(next_position) => co_shape.compute_swept_aabb(co_pos, next_position),
+            None => co_shape.compute_aabb(co_pos),
+        };
+        let mut aabb = aabb.loosened(prediction_distance / 2.0);
 
         if aabb.mins.coords.iter().any(|e| !e.is_finite())
             || aabb.maxs.coords.iter().any(|e| !e.is_finite())
@@ -451,10 +542,68 @@ impl BroadPhase {
     }
 
     /// Updates the broad-phase, taking into account the new collider positions.
+    ///
+    /// The Aabbs of the colliders attached to a rigid-body with CCD enabled are enlarged
+    /// to contain the motion of the collider predicted over the next `dt` seconds. Set
+    /// `dt` to zero to always use tight Aabbs.
     pub fn update(
         &mut self,
+        dt: Real,
+        prediction_distance: Real,
+        colliders: &mut ColliderSet,
+        bodies: &RigidBodySet,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+        events: &mut Vec<BroadPhasePairEvent>,
+    ) {
+        // Keep track of the colliders entering or leaving the broad-phase. The candidates
+        // for addition are only kept if they actually got a proxy during this update.
+        for handle in removed_colliders {
+            if self.contains_collider(*handle) {
+                self.removed_colliders.push(*handle);
+            }
+        }
+
+        let first_added = self.added_colliders.len();
+        for handle in modified_colliders {
+            if !self.contains_collider(*handle) {
+                self.added_colliders.push(*handle);
+            }
+        }
+
+        if let Some(spatial_hash) = &mut self.spatial_hash {
+            spatial_hash.update(
+                dt,
+                prediction_distance,
+                colliders,
+                bodies,
+                modified_colliders,
+                removed_colliders,
+                events,
+            );
+        } else {
+            self.update_sap(
+                dt,
+                prediction_distance,
+                colliders,
+                bodies,
+                modified_colliders,
+                removed_colliders,
+                events,
+            );
+        }
+
+        let mut candidates = self.added_colliders.split_off(first_added);
+        candidates.retain(|handle| self.contains_collider(*handle));
+        self.added_colliders.append(&mut candidates);
+    }
+
+    fn update_sap(
+        &mut self,
+        dt: Real,
         prediction_distance: Real,
         colliders: &mut ColliderSet,
+        bodies: &RigidBodySet,
         modified_colliders: &[ColliderHandle],
         removed_colliders: &[ColliderHandle],
         events: &mut Vec<BroadPhasePairEvent>,
@@ -474,12 +623,19 @@ impl BroadPhase {
                 }
 
                 let mut new_proxy_id = co.bf_data.proxy_index;
+                let next_position = co.parent.filter(|_| dt != 0.0).and_then(|parent| {
+                    let rb = bodies.get(parent.handle)?;
+                    rb.is_ccd_enabled().then(|| {
+                        rb.predict_position_using_velocity_and_forces(dt) * parent.pos_wrt_parent
+                    })
+                });
 
                 if self.handle_modified_collider(
                     prediction_distance,
                     *handle,
                     &mut new_proxy_id,
                     (&co.pos, &co.shape, &co.changes),
+                    next_position.as_ref(),
                 ) {
                     need_region_propagation = true;
                 }
@@ -624,7 +780,10 @@ mod test {
     use crate::dynamics::{
         ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBodyBuilder, RigidBodySet,
     };
-    use crate::geometry::{BroadPhase, ColliderBuilder, ColliderSet};
+    use crate::geometry::{BroadPhase, BroadPhasePairEvent, ColliderBuilder, ColliderSet};
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use std::collections::HashSet;
 
     #[test]
     fn test_add_update_remove() {
@@ -641,7 +800,7 @@ mod test {
         let coh = colliders.insert_with_parent(co, hrb, &mut bodies);
 
         let mut events = Vec::new();
-        broad_phase.update(0.0, &mut colliders, &[coh], &[], &mut events);
+        broad_phase.update(0.0, 0.0, &mut colliders, &bodies, &[coh], &[], &mut events);
 
         bodies.remove(
             hrb,
@@ -651,7 +810,7 @@ mod test {
             &mut multibody_joints,
             true,
         );
-        broad_phase.update(0.0, &mut colliders, &[], &[coh], &mut events);
+        broad_phase.update(0.0, 0.0, &mut colliders, &bodies, &[], &[coh], &mut events);
 
         // Create another body.
         let rb = RigidBodyBuilder::dynamic().build();
@@ -660,6 +819,231 @@ mod test {
         let coh = colliders.insert_with_parent(co, hrb, &mut bodies);
 
         // Make sure the proxy handles is recycled properly.
-        broad_phase.update(0.0, &mut colliders, &[coh], &[], &mut events);
+        broad_phase.update(0.0, 0.0, &mut colliders, &bodies, &[coh], &[], &mut events);
+    }
+
+    #[test]
+    fn spatial_hash_and_sap_find_the_same_pairs() {
+        fn apply_events(
+            pairs: &mut HashSet<((u32, u32), (u32, u32))>,
+            events: &[BroadPhasePairEvent],
+        ) {
+            for event in events {
+                match event {
+                    BroadPhasePairEvent::AddPair(pair) | BroadPhasePairEvent::DeletePair(pair) => {
+                        let key1 = pair.collider1.0.into_raw_parts();
+                        let key2 = pair.collider2.0.into_raw_parts();
+                        let key = (key1.min(key2), key1.max(key2));
+
+                        if let BroadPhasePairEvent::AddPair(_) = event {
+                            let _ = pairs.insert(key);
+                        } else {
+                            let _ = pairs.remove(&key);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut sap = BroadPhase::new();
+        let mut spatial_hash = BroadPhase::with_spatial_hash(1.0);
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut islands = IslandManager::new();
+
+        let mut seed = 42u32;
+        let mut rand = move || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as Real / (1u32 << 24) as Real
+        };
+
+        let mut handles = vec![colliders.insert(ColliderBuilder::halfspace(Vector::y_axis()))];
+        for _ in 0..300 {
+            let co = ColliderBuilder::ball(0.1 + rand() * 0.4)
+                .translation(Vector::from_fn(|_, _| rand() * 10.0))
+                .build();
+            handles.push(colliders.insert(co));
+        }
+
+        let mut sap_pairs = HashSet::new();
+        let mut spatial_hash_pairs = HashSet::new();
+        let mut removed = vec![];
+
+        for step in 0..10 {
+            let mut events = vec![];
+            sap.update(
+                0.0,
+                0.0,
+                &mut colliders,
+                &bodies,
+                &handles,
+                &removed,
+                &mut events,
+            );
+            apply_events(&mut sap_pairs, &events);
+
+            events.clear();
+            spatial_hash.update(
+                0.0,
+                0.0,
+                &mut colliders,
+                &bodies,
+                &handles,
+                &removed,
+                &mut events,
+            );
+            apply_events(&mut spatial_hash_pairs, &events);
+
+            // The pairs involving removed colliders are not reported.
+            for handle in removed.drain(..) {
+                let key = handle.0.into_raw_parts();
+                sap_pairs.retain(|(key1, key2)| *key1 != key && *key2 != key);
+                spatial_hash_pairs.retain(|(key1, key2)| *key1 != key && *key2 != key);
+            }
+
+            assert!(!sap_pairs.is_empty());
+            assert_eq!(sap_pairs, spatial_hash_pairs);
+
+            // Move the colliders, and remove some of them.
+            for handle in &handles[1..] {
+                let shift = Vector::from_fn(|_, _| rand() - 0.5);
+                let new_translation = colliders[*handle].translation() + shift;
+                colliders[*handle].set_translation(new_translation);
+            }
+
+            if step % 3 == 2 {
+                let handle = handles.pop().unwrap();
+                colliders.remove(handle, &mut islands, &mut bodies, false);
+                removed.push(handle);
+            }
+        }
+    }
+
+    #[test]
+    fn broad_phase_reports_added_and_removed_colliders() {
+        for broad_phase in [BroadPhase::new(), BroadPhase::with_spatial_hash(1.0)] {
+            let mut world = TestWorld::new();
+            world.gravity = Vector::zeros();
+            world.broad_phase = broad_phase;
+
+            let body = world.bodies.insert(RigidBodyBuilder::dynamic().build());
+            let collider1 = world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.5).build(),
+                body,
+                &mut world.bodies,
+            );
+            let collider2 = world.colliders.insert(ColliderBuilder::ball(0.5).build());
+
+            let step = |world: &mut TestWorld| -> (Vec<_>, Vec<_>) {
+                world.step();
+                (
+                    world.broad_phase.added_colliders().to_vec(),
+                    world.broad_phase.removed_colliders().to_vec(),
+                )
+            };
+
+            let (mut added, removed) = step(&mut world);
+            added.sort_by_key(|h| h.into_raw_parts());
+            assert_eq!(added, [collider1, collider2]);
+            assert!(removed.is_empty());
+
+            // The lists are cleared at each step.
+            assert_eq!(step(&mut world), (vec![], vec![]));
+
+            world.colliders[collider2].set_enabled(false);
+            assert_eq!(step(&mut world), (vec![], vec![collider2]));
+
+            world.colliders[collider2].set_enabled(true);
+            assert_eq!(step(&mut world), (vec![collider2], vec![]));
+
+            let _ = world
+                .colliders
+                .remove(collider1, &mut world.islands, &mut world.bodies, true);
+            assert_eq!(step(&mut world), (vec![], vec![collider1]));
+        }
+    }
+
+    #[test]
+    fn broad_phase_margin_controls_the_candidate_pairs() {
+        let mut num_pairs = vec![];
+
+        for margin in [None, Some(0.0), Some(0.1)] {
+            let mut world = TestWorld::new();
+            world.gravity = Vector::zeros();
+            world.params.broad_phase_margin = margin;
+
+            // A row of balls separated by 0.001, then a row separated by 0.05.
+            for i in 0..10 {
+                let x = if i < 5 {
+                    i as Real * 1.001
+                } else {
+                    10.0 + i as Real * 1.05
+                };
+                world
+                    .colliders
+                    .insert(ColliderBuilder::ball(0.5).translation(Vector::x() * x));
+            }
+
+            world.step();
+
+            num_pairs.push(world.narrow_phase.contact_pairs().count());
+        }
+
+        assert_eq!(num_pairs, [4, 0, 8]);
+    }
+
+    #[test]
+    fn only_ccd_enabled_colliders_get_swept_aabbs() {
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let dt = 1.0 / 60.0;
+
+        // Two fast bodies, each moving by `100 * dt` along `x`, toward a ball they don't
+        // overlap yet.
+        let mut handles = vec![];
+        for (y, ccd_enabled) in [(0.0, true), (10.0, false)] {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * y)
+                .linvel(Vector::x() * 100.0)
+                .ccd_enabled(ccd_enabled);
+            let body = bodies.insert(body);
+            let moving =
+                colliders.insert_with_parent(ColliderBuilder::ball(0.1), body, &mut bodies);
+            let target =
+                ColliderBuilder::ball(0.1).translation(Vector::x() * 1.5 + Vector::y() * y);
+            handles.push((moving, colliders.insert(target)));
+        }
+        let modified: Vec<_> = colliders.iter().map(|(handle, _)| handle).collect();
+
+        for (dt, expected_pairs) in [(0.0, vec![]), (dt, vec![handles[0]])] {
+            let mut broad_phase = BroadPhase::new();
+            let mut events = vec![];
+            broad_phase.update(
+                dt,
+                0.0,
+                &mut colliders,
+                &bodies,
+                &modified,
+                &[],
+                &mut events,
+            );
+
+            let pairs: Vec<_> = events
+                .iter()
+                .map(|event| match event {
+                    BroadPhasePairEvent::AddPair(pair) => {
+                        let pair = (pair.collider1, pair.collider2);
+                        // Put the moving collider first.
+                        if handles.iter().any(|(moving, _)| *moving == pair.1) {
+                            (pair.1, pair.0)
+                        } else {
+                            pair
+                        }
+                    }
+                    BroadPhasePairEvent::DeletePair(_) => unreachable!(),
+                })
+                .collect();
+            assert_eq!(pairs, expected_pairs);
+        }
     }
 }
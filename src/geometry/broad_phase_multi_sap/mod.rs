@@ -2,6 +2,8 @@ pub use self::broad_phase::BroadPhase;
 pub use self::broad_phase_pair_event::{BroadPhasePairEvent, ColliderPair};
 pub use self::sap_proxy::SAPProxyIndex;
 
+pub(crate) use self::sap_utils::{clamp_point, point_key};
+
 pub(self) use self::sap_axis::*;
 pub(self) use self::sap_endpoint::*;
 pub(self) use self::sap_layer::*;
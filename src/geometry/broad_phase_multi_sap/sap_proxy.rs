@@ -101,6 +101,13 @@ impl SAPProxies {
         }
     }
 
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            elements: Vec::with_capacity(capacity),
+            first_free: NEXT_FREE_SENTINEL,
+        }
+    }
+
     pub fn insert(&mut self, proxy: SAPProxy) -> SAPProxyIndex {
         let result = if self.first_free != NEXT_FREE_SENTINEL {
             let proxy_id = self.first_free;
@@ -0,0 +1,389 @@
+use crate::data::Coarena;
+use crate::dynamics::RigidBodySet;
+use crate::geometry::broad_phase_multi_sap::{clamp_point, point_key};
+use crate::geometry::{BroadPhasePairEvent, ColliderHandle, ColliderPair, ColliderSet};
+use crate::math::{Point, Real};
+use parry::bounding_volume::{Aabb, BoundingVolume};
+use parry::utils::hashmap::HashMap;
+
+/// Colliders covering more grid cells than this are not inserted into the grid. They are
+/// tested against all the other colliders instead, to avoid the discretization of very
+/// large (or infinite) Aabbs.
+const MAX_CELLS_PER_PROXY: u64 = 64;
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+struct SpatialHashProxy {
+    aabb: Aabb,
+    // The first and last cells covered by the Aabb, or `None` if the Aabb is too large
+    // to be inserted into the grid.
+    cells: Option<(Point<i32>, Point<i32>)>,
+    // The colliders with an Aabb intersecting `self.aabb`.
+    neighbors: Vec<ColliderHandle>,
+}
+
+impl Default for SpatialHashProxy {
+    fn default() -> Self {
+        Self {
+            aabb: Aabb::new_invalid(),
+            cells: None,
+            neighbors: vec![],
+        }
+    }
+}
+
+/// A broad-phase based on a uniform grid where each cell is stored into a hashmap.
+///
+/// This is faster than the hierarchical SAP for scenes where most colliders have
+/// roughly the same size, provided that the cell size is chosen close to that size.
+/// Colliders much larger than a cell are supported but handled with a brute-force
+/// approach.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub(crate) struct BroadPhaseSpatialHash {
+    cell_size: Real,
+    proxies: Coarena<SpatialHashProxy>,
+    // NOTE: the Aabbs are duplicated into each cell so that finding pairs doesn’t require
+    //       random accesses to the proxies.
+    cells: HashMap<Point<i32>, Vec<(ColliderHandle, Aabb)>>,
+    large_proxies: Vec<ColliderHandle>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    updated: Vec<ColliderHandle>, // Workspace
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    candidates: Vec<ColliderHandle>, // Workspace
+    // The last `timestamp` at which the pairs of each collider were updated.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    timestamps: Vec<u32>, // Workspace
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    timestamp: u32, // Workspace
+}
+
+impl BroadPhaseSpatialHash {
+    pub fn new(cell_size: Real) -> Self {
+        assert!(
+            cell_size > 0.0,
+            "The cell size of the spatial-hash broad-phase must be positive."
+        );
+
+        Self {
+            cell_size,
+            proxies: Coarena::new(),
+            cells: HashMap::default(),
+            large_proxies: vec![],
+            updated: vec![],
+            candidates: vec![],
+            timestamps: vec![],
+            timestamp: 0,
+        }
+    }
+
+    pub fn cell_size(&self) -> Real {
+        self.cell_size
+    }
+
+    pub fn contains(&self, handle: ColliderHandle) -> bool {
+        self.proxies.get(handle.0).is_some()
+    }
+
+    pub fn update(
+        &mut self,
+        dt: Real,
+        prediction_distance: Real,
+        colliders: &ColliderSet,
+        bodies: &RigidBodySet,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+        events: &mut Vec<BroadPhasePairEvent>,
+    ) {
+        for handle in removed_colliders {
+            self.remove_proxy(*handle);
+        }
+
+        // Update all the Aabbs first, so the pairs between two modified colliders
+        // are found using their latest Aabbs.
+        self.updated.clear();
+
+        for handle in modified_colliders {
+            // NOTE: we use `get` because the collider may no longer
+            //       exist if it has been removed.
+            if let Some(co) = colliders.get(*handle) {
+                if !co.is_enabled() || !co.changes.needs_broad_phase_update() {
+                    continue;
+                }
+
+                let next_position = co.parent.filter(|_| dt != 0.0).and_then(|parent| {
+                    let rb = bodies.get(parent.handle)?;
+                    rb.is_ccd_enabled().then(|| {
+                        rb.predict_position_using_velocity_and_forces(dt) * parent.pos_wrt_parent
+                    })
+                });
+
+                let aabb = match next_position {
+                    // Cover the whole motion of the collider so the pairs needed by CCD aren't missed.
+                    Some(next_position) => co.shape.compute_swept_aabb(&co.pos, &next_position),
+                    None => co.shape.compute_aabb(&co.pos),
+                };
+                let mut aabb = aabb.loosened(prediction_distance / 2.0);
+
+                if aabb.mins.coords.iter().any(|e| !e.is_finite())
+                    || aabb.maxs.coords.iter().any(|e| !e.is_finite())
+                {
+                    // Reject Aabbs with non-finite values.
+                    continue;
+                }
+
+                aabb.mins = clamp_point(aabb.mins);
+                aabb.maxs = clamp_point(aabb.maxs);
+
+                if self.update_proxy(*handle, aabb) {
+                    self.updated.push(*handle);
+                }
+            }
+        }
+
+        self.timestamp = self.timestamp.wrapping_add(1);
+        if self.timestamp == 0 {
+            // Don’t confuse old timestamps with the new ones after a wrap-around.
+            self.timestamps.iter_mut().for_each(|t| *t = 0);
+            self.timestamp = 1;
+        }
+
+        for i in 0..self.updated.len() {
+            let handle = self.updated[i];
+            self.update_pairs(handle, events);
+        }
+    }
+
+    fn cells_range(&self, aabb: &Aabb) -> Option<(Point<i32>, Point<i32>)> {
+        let mins = point_key(aabb.mins, self.cell_size);
+        let maxs = point_key(aabb.maxs, self.cell_size);
+        let num_cells = mins
+            .iter()
+            .zip(maxs.iter())
+            .map(|(min, max)| (*max as i64 - *min as i64 + 1) as u64)
+            .fold(1u64, |acc, n| acc.saturating_mul(n));
+
+        (num_cells <= MAX_CELLS_PER_PROXY).then_some((mins, maxs))
+    }
+
+    /// Updates the Aabb of the given collider, and returns `false` if it didn’t change.
+    fn update_proxy(&mut self, handle: ColliderHandle, aabb: Aabb) -> bool {
+        let cells = self.cells_range(&aabb);
+
+        if let Some(proxy) = self.proxies.get_mut(handle.0) {
+            if proxy.aabb == aabb {
+                // NOTE: the pairs only depend on the Aabbs of both colliders. So if the Aabb
+                //       didn’t change, the pairs that did will be found when updating the
+                //       other collider.
+                return false;
+            }
+
+            proxy.aabb = aabb;
+
+            if proxy.cells == cells {
+                // Same cells, just update the Aabb stored into each of them.
+                if let Some((mins, maxs)) = cells {
+                    let all_cells = &mut self.cells;
+                    for_each_cell(mins, maxs, |key| {
+                        if let Some(entry) = all_cells
+                            .get_mut(&key)
+                            .and_then(|cell| cell.iter_mut().find(|e| e.0 == handle))
+                        {
+                            entry.1 = aabb;
+                        }
+                    });
+                }
+
+                return true;
+            }
+
+            let old_cells = std::mem::replace(&mut proxy.cells, cells);
+            self.remove_from_cells(handle, old_cells);
+        } else {
+            self.proxies.insert(
+                handle.0,
+                SpatialHashProxy {
+                    aabb,
+                    cells,
+                    neighbors: vec![],
+                },
+            );
+        }
+
+        match cells {
+            Some((mins, maxs)) => {
+                let all_cells = &mut self.cells;
+                for_each_cell(mins, maxs, |key| {
+                    all_cells.entry(key).or_default().push((handle, aabb))
+                });
+            }
+            None => self.large_proxies.push(handle),
+        }
+
+        true
+    }
+
+    fn remove_proxy(&mut self, handle: ColliderHandle) {
+        if let Some(proxy) = self.proxies.remove(handle.0, SpatialHashProxy::default()) {
+            self.remove_from_cells(handle, proxy.cells);
+
+            // NOTE: just like the SAP broad-phase, we don't report the pairs involving
+            //       removed colliders. The narrow-phase takes care of them.
+            for neighbor in proxy.neighbors {
+                if let Some(neighbor_proxy) = self.proxies.get_mut(neighbor.0) {
+                    neighbor_proxy.neighbors.retain(|h| *h != handle);
+                }
+            }
+        }
+    }
+
+    fn remove_from_cells(
+        &mut self,
+        handle: ColliderHandle,
+        cells: Option<(Point<i32>, Point<i32>)>,
+    ) {
+        match cells {
+            Some((mins, maxs)) => {
+                let all_cells = &mut self.cells;
+                for_each_cell(mins, maxs, |key| {
+                    if let Some(cell) = all_cells.get_mut(&key) {
+                        if let Some(i) = cell.iter().position(|e| e.0 == handle) {
+                            let _ = cell.swap_remove(i);
+                        }
+
+                        if cell.is_empty() {
+                            let _ = all_cells.remove(&key);
+                        }
+                    }
+                });
+            }
+            None => self.large_proxies.retain(|h| *h != handle),
+        }
+    }
+
+    fn update_pairs(&mut self, handle: ColliderHandle, events: &mut Vec<BroadPhasePairEvent>) {
+        let (aabb, cells) = match self.proxies.get(handle.0) {
+            Some(proxy) => (proxy.aabb, proxy.cells),
+            None => return,
+        };
+
+        // The pairs with the colliders already handled during this update are up-to-date
+        // since both Aabbs were already final. So we only need to test the other ones.
+        let timestamp = self.timestamp;
+        let timestamps = &self.timestamps;
+        let already_handled = |other: &ColliderHandle| {
+            timestamps.get(other.0.into_raw_parts().0 as usize) == Some(&timestamp)
+        };
+
+        // Collect the colliders with an Aabb intersecting `aabb`.
+        self.candidates.clear();
+        let proxies = &self.proxies;
+        let candidates = &mut self.candidates;
+
+        match cells {
+            Some((mins, maxs)) => {
+                let all_cells = &self.cells;
+                let cell_size = self.cell_size;
+                for_each_cell(mins, maxs, |key| {
+                    if let Some(cell) = all_cells.get(&key) {
+                        for (other, other_aabb) in cell {
+                            if *other != handle
+                                && other_aabb.intersects(&aabb)
+                                && !already_handled(other)
+                            {
+                                // Two intersecting Aabbs share all the cells covering their
+                                // intersection. To avoid duplicates, only keep the pair from
+                                // the cell containing the minimum corner of the intersection.
+                                let intersection_mins = aabb.mins.sup(&other_aabb.mins);
+
+                                if point_key(intersection_mins, cell_size) == key {
+                                    candidates.push(*other);
+                                }
+                            }
+                        }
+                    }
+                });
+                candidates.extend(self.large_proxies.iter().copied().filter(|other| {
+                    *other != handle
+                        && !already_handled(other)
+                        && proxies
+                            .get(other.0)
+                            .map(|proxy| proxy.aabb.intersects(&aabb))
+                            .unwrap_or(false)
+                }));
+            }
+            None => candidates.extend(
+                proxies
+                    .iter()
+                    .map(|(h, proxy)| (ColliderHandle(h), proxy))
+                    .filter(|(h, proxy)| {
+                        *h != handle && !already_handled(h) && proxy.aabb.intersects(&aabb)
+                    })
+                    .map(|(h, _)| h),
+            ),
+        }
+
+        let mut neighbors = std::mem::take(&mut self.proxies.get_mut(handle.0).unwrap().neighbors);
+
+        // Report the pairs that stopped intersecting.
+        for neighbor in &neighbors {
+            if !already_handled(neighbor) && !self.candidates.contains(neighbor) {
+                if let Some(neighbor_proxy) = self.proxies.get_mut(neighbor.0) {
+                    neighbor_proxy.neighbors.retain(|h| *h != handle);
+                }
+
+                events.push(BroadPhasePairEvent::DeletePair(ColliderPair::new(
+                    handle, *neighbor,
+                )));
+            }
+        }
+
+        // Report the pairs that started intersecting.
+        for neighbor in &self.candidates {
+            if !neighbors.contains(neighbor) {
+                if let Some(neighbor_proxy) = self.proxies.get_mut(neighbor.0) {
+                    neighbor_proxy.neighbors.push(handle);
+                }
+
+                events.push(BroadPhasePairEvent::AddPair(ColliderPair::new(
+                    handle, *neighbor,
+                )));
+            }
+        }
+
+        neighbors.retain(already_handled);
+        neighbors.extend_from_slice(&self.candidates);
+        self.proxies.get_mut(handle.0).unwrap().neighbors = neighbors;
+
+        let i = handle.0.into_raw_parts().0 as usize;
+        if self.timestamps.len() <= i {
+            self.timestamps.resize(i + 1, 0);
+        }
+        self.timestamps[i] = timestamp;
+    }
+}
+
+/// Calls `f` on all the cells between `mins` and `maxs` (both included).
+fn for_each_cell(mins: Point<i32>, maxs: Point<i32>, mut f: impl FnMut(Point<i32>)) {
+    let mut curr = mins;
+
+    loop {
+        f(curr);
+
+        // Move to the next cell, like an odometer.
+        let mut i = 0;
+        loop {
+            if i == curr.len() {
+                return;
+            }
+
+            if curr[i] < maxs[i] {
+                curr[i] += 1;
+                break;
+            }
+
+            curr[i] = mins[i];
+            i += 1;
+        }
+    }
+}
@@ -1,8 +1,17 @@
 use crate::dynamics::{CoefficientCombineRule, MassProperties, RigidBodyHandle};
+#[cfg(feature = "dim3")]
+use crate::geometry::AnisotropicFriction;
+#[cfg(feature = "dim2")]
+use crate::geometry::ConvexPolygon;
+#[cfg(feature = "dim3")]
+use crate::geometry::ConvexPolyhedron;
 use crate::geometry::{
     ActiveCollisionTypes, ColliderBroadPhaseData, ColliderChanges, ColliderFlags,
     ColliderMassProps, ColliderMaterial, ColliderParent, ColliderPosition, ColliderShape,
-    ColliderType, InteractionGroups, SharedShape,
+    ColliderType, ContactSoftness, InteractionGroups, OneWayPlatform, SharedShape,
+};
+use crate::geometry::{
+    Ball, Capsule, Compound, Cuboid, HeightField, RoundShape, ShapeType, TriMesh,
 };
 use crate::math::{AngVector, Isometry, Point, Real, Rotation, Vector, DIM};
 use crate::parry::transformation::vhacd::VHACDParameters;
@@ -12,6 +21,15 @@ use na::Unit;
 use parry::bounding_volume::Aabb;
 use parry::shape::{Shape, TriMeshFlags};
 
+/// The size (or volume), relative to the largest extent of the points, below which the
+/// convex-hull of these points is considered degenerate.
+const DEGENERATE_CONVEX_HULL_TOLERANCE: Real = 1.0e-6;
+
+#[cfg(feature = "dim2")]
+type ConvexHull = ConvexPolygon;
+#[cfg(feature = "dim3")]
+type ConvexHull = ConvexPolyhedron;
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone)]
 /// A geometric entity that can be attached to a body so it can be affected by contacts and proximity queries.
@@ -28,6 +46,11 @@ pub struct Collider {
     pub(crate) flags: ColliderFlags,
     pub(crate) bf_data: ColliderBroadPhaseData,
     contact_force_event_threshold: Real,
+    contact_event_impulse_threshold: Real,
+    pub(crate) one_way_platform: Option<OneWayPlatform>,
+    pub(crate) one_sided: bool,
+    // The max distance used to remap the existing contacts after the last shape change.
+    pub(crate) contact_remap_distance: Option<Real>,
     /// User-defined data associated to this collider.
     pub user_data: u128,
 }
@@ -50,11 +73,37 @@ impl Collider {
         }
     }
 
+    /// Can the contacts involving this collider generate contact force, contact impulse
+    /// clamp, or impulse-throttled collision events after the constraints resolution?
+    pub(crate) fn may_emit_solver_events(&self) -> bool {
+        self.effective_contact_force_event_threshold() < Real::MAX
+            || self.material.max_contact_impulse < Real::MAX
+            || (self.contact_event_impulse_threshold > 0.0
+                && self
+                    .flags
+                    .active_events
+                    .contains(ActiveEvents::COLLISION_EVENTS))
+    }
+
     /// The rigid body this collider is attached to.
     pub fn parent(&self) -> Option<RigidBodyHandle> {
         self.parent.map(|parent| parent.handle)
     }
 
+    /// The user-data of this collider, unpacked as two `u64`.
+    ///
+    /// See [`crate::utils::unpack_u64s`] for details.
+    pub fn user_data_u64s(&self) -> [u64; 2] {
+        crate::utils::unpack_u64s(self.user_data)
+    }
+
+    /// Sets the user-data of this collider by packing two `u64`.
+    ///
+    /// See [`crate::utils::pack_u64s`] for details.
+    pub fn set_user_data_u64s(&mut self, data: [u64; 2]) {
+        self.user_data = crate::utils::pack_u64s(data);
+    }
+
     /// Is this collider a sensor?
     pub fn is_sensor(&self) -> bool {
         self.coll_type.is_sensor()
@@ -138,11 +187,104 @@ impl Collider {
         self.material.restitution_combine_rule = rule;
     }
 
+    /// Does this collider cancel the restitution of all the contacts it is involved in?
+    pub fn absorbs_restitution(&self) -> bool {
+        self.material.absorbs_restitution
+    }
+
+    /// Sets whether this collider cancels the restitution of all the contacts it is involved in.
+    ///
+    /// If `true`, the effective restitution of these contacts is zero, overriding the restitution
+    /// coefficients and combine rules of both colliders. This is read each time the contacts
+    /// are computed so it takes effect at the next timestep.
+    pub fn set_absorbs_restitution(&mut self, absorbs: bool) {
+        self.material.absorbs_restitution = absorbs;
+    }
+
+    /// The stiffness and damping of the contacts involving this collider.
+    pub fn contact_softness(&self) -> ContactSoftness {
+        self.material.contact_softness
+    }
+
+    /// Sets the stiffness and damping of the contacts involving this collider.
+    ///
+    /// The softness of both colliders in contact are combined with [`ContactSoftness::combine`].
+    /// Use [`ContactSoftness::rigid`] to restore regular contacts.
+    pub fn set_contact_softness(&mut self, softness: ContactSoftness) {
+        self.material.contact_softness = softness;
+    }
+
+    /// The maximum impulse the solver may apply at each contact point involving this collider.
+    pub fn max_contact_impulse(&self) -> Real {
+        self.material.max_contact_impulse
+    }
+
+    /// Sets the maximum impulse the solver may apply at each contact point involving this collider.
+    ///
+    /// The impulse of each contact point is clamped to the smallest maximum contact impulse of
+    /// both colliders in contact. Whenever a contact point would have needed a larger impulse than
+    /// the limit of this collider, a [`ContactImpulseClampEvent`](crate::geometry::ContactImpulseClampEvent)
+    /// is emitted after the constraints resolution. This can be used to break fragile objects.
+    ///
+    /// Set to `Real::MAX` to remove the limit.
+    pub fn set_max_contact_impulse(&mut self, max_impulse: Real) {
+        self.material.max_contact_impulse = max_impulse;
+    }
+
+    /// The anisotropic friction of this collider, if any.
+    #[cfg(feature = "dim3")]
+    pub fn anisotropic_friction(&self) -> Option<&AnisotropicFriction> {
+        self.material.anisotropic_friction.as_ref()
+    }
+
+    /// Sets the anisotropic friction of this collider.
+    ///
+    /// If set, its coefficients replace the isotropic friction coefficient of this collider. Set to
+    /// `None` to restore isotropic friction.
+    #[cfg(feature = "dim3")]
+    pub fn set_anisotropic_friction(&mut self, friction: Option<AnisotropicFriction>) {
+        self.material.anisotropic_friction = friction;
+    }
+
     /// Sets the total force magnitude beyond which a contact force event can be emitted.
     pub fn set_contact_force_event_threshold(&mut self, threshold: Real) {
         self.contact_force_event_threshold = threshold;
     }
 
+    /// Sets the minimum total contact impulse needed for the contacts of this collider to emit
+    /// collision events and contact force events.
+    ///
+    /// See [`ColliderBuilder::contact_event_impulse_threshold`] for details.
+    pub fn set_contact_event_impulse_threshold(&mut self, threshold: Real) {
+        self.contact_event_impulse_threshold = threshold;
+    }
+
+    /// The one-way platform behavior of this collider, if any.
+    pub fn one_way_platform(&self) -> Option<&OneWayPlatform> {
+        self.one_way_platform.as_ref()
+    }
+
+    /// Sets the one-way platform behavior of this collider.
+    ///
+    /// Set to `None` to make this collider generate contacts from all directions again.
+    pub fn set_one_way_platform(&mut self, one_way_platform: Option<OneWayPlatform>) {
+        self.one_way_platform = one_way_platform;
+    }
+
+    /// Is this collider one-sided?
+    ///
+    /// See [`ColliderBuilder::one_sided`] for details.
+    pub fn is_one_sided(&self) -> bool {
+        self.one_sided
+    }
+
+    /// Sets whether or not this collider is one-sided.
+    ///
+    /// See [`ColliderBuilder::one_sided`] for details.
+    pub fn set_one_sided(&mut self, one_sided: bool) {
+        self.one_sided = one_sided;
+    }
+
     /// Sets whether or not this is a sensor collider.
     pub fn set_sensor(&mut self, is_sensor: bool) {
         if is_sensor != self.is_sensor() {
@@ -200,6 +342,12 @@ impl Collider {
     }
 
     /// The world-space position of this collider.
+    ///
+    /// For a collider attached to a rigid-body, this is the rigid-body position composed with
+    /// [`Collider::position_wrt_parent`]. It is cached, and updated at each timestep. If the
+    /// rigid-body is moved manually, it can be updated without running a timestep with
+    /// [`RigidBodySet::propagate_modified_body_positions_to_colliders`](crate::dynamics::RigidBodySet::propagate_modified_body_positions_to_colliders).
+    /// The sub-shapes of a compound shape are positioned relative to this position.
     pub fn position(&self) -> &Isometry<Real> {
         &self.pos
     }
@@ -271,6 +419,16 @@ impl Collider {
         }
     }
 
+    /// The CCD groups used by this collider.
+    pub fn ccd_groups(&self) -> InteractionGroups {
+        self.flags.ccd_groups
+    }
+
+    /// Sets the CCD groups of this collider.
+    pub fn set_ccd_groups(&mut self, groups: InteractionGroups) {
+        self.flags.ccd_groups = groups;
+    }
+
     /// The material (friction and restitution properties) of this collider.
     pub fn material(&self) -> &ColliderMaterial {
         &self.material
@@ -278,7 +436,7 @@ impl Collider {
 
     /// The volume (or surface in 2D) of this collider.
     pub fn volume(&self) -> Real {
-        self.shape.mass_properties(1.0).mass()
+        ColliderMassProps::shape_mass_properties(&*self.shape, 1.0).mass()
     }
 
     /// The density of this collider.
@@ -286,11 +444,13 @@ impl Collider {
         match &self.mprops {
             ColliderMassProps::Density(density) => *density,
             ColliderMassProps::Mass(mass) => {
-                let inv_volume = self.shape.mass_properties(1.0).inv_mass;
+                let inv_volume =
+                    ColliderMassProps::shape_mass_properties(&*self.shape, 1.0).inv_mass;
                 mass * inv_volume
             }
             ColliderMassProps::MassProperties(mprops) => {
-                let inv_volume = self.shape.mass_properties(1.0).inv_mass;
+                let inv_volume =
+                    ColliderMassProps::shape_mass_properties(&*self.shape, 1.0).inv_mass;
                 mprops.mass() * inv_volume
             }
         }
@@ -299,7 +459,9 @@ impl Collider {
     /// The mass of this collider.
     pub fn mass(&self) -> Real {
         match &self.mprops {
-            ColliderMassProps::Density(density) => self.shape.mass_properties(*density).mass(),
+            ColliderMassProps::Density(density) => {
+                ColliderMassProps::shape_mass_properties(&*self.shape, *density).mass()
+            }
             ColliderMassProps::Mass(mass) => *mass,
             ColliderMassProps::MassProperties(mprops) => mprops.mass(),
         }
@@ -360,12 +522,30 @@ impl Collider {
     /// shape that you can modify.
     pub fn shape_mut(&mut self) -> &mut dyn Shape {
         self.changes.insert(ColliderChanges::SHAPE);
+        self.contact_remap_distance = None;
         self.shape.make_mut()
     }
 
     /// Sets the shape of this collider.
     pub fn set_shape(&mut self, shape: SharedShape) {
         self.changes.insert(ColliderChanges::SHAPE);
+        self.contact_remap_distance = None;
+        self.shape = shape;
+    }
+
+    /// Sets the shape of this collider, attempting to preserve its existing contacts.
+    ///
+    /// During the next timestep, each contact computed with the new shape inherits the contact
+    /// data (e.g. the impulse reported in [`ContactData`](crate::geometry::ContactData)) of the
+    /// previous contact closest to it, provided their contact points are less than
+    /// `max_remap_distance` apart (in the local-space of each collider). Such contacts aren’t
+    /// considered new (see [`SolverContact::is_new`](crate::geometry::SolverContact::is_new)),
+    /// so they aren’t handled as impacts. Contacts that can’t be matched start from scratch.
+    /// This avoids a visible jump of resting bodies after small shape modifications (e.g. a
+    /// slight scaling), while larger modifications fall back to a clean reset of the contacts.
+    pub fn set_shape_preserving_contacts(&mut self, shape: SharedShape, max_remap_distance: Real) {
+        self.changes.insert(ColliderChanges::SHAPE);
+        self.contact_remap_distance = Some(max_remap_distance);
         self.shape = shape;
     }
 
@@ -374,6 +554,56 @@ impl Collider {
         &self.shape
     }
 
+    /// The type of the shape of this collider.
+    ///
+    /// This is the type of the shape itself, regardless of the builder used to create the
+    /// collider, nor of the number of colliders sharing this shape.
+    pub fn shape_type(&self) -> ShapeType {
+        self.shape.shape_type()
+    }
+
+    /// The shape of this collider, if it is of type `S`.
+    ///
+    /// The `as_ball`, `as_cuboid`, etc. methods are shortcuts for the most common shape types.
+    /// Note that rounded shapes (e.g. created with [`ColliderBuilder::round_cuboid`]) have
+    /// their own type, so `as_cuboid` returns `None` for a rounded cuboid.
+    pub fn as_shape<S: Shape>(&self) -> Option<&S> {
+        self.shape.as_shape()
+    }
+
+    /// The shape of this collider, if it is a ball.
+    pub fn as_ball(&self) -> Option<&Ball> {
+        self.shape.as_ball()
+    }
+
+    /// The shape of this collider, if it is a cuboid.
+    pub fn as_cuboid(&self) -> Option<&Cuboid> {
+        self.shape.as_cuboid()
+    }
+
+    /// The shape of this collider, if it is a capsule.
+    pub fn as_capsule(&self) -> Option<&Capsule> {
+        self.shape.as_capsule()
+    }
+
+    /// The shape of this collider, if it is a triangle mesh.
+    pub fn as_trimesh(&self) -> Option<&TriMesh> {
+        self.shape.as_trimesh()
+    }
+
+    /// The shape of this collider, if it is a heightfield.
+    pub fn as_heightfield(&self) -> Option<&HeightField> {
+        self.shape.as_heightfield()
+    }
+
+    /// The shape of this collider, if it is a compound shape.
+    ///
+    /// The sub-shapes of the compound shape can be inspected the same way, with the `as_ball`,
+    /// `as_cuboid`, etc. methods of [`dyn Shape`](crate::geometry::Shape).
+    pub fn as_compound(&self) -> Option<&Compound> {
+        self.shape.as_compound()
+    }
+
     /// Compute the axis-aligned bounding box of this collider.
     pub fn compute_aabb(&self) -> Aabb {
         self.shape.compute_aabb(&self.pos)
@@ -394,6 +624,12 @@ impl Collider {
     pub fn contact_force_event_threshold(&self) -> Real {
         self.contact_force_event_threshold
     }
+
+    /// The minimum total contact impulse needed for the contacts of this collider to emit
+    /// collision events and contact force events.
+    pub fn contact_event_impulse_threshold(&self) -> Real {
+        self.contact_event_impulse_threshold
+    }
 }
 
 /// A structure responsible for building a new collider.
@@ -413,6 +649,15 @@ pub struct ColliderBuilder {
     pub restitution: Real,
     /// The rule used to combine two restitution coefficients.
     pub restitution_combine_rule: CoefficientCombineRule,
+    /// Will the collider being built cancel the restitution of all its contacts?
+    pub absorbs_restitution: bool,
+    /// The stiffness and damping of the contacts of the collider to be built.
+    pub contact_softness: ContactSoftness,
+    /// The maximum impulse the solver may apply at each contact point of the collider to be built.
+    pub max_contact_impulse: Real,
+    /// The anisotropic friction of the collider to be built.
+    #[cfg(feature = "dim3")]
+    pub anisotropic_friction: Option<AnisotropicFriction>,
     /// The position of this collider.
     pub position: Isometry<Real>,
     /// Is this collider a sensor?
@@ -429,10 +674,19 @@ pub struct ColliderBuilder {
     pub collision_groups: InteractionGroups,
     /// The solver groups for the collider being built.
     pub solver_groups: InteractionGroups,
+    /// The CCD groups for the collider being built.
+    pub ccd_groups: InteractionGroups,
     /// Will the collider being built be enabled?
     pub enabled: bool,
     /// The total force magnitude beyond which a contact force event can be emitted.
     pub contact_force_event_threshold: Real,
+    /// The minimum total contact impulse needed for the contacts of the collider being built to
+    /// emit collision events and contact force events.
+    pub contact_event_impulse_threshold: Real,
+    /// The one-way platform behavior of the collider being built.
+    pub one_way_platform: Option<OneWayPlatform>,
+    /// Whether or not the collider being built is one-sided.
+    pub one_sided: bool,
 }
 
 impl ColliderBuilder {
@@ -448,21 +702,60 @@ impl ColliderBuilder {
             user_data: 0,
             collision_groups: InteractionGroups::all(),
             solver_groups: InteractionGroups::all(),
+            ccd_groups: InteractionGroups::all(),
             friction_combine_rule: CoefficientCombineRule::Average,
             restitution_combine_rule: CoefficientCombineRule::Average,
+            absorbs_restitution: false,
+            contact_softness: ContactSoftness::rigid(),
+            max_contact_impulse: Real::MAX,
+            #[cfg(feature = "dim3")]
+            anisotropic_friction: None,
             active_collision_types: ActiveCollisionTypes::default(),
             active_hooks: ActiveHooks::empty(),
             active_events: ActiveEvents::empty(),
             enabled: true,
             contact_force_event_threshold: 0.0,
+            contact_event_impulse_threshold: 0.0,
+            one_way_platform: None,
+            one_sided: false,
         }
     }
 
     /// Initialize a new collider builder with a compound shape.
+    ///
+    /// Panics if one of the shapes is a composite shape (compound, triangle mesh, polyline or
+    /// heightfield): nested compounds must be flattened with [`Self::flattened_compound`].
     pub fn compound(shapes: Vec<(Isometry<Real>, SharedShape)>) -> Self {
         Self::new(SharedShape::compound(shapes))
     }
 
+    /// Initialize a new collider builder with a compound shape, where some of the given shapes
+    /// may be compounds themselves.
+    ///
+    /// The parts of the nested compounds are moved to the resulting compound, with their position
+    /// relative to the nested compound baked into their position relative to the resulting
+    /// compound. The resulting parts are ordered like the given shapes, each nested compound
+    /// being replaced by all its parts in order. The mass properties are the same as if the
+    /// compounds were nested.
+    pub fn flattened_compound(shapes: Vec<(Isometry<Real>, SharedShape)>) -> Self {
+        let mut flattened = Vec::with_capacity(shapes.len());
+
+        for (pos, shape) in shapes {
+            if let Some(compound) = shape.as_compound() {
+                flattened.extend(
+                    compound
+                        .shapes()
+                        .iter()
+                        .map(|(sub_pos, sub_shape)| (pos * sub_pos, sub_shape.clone())),
+                );
+            } else {
+                flattened.push((pos, shape));
+            }
+        }
+
+        Self::compound(flattened)
+    }
+
     /// Initialize a new collider builder with a ball shape defined by its radius.
     pub fn ball(radius: Real) -> Self {
         Self::new(SharedShape::ball(radius))
@@ -571,10 +864,35 @@ impl ColliderBuilder {
     }
 
     /// Initializes a collider builder with a polyline shape defined by its vertex and index buffers.
+    ///
+    /// If `indices` is `None`, the vertices are linked in order by an open chain of segments.
+    /// Ray-casts on the colliders of a `QueryPipeline` report the index of the hit polyline
+    /// segment in the feature-id of the ray intersection.
     pub fn polyline(vertices: Vec<Point<Real>>, indices: Option<Vec<[u32; 2]>>) -> Self {
         Self::new(SharedShape::polyline(vertices, indices))
     }
 
+    /// Initializes a collider builder with a polyline shape linking the given vertices in order,
+    /// and linking the last vertex to the first one.
+    ///
+    /// This is suited for closed boundaries, e.g., the outline of a 2D level. The `i`-th segment
+    /// of the polyline starts at `vertices[i]`. If the vertices are given counterclockwise, the
+    /// front side of every segment faces the exterior of the loop (see
+    /// [`ColliderBuilder::one_sided`]).
+    ///
+    /// Returns `None` if less than two vertices are given.
+    pub fn closed_polyline(vertices: Vec<Point<Real>>) -> Option<Self> {
+        if vertices.len() < 2 {
+            return None;
+        }
+
+        let num_vertices = vertices.len() as u32;
+        let indices = (0..num_vertices)
+            .map(|i| [i, (i + 1) % num_vertices])
+            .collect();
+        Some(Self::polyline(vertices, Some(indices)))
+    }
+
     /// Initializes a collider builder with a triangle mesh shape defined by its vertex and index buffers.
     pub fn trimesh(vertices: Vec<Point<Real>>, indices: Vec<[u32; 3]>) -> Self {
         Self::new(SharedShape::trimesh(vertices, indices))
@@ -640,15 +958,59 @@ impl ColliderBuilder {
 
     /// Initializes a new collider builder with a 2D convex polygon or 3D convex polyhedron
     /// obtained after computing the convex-hull of the given points.
+    ///
+    /// Duplicate points are allowed. Returns `None` if the points don’t span a non-degenerate
+    /// volume (in 3D) or area (in 2D), e.g., if they are all collinear, or coplanar in 3D.
     pub fn convex_hull(points: &[Point<Real>]) -> Option<Self> {
-        SharedShape::convex_hull(points).map(Self::new)
+        Self::unit_scale_convex_hull(points).map(|hull| Self::new(SharedShape::new(hull)))
     }
 
     /// Initializes a new collider builder with a round 2D convex polygon or 3D convex polyhedron
     /// obtained after computing the convex-hull of the given points. The shape is dilated
     /// by a sphere of radius `border_radius`.
+    ///
+    /// Returns `None` if the convex-hull of the points is degenerate, like [`Self::convex_hull`].
     pub fn round_convex_hull(points: &[Point<Real>], border_radius: Real) -> Option<Self> {
-        SharedShape::round_convex_hull(points, border_radius).map(Self::new)
+        Self::unit_scale_convex_hull(points).map(|inner_shape| {
+            Self::new(SharedShape::new(RoundShape {
+                inner_shape,
+                border_radius,
+            }))
+        })
+    }
+
+    // NOTE: parry relies on absolute tolerances to build convex shapes, so we compute the
+    //       convex-hull of the points scaled to a unit size, and scale it back afterward.
+    //       Otherwise, the convex-hull of tiny (but otherwise well-shaped) point clouds end up
+    //       with invalid normals and mass properties.
+    fn unit_scale_convex_hull(points: &[Point<Real>]) -> Option<ConvexHull> {
+        if points.len() <= DIM
+            || points
+                .iter()
+                .any(|pt| !pt.coords.iter().all(|e| e.is_finite()))
+        {
+            return None;
+        }
+
+        let extents = Aabb::from_points(points).extents();
+        let size = extents.max();
+
+        // NOTE: the convex-hull computation may panic if the points aren’t spread along every
+        //       axis.
+        if extents.min() <= size * DEGENERATE_CONVEX_HULL_TOLERANCE {
+            return None;
+        }
+
+        let unit_points: Vec<_> = points.iter().map(|pt| pt / size).collect();
+        let hull = ConvexHull::from_convex_hull(&unit_points)?;
+
+        if ColliderMassProps::shape_mass_properties(&hull, 1.0).mass()
+            <= DEGENERATE_CONVEX_HULL_TOLERANCE
+        {
+            return None;
+        }
+
+        hull.scaled(&Vector::repeat(size))
     }
 
     /// Creates a new collider builder that is a convex polygon formed by the
@@ -701,6 +1063,48 @@ impl ColliderBuilder {
         Self::new(SharedShape::heightfield(heights, scale))
     }
 
+    /// Initializes a collider builder with a heightfield shape defined by its set of heights and
+    /// a scale factor along each coordinate axis, with its first height located at `offset`.
+    ///
+    /// Unlike [`ColliderBuilder::heightfield`], which centers the heightfield on the collider,
+    /// this places the corner of the heightfield at its first height, with a height of zero, at
+    /// the point `offset` of the collider’s parent (or of the world, if it doesn’t have any
+    /// parent). This lets adjacent heightfield tiles be placed with their grid origin directly.
+    ///
+    /// The offset is stored as the translation of the builder’s position, so calling
+    /// [`ColliderBuilder::translation`] or [`ColliderBuilder::position`] afterward replaces it.
+    #[cfg(feature = "dim2")]
+    pub fn heightfield_with_offset(
+        heights: na::DVector<Real>,
+        scale: Vector<Real>,
+        offset: Vector<Real>,
+    ) -> Self {
+        let center = offset + Vector::x() * (scale.x / 2.0);
+        Self::heightfield(heights, scale).translation(center)
+    }
+
+    /// Initializes a collider builder with a heightfield shape defined by its set of heights and
+    /// a scale factor along each coordinate axis, with its first row and column located at `offset`.
+    ///
+    /// Unlike [`ColliderBuilder::heightfield`], which centers the heightfield on the collider,
+    /// this places the corner of the heightfield at its first row and column, with a height of
+    /// zero, at the point `offset` of the collider’s parent (or of the world, if it doesn’t have
+    /// any parent). This lets adjacent heightfield tiles, with different scales, be placed with
+    /// their grid origin directly. Use [`heightfield_cell`](crate::geometry::heightfield_cell) to
+    /// map the triangles and faces of the heightfield back to the cells of `heights`.
+    ///
+    /// The offset is stored as the translation of the builder’s position, so calling
+    /// [`ColliderBuilder::translation`] or [`ColliderBuilder::position`] afterward replaces it.
+    #[cfg(feature = "dim3")]
+    pub fn heightfield_with_offset(
+        heights: na::DMatrix<Real>,
+        scale: Vector<Real>,
+        offset: Vector<Real>,
+    ) -> Self {
+        let center = offset + Vector::new(scale.x / 2.0, 0.0, scale.z / 2.0);
+        Self::heightfield(heights, scale).translation(center)
+    }
+
     /// The default friction coefficient used by the collider builder.
     pub fn default_friction() -> Real {
         0.5
@@ -735,7 +1139,23 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets the CCD groups used by this collider.
+    ///
+    /// Continuous collision detection will only prevent tunneling between two colliders
+    /// iff their CCD groups are compatible. See [InteractionGroups::test] for details.
+    /// This can be used to reduce the cost of CCD, for example, by disabling it between
+    /// fast projectiles while keeping it enabled between projectiles and walls.
+    pub fn ccd_groups(mut self, groups: InteractionGroups) -> Self {
+        self.ccd_groups = groups;
+        self
+    }
+
     /// Sets whether or not the collider built by this builder is a sensor.
+    ///
+    /// Being a sensor only affects the contact resolution, not the events: a solid collider with
+    /// [`ActiveEvents::COLLISION_EVENTS`] blocks the other colliders and reports the start and end
+    /// of its contacts with them as collision events too. To also detect colliders that are only
+    /// approaching, attach a sensor with a larger shape to the same rigid-body.
     pub fn sensor(mut self, is_sensor: bool) -> Self {
         self.is_sensor = is_sensor;
         self
@@ -772,6 +1192,12 @@ impl ColliderBuilder {
     }
 
     /// Sets the restitution coefficient of the collider this builder will build.
+    ///
+    /// Restitution applies to the relative velocity at the contact, including the velocity of
+    /// kinematic rigid-bodies: a ball bouncing on a moving kinematic paddle gains the paddle’s
+    /// speed, while a still paddle behaves like a fixed one. The kinematic body must be moved
+    /// through its velocity or [`RigidBody::set_next_kinematic_position`](crate::dynamics::RigidBody::set_next_kinematic_position)
+    /// though: teleporting it with `set_translation` doesn’t give it any velocity.
     pub fn restitution(mut self, restitution: Real) -> Self {
         self.restitution = restitution;
         self
@@ -783,6 +1209,48 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets whether the collider this builder will build cancels the restitution of all the
+    /// contacts it is involved in, regardless of the combine rules.
+    pub fn absorbs_restitution(mut self, absorbs: bool) -> Self {
+        self.absorbs_restitution = absorbs;
+        self
+    }
+
+    /// Sets the stiffness and damping of the contacts of the collider this builder will build.
+    pub fn contact_softness(mut self, softness: ContactSoftness) -> Self {
+        self.contact_softness = softness;
+        self
+    }
+
+    /// Sets the maximum impulse the solver may apply at each contact point of the collider this
+    /// builder will build.
+    ///
+    /// See [`Collider::set_max_contact_impulse`] for details.
+    pub fn max_contact_impulse(mut self, max_impulse: Real) -> Self {
+        self.max_contact_impulse = max_impulse;
+        self
+    }
+
+    /// Sets the anisotropic friction of the collider this builder will build.
+    ///
+    /// The friction coefficient is `friction_along` for sliding along `direction`, expressed in the
+    /// local-space of the collider, and `friction_across` for sliding orthogonally to it. These
+    /// replace the isotropic friction coefficient set with [`ColliderBuilder::friction`].
+    #[cfg(feature = "dim3")]
+    pub fn anisotropic_friction(
+        mut self,
+        direction: Vector<Real>,
+        friction_along: Real,
+        friction_across: Real,
+    ) -> Self {
+        self.anisotropic_friction = Some(AnisotropicFriction::new(
+            direction,
+            friction_along,
+            friction_across,
+        ));
+        self
+    }
+
     /// Sets the uniform density of the collider this builder will build.
     ///
     /// This will be overridden by a call to [`Self::mass`] or [`Self::mass_properties`] so it only
@@ -822,6 +1290,25 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets the minimum total contact impulse needed for the contacts of this collider to emit
+    /// collision events and contact force events.
+    ///
+    /// This filters out the events of trivial contacts, like a body sliding or resting on
+    /// another. The threshold is compared, after the constraints resolution, to the sum of the
+    /// impulse magnitudes applied at all the contact points of a contact pair during one timestep;
+    /// divide it by the timestep length to get the equivalent force. Events are emitted only if
+    /// this total impulse is at least the largest threshold of the two colliders involved.
+    ///
+    /// The `CollisionEvent::Started` event of a contact pair is emitted the first time the solver
+    /// applies a large enough impulse while the colliders touch, instead of as soon as they touch,
+    /// so a hard impact emits it exactly once. The matching `CollisionEvent::Stopped` event is
+    /// only emitted if the start event was. Sensors aren't affected. A threshold of `0.0`, the
+    /// default, disables this filtering.
+    pub fn contact_event_impulse_threshold(mut self, threshold: Real) -> Self {
+        self.contact_event_impulse_threshold = threshold;
+        self
+    }
+
     /// Sets the initial translation of the collider to be created.
     ///
     /// If the collider will be attached to a rigid-body, this sets the translation relative to the
@@ -864,6 +1351,38 @@ impl ColliderBuilder {
         self
     }
 
+    /// Makes the collider being built behave as a one-way platform.
+    ///
+    /// Contacts with this collider will only be taken into account by the constraints
+    /// solver if their normal (pointing toward the exterior of this collider) makes an
+    /// angle smaller than `allowed_angle` with `allowed_local_normal`, expressed in the
+    /// local-space of this collider. A body approaching from the other side will pass
+    /// through the platform until it no longer penetrates it.
+    pub fn one_way_platform(
+        mut self,
+        allowed_local_normal: Vector<Real>,
+        allowed_angle: Real,
+    ) -> Self {
+        self.one_way_platform = Some(OneWayPlatform {
+            allowed_local_normal,
+            allowed_angle,
+        });
+        self
+    }
+
+    /// Sets whether or not the collider being built is one-sided.
+    ///
+    /// This only affects polylines (in 2D) and triangle meshes (in 3D): the contacts touching
+    /// the back side of their segments or triangles are ignored by the constraints solver, so a
+    /// body can pass through them from the back. The front side of a segment is on the right of
+    /// the direction from its first to its second vertex, i.e., toward the exterior of a
+    /// polyline looping counterclockwise. The front side of a triangle is the one its
+    /// counterclockwise normal points to.
+    pub fn one_sided(mut self, one_sided: bool) -> Self {
+        self.one_sided = one_sided;
+        self
+    }
+
     /// Enable or disable the collider after its creation.
     pub fn enabled(mut self, enabled: bool) -> Self {
         self.enabled = enabled;
@@ -878,10 +1397,16 @@ impl ColliderBuilder {
             restitution: self.restitution,
             friction_combine_rule: self.friction_combine_rule,
             restitution_combine_rule: self.restitution_combine_rule,
+            absorbs_restitution: self.absorbs_restitution,
+            contact_softness: self.contact_softness,
+            max_contact_impulse: self.max_contact_impulse,
+            #[cfg(feature = "dim3")]
+            anisotropic_friction: self.anisotropic_friction,
         };
         let flags = ColliderFlags {
             collision_groups: self.collision_groups,
             solver_groups: self.solver_groups,
+            ccd_groups: self.ccd_groups,
             active_collision_types: self.active_collision_types,
             active_hooks: self.active_hooks,
             active_events: self.active_events,
@@ -911,6 +1436,10 @@ impl ColliderBuilder {
             flags,
             coll_type,
             contact_force_event_threshold: self.contact_force_event_threshold,
+            contact_event_impulse_threshold: self.contact_event_impulse_threshold,
+            one_way_platform: self.one_way_platform,
+            one_sided: self.one_sided,
+            contact_remap_distance: None,
             user_data: self.user_data,
         }
     }
@@ -921,3 +1450,210 @@ impl Into<Collider> for ColliderBuilder {
         self.build()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::ColliderBuilder;
+    use crate::geometry::SharedShape;
+    use crate::math::{Isometry, Point, Real, Vector, DIM};
+
+    fn box_points(half_extents: Vector<Real>) -> Vec<Point<Real>> {
+        (0..1u32 << DIM)
+            .map(|id| {
+                Point::from(Vector::from_fn(|i, _| {
+                    if id & (1 << i) != 0 {
+                        half_extents[i]
+                    } else {
+                        -half_extents[i]
+                    }
+                }))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn convex_hull_of_tiny_point_clouds() {
+        for half_extent in [1.0, 1.0e-2, 1.0e-4, 1.0e-6] {
+            let offset = Vector::repeat(half_extent * 3.0);
+            let points: Vec<_> = box_points(Vector::repeat(half_extent))
+                .into_iter()
+                .map(|pt| pt + offset)
+                .collect();
+            let expected_volume = (half_extent * 2.0).powi(DIM as i32);
+
+            let collider = ColliderBuilder::convex_hull(&points).unwrap().build();
+            let mprops = collider.mass_properties();
+            assert!((collider.volume() / expected_volume - 1.0).abs() < 1.0e-3);
+            assert!((mprops.local_com - Point::from(offset)).norm() < half_extent * 1.0e-3);
+            assert!(mprops.principal_inertia().iter().all(|i| *i > 0.0));
+            #[cfg(feature = "dim3")]
+            assert_eq!(
+                collider
+                    .shape()
+                    .as_convex_polyhedron()
+                    .unwrap()
+                    .points()
+                    .len(),
+                8
+            );
+
+            let round = ColliderBuilder::round_convex_hull(&points, half_extent * 0.1).unwrap();
+            assert!((round.build().volume() / expected_volume - 1.0).abs() < 1.0e-3);
+        }
+    }
+
+    #[test]
+    fn convex_hull_of_duplicate_points() {
+        let mut points = box_points(Vector::repeat(1.0));
+        points.extend_from_within(..);
+        points.extend(box_points(Vector::repeat(1.0 - 1.0e-6)));
+
+        let collider = ColliderBuilder::convex_hull(&points).unwrap().build();
+        assert!((collider.volume() / (2.0 as Real).powi(DIM as i32) - 1.0).abs() < 1.0e-3);
+    }
+
+    #[test]
+    fn convex_hull_of_degenerate_point_clouds() {
+        // Not enough points.
+        assert!(ColliderBuilder::convex_hull(&[]).is_none());
+        assert!(ColliderBuilder::convex_hull(&box_points(Vector::repeat(1.0))[..DIM]).is_none());
+
+        // Collinear points.
+        let collinear: Vec<_> = (0..10)
+            .map(|i| Point::from(Vector::repeat(i as Real)))
+            .collect();
+        assert!(ColliderBuilder::convex_hull(&collinear).is_none());
+
+        // Points flat along one axis, relative to their size, even if the hull is large.
+        for size in [1.0e-4, 1.0, 1.0e4] {
+            let mut half_extents = Vector::repeat(size);
+            half_extents[DIM - 1] = size * 1.0e-7;
+            assert!(ColliderBuilder::convex_hull(&box_points(half_extents)).is_none());
+            assert!(ColliderBuilder::round_convex_hull(&box_points(half_extents), 0.1).is_none());
+        }
+
+        // Non-finite points.
+        let mut points = box_points(Vector::repeat(1.0));
+        points[0].x = Real::NAN;
+        assert!(ColliderBuilder::convex_hull(&points).is_none());
+    }
+
+    #[test]
+    fn closed_polyline_links_the_last_vertex_to_the_first() {
+        let vertices = box_points(Vector::repeat(1.0));
+        let collider = ColliderBuilder::closed_polyline(vertices.clone())
+            .unwrap()
+            .build();
+        let polyline = collider.shape().as_polyline().unwrap();
+        let n = vertices.len() as u32;
+
+        assert_eq!(polyline.num_segments(), vertices.len());
+        for (i, idx) in polyline.indices().iter().enumerate() {
+            assert_eq!(*idx, [i as u32, (i as u32 + 1) % n]);
+        }
+
+        assert!(ColliderBuilder::closed_polyline(vec![]).is_none());
+        assert!(ColliderBuilder::closed_polyline(vec![Point::origin()]).is_none());
+    }
+
+    #[test]
+    fn flattened_compounds_bake_the_nested_positions() {
+        let nested = SharedShape::compound(vec![
+            (Isometry::from(Vector::x()), SharedShape::ball(0.5)),
+            (Isometry::from(Vector::x() * 2.0), SharedShape::ball(0.25)),
+        ]);
+        let flattened = ColliderBuilder::flattened_compound(vec![
+            (Isometry::identity(), SharedShape::ball(1.0)),
+            (Isometry::from(Vector::y()), nested),
+        ])
+        .build();
+        let expected = ColliderBuilder::compound(vec![
+            (Isometry::identity(), SharedShape::ball(1.0)),
+            (
+                Isometry::from(Vector::x() + Vector::y()),
+                SharedShape::ball(0.5),
+            ),
+            (
+                Isometry::from(Vector::x() * 2.0 + Vector::y()),
+                SharedShape::ball(0.25),
+            ),
+        ])
+        .build();
+
+        let parts = flattened.shape().as_compound().unwrap().shapes();
+        let expected_parts = expected.shape().as_compound().unwrap().shapes();
+        assert_eq!(parts.len(), expected_parts.len());
+        for (part, expected_part) in parts.iter().zip(expected_parts) {
+            assert_eq!(part.0, expected_part.0);
+            assert_eq!(
+                part.1.as_ball().unwrap().radius,
+                expected_part.1.as_ball().unwrap().radius
+            );
+        }
+        assert_eq!(flattened.mass_properties(), expected.mass_properties());
+    }
+
+    #[test]
+    fn collider_shapes_can_be_inspected() {
+        use crate::geometry::{Cuboid, RoundCuboid, ShapeType};
+
+        let ball = ColliderBuilder::ball(0.5).build();
+        assert_eq!(ball.shape_type(), ShapeType::Ball);
+        assert_eq!(ball.as_ball().map(|b| b.radius), Some(0.5));
+        assert!(ball.as_cuboid().is_none());
+
+        let capsule = ColliderBuilder::capsule_y(1.0, 0.5).build();
+        assert_eq!(capsule.shape_type(), ShapeType::Capsule);
+        assert_eq!(capsule.as_capsule().map(|c| c.radius), Some(0.5));
+
+        // Rounded shapes have their own type.
+        #[cfg(feature = "dim2")]
+        let round = ColliderBuilder::round_cuboid(1.0, 1.0, 0.1).build();
+        #[cfg(feature = "dim3")]
+        let round = ColliderBuilder::round_cuboid(1.0, 1.0, 1.0, 0.1).build();
+        assert_eq!(round.shape_type(), ShapeType::RoundCuboid);
+        assert!(round.as_cuboid().is_none());
+        assert!(round.as_shape::<RoundCuboid>().is_some());
+
+        // Shared shapes and compound shapes.
+        let cube = SharedShape::new(Cuboid::new(Vector::repeat(0.5)));
+        let compound = ColliderBuilder::compound(vec![
+            (Isometry::identity(), cube.clone()),
+            (
+                Isometry::new(Vector::x() * 2.0, na::zero()),
+                SharedShape::ball(0.5),
+            ),
+        ])
+        .build();
+        let shared = ColliderBuilder::new(cube).build();
+        assert_eq!(shared.as_cuboid().map(|c| c.half_extents.x), Some(0.5));
+        assert_eq!(compound.shape_type(), ShapeType::Compound);
+        let parts = compound.as_compound().unwrap().shapes();
+        assert!(parts[0].1.as_cuboid().is_some());
+        assert!(parts[1].1.as_ball().is_some());
+    }
+
+    #[test]
+    fn user_data_packs_two_u64s() {
+        use crate::dynamics::RigidBodyBuilder;
+
+        let data = [u64::MAX - 1, 42];
+        let mut collider = ColliderBuilder::ball(0.5).build();
+        let mut body = RigidBodyBuilder::dynamic().build();
+        collider.set_user_data_u64s(data);
+        body.set_user_data_u64s(data);
+
+        // The first element is stored in the least significant bits.
+        assert_eq!(collider.user_data, (42 << 64) | (u64::MAX - 1) as u128);
+        assert_eq!(body.user_data, collider.user_data);
+        assert_eq!(collider.user_data_u64s(), data);
+        assert_eq!(body.user_data_u64s(), data);
+
+        #[cfg(feature = "serde-serialize")]
+        {
+            let collider: super::Collider =
+                bincode::deserialize(&bincode::serialize(&collider).unwrap()).unwrap();
+            assert_eq!(collider.user_data_u64s(), data);
+        }
+    }
+}
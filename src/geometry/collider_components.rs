@@ -1,8 +1,13 @@
-use crate::dynamics::{CoefficientCombineRule, MassProperties, RigidBodyHandle, RigidBodyType};
+use crate::dynamics::{
+    CoefficientCombineRule, IntegrationParameters, MassProperties, RigidBodyHandle, RigidBodyType,
+};
 use crate::geometry::{InteractionGroups, SAPProxyIndex, Shape, SharedShape};
-use crate::math::{Isometry, Real};
+#[cfg(feature = "dim3")]
+use crate::math::Point;
+use crate::math::{Isometry, Real, Vector};
 use crate::parry::partitioning::IndexedData;
 use crate::pipeline::{ActiveEvents, ActiveHooks};
+use na::RealField;
 use std::ops::{Deref, DerefMut};
 
 /// The unique identifier of a collider added to a collider set.
@@ -66,6 +71,11 @@ bitflags::bitflags! {
         const PARENT_EFFECTIVE_DOMINANCE = 1 << 7; // NF update.
         /// Flag indicating that whether or not the collider is enabled was changed.
         const ENABLED_OR_DISABLED = 1 << 8; // BF & NF updates.
+        /// Flag indicating that the parent rigid-body of this collider was teleported.
+        ///
+        /// This flags is automatically set by the `PhysicsPipeline` when the `RigidBodyChanges::TELEPORTED`
+        /// of the parent rigid-body of this collider is detected.
+        const PARENT_TELEPORTED = 1 << 9; // NF update. NF pair invalidation.
     }
 }
 
@@ -79,7 +89,10 @@ impl ColliderChanges {
     /// Do these changes justify a broad-phase update?
     pub fn needs_broad_phase_update(self) -> bool {
         self.intersects(
-            ColliderChanges::PARENT | ColliderChanges::POSITION | ColliderChanges::SHAPE,
+            ColliderChanges::PARENT
+                | ColliderChanges::POSITION
+                | ColliderChanges::SHAPE
+                | ColliderChanges::ENABLED_OR_DISABLED,
         )
     }
 
@@ -169,14 +182,14 @@ impl ColliderMassProps {
         match self {
             ColliderMassProps::Density(density) => {
                 if *density != 0.0 {
-                    shape.mass_properties(*density)
+                    Self::shape_mass_properties(shape, *density)
                 } else {
                     MassProperties::default()
                 }
             }
             ColliderMassProps::Mass(mass) => {
                 if *mass != 0.0 {
-                    let mut mprops = shape.mass_properties(1.0);
+                    let mut mprops = Self::shape_mass_properties(shape, 1.0);
                     mprops.set_mass(*mass, true);
                     mprops
                 } else {
@@ -186,6 +199,42 @@ impl ColliderMassProps {
             ColliderMassProps::MassProperties(mass_properties) => **mass_properties,
         }
     }
+
+    /// The mass-properties of the given shape with a uniform density.
+    ///
+    /// This is the same as `shape.mass_properties(density)`, except that the mass-properties of
+    /// convex polyhedra remain accurate even if they are tiny or far from their local origin.
+    pub(crate) fn shape_mass_properties(shape: &dyn Shape, density: Real) -> MassProperties {
+        // NOTE: parry integrates the volume of convex polyhedra from a fixed point, which loses
+        //       most of the precision if the polyhedron is small relative to its distance to that
+        //       point. So we compute it for the polyhedron centered and scaled to a unit size.
+        #[cfg(feature = "dim3")]
+        if let Some(polyhedron) = shape.as_convex_polyhedron().or_else(|| {
+            shape
+                .as_round_convex_polyhedron()
+                .map(|round| &round.inner_shape)
+        }) {
+            let aabb = polyhedron.local_aabb();
+            let (center, size) = (aabb.center(), aabb.extents().max());
+
+            if size > 0.0 {
+                let (mut vertices, indices) = polyhedron.to_trimesh();
+                vertices
+                    .iter_mut()
+                    .for_each(|pt| *pt = Point::from((*pt - center) / size));
+
+                let mut mprops =
+                    MassProperties::from_convex_polyhedron(density, &vertices, &indices);
+                mprops.local_com = center + mprops.local_com.coords * size;
+                mprops.inv_mass /= size * size * size;
+                // The angular inertia scales with `size⁵`.
+                mprops.inv_principal_inertia_sqrt /= size * size * size.sqrt();
+                return mprops;
+            }
+        }
+
+        shape.mass_properties(density)
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -272,6 +321,20 @@ pub struct ColliderMaterial {
     pub friction_combine_rule: CoefficientCombineRule,
     /// The rule applied to combine the restitution coefficients of two colliders.
     pub restitution_combine_rule: CoefficientCombineRule,
+    /// If `true`, the contacts involving this collider have a restitution of zero, whatever the
+    /// restitution coefficients and combine rules of both colliders.
+    pub absorbs_restitution: bool,
+    /// The stiffness and damping of the contacts involving this collider.
+    pub contact_softness: ContactSoftness,
+    /// The maximum impulse the solver may apply at each contact point involving this collider.
+    ///
+    /// Defaults to `Real::MAX`, i.e., no limit.
+    pub max_contact_impulse: Real,
+    /// The anisotropic friction of this collider, if any.
+    ///
+    /// If set, its friction coefficients replace `friction`.
+    #[cfg(feature = "dim3")]
+    pub anisotropic_friction: Option<AnisotropicFriction>,
 }
 
 impl ColliderMaterial {
@@ -292,6 +355,145 @@ impl Default for ColliderMaterial {
             restitution: 0.0,
             friction_combine_rule: CoefficientCombineRule::default(),
             restitution_combine_rule: CoefficientCombineRule::default(),
+            absorbs_restitution: false,
+            contact_softness: ContactSoftness::rigid(),
+            max_contact_impulse: Real::MAX,
+            #[cfg(feature = "dim3")]
+            anisotropic_friction: None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+/// The stiffness and damping of the contacts involving a collider.
+///
+/// Soft contacts behave like a damped spring along the contact normal: they let the colliders
+/// penetrate under load and push them apart again once the load is removed. The default,
+/// [`ContactSoftness::rigid`], has an infinite stiffness and matches the regular contacts
+/// configured by the global [`IntegrationParameters`].
+pub struct ContactSoftness {
+    /// The natural frequency (in Hz) of the spring-like contacts. Smaller values yield softer
+    /// contacts. Set to `Real::INFINITY` for rigid contacts.
+    pub natural_frequency: Real,
+    /// The damping ratio of the spring-like contacts. A value of `1.0` is critically damped,
+    /// smaller values make the colliders oscillate before coming to rest.
+    ///
+    /// This is ignored if the contacts are rigid.
+    pub damping_ratio: Real,
+}
+
+impl Default for ContactSoftness {
+    fn default() -> Self {
+        Self::rigid()
+    }
+}
+
+impl ContactSoftness {
+    /// Soft contacts with the given natural frequency (in Hz) and damping ratio.
+    pub fn new(natural_frequency: Real, damping_ratio: Real) -> Self {
+        Self {
+            natural_frequency,
+            damping_ratio,
+        }
+    }
+
+    /// Rigid contacts, with an infinite stiffness.
+    pub fn rigid() -> Self {
+        Self {
+            natural_frequency: Real::INFINITY,
+            damping_ratio: 1.0,
+        }
+    }
+
+    /// Are these contacts rigid (infinite stiffness)?
+    pub fn is_rigid(&self) -> bool {
+        self.natural_frequency == Real::INFINITY
+    }
+
+    /// Combines the softness of two colliders in contact.
+    ///
+    /// Both contacts are treated as springs in series, so the result is softer than either of
+    /// them. The damping ratio of the softest collider is kept.
+    pub fn combine(&self, other: &Self) -> Self {
+        if self.is_rigid() {
+            return *other;
+        }
+        if other.is_rigid() {
+            return *self;
+        }
+
+        let sq_freq1 = self.natural_frequency * self.natural_frequency;
+        let sq_freq2 = other.natural_frequency * other.natural_frequency;
+        let damping_ratio = if self.natural_frequency <= other.natural_frequency {
+            self.damping_ratio
+        } else {
+            other.damping_ratio
+        };
+
+        Self {
+            natural_frequency: (sq_freq1 * sq_freq2 / (sq_freq1 + sq_freq2)).sqrt(),
+            damping_ratio,
+        }
+    }
+
+    /// The error reduction parameter divided by `dt`, and the constraint force mixing factor,
+    /// applied by the solver to contacts with this softness.
+    pub(crate) fn erp_inv_dt_and_cfm_factor(&self, params: &IntegrationParameters) -> (Real, Real) {
+        if self.is_rigid() || params.dt == 0.0 {
+            return (params.erp_inv_dt(), params.cfm_factor());
+        }
+
+        let ang_freq = self.natural_frequency.max(0.0) * Real::two_pi();
+        let dt_freq = params.dt * ang_freq;
+        let denom = dt_freq + 2.0 * self.damping_ratio.max(0.0);
+
+        if denom == 0.0 {
+            // A zero stiffness without damping: the contact doesn't push back at all.
+            return (0.0, 0.0);
+        }
+
+        let d = dt_freq * denom;
+        (ang_freq / denom, d / (1.0 + d))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[cfg(feature = "dim3")]
+/// Friction coefficients that depend on the sliding direction, e.g., for skate blades or
+/// brushed surfaces.
+///
+/// The friction along `direction` and the friction across it are given by two different
+/// coefficients. The friction limit for sliding along any other tangent direction follows an
+/// ellipse (or a rectangle with [`FrictionModel::Pyramid`](crate::dynamics::FrictionModel))
+/// with these two coefficients as semi-axes.
+///
+/// The coefficients of both colliders in contact are combined axis by axis with their friction
+/// combine rules, a collider without anisotropic friction using its isotropic friction
+/// coefficient along both axes. If both colliders have anisotropic friction, the direction of the
+/// first one is used. If the combined coefficients are equal, the contact friction is isotropic.
+pub struct AnisotropicFriction {
+    /// The principal friction direction.
+    ///
+    /// This is expressed in the local-space of the collider, so it rotates with it, except in
+    /// [`ContactManifoldData::anisotropic_friction`](crate::geometry::ContactManifoldData::anisotropic_friction)
+    /// where it is expressed in world-space.
+    pub direction: Vector<Real>,
+    /// The friction coefficient for sliding along `direction`.
+    pub friction_along: Real,
+    /// The friction coefficient for sliding along the tangent direction orthogonal to `direction`.
+    pub friction_across: Real,
+}
+
+#[cfg(feature = "dim3")]
+impl AnisotropicFriction {
+    /// Anisotropic friction with the given principal direction and friction coefficients.
+    pub fn new(direction: Vector<Real>, friction_along: Real, friction_across: Real) -> Self {
+        Self {
+            direction,
+            friction_along,
+            friction_across,
         }
     }
 }
@@ -374,6 +576,38 @@ impl Default for ActiveCollisionTypes {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+/// Configures a collider to behave as a one-way platform.
+///
+/// A one-way platform only generates contacts with other colliders if they touch it
+/// from its allowed side. For example, a platform with an allowed local normal of
+/// `+y` lets bodies jump through it from below, but lets them land on its top.
+pub struct OneWayPlatform {
+    /// The direction, in the local-space of the collider, of the contact normals
+    /// (pointing toward the exterior of the platform) that are allowed to generate contacts.
+    pub allowed_local_normal: Vector<Real>,
+    /// The maximum angle between a contact normal and `allowed_local_normal` for
+    /// this contact to be allowed.
+    pub allowed_angle: Real,
+}
+
+impl OneWayPlatform {
+    /// Creates a one-way platform configuration allowing contacts with normals close
+    /// to `allowed_local_normal`.
+    pub fn new(allowed_local_normal: Vector<Real>) -> Self {
+        Self {
+            allowed_local_normal,
+            allowed_angle: Self::default_allowed_angle(),
+        }
+    }
+
+    /// The default maximum angle between a contact normal and the allowed normal.
+    pub fn default_allowed_angle() -> Real {
+        0.1
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 /// Enum indicating whether or not a collider is enabled.
@@ -400,6 +634,9 @@ pub struct ColliderFlags {
     /// The groups controlling the pairs of collider that have their contact
     /// points taken into account for force computation.
     pub solver_groups: InteractionGroups,
+    /// The groups controlling the pairs of colliders that are taken into account by
+    /// continuous collision detection.
+    pub ccd_groups: InteractionGroups,
     /// The physics hooks enabled for contact pairs and intersection pairs involving this collider.
     pub active_hooks: ActiveHooks,
     /// The events enabled for this collider.
@@ -414,6 +651,7 @@ impl Default for ColliderFlags {
             active_collision_types: ActiveCollisionTypes::default(),
             collision_groups: InteractionGroups::all(),
             solver_groups: InteractionGroups::all(),
+            ccd_groups: InteractionGroups::all(),
             active_hooks: ActiveHooks::empty(),
             active_events: ActiveEvents::empty(),
             enabled: ColliderEnabled::Enabled,
@@ -438,3 +676,62 @@ impl From<ActiveEvents> for ColliderFlags {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::ContactSoftness;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    /// The heights of a ball resting on the ground, after settling, while pushed down by a load
+    /// of eight times its weight, and after the load is removed.
+    fn loaded_ball_heights(softness: ContactSoftness) -> [Real; 3] {
+        let mut world = TestWorld::new();
+
+        world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+        let ball = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        let collider = ColliderBuilder::ball(0.5).contact_softness(softness);
+        world
+            .colliders
+            .insert_with_parent(collider, ball, &mut world.bodies);
+        let load = world.gravity * world.bodies[ball].mass() * 8.0;
+
+        let mut heights = [0.0; 3];
+
+        for (i, height) in heights.iter_mut().enumerate() {
+            if i == 1 {
+                world.bodies[ball].add_force(load, true);
+            } else {
+                world.bodies[ball].reset_forces(true);
+            }
+
+            for _ in 0..120 {
+                world.step();
+            }
+
+            *height = world.bodies[ball].translation().y;
+        }
+
+        heights
+    }
+
+    #[test]
+    fn soft_contacts_compress_under_load_and_recover() {
+        let [rest, loaded, unloaded] = loaded_ball_heights(ContactSoftness::new(5.0, 1.0));
+        assert!(rest < 0.5 && rest > 0.45);
+        assert!(loaded < rest - 0.05);
+        assert!((unloaded - rest).abs() < 1.0e-2);
+
+        // Rigid contacts barely compress.
+        let [rest, loaded, unloaded] = loaded_ball_heights(ContactSoftness::rigid());
+        assert!((rest - 0.5).abs() < 1.0e-2);
+        assert!((loaded - 0.5).abs() < 1.0e-2);
+        assert!((unloaded - 0.5).abs() < 1.0e-2);
+    }
+}
@@ -23,10 +23,31 @@ impl ColliderSet {
         }
     }
 
+    /// Create a new empty set of colliders, with room for `capacity` colliders.
+    ///
+    /// The set grows as usual (without panicking) if more colliders are inserted.
+    pub fn with_capacity(capacity: usize) -> Self {
+        ColliderSet {
+            colliders: Arena::with_capacity(capacity),
+            modified_colliders: Vec::new(),
+            removed_colliders: Vec::new(),
+        }
+    }
+
     pub(crate) fn take_modified(&mut self) -> Vec<ColliderHandle> {
         std::mem::replace(&mut self.modified_colliders, vec![])
     }
 
+    // Same as `take_modified`, but the next modified colliders are recorded into `buffer` (once
+    // cleared) to reuse its allocation.
+    pub(crate) fn take_modified_with_buffer(
+        &mut self,
+        mut buffer: Vec<ColliderHandle>,
+    ) -> Vec<ColliderHandle> {
+        buffer.clear();
+        std::mem::replace(&mut self.modified_colliders, buffer)
+    }
+
     pub(crate) fn take_removed(&mut self) -> Vec<ColliderHandle> {
         std::mem::replace(&mut self.removed_colliders, vec![])
     }
@@ -41,6 +62,16 @@ impl ColliderSet {
         self.colliders.iter().map(|(h, c)| (ColliderHandle(h), c))
     }
 
+    /// Iterate through all the colliders on this set, in the order they were inserted.
+    ///
+    /// Unlike [`ColliderSet::iter`], this order doesn't depend on the removals that happened
+    /// before the insertion of the colliders.
+    pub fn iter_in_insertion_order(&self) -> impl Iterator<Item = (ColliderHandle, &Collider)> {
+        self.colliders
+            .iter_in_insertion_order()
+            .map(|(h, c)| (ColliderHandle(h), c))
+    }
+
     /// Iterate through all the enabled colliders on this set.
     pub fn iter_enabled(&self) -> impl Iterator<Item = (ColliderHandle, &Collider)> {
         self.colliders
@@ -256,6 +287,14 @@ impl ColliderSet {
         self.colliders.get(handle.0)
     }
 
+    /// The handle of the rigid-body the given collider is attached to.
+    ///
+    /// Returns `None` if the collider has no parent, or if it doesn’t exist (for example
+    /// because it was removed, as reported by some `CollisionEvent::Stopped` events).
+    pub fn parent(&self, handle: ColliderHandle) -> Option<RigidBodyHandle> {
+        self.get(handle)?.parent()
+    }
+
     fn mark_as_modified(
         handle: ColliderHandle,
         collider: &mut Collider,
@@ -296,6 +335,9 @@ impl ColliderSet {
     }
 }
 
+/// Panics if there is no collider with the given index.
+///
+/// Use `ColliderSet::get` with `ColliderHandle(index)` for a non-panicking alternative.
 impl Index<crate::data::Index> for ColliderSet {
     type Output = Collider;
 
@@ -304,6 +346,9 @@ impl Index<crate::data::Index> for ColliderSet {
     }
 }
 
+/// Panics if there is no collider with the given handle.
+///
+/// Use `ColliderSet::get` for a non-panicking alternative.
 impl Index<ColliderHandle> for ColliderSet {
     type Output = Collider;
 
@@ -313,6 +358,9 @@ impl Index<ColliderHandle> for ColliderSet {
 }
 
 #[cfg(not(feature = "dev-remove-slow-accessors"))]
+/// Panics if there is no collider with the given handle.
+///
+/// Use `ColliderSet::get_mut` for a non-panicking alternative.
 impl IndexMut<ColliderHandle> for ColliderSet {
     fn index_mut(&mut self, handle: ColliderHandle) -> &mut Collider {
         let collider = &mut self.colliders[handle.0];
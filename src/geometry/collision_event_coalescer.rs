@@ -0,0 +1,435 @@
+use crate::data::arena::Index;
+use crate::dynamics::RigidBodySet;
+use crate::geometry::{
+    ColliderHandle, ColliderSet, CollisionEvent, ContactImpulseClampEvent, ContactPair,
+};
+use crate::math::Real;
+use crate::pipeline::EventHandler;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Collision events emitted by the narrow-phase during the current timestep, and not yet
+/// delivered to the user's event handler.
+///
+/// The contact pair given along a start event to the event handler is retrieved when the events
+/// are delivered. The one given along a stop event is copied when the event is emitted, because
+/// the pair may be removed from the narrow-phase before the events are delivered.
+#[derive(Clone, Default)]
+pub(crate) struct PendingCollisionEvents {
+    pairs: HashMap<(Index, Index), PairEvents>,
+    // NOTE: kept only to reuse its allocation from one flush to the next.
+    sorted: Vec<((Index, Index), PairEvents)>,
+}
+
+/// The net collision events of a collider pair, in emission order.
+///
+/// The events of a pair alternate between start and stop events, so two of them are enough to
+/// report both an uncancelable start event and the stop event following it.
+#[derive(Clone, Default)]
+struct PairEvents {
+    // NOTE: the boolean is `true` if the event is canceled by a later opposite transition.
+    events: [Option<(CollisionEvent, bool)>; 2],
+    // The contact pair given along the last stop event.
+    stopped_pair: Option<ContactPair>,
+}
+
+impl PairEvents {
+    fn push(
+        &mut self,
+        event: CollisionEvent,
+        cancelable: bool,
+        contact_pair: Option<&ContactPair>,
+    ) {
+        if event.stopped() {
+            self.stopped_pair = contact_pair.cloned();
+        }
+
+        let cancels = |pending: (CollisionEvent, bool)| {
+            // A start followed by a stop (or the opposite) has no net effect.
+            cancelable && pending.1 && pending.0.started() != event.started()
+        };
+
+        match self.events {
+            [None, _] => self.events[0] = Some((event, cancelable)),
+            [Some(first), None] if cancels(first) => self.events[0] = None,
+            [Some(_), None] => self.events[1] = Some((event, cancelable)),
+            [Some(_), Some(last)] if cancels(last) => self.events[1] = None,
+            [Some(first), Some(last)] => {
+                // `first` and `event` are the same transition, and `last` followed by `event`
+                // has no net effect, so we only keep `event`.
+                let cancelable = cancelable && first.1 && last.1;
+                self.events = [Some((event, cancelable)), None];
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.events[0].is_none()
+    }
+}
+
+/// The handles of the colliders involved in `event`, smallest first.
+fn sorted_pair_key(event: &CollisionEvent) -> (Index, Index) {
+    let (h1, h2) = (event.collider1().0, event.collider2().0);
+    if h1 <= h2 {
+        (h1, h2)
+    } else {
+        (h2, h1)
+    }
+}
+
+impl PendingCollisionEvents {
+    fn push(
+        &mut self,
+        event: CollisionEvent,
+        cancelable: bool,
+        contact_pair: Option<&ContactPair>,
+    ) {
+        let key = sorted_pair_key(&event);
+        let pair = self.pairs.entry(key).or_default();
+        pair.push(event, cancelable, contact_pair);
+
+        if pair.is_empty() {
+            let _ = self.pairs.remove(&key);
+        }
+    }
+
+    /// Queues an event that a later opposite transition within the same timestep won't cancel.
+    ///
+    /// This is used for the start events emitted after the constraints resolution: the impact
+    /// that triggered them is reported even if the colliders separate right away.
+    pub fn push_uncancelable(&mut self, event: CollisionEvent) {
+        self.push(event, false, None)
+    }
+
+    /// Delivers all the pending events to `events`, sorted by the handles of the colliders
+    /// involved, smallest first.
+    ///
+    /// The events are sorted so that their order doesn't depend on the order they were emitted
+    /// in, which isn't deterministic when the narrow-phase runs in parallel. The contact pair
+    /// given along each start event is obtained from `contact_pair`.
+    pub fn flush<'a>(
+        &mut self,
+        contact_pair: impl Fn(ColliderHandle, ColliderHandle) -> Option<&'a ContactPair>,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        events: &dyn EventHandler,
+    ) {
+        self.sorted.extend(self.pairs.drain());
+        self.sorted.sort_unstable_by_key(|(key, _)| *key);
+
+        for (_, pair_events) in self.sorted.drain(..) {
+            for (event, _) in pair_events.events.into_iter().flatten() {
+                let pair = if event.sensor() {
+                    None
+                } else if event.stopped() {
+                    pair_events.stopped_pair.as_ref()
+                } else {
+                    contact_pair(event.collider1(), event.collider2())
+                };
+                events.handle_collision_event(bodies, colliders, event, pair);
+            }
+        }
+    }
+}
+
+/// An event handler that buffers the collision events emitted by the narrow-phase so that
+/// at most one net transition per collider pair is reported for each timestep.
+///
+/// Contact force events are forwarded immediately to the wrapped event handler.
+pub(crate) struct CollisionEventCoalescer<'a> {
+    events: &'a dyn EventHandler,
+    pending: Mutex<PendingCollisionEvents>,
+}
+
+impl<'a> CollisionEventCoalescer<'a> {
+    pub fn new(events: &'a dyn EventHandler, pending: PendingCollisionEvents) -> Self {
+        Self {
+            events,
+            pending: Mutex::new(pending),
+        }
+    }
+
+    pub fn into_pending(self) -> PendingCollisionEvents {
+        self.pending.into_inner().unwrap()
+    }
+}
+
+impl<'a> EventHandler for CollisionEventCoalescer<'a> {
+    fn handle_collision_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        event: CollisionEvent,
+        contact_pair: Option<&ContactPair>,
+    ) {
+        self.pending.lock().unwrap().push(event, true, contact_pair);
+    }
+
+    fn handle_contact_force_event(
+        &self,
+        dt: Real,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        contact_pair: &ContactPair,
+        total_force_magnitude: Real,
+    ) {
+        self.events.handle_contact_force_event(
+            dt,
+            bodies,
+            colliders,
+            contact_pair,
+            total_force_magnitude,
+        )
+    }
+
+    fn handle_contact_impulse_clamp_event(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        event: ContactImpulseClampEvent,
+        contact_pair: &ContactPair,
+    ) {
+        self.events
+            .handle_contact_impulse_clamp_event(bodies, colliders, event, contact_pair)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PendingCollisionEvents;
+    use crate::dynamics::{RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{
+        ColliderBuilder, ColliderHandle, ColliderSet, CollisionEvent, CollisionEventFlags,
+    };
+    use crate::math::Vector;
+    use crate::pipeline::test_world::TestWorld;
+    use crate::pipeline::ChannelEventCollector;
+
+    fn started(h1: u32, h2: u32) -> CollisionEvent {
+        CollisionEvent::Started(
+            ColliderHandle::from_raw_parts(h1, 0),
+            ColliderHandle::from_raw_parts(h2, 0),
+            CollisionEventFlags::empty(),
+        )
+    }
+
+    fn stopped(h1: u32, h2: u32) -> CollisionEvent {
+        CollisionEvent::Stopped(
+            ColliderHandle::from_raw_parts(h1, 0),
+            ColliderHandle::from_raw_parts(h2, 0),
+            CollisionEventFlags::empty(),
+        )
+    }
+
+    fn flush(pending: &mut PendingCollisionEvents) -> Vec<CollisionEvent> {
+        let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, _) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send);
+        pending.flush(
+            |_, _| None,
+            &RigidBodySet::new(),
+            &ColliderSet::new(),
+            &events,
+        );
+        collision_recv.try_iter().collect()
+    }
+
+    #[test]
+    fn opposite_transitions_cancel_out() {
+        let mut pending = PendingCollisionEvents::default();
+        pending.push(started(0, 1), true, None);
+        pending.push(stopped(1, 0), true, None);
+        pending.push(stopped(2, 3), true, None);
+        pending.push(started(2, 3), true, None);
+        assert!(pending.pairs.is_empty());
+        assert!(flush(&mut pending).is_empty());
+    }
+
+    #[test]
+    fn uncancelable_start_events_are_reported_once() {
+        let mut pending = PendingCollisionEvents::default();
+
+        // A pair repeatedly hitting and leaving another collider between two flushes only keeps
+        // the first impact and the final separation.
+        for _ in 0..10 {
+            pending.push_uncancelable(started(0, 1));
+            pending.push(stopped(0, 1), true, None);
+        }
+        pending.push(stopped(2, 3), true, None);
+        assert_eq!(pending.pairs.len(), 2);
+
+        let events: Vec<_> = flush(&mut pending)
+            .into_iter()
+            .map(|e| (e.started(), e.collider1().0.into_raw_parts().0))
+            .collect();
+        assert_eq!(events, vec![(true, 0), (false, 0), (false, 2)]);
+        assert!(pending.pairs.is_empty());
+    }
+
+    #[test]
+    fn collision_events_are_coalesced_within_a_step() {
+        use crate::geometry::ActiveCollisionTypes;
+        use crate::pipeline::ActiveEvents;
+
+        let mut world = TestWorld::new();
+
+        let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, _contact_force_recv) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()).build());
+        let resting = Vector::y() * 0.5;
+        let ball_body = RigidBodyBuilder::kinematic_position_based()
+            .translation(resting)
+            .build();
+        let ball_body = world.bodies.insert(ball_body);
+        let ball = ColliderBuilder::ball(0.5)
+            .active_events(ActiveEvents::COLLISION_EVENTS)
+            .active_collision_types(ActiveCollisionTypes::all())
+            .build();
+        world
+            .colliders
+            .insert_with_parent(ball, ball_body, &mut world.bodies);
+
+        let step = |world: &mut TestWorld| {
+            world.step_with(&(), &events);
+            collision_recv.try_iter().collect::<Vec<_>>()
+        };
+
+        world.gravity = Vector::zeros();
+        let started = step(&mut world);
+        assert_eq!(started.len(), 1);
+        assert!(started[0].started());
+
+        // Teleport the ball away, and move it back to its resting position during the
+        // same step: the contact stops, then starts again, which is no net change.
+        world.bodies[ball_body].set_translation(Vector::y() * 10.0, true);
+        world.bodies[ball_body].set_next_kinematic_translation(resting);
+        assert!(step(&mut world).is_empty());
+
+        world.bodies[ball_body].set_next_kinematic_translation(Vector::y() * 10.0);
+        let stopped = step(&mut world);
+        assert_eq!(stopped.len(), 1);
+        assert!(matches!(stopped[0], CollisionEvent::Stopped(..)));
+    }
+
+    #[test]
+    fn contact_event_impulse_threshold_ignores_trivial_contacts() {
+        use crate::pipeline::ActiveEvents;
+
+        let mut world = TestWorld::new();
+        let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, contact_force_recv) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        let ground = world.colliders.insert(
+            ColliderBuilder::halfspace(Vector::y_axis())
+                .active_events(ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS)
+                .contact_event_impulse_threshold(1.0),
+        );
+        // A ball resting on the ground, and another one falling on it from high up.
+        let resting = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        let resting_collider = world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5),
+            resting,
+            &mut world.bodies,
+        );
+        let falling = world.bodies.insert(
+            RigidBodyBuilder::dynamic().translation(Vector::x() * 3.0 + Vector::y() * 10.0),
+        );
+        let falling_collider = world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5),
+            falling,
+            &mut world.bodies,
+        );
+
+        let mut collisions = vec![];
+        let mut contact_forces = vec![];
+        for _ in 0..180 {
+            world.step_with(&(), &events);
+            collisions.extend(collision_recv.try_iter());
+            contact_forces.extend(contact_force_recv.try_iter());
+        }
+
+        // The resting ball touches the ground from the start, but never hard enough.
+        assert!(
+            world
+                .narrow_phase
+                .contact_pair(ground, resting_collider)
+                .unwrap()
+                .has_any_active_contact
+        );
+        assert_eq!(collisions.len(), 1);
+        assert!(matches!(collisions[0], CollisionEvent::Started(h1, h2, f)
+            if f == CollisionEventFlags::empty() && [h1, h2] == [ground, falling_collider]));
+        assert_eq!(contact_forces.len(), 1);
+        assert_eq!(contact_forces[0].collider2, falling_collider);
+        assert!(contact_forces[0].total_force_magnitude * world.params.dt >= 1.0);
+        assert!(world.bodies[falling].linvel().norm() < 0.1);
+    }
+
+    #[test]
+    fn stop_events_keep_the_contact_pair_of_removed_colliders() {
+        use crate::geometry::ContactPair;
+        use crate::math::Real;
+        use crate::pipeline::{ActiveEvents, EventHandler};
+        use std::sync::Mutex;
+
+        // Records the collision events, with the number of contact points of their pair.
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<(CollisionEvent, Option<usize>)>>);
+
+        impl EventHandler for Recorder {
+            fn handle_collision_event(
+                &self,
+                _bodies: &RigidBodySet,
+                _colliders: &ColliderSet,
+                event: CollisionEvent,
+                contact_pair: Option<&ContactPair>,
+            ) {
+                let num_points =
+                    contact_pair.map(|pair| pair.manifolds.iter().map(|m| m.points.len()).sum());
+                self.0.lock().unwrap().push((event, num_points));
+            }
+
+            fn handle_contact_force_event(
+                &self,
+                _dt: Real,
+                _bodies: &RigidBodySet,
+                _colliders: &ColliderSet,
+                _contact_pair: &ContactPair,
+                _total_force_magnitude: Real,
+            ) {
+            }
+        }
+
+        let mut world = TestWorld::new();
+        let recorder = Recorder::default();
+
+        world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+        let body = RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5);
+        let ball = ColliderBuilder::ball(0.5).active_events(ActiveEvents::COLLISION_EVENTS);
+        let (_, ball) = world.insert(body, ball);
+
+        world.step_with(&(), &recorder);
+        let started = std::mem::take(&mut *recorder.0.lock().unwrap());
+        assert_eq!(started.len(), 1);
+        assert!(started[0].0.started() && started[0].1 > Some(0));
+
+        // The pair is removed along with the ball, before the events are delivered.
+        world
+            .colliders
+            .remove(ball, &mut world.islands, &mut world.bodies, true);
+        world.step_with(&(), &recorder);
+        let stopped = std::mem::take(&mut *recorder.0.lock().unwrap());
+        assert_eq!(stopped.len(), 1);
+        assert!(stopped[0].0.removed() && stopped[0].1 > Some(0));
+    }
+}
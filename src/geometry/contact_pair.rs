@@ -1,9 +1,13 @@
 use crate::dynamics::{RigidBodyHandle, RigidBodySet};
-use crate::geometry::{ColliderHandle, ColliderSet, Contact, ContactManifold};
-use crate::math::{Point, Real, Vector};
+#[cfg(feature = "dim3")]
+use crate::geometry::AnisotropicFriction;
+use crate::geometry::{ColliderHandle, ColliderSet, Contact, ContactManifold, ContactSoftness};
+use crate::math::{Point, Real, Vector, DIM};
 use crate::pipeline::EventHandler;
 use crate::prelude::CollisionEventFlags;
 use parry::query::ContactManifoldsWorkspace;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
 use super::CollisionEvent;
 
@@ -26,6 +30,10 @@ impl Default for SolverFlags {
 #[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 /// A single contact between two collider.
+///
+/// This is the user-data attached to each contact point of a [`ContactManifold`]. The
+/// geometric information of a contact point, including its signed distance `dist` (negative
+/// when penetrating), is stored in the [`Contact`] containing this data.
 pub struct ContactData {
     /// The impulse, along the contact normal, applied by this contact to the first collider's rigid-body.
     ///
@@ -39,6 +47,14 @@ pub struct ContactData {
     /// collider's rigid-body.
     #[cfg(feature = "dim3")]
     pub tangent_impulse: na::Vector2<Real>,
+    /// The impulse, along the contact normal, this contact would have applied if it wasn’t
+    /// limited by the maximum contact impulse of the colliders.
+    ///
+    /// This is equal to `impulse` unless the limit was reached.
+    pub unclamped_impulse: Real,
+    /// Set if this contact applied an impulse since it was created, provided the contact
+    /// persistence distance isn't zero.
+    pub(crate) has_rested: bool,
 }
 
 impl Default for ContactData {
@@ -46,6 +62,8 @@ impl Default for ContactData {
         Self {
             impulse: 0.0,
             tangent_impulse: na::zero(),
+            unclamped_impulse: 0.0,
+            has_rested: false,
         }
     }
 }
@@ -106,20 +124,45 @@ impl IntersectionPair {
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone)]
 /// The description of all the contacts between a pair of colliders.
+///
+/// All the contact information (normals, contact points, impulses) stored in this pair is expressed
+/// relative to the order given by `self.collider1` and `self.collider2`: normals always point from
+/// `collider1` toward `collider2`, whatever the order in which the colliders were inserted, or the
+/// order of the collider handles given to [`NarrowPhase::contact_pair`](crate::geometry::NarrowPhase::contact_pair).
 pub struct ContactPair {
     /// The first collider involved in the contact pair.
+    ///
+    /// Contact normals point away from this collider.
     pub collider1: ColliderHandle,
     /// The second collider involved in the contact pair.
+    ///
+    /// Contact normals point toward this collider.
     pub collider2: ColliderHandle,
     /// The set of contact manifolds between the two colliders.
     ///
     /// All contact manifold contain themselves contact points between the colliders.
+    ///
+    /// If any of the colliders has a composite shape (compound, triangle mesh, heightfield,
+    /// polyline), there is one contact manifold for each pair of touching sub-shapes, so the
+    /// contacts of parts touching at separate places are never merged. The touching sub-shapes
+    /// are identified by the `subshape1` and `subshape2` indices of each manifold. The local
+    /// contact points and normals (`local_n1`, `local_n2`) of a manifold are expressed in the
+    /// local-space of its sub-shapes, positioned relative to their collider by `subshape_pos1`
+    /// and `subshape_pos2`, while `manifold.data.normal` is the world-space normal.
     pub manifolds: Vec<ContactManifold>,
     /// Is there any active contact in this contact pair?
+    ///
+    /// A contact is active if the distance between the colliders is smaller than the prediction
+    /// distance at the last timestep. This includes penetrating contacts, and contacts that are
+    /// ignored by the constraints solver.
     pub has_any_active_contact: bool,
     /// Was a `CollisionEvent::Started` emitted for this collider?
     pub(crate) start_event_emited: bool,
     pub(crate) workspace: Option<ContactManifoldsWorkspace>,
+    /// Was this pair seeded or cleared through the narrow-phase since its last update?
+    pub(crate) needs_update: bool,
+    /// Were the current contacts of this pair provided by the user instead of being computed?
+    pub(crate) seeded: bool,
 }
 
 impl ContactPair {
@@ -131,6 +174,8 @@ impl ContactPair {
             manifolds: Vec::new(),
             start_event_emited: false,
             workspace: None,
+            needs_update: false,
+            seeded: false,
         }
     }
 
@@ -141,7 +186,42 @@ impl ContactPair {
         self.workspace = None;
     }
 
+    /// The number of active contact points of this pair, and a hash of their sub-shapes and
+    /// features, used to detect that some of them were added or removed.
+    pub(crate) fn active_contacts_signature(&self, prediction_distance: Real) -> (usize, u64) {
+        let mut num_contacts = 0;
+        let mut hash = 0u64;
+
+        for manifold in &self.manifolds {
+            for contact in &manifold.points {
+                if contact.dist < prediction_distance {
+                    // NOTE: the hashes are summed so the order of the contacts doesn't matter.
+                    let mut hasher = DefaultHasher::new();
+                    (manifold.subshape1, manifold.subshape2).hash(&mut hasher);
+                    (contact.fid1, contact.fid2).hash(&mut hasher);
+                    hash = hash.wrapping_add(hasher.finish());
+                    num_contacts += 1;
+                }
+            }
+        }
+
+        (num_contacts, hash)
+    }
+
+    /// Is there any contact, seen by the constraints solver, at which the colliders are touching
+    /// or penetrating?
+    ///
+    /// Unlike [`ContactPair::has_any_active_contact`], this ignores the speculative contacts, i.e.,
+    /// contacts between colliders that are close but not touching yet.
+    pub fn has_any_touching_contact(&self) -> bool {
+        self.manifolds
+            .iter()
+            .any(|m| m.data.solver_contacts.iter().any(|c| !c.is_speculative()))
+    }
+
     /// The sum of all the impulses applied by contacts on this contact pair.
+    ///
+    /// This is expressed along the contact normals, i.e., from `self.collider1` toward `self.collider2`.
     pub fn total_impulse(&self) -> Vector<Real> {
         self.manifolds
             .iter()
@@ -157,6 +237,8 @@ impl ContactPair {
     }
 
     /// The magnitude and (unit) direction of the maximum impulse on this contact pair.
+    ///
+    /// The direction points from `self.collider1` toward `self.collider2`.
     pub fn max_impulse(&self) -> (Real, Vector<Real>) {
         let mut result = (0.0, Vector::zeros());
 
@@ -233,6 +315,34 @@ impl ContactPair {
     }
 }
 
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The way the contacts of a contact pair changed.
+pub enum ContactPairChangeKind {
+    /// The colliders started having active contacts.
+    Started,
+    /// The colliders still have active contacts, but some active contact points were added or
+    /// removed.
+    Modified,
+    /// The colliders no longer have active contacts, or their contact pair was removed while
+    /// they had active contacts.
+    Stopped,
+}
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// A change of the active contacts between two colliders.
+///
+/// See [`NarrowPhase::contact_pair_changes`](crate::geometry::NarrowPhase::contact_pair_changes).
+pub struct ContactPairChange {
+    /// The first collider of the contact pair.
+    pub collider1: ColliderHandle,
+    /// The second collider of the contact pair.
+    pub collider2: ColliderHandle,
+    /// The way the active contacts of the pair changed.
+    pub kind: ContactPairChangeKind,
+}
+
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 /// A contact manifold between two colliders.
@@ -250,6 +360,8 @@ pub struct ContactManifoldData {
     /// Flags used to control some aspects of the constraints solver for this contact manifold.
     pub solver_flags: SolverFlags,
     /// The world-space contact normal shared by all the contact in this contact manifold.
+    ///
+    /// This normal always points from the contact pair’s `collider1` toward its `collider2`.
     // NOTE: read the comment of `solver_contacts` regarding serialization. It applies
     // to this field as well.
     pub normal: Vector<Real>,
@@ -275,6 +387,31 @@ pub struct ContactManifoldData {
     pub relative_dominance: i16,
     /// A user-defined piece of data.
     pub user_data: u32,
+    /// The softness of the contacts of this manifold, combined from the softness of both colliders.
+    pub contact_softness: ContactSoftness,
+    /// The maximum impulse applied by the solver at each contact point of this manifold, i.e.,
+    /// the smallest maximum contact impulse of both colliders.
+    pub max_contact_impulse: Real,
+    /// The anisotropic friction of the contacts of this manifold, combined from both colliders,
+    /// with a world-space principal direction.
+    ///
+    /// This is `None` if the contact friction is isotropic. Otherwise, the `friction` of the solver
+    /// contacts is the largest of both coefficients, and the solver scales the friction limit
+    /// along the principal direction and across it by the ratio between each coefficient and
+    /// this largest coefficient.
+    #[cfg(feature = "dim3")]
+    pub anisotropic_friction: Option<AnisotropicFriction>,
+    /// The world-space directions of the friction impulses `ContactData::tangent_impulse` of the
+    /// contacts of this manifold, as computed by the constraints solver at the last step.
+    ///
+    /// They are orthogonal to `self.normal`, and oriented so that the total impulse applied by a
+    /// contact to the rigid-body of the contact pair’s `collider2` is
+    /// `normal * impulse + tangents[0] * tangent_impulse[0] (+ tangents[1] * tangent_impulse[1] in 3D)`,
+    /// the opposite impulse being applied to the rigid-body of `collider1`. See
+    /// [`ContactManifoldExt::friction_impulse`].
+    pub tangents: [Vector<Real>; DIM - 1],
+    /// The state of the one-way platform behavior of each collider of this manifold.
+    pub(crate) one_way_states: [u32; 2],
 }
 
 /// A contact seen by the constraints solver for computing forces.
@@ -287,10 +424,19 @@ pub struct SolverContact {
     pub point: Point<Real>,
     /// The distance between the two original contacts points along the contact normal.
     /// If negative, this is measures the penetration depth.
+    ///
+    /// This is the same as the `dist` of the contact manifold point it originates from,
+    /// unless it was modified by a physics hook.
     pub dist: Real,
     /// The effective friction coefficient at this contact point.
+    ///
+    /// This is initialized with the combined friction coefficients of the colliders, and can be
+    /// overridden by a physics hook to affect the current timestep.
     pub friction: Real,
     /// The effective restitution coefficient at this contact point.
+    ///
+    /// This is initialized with the combined restitution coefficients of the colliders, and can
+    /// be overridden by a physics hook to affect the current timestep.
     pub restitution: Real,
     /// The desired tangent relative velocity at the contact point.
     ///
@@ -302,6 +448,23 @@ pub struct SolverContact {
 }
 
 impl SolverContact {
+    /// The index of the contact point of the contact manifold used to generate this solver contact.
+    ///
+    /// This can be used to read the impulse applied at this contact point with
+    /// `manifold.points[solver_contact.contact_id()].data.impulse`.
+    pub fn contact_id(&self) -> usize {
+        self.contact_id as usize
+    }
+
+    /// Is this a speculative contact, i.e., are the colliders still separated at this point?
+    ///
+    /// The constraints solver only applies a normal impulse at a speculative contact if the
+    /// bodies would otherwise penetrate before the end of the timestep. Contacts that aren’t
+    /// speculative are touching or penetrating.
+    pub fn is_speculative(&self) -> bool {
+        self.dist > 0.0
+    }
+
     /// Should we treat this contact as a bouncy contact?
     /// If `true`, use [`Self::restitution`].
     pub fn is_bouncy(&self) -> bool {
@@ -336,6 +499,12 @@ impl ContactManifoldData {
             solver_contacts: Vec::new(),
             relative_dominance: 0,
             user_data: 0,
+            contact_softness: ContactSoftness::rigid(),
+            max_contact_impulse: Real::MAX,
+            #[cfg(feature = "dim3")]
+            anisotropic_friction: None,
+            tangents: [Vector::zeros(); DIM - 1],
+            one_way_states: [0; 2],
         }
     }
 
@@ -353,6 +522,26 @@ pub trait ContactManifoldExt {
     fn total_impulse(&self) -> Real;
     /// Computes the maximum impulse applied by contacts from this contact manifold.
     fn max_impulse(&self) -> Real;
+    /// The point of `contact` on the first collider, expressed in the local-space of this
+    /// collider.
+    ///
+    /// Unlike `contact.local_p1`, which is relative to the sub-shape `self.subshape1` if the
+    /// collider has a composite shape (e.g. a compound), this is relative to the collider itself,
+    /// so that `collider1.position() * point` stays on the collider as it moves. The feature of
+    /// the sub-shape supporting this contact is given by `contact.fid1`.
+    fn collider_local_p1(&self, contact: &Contact) -> Point<Real>;
+    /// The point of `contact` on the second collider, expressed in the local-space of this
+    /// collider.
+    ///
+    /// See [`ContactManifoldExt::collider_local_p1`].
+    fn collider_local_p2(&self, contact: &Contact) -> Point<Real>;
+    /// The world-space friction impulse applied by `contact` to the rigid-body of the contact
+    /// pair’s `collider2` at the last step.
+    ///
+    /// This is `contact.data.tangent_impulse` expressed along the friction directions
+    /// `self.data.tangents`. The friction impulse applied to the rigid-body of `collider1` is the
+    /// opposite.
+    fn friction_impulse(&self, contact: &Contact) -> Vector<Real>;
 }
 
 impl ContactManifoldExt for ContactManifold {
@@ -363,4 +552,27 @@ impl ContactManifoldExt for ContactManifold {
     fn max_impulse(&self) -> Real {
         self.points.iter().fold(0.0, |a, pt| a.max(pt.data.impulse))
     }
+
+    fn collider_local_p1(&self, contact: &Contact) -> Point<Real> {
+        self.subshape_pos1
+            .map(|pos| pos * contact.local_p1)
+            .unwrap_or(contact.local_p1)
+    }
+
+    fn collider_local_p2(&self, contact: &Contact) -> Point<Real> {
+        self.subshape_pos2
+            .map(|pos| pos * contact.local_p2)
+            .unwrap_or(contact.local_p2)
+    }
+
+    #[cfg(feature = "dim2")]
+    fn friction_impulse(&self, contact: &Contact) -> Vector<Real> {
+        self.data.tangents[0] * contact.data.tangent_impulse
+    }
+
+    #[cfg(feature = "dim3")]
+    fn friction_impulse(&self, contact: &Contact) -> Vector<Real> {
+        self.data.tangents[0] * contact.data.tangent_impulse.x
+            + self.data.tangents[1] * contact.data.tangent_impulse.y
+    }
 }
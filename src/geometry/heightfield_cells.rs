@@ -0,0 +1,170 @@
+//! Mapping of the triangles and faces of a heightfield back to the cells of its height grid.
+
+use crate::geometry::HeightField;
+
+/// The index of the cell of `heightfield` containing the given segment or face.
+///
+/// The `id` may either be the index of a segment (e.g. the `subshape1` or `subshape2` of a
+/// contact manifold involving the heightfield), or the id of a face (e.g. the
+/// `FeatureId::Face` of a ray hitting the heightfield, which may identify the back of a segment).
+///
+/// The cell `i` lies between the heights `i` and `i + 1`. The scale of the heightfield doesn’t
+/// affect this indexing. Returns `None` if `id` is out of bounds.
+#[cfg(feature = "dim2")]
+pub fn heightfield_cell(heightfield: &HeightField, id: u32) -> Option<usize> {
+    let num_cells = heightfield.num_cells();
+    let id = id as usize;
+
+    // NOTE: the id of a back face is the id of its front face plus the number of cells.
+    (id < num_cells * 2).then(|| id % num_cells)
+}
+
+/// The row and column of the cell of `heightfield` containing the given triangle or face.
+///
+/// The `id` may either be the index of a triangle (e.g. the `subshape1` or `subshape2` of a
+/// contact manifold involving the heightfield), or the id of a face (e.g. the
+/// `FeatureId::Face` of a ray hitting the heightfield, which may identify the back of a
+/// triangle).
+///
+/// The cell `(i, j)` lies between the rows `i` and `i + 1`, and the columns `j` and `j + 1`, of
+/// the height matrix of the heightfield. Rows are laid out along the local `z` axis, and columns
+/// along the local `x` axis. The scale of the heightfield doesn’t affect this indexing. Returns
+/// `None` if `id` is out of bounds.
+#[cfg(feature = "dim3")]
+pub fn heightfield_cell(heightfield: &HeightField, id: u32) -> Option<(usize, usize)> {
+    let (nrows, ncols) = (heightfield.nrows(), heightfield.ncols());
+    let num_triangles = nrows * ncols * 2;
+    let id = id as usize;
+
+    if id >= num_triangles * 2 {
+        return None;
+    }
+
+    // NOTE: the id of a back face is the id of its front face plus the number of triangles, and
+    //       the ids of the right triangles of each cell follow the ids of all the left triangles.
+    let cell_id = id % num_triangles % (nrows * ncols);
+    Some((cell_id % nrows, cell_id / nrows))
+}
+
+#[cfg(test)]
+mod test {
+    use super::heightfield_cell;
+    use crate::geometry::{HeightField, Ray};
+    use crate::math::{Point, Real, Vector};
+    use parry::query::RayCast;
+    use parry::shape::FeatureId;
+
+    /// The face of `heightfield` hit by a vertical ray at the given local `x` (and `z`) coordinates,
+    /// coming from above or from below.
+    fn hit_face(heightfield: &HeightField, point: Point<Real>, from_above: bool) -> Option<u32> {
+        let dir = if from_above {
+            -Vector::y()
+        } else {
+            Vector::y()
+        };
+        let ray = Ray::new(point - dir * 10.0, dir);
+
+        match heightfield
+            .cast_local_ray_and_get_normal(&ray, Real::MAX, true)?
+            .feature
+        {
+            FeatureId::Face(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "dim2")]
+    #[test]
+    fn heightfield_cells_of_segments_and_faces() {
+        let heightfield = HeightField::new(
+            na::DVector::from_vec(vec![0.0, 1.0, 0.5, 2.0]),
+            Vector::new(6.0, 1.0),
+        );
+        let num_cells = heightfield.num_cells();
+
+        // Every segment lies in its cell, with a unit width in this scale.
+        for id in 0..num_cells as u32 {
+            let segment = heightfield.segment_at(id as usize).unwrap();
+            let center_x = (segment.a.x + segment.b.x) / 2.0;
+            let expected = ((center_x + 3.0) / 2.0).floor() as usize;
+            assert_eq!(heightfield_cell(&heightfield, id), Some(expected));
+        }
+
+        for cell in 0..num_cells {
+            // A point in the middle of the cell, hit from both sides.
+            let x = -3.0 + 2.0 * cell as Real + 1.0;
+            for from_above in [true, false] {
+                let id = hit_face(&heightfield, Point::new(x, 0.0), from_above).unwrap();
+                assert_eq!(heightfield_cell(&heightfield, id), Some(cell));
+            }
+        }
+
+        // A point on the edge between two cells belongs to one of them.
+        let id = hit_face(&heightfield, Point::new(-1.0, 0.0), true).unwrap();
+        assert!(matches!(heightfield_cell(&heightfield, id), Some(0 | 1)));
+
+        // Points outside of the heightfield aren't hit, and ids out of bounds have no cell.
+        assert_eq!(hit_face(&heightfield, Point::new(3.5, 0.0), true), None);
+        assert_eq!(heightfield_cell(&heightfield, num_cells as u32 * 2), None);
+        assert_eq!(heightfield_cell(&heightfield, u32::MAX), None);
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn heightfield_cells_of_triangles_and_faces() {
+        // 3 rows and 2 columns of cells, each with a unit size in this scale.
+        let heightfield = HeightField::new(
+            na::DMatrix::from_row_slice(
+                4,
+                3,
+                &[0.0, 1.0, 0.5, 2.0, 0.0, 1.0, 0.5, 0.5, 0.0, 1.0, 2.0, 1.5],
+            ),
+            Vector::new(2.0, 1.0, 3.0),
+        );
+        let (nrows, ncols) = (heightfield.nrows(), heightfield.ncols());
+        let num_triangles = (nrows * ncols * 2) as u32;
+        let cell_at = |point: &Point<Real>| {
+            let i = (point.z + 1.5).floor() as usize;
+            let j = (point.x + 1.0).floor() as usize;
+            (i, j)
+        };
+
+        // Every triangle lies in its cell.
+        for id in 0..num_triangles {
+            let triangle = heightfield.triangle_at_id(id).unwrap();
+            assert_eq!(
+                heightfield_cell(&heightfield, id),
+                Some(cell_at(&triangle.center()))
+            );
+        }
+
+        for i in 0..nrows {
+            for j in 0..ncols {
+                // Points near two opposite corners of the cell, in each of its triangles, hit from
+                // both sides.
+                for (dx, dz) in [(0.2, 0.3), (0.8, 0.7)] {
+                    let point = Point::new(j as Real - 1.0 + dx, 0.0, i as Real - 1.5 + dz);
+                    for from_above in [true, false] {
+                        let id = hit_face(&heightfield, point, from_above).unwrap();
+                        assert_eq!(heightfield_cell(&heightfield, id), Some((i, j)));
+                    }
+                }
+            }
+        }
+
+        // A point on the edge between two cells belongs to one of them.
+        let id = hit_face(&heightfield, Point::new(0.0, 0.0, -1.0), true).unwrap();
+        assert!(matches!(
+            heightfield_cell(&heightfield, id),
+            Some((0, 0 | 1))
+        ));
+
+        // Points outside of the heightfield aren't hit, and ids out of bounds have no cell.
+        assert_eq!(
+            hit_face(&heightfield, Point::new(1.5, 0.0, 0.0), true),
+            None
+        );
+        assert_eq!(heightfield_cell(&heightfield, num_triangles * 2), None);
+        assert_eq!(heightfield_cell(&heightfield, u32::MAX), None);
+    }
+}
@@ -28,6 +28,14 @@ impl<N: Copy, E> InteractionGraph<N, E> {
         }
     }
 
+    /// Creates a new empty collection of collision objects, with room for `nodes` collision
+    /// objects and `edges` interactions.
+    pub fn with_capacity(nodes: usize, edges: usize) -> Self {
+        InteractionGraph {
+            graph: Graph::with_capacity(nodes, edges),
+        }
+    }
+
     /// The underlying raw graph structure of this interaction graph.
     pub fn raw_graph(&self) -> &Graph<N, E> {
         &self.graph
@@ -0,0 +1,361 @@
+//! Removal of the contact normals generated by internal edges of triangle meshes, heightfields,
+//! and 2D polylines.
+//!
+//! Each face of a mesh generates its own contact manifold. When a shape slides across an edge
+//! (or vertex) shared by coplanar faces, the face it is moving into may report a contact normal
+//! almost orthogonal to the surface because that face, considered alone, is hit from the side.
+//! Such a contact acts as an invisible step, making bodies bounce or stop. Here, the normal of
+//! these contacts are replaced by the normal of their face, unless the feature they lie on is
+//! actually convex.
+//!
+//! This also detects the contacts touching the back side of the faces of one-sided meshes.
+
+use crate::geometry::ContactManifold;
+#[cfg(feature = "dim2")]
+use crate::geometry::Polyline;
+#[cfg(feature = "dim3")]
+use crate::geometry::TriMesh;
+use crate::geometry::{HeightField, Shape};
+use crate::math::{Isometry, Point, Real, Vector};
+use parry::bounding_volume::Aabb;
+use parry::query::PointQuery;
+#[cfg(feature = "dim2")]
+use parry::shape::SimdCompositeShape;
+use parry::utils::IsometryOpt;
+
+#[cfg(feature = "dim2")]
+use crate::geometry::Segment as MeshFace;
+#[cfg(feature = "dim3")]
+use crate::geometry::Triangle as MeshFace;
+
+/// Maximum distance between a contact point and a face for the face to be considered part of
+/// the feature the contact lies on.
+const FEATURE_TOLERANCE: Real = 1.0e-3;
+/// Sine of the largest angle between two faces sharing an edge for this edge to still be
+/// considered flat (about one degree).
+const FLAT_ANGLE_SIN: Real = 0.0175;
+/// Contact normals closer than this to the face normal are left unchanged.
+const FACE_NORMAL_COS: Real = 1.0 - 1.0e-5;
+
+enum MeshShape<'a> {
+    #[cfg(feature = "dim2")]
+    Polyline(&'a Polyline),
+    #[cfg(feature = "dim3")]
+    TriMesh(&'a TriMesh),
+    HeightField(&'a HeightField),
+}
+
+impl<'a> MeshShape<'a> {
+    fn from_shape(shape: &'a dyn Shape) -> Option<Self> {
+        #[cfg(feature = "dim2")]
+        if let Some(polyline) = shape.as_polyline() {
+            return Some(MeshShape::Polyline(polyline));
+        }
+
+        #[cfg(feature = "dim3")]
+        if let Some(trimesh) = shape.as_trimesh() {
+            return Some(MeshShape::TriMesh(trimesh));
+        }
+
+        shape.as_heightfield().map(MeshShape::HeightField)
+    }
+
+    fn face(&self, id: u32) -> Option<MeshFace> {
+        match self {
+            #[cfg(feature = "dim2")]
+            MeshShape::Polyline(polyline) => {
+                ((id as usize) < polyline.num_segments()).then(|| polyline.segment(id))
+            }
+            #[cfg(feature = "dim3")]
+            MeshShape::TriMesh(trimesh) => {
+                ((id as usize) < trimesh.num_triangles()).then(|| trimesh.triangle(id))
+            }
+            #[cfg(feature = "dim3")]
+            MeshShape::HeightField(heightfield) => heightfield.triangle_at_id(id),
+            #[cfg(feature = "dim2")]
+            MeshShape::HeightField(heightfield) => heightfield.segment_at(id as usize),
+        }
+    }
+
+    fn map_faces_in_aabb(&self, aabb: &Aabb, mut f: impl FnMut(&MeshFace)) {
+        match self {
+            #[cfg(feature = "dim2")]
+            MeshShape::Polyline(polyline) => {
+                let mut ids = Vec::new();
+                SimdCompositeShape::qbvh(*polyline).intersect_aabb(aabb, &mut ids);
+
+                for id in ids {
+                    f(&polyline.segment(id));
+                }
+            }
+            #[cfg(feature = "dim3")]
+            MeshShape::TriMesh(trimesh) => {
+                let mut ids = Vec::new();
+                trimesh.qbvh().intersect_aabb(aabb, &mut ids);
+
+                for id in ids {
+                    f(&trimesh.triangle(id));
+                }
+            }
+            MeshShape::HeightField(heightfield) => {
+                heightfield.map_elements_in_local_aabb(aabb, &mut |_, face| f(face))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "dim2")]
+fn face_vertices(face: &MeshFace) -> [Point<Real>; 2] {
+    [face.a, face.b]
+}
+
+#[cfg(feature = "dim3")]
+fn face_vertices(face: &MeshFace) -> [Point<Real>; 3] {
+    *face.vertices()
+}
+
+/// Replaces by their face normal the normals of the contact manifolds generated by the internal
+/// edges and vertices of a triangle mesh, heightfield, or 2D polyline.
+///
+/// This does nothing if neither `shape1` nor `shape2` is one of these shapes.
+pub(crate) fn fix_internal_edge_normals(
+    pos12: &Isometry<Real>,
+    shape1: &dyn Shape,
+    shape2: &dyn Shape,
+    manifolds: &mut [ContactManifold],
+) {
+    if let Some(mesh) = MeshShape::from_shape(shape1) {
+        for manifold in manifolds {
+            fix_manifold_normal(&mesh, pos12, manifold, false);
+        }
+    } else if let Some(mesh) = MeshShape::from_shape(shape2) {
+        let pos21 = pos12.inverse();
+
+        for manifold in manifolds {
+            fix_manifold_normal(&mesh, &pos21, manifold, true);
+        }
+    }
+}
+
+fn fix_manifold_normal(
+    mesh: &MeshShape,
+    pos_mesh_other: &Isometry<Real>,
+    manifold: &mut ContactManifold,
+    flipped: bool,
+) {
+    if manifold.points.is_empty() {
+        return;
+    }
+
+    let (face_id, normal, subshape_pos_other) = if flipped {
+        (
+            manifold.subshape2,
+            manifold.local_n2,
+            manifold.subshape_pos1.as_ref(),
+        )
+    } else {
+        (
+            manifold.subshape1,
+            manifold.local_n1,
+            manifold.subshape_pos2.as_ref(),
+        )
+    };
+
+    let face = match mesh.face(face_id) {
+        Some(face) => face,
+        None => return,
+    };
+    let mut face_normal = match face.normal() {
+        Some(face_normal) => face_normal.into_inner(),
+        None => return,
+    };
+    let pos_other = subshape_pos_other.prepend_to(pos_mesh_other);
+
+    // Make the face normal point toward the side of the face the other shape is on.
+    if (Point::from(pos_other.translation.vector) - face_vertices(&face)[0]).dot(&face_normal) < 0.0
+    {
+        face_normal = -face_normal;
+    }
+
+    if face_normal.dot(&normal) > FACE_NORMAL_COS {
+        return;
+    }
+
+    for pt in &manifold.points {
+        let point = if flipped { pt.local_p2 } else { pt.local_p1 };
+
+        if is_on_convex_feature(mesh, &point, &face_normal) {
+            return;
+        }
+    }
+
+    for pt in &mut manifold.points {
+        let (point, point_other) = if flipped {
+            (pt.local_p2, pt.local_p1)
+        } else {
+            (pt.local_p1, pt.local_p2)
+        };
+        pt.dist = (pos_other * point_other - point).dot(&face_normal);
+    }
+
+    let normal_other = -pos_other.inverse_transform_vector(&face_normal);
+
+    if flipped {
+        manifold.local_n1 = normal_other;
+        manifold.local_n2 = face_normal;
+    } else {
+        manifold.local_n1 = face_normal;
+        manifold.local_n2 = normal_other;
+    }
+}
+
+/// Checks if a contact with the normal `local_n`, pointing toward the exterior of `shape`,
+/// touches the back side of the face `face_id` of a 2D polyline or of a triangle mesh.
+///
+/// This is always `false` for any other shape.
+pub(crate) fn is_back_face_contact(
+    shape: &dyn Shape,
+    face_id: u32,
+    local_n: &Vector<Real>,
+) -> bool {
+    let face = match MeshShape::from_shape(shape) {
+        #[cfg(feature = "dim2")]
+        Some(mesh @ MeshShape::Polyline(_)) => mesh.face(face_id),
+        #[cfg(feature = "dim3")]
+        Some(mesh @ MeshShape::TriMesh(_)) => mesh.face(face_id),
+        _ => None,
+    };
+
+    face.and_then(|face| face.normal())
+        .map_or(false, |face_normal| face_normal.dot(local_n) < 0.0)
+}
+
+/// Checks if any face of `mesh` touching `point` goes below the plane with the given `normal`
+/// passing through `point`, i.e., if `point` lies on a convex edge or vertex.
+fn is_on_convex_feature(mesh: &MeshShape, point: &Point<Real>, normal: &Vector<Real>) -> bool {
+    let aabb = Aabb::from_half_extents(*point, Vector::repeat(FEATURE_TOLERANCE));
+    let mut convex = false;
+
+    mesh.map_faces_in_aabb(&aabb, |face| {
+        if !convex && face.distance_to_local_point(point, true) <= FEATURE_TOLERANCE {
+            convex = face_vertices(face).iter().any(|vertex| {
+                let dpt = vertex - point;
+                let dist = dpt.norm();
+                dist > FEATURE_TOLERANCE && dpt.dot(normal) < -FLAT_ANGLE_SIN * dist
+            });
+        }
+    });
+
+    convex
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn one_sided_meshes_let_bodies_through_from_behind() {
+        // A mesh face with its front side toward +Y.
+        #[cfg(feature = "dim2")]
+        let ground =
+            ColliderBuilder::polyline(vec![Point::new(5.0, 0.0), Point::new(-5.0, 0.0)], None);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::trimesh(
+            vec![
+                Point::new(-5.0, 0.0, -5.0),
+                Point::new(-5.0, 0.0, 5.0),
+                Point::new(5.0, 0.0, 0.0),
+            ],
+            vec![[0, 1, 2]],
+        );
+
+        for one_sided in [false, true] {
+            let mut world = TestWorld::new();
+
+            world
+                .colliders
+                .insert(ground.clone().one_sided(one_sided).build());
+
+            let ball_body = RigidBodyBuilder::dynamic()
+                .translation(-Vector::y() * 2.0)
+                .linvel(Vector::y() * 10.0)
+                .build();
+            let ball_body = world.bodies.insert(ball_body);
+            let ball = ColliderBuilder::ball(0.5).build();
+            world
+                .colliders
+                .insert_with_parent(ball, ball_body, &mut world.bodies);
+
+            let mut max_height = world.bodies[ball_body].translation().y;
+
+            for _ in 0..240 {
+                world.step();
+                max_height = max_height.max(world.bodies[ball_body].translation().y);
+            }
+
+            if one_sided {
+                // The ball went through the back of the face, and landed on its front.
+                assert!(max_height > 2.0);
+                assert!((world.bodies[ball_body].translation().y - 0.5).abs() < 0.1);
+            } else {
+                assert!(max_height < 0.0);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "dim3")]
+    fn box_slides_on_triangulated_floor_without_bumps() {
+        use crate::math::Real;
+
+        let mut world = TestWorld::new();
+
+        // A flat floor made of 1x1 cells, each split into two triangles.
+        let n = 41;
+        let mut vertices = vec![];
+        let mut indices = vec![];
+
+        for i in 0..n {
+            for j in 0..n {
+                let x = i as Real - (n / 2) as Real;
+                let z = j as Real - (n / 2) as Real;
+                vertices.push(Point::new(x, 0.0, z));
+            }
+        }
+
+        for i in 0..n as u32 - 1 {
+            for j in 0..n as u32 - 1 {
+                let id = i * n as u32 + j;
+                indices.push([id, id + 1, id + n as u32]);
+                indices.push([id + 1, id + n as u32 + 1, id + n as u32]);
+            }
+        }
+
+        let floor = ColliderBuilder::trimesh(vertices, indices).friction(0.0);
+        world.colliders.insert(floor.build());
+        // The box edges are aligned with the floor edges, which is the worst case.
+        let box_body = RigidBodyBuilder::dynamic()
+            .translation(Vector::new(-10.0, 0.5, 0.5))
+            .linvel(Vector::new(5.0, 0.0, 0.0))
+            .build();
+        let box_body = world.bodies.insert(box_body);
+        let cuboid = ColliderBuilder::cuboid(0.5, 0.5, 0.5).friction(0.0).build();
+        world
+            .colliders
+            .insert_with_parent(cuboid, box_body, &mut world.bodies);
+
+        for i in 0..200 {
+            world.step();
+
+            if i > 10 {
+                // The box crosses many internal edges without being pushed up or slowed down.
+                let linvel = world.bodies[box_body].linvel();
+                assert!(linvel.y.abs() < 0.05, "vertical bump: {}", linvel.y);
+                assert!(linvel.x > 4.9, "horizontal slow-down: {}", linvel.x);
+            }
+        }
+    }
+}
@@ -4,15 +4,19 @@ pub use self::broad_phase_multi_sap::{BroadPhasePairEvent, ColliderPair};
 
 pub use self::broad_phase_multi_sap::BroadPhase;
 // pub use self::broad_phase_qbvh::BroadPhase;
+pub(crate) use self::broad_phase_spatial_hash::BroadPhaseSpatialHash;
 pub use self::collider_components::*;
 pub use self::contact_pair::{
-    ContactData, ContactManifoldData, ContactPair, IntersectionPair, SolverContact, SolverFlags,
+    ContactData, ContactManifoldData, ContactManifoldExt, ContactPair, ContactPairChange,
+    ContactPairChangeKind, IntersectionPair, SolverContact, SolverFlags,
 };
+pub use self::heightfield_cells::heightfield_cell;
 pub use self::interaction_graph::{
     ColliderGraphIndex, InteractionGraph, RigidBodyGraphIndex, TemporaryInteractionIndex,
 };
 pub use self::interaction_groups::{Group, InteractionGroups};
 pub use self::narrow_phase::NarrowPhase;
+pub use self::support_face::{support_face, SupportFeature};
 
 pub use self::collider::{Collider, ColliderBuilder};
 pub use self::collider_set::ColliderSet;
@@ -179,7 +183,25 @@ impl ContactForceEvent {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// Event occurring when a contact point involving a collider with a finite maximum contact
+/// impulse would have needed a larger impulse than this limit.
+pub struct ContactImpulseClampEvent {
+    /// The collider whose maximum contact impulse was exceeded.
+    pub collider: ColliderHandle,
+    /// The other collider involved in the contact.
+    pub other_collider: ColliderHandle,
+    /// The largest impulse that would have been applied at a single contact point of this
+    /// contact pair without the limit.
+    pub unclamped_impulse: Real,
+    /// The maximum contact impulse of `self.collider`.
+    pub max_contact_impulse: Real,
+}
+
 pub(crate) use self::broad_phase_multi_sap::SAPProxyIndex;
+pub(crate) use self::collision_event_coalescer::{CollisionEventCoalescer, PendingCollisionEvents};
+pub(crate) use self::mesh_internal_edges::{fix_internal_edge_normals, is_back_face_contact};
+pub(crate) use self::narrow_phase::generate_contact_manifolds;
 pub(crate) use self::narrow_phase::ContactManifoldIndex;
 pub(crate) use parry::partitioning::Qbvh;
 pub use parry::shape::*;
@@ -203,5 +225,10 @@ mod interaction_groups;
 mod narrow_phase;
 
 mod broad_phase_qbvh;
+mod broad_phase_spatial_hash;
 mod collider;
 mod collider_set;
+mod collision_event_coalescer;
+mod heightfield_cells;
+mod mesh_internal_edges;
+mod support_face;
@@ -4,30 +4,37 @@ use rayon::prelude::*;
 use crate::data::graph::EdgeIndex;
 use crate::data::Coarena;
 use crate::dynamics::{
-    CoefficientCombineRule, ImpulseJointSet, IslandManager, RigidBodyDominance, RigidBodySet,
-    RigidBodyType,
+    CoefficientCombineRule, ImpulseJointSet, IslandManager, RigidBodyDominance, RigidBodyHandle,
+    RigidBodySet, RigidBodyType,
 };
+#[cfg(feature = "dim3")]
+use crate::geometry::AnisotropicFriction;
 use crate::geometry::{
-    BroadPhasePairEvent, ColliderChanges, ColliderGraphIndex, ColliderHandle, ColliderPair,
-    ColliderSet, CollisionEvent, ContactData, ContactManifold, ContactManifoldData, ContactPair,
-    InteractionGraph, IntersectionPair, SolverContact, SolverFlags, TemporaryInteractionIndex,
+    BroadPhasePairEvent, Collider, ColliderChanges, ColliderGraphIndex, ColliderHandle,
+    ColliderPair, ColliderSet, CollisionEvent, CollisionEventCoalescer, ContactData,
+    ContactManifold, ContactManifoldData, ContactPair, ContactPairChange, ContactPairChangeKind,
+    InteractionGraph, IntersectionPair, PendingCollisionEvents, SolverContact, SolverFlags,
+    TemporaryInteractionIndex,
 };
-use crate::math::{Real, Vector};
+use crate::math::{Isometry, Point, Real, Vector};
 use crate::pipeline::{
     ActiveEvents, ActiveHooks, ContactModificationContext, EventHandler, PairFilterContext,
     PhysicsHooks,
 };
 use crate::prelude::{CollisionEventFlags, MultibodyJointSet};
-use parry::query::{DefaultQueryDispatcher, PersistentQueryDispatcher};
+use parry::query::{ContactManifoldsWorkspace, DefaultQueryDispatcher, PersistentQueryDispatcher};
 use parry::utils::IsometryOpt;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 struct ColliderGraphIndices {
     contact_graph_index: ColliderGraphIndex,
     intersection_graph_index: ColliderGraphIndex,
+    // Set if the active contacts of this collider changed since the beginning of the step.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    contacts_changed: bool,
 }
 
 impl ColliderGraphIndices {
@@ -35,6 +42,7 @@ impl ColliderGraphIndices {
         Self {
             contact_graph_index: InteractionGraph::<(), ()>::invalid_graph_index(),
             intersection_graph_index: InteractionGraph::<(), ()>::invalid_graph_index(),
+            contacts_changed: false,
         }
     }
 }
@@ -58,6 +66,16 @@ pub struct NarrowPhase {
     contact_graph: InteractionGraph<ColliderHandle, ContactPair>,
     intersection_graph: InteractionGraph<ColliderHandle, IntersectionPair>,
     graph_indices: Coarena<ColliderGraphIndices>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    pending_collision_events: PendingCollisionEvents,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    contact_pair_changes: Vec<ContactPairChange>,
+    #[cfg_attr(feature = "serde-serialize", serde(default))]
+    track_contact_pair_changes: bool,
+    // Set if some contact pairs were seeded or cleared since the last contact computation.
+    has_pairs_to_update: bool,
+    // The seeded pairs that aren't known to the broad-phase yet.
+    unconfirmed_seeds: Vec<ColliderPair>,
 }
 
 pub(crate) type ContactManifoldIndex = usize;
@@ -74,6 +92,19 @@ impl NarrowPhase {
         Self::with_query_dispatcher(DefaultQueryDispatcher)
     }
 
+    /// Creates a new empty narrow-phase, with room for `num_colliders` colliders and
+    /// `num_contact_pairs` contact pairs.
+    ///
+    /// The narrow-phase grows as usual (without panicking) if these capacities are exceeded.
+    pub fn with_capacity(num_colliders: usize, num_contact_pairs: usize) -> Self {
+        Self {
+            contact_graph: InteractionGraph::with_capacity(num_colliders, num_contact_pairs),
+            graph_indices: Coarena::with_capacity(num_colliders),
+            contact_pair_changes: Vec::with_capacity(num_contact_pairs),
+            ..Self::new()
+        }
+    }
+
     /// Creates a new empty narrow-phase with a custom query dispatcher.
     pub fn with_query_dispatcher<D>(d: D) -> Self
     where
@@ -84,6 +115,11 @@ impl NarrowPhase {
             contact_graph: InteractionGraph::new(),
             intersection_graph: InteractionGraph::new(),
             graph_indices: Coarena::new(),
+            pending_collision_events: PendingCollisionEvents::default(),
+            contact_pair_changes: Vec::new(),
+            track_contact_pair_changes: false,
+            has_pairs_to_update: false,
+            unconfirmed_seeds: Vec::new(),
         }
     }
 
@@ -131,6 +167,20 @@ impl NarrowPhase {
             .map(|pair| pair.2)
     }
 
+    /// All the contact pairs involving the given collider that have at least one active contact.
+    ///
+    /// This reflects the state of the contacts computed during the last timestep, including
+    /// deeply penetrating contacts, and contacts that are ignored by the constraints solver
+    /// (e.g., because of the solver groups). Checking if this iterator is empty is the simplest
+    /// way to determine if a collider is touching anything.
+    pub fn active_contacts_with(
+        &self,
+        collider: ColliderHandle,
+    ) -> impl Iterator<Item = &ContactPair> {
+        self.contacts_with(collider)
+            .filter(|pair| pair.has_any_active_contact)
+    }
+
     /// All the intersections involving the given collider.
     ///
     /// It is strongly recommended to use the [`NarrowPhase::intersections_with`]  method instead.
@@ -178,7 +228,7 @@ impl NarrowPhase {
     ///
     /// If this returns `None`, there is no contact between the two colliders.
     /// If this returns `Some`, then there may be a contact between the two colliders. Check the
-    /// result [`ContactPair::has_any_active_collider`] method to see if there is an actual contact.
+    /// result [`ContactPair::has_any_active_contact`] field to see if there is an actual contact.
     pub fn contact_pair_unknown_gen(&self, collider1: u32, collider2: u32) -> Option<&ContactPair> {
         let id1 = self.graph_indices.get_unknown_gen(collider1)?;
         let id2 = self.graph_indices.get_unknown_gen(collider2)?;
@@ -191,7 +241,11 @@ impl NarrowPhase {
     ///
     /// If this returns `None`, there is no contact between the two colliders.
     /// If this returns `Some`, then there may be a contact between the two colliders. Check the
-    /// result [`ContactPair::has_any_active_collider`] method to see if there is an actual contact.
+    /// result [`ContactPair::has_any_active_contact`] field to see if there is an actual contact.
+    ///
+    /// Note that the returned pair’s `collider1` isn’t necessarily equal to the `collider1`
+    /// argument. The contact normals always point from `ContactPair::collider1` toward
+    /// `ContactPair::collider2`.
     pub fn contact_pair(
         &self,
         collider1: ColliderHandle,
@@ -204,6 +258,22 @@ impl NarrowPhase {
             .map(|c| c.2)
     }
 
+    /// The number of active contact points between two specific colliders.
+    ///
+    /// This counts the contact points that were taken into account by the constraints solver
+    /// during the last timestep (after the contact modifications applied by physics hooks).
+    /// Returns `0` if there is no contact pair between these colliders.
+    pub fn num_contacts(&self, collider1: ColliderHandle, collider2: ColliderHandle) -> usize {
+        self.contact_pair(collider1, collider2)
+            .map(|pair| {
+                pair.manifolds
+                    .iter()
+                    .map(|m| m.data.num_active_contacts())
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+
     /// The intersection pair involving two specific colliders.
     ///
     /// It is strongly recommended to use the [`NarrowPhase::intersection_pair`] method instead. This
@@ -240,7 +310,175 @@ impl NarrowPhase {
         self.contact_graph.interactions()
     }
 
+    /// Groups the rigid-bodies into sets of bodies connected by touching contacts.
+    ///
+    /// Two rigid-bodies are in the same group if there is a chain of rigid-bodies between them,
+    /// each touching the next one (see [`ContactPair::has_any_touching_contact`]). If `joints`
+    /// is provided, rigid-bodies attached by an impulse joint or a multibody joint are connected
+    /// too. If `dynamic_only` is `true`, the non-dynamic rigid-bodies are ignored, so a pile of
+    /// bodies resting on the ground isn’t merged with other piles resting on the same ground.
+    ///
+    /// This reflects the contacts computed during the last timestep. Every rigid-body considered
+    /// belongs to exactly one group, possibly containing only itself. The groups are sorted by the
+    /// order of their first rigid-body in `bodies`.
+    pub fn contact_connected_components(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        joints: Option<(&ImpulseJointSet, &MultibodyJointSet)>,
+        dynamic_only: bool,
+    ) -> Vec<Vec<RigidBodyHandle>> {
+        let handles: Vec<_> = bodies
+            .iter()
+            .filter(|(_, rb)| !dynamic_only || rb.is_dynamic())
+            .map(|(handle, _)| handle)
+            .collect();
+        let ids: HashMap<_, _> = handles.iter().enumerate().map(|(i, h)| (*h, i)).collect();
+        let mut parents: Vec<_> = (0..handles.len()).collect();
+
+        fn find(parents: &mut [usize], mut i: usize) -> usize {
+            while parents[i] != i {
+                parents[i] = parents[parents[i]];
+                i = parents[i];
+            }
+            i
+        }
+
+        let mut union = |body1: RigidBodyHandle, body2: RigidBodyHandle| {
+            if let (Some(id1), Some(id2)) = (ids.get(&body1), ids.get(&body2)) {
+                let root1 = find(&mut parents, *id1);
+                let root2 = find(&mut parents, *id2);
+                parents[root1.max(root2)] = root1.min(root2);
+            }
+        };
+
+        for pair in self.contact_pairs() {
+            if !pair.has_any_touching_contact() {
+                continue;
+            }
+
+            let parent1 = colliders.get(pair.collider1).and_then(|co| co.parent());
+            let parent2 = colliders.get(pair.collider2).and_then(|co| co.parent());
+
+            if let (Some(body1), Some(body2)) = (parent1, parent2) {
+                union(body1, body2);
+            }
+        }
+
+        if let Some((impulse_joints, multibody_joints)) = joints {
+            for (_, joint) in impulse_joints.iter() {
+                union(joint.body1, joint.body2);
+            }
+
+            for (_, multibody) in multibody_joints.multibodies.iter() {
+                let root = multibody.root().rigid_body_handle();
+
+                for link in multibody.links() {
+                    union(root, link.rigid_body_handle());
+                }
+            }
+        }
+
+        let mut components: Vec<Vec<RigidBodyHandle>> = vec![];
+        let mut component_ids = HashMap::new();
+
+        for (i, handle) in handles.iter().enumerate() {
+            let root = find(&mut parents, i);
+            let component_id = *component_ids.entry(root).or_insert_with(|| {
+                components.push(vec![]);
+                components.len() - 1
+            });
+            components[component_id].push(*handle);
+        }
+
+        components
+    }
+
+    /// Clears the cached contacts between two colliders.
+    ///
+    /// This removes all the contact manifolds of this pair, so that they are computed from scratch
+    /// (without relying on spatial coherence) at the next timestep, even if none of the colliders
+    /// moved. The contacts computed then are all considered new (see [`SolverContact::is_new`]).
+    ///
+    /// Returns `false` if there is no contact pair between these colliders.
+    pub fn clear_contact_pair(
+        &mut self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) -> bool {
+        let (id1, id2) = match (
+            self.graph_indices.get(collider1.0),
+            self.graph_indices.get(collider2.0),
+        ) {
+            (Some(id1), Some(id2)) => (id1.contact_graph_index, id2.contact_graph_index),
+            _ => return false,
+        };
+
+        match self.contact_graph.interaction_pair_mut(id1, id2) {
+            Some((_, _, pair)) => {
+                pair.clear();
+                pair.needs_update = true;
+                self.has_pairs_to_update = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the cached contacts between `pair.collider1` and `pair.collider2`.
+    ///
+    /// The given pair is typically a clone of a pair previously returned by
+    /// [`NarrowPhase::contact_pair`], saved alongside the rest of the simulation state. Its
+    /// contact manifolds are used as the previous contacts of this pair at the next timestep,
+    /// even if the colliders were just inserted or teleported. The contacts matching them are
+    /// then not considered new (see [`SolverContact::is_new`]), so a restored resting
+    /// configuration isn't handled as an impact: it doesn't bounce at the first timestep. This
+    /// doesn't warmstart the constraints solver though, which always starts from zero impulses.
+    ///
+    /// The seeded pair is dropped at the next timestep if the broad-phase doesn't find it, i.e.,
+    /// if the Aabbs of the colliders don't overlap.
+    ///
+    /// Returns `false` (and does nothing) if one of the colliders doesn't exist, is a sensor,
+    /// or if both colliders are attached to the same rigid-body.
+    pub fn seed_contact_pair(&mut self, colliders: &ColliderSet, mut pair: ContactPair) -> bool {
+        match (colliders.get(pair.collider1), colliders.get(pair.collider2)) {
+            (Some(co1), Some(co2)) => {
+                if co1.is_sensor()
+                    || co2.is_sensor()
+                    || (co1.parent.is_some()
+                        && co1.parent.map(|p| p.handle) == co2.parent.map(|p| p.handle))
+                {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+
+        let existed = self.contact_pair(pair.collider1, pair.collider2).is_some();
+        let id = self.ensure_contact_pair_exists(pair.collider1, pair.collider2);
+        let old_pair = &mut self.contact_graph.graph.edges[id.index()].weight;
+
+        if existed {
+            // Keep track of the collision events already emitted for the replaced pair.
+            pair.start_event_emited = old_pair.start_event_emited;
+        } else {
+            self.unconfirmed_seeds
+                .push(ColliderPair::new(pair.collider1, pair.collider2));
+        }
+
+        pair.needs_update = true;
+        pair.seeded = true;
+        *old_pair = pair;
+        self.has_pairs_to_update = true;
+        true
+    }
+
     /// All the intersection pairs maintained by this narrow-phase.
+    ///
+    /// An intersection pair is maintained for each pair of colliders involving at least one
+    /// sensor, as long as their Aabbs overlap in the broad-phase. The returned boolean indicates
+    /// if the colliders were actually intersecting at the end of the last timestep, so pairs that
+    /// are watched but not intersecting yet are listed too.
     pub fn intersection_pairs(
         &self,
     ) -> impl Iterator<Item = (ColliderHandle, ColliderHandle, bool)> + '_ {
@@ -249,13 +487,160 @@ impl NarrowPhase {
             .map(|e| (e.0, e.1, e.2.intersecting))
     }
 
+    /// Delivers to `events` the collision events emitted since the last call to this method.
+    ///
+    /// Collision events are coalesced per collider pair until this is called, so that a pair
+    /// that starts and stops colliding (or the opposite) between two flushes doesn't generate
+    /// any event. This is called automatically at the end of each step of the physics and
+    /// collision pipelines.
+    ///
+    /// The events are delivered sorted by the handles of the colliders involved: by the smallest
+    /// handle of each pair first, then by the largest one. This order doesn't depend on the
+    /// order the pairs are processed in, including when the `parallel` feature is enabled.
+    ///
+    /// The contact pair given along each start event is its state at the time of this call, and the
+    /// one given along each stop event is its state when the contacts stopped. It is `None` if one
+    /// of the colliders is a sensor, or if the pair of a start event no longer exists (e.g. because
+    /// one of its colliders was removed).
+    pub fn flush_collision_events(
+        &mut self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        events: &dyn EventHandler,
+    ) {
+        let mut pending = std::mem::take(&mut self.pending_collision_events);
+        pending.flush(
+            |collider1, collider2| self.contact_pair(collider1, collider2),
+            bodies,
+            colliders,
+            events,
+        );
+        self.pending_collision_events = pending;
+    }
+
+    /// Emits the `CollisionEvent::Started` event of a contact pair that was held back until the
+    /// solver applied an impulse larger than the pair's contact event impulse threshold.
+    pub(crate) fn emit_impulse_threshold_start_event(
+        &mut self,
+        pair_id: TemporaryInteractionIndex,
+    ) {
+        let pair = &mut self.contact_graph.graph.edges[pair_id.index()].weight;
+        pair.start_event_emited = true;
+        self.pending_collision_events
+            .push_uncancelable(CollisionEvent::Started(
+                pair.collider1,
+                pair.collider2,
+                CollisionEventFlags::empty(),
+            ));
+    }
+
+    /// The contact pairs whose active contacts started, stopped, or changed during the last step.
+    ///
+    /// This is always empty unless the tracking of these changes is enabled with
+    /// [`NarrowPhase::enable_contact_pair_change_tracking`].
+    ///
+    /// This lets the contact pairs affected by a step be processed without going through all the
+    /// contact pairs. A pair is reported as [`ContactPairChangeKind::Modified`] if it still has
+    /// active contacts but some of its active contact points were added or removed: contacts that
+    /// only moved aren't reported. A pair removed while it had active contacts (e.g. because one of
+    /// its colliders was removed) is reported as [`ContactPairChangeKind::Stopped`].
+    ///
+    /// This list is cleared at the beginning of each step of the physics and collision pipelines.
+    /// It is sorted by the handles of the colliders involved, and may contain several changes for
+    /// the same pair if its contacts were computed several times during the step (e.g. because
+    /// of CCD).
+    pub fn contact_pair_changes(&self) -> &[ContactPairChange] {
+        &self.contact_pair_changes
+    }
+
+    /// Enables or disables the tracking of the [`NarrowPhase::contact_pair_changes`].
+    ///
+    /// This is disabled by default because it requires comparing the active contacts of every
+    /// updated contact pair before and after its update, at each step. Disabling it clears the
+    /// changes reported by the last step.
+    pub fn enable_contact_pair_change_tracking(&mut self, enabled: bool) {
+        if !enabled {
+            self.clear_contact_pair_changes();
+        }
+
+        self.track_contact_pair_changes = enabled;
+    }
+
+    /// Is the tracking of the [`NarrowPhase::contact_pair_changes`] enabled?
+    pub fn is_contact_pair_change_tracking_enabled(&self) -> bool {
+        self.track_contact_pair_changes
+    }
+
+    /// Did the active contacts of this collider start, stop, or change during the last step?
+    ///
+    /// This is `true` if the collider is involved in any of the
+    /// [`NarrowPhase::contact_pair_changes`], and is cleared at the beginning of each step of
+    /// the physics and collision pipelines. It only costs a lookup so it can be polled for every
+    /// collider, e.g., to skip the colliders with stable contacts.
+    ///
+    /// Like the contact pair changes it is based on, this is always `false` unless their tracking
+    /// is enabled with [`NarrowPhase::enable_contact_pair_change_tracking`].
+    pub fn contacts_changed(&self, collider: ColliderHandle) -> bool {
+        self.graph_indices
+            .get(collider.0)
+            .map(|id| id.contacts_changed)
+            .unwrap_or(false)
+    }
+
+    pub(crate) fn clear_contact_pair_changes(&mut self) {
+        for change in &self.contact_pair_changes {
+            for handle in [change.collider1, change.collider2] {
+                if let Some(id) = self.graph_indices.get_mut(handle.0) {
+                    id.contacts_changed = false;
+                }
+            }
+        }
+
+        self.contact_pair_changes.clear();
+    }
+
+    // Flags the colliders involved in the contact pair changes.
+    fn flag_changed_colliders(&mut self) {
+        for change in &self.contact_pair_changes {
+            for handle in [change.collider1, change.collider2] {
+                if let Some(id) = self.graph_indices.get_mut(handle.0) {
+                    id.contacts_changed = true;
+                }
+            }
+        }
+    }
+
     // #[cfg(feature = "parallel")]
     // pub(crate) fn contact_pairs_vec_mut(&mut self) -> &mut Vec<ContactPair> {
     //     &mut self.contact_graph.interactions
     // }
 
     /// Maintain the narrow-phase internal state by taking collider removal into account.
+    ///
+    /// The collision events emitted by this method are delivered to `events` before it returns.
     pub fn handle_user_changes(
+        &mut self,
+        islands: Option<&mut IslandManager>,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+        colliders: &mut ColliderSet,
+        bodies: &mut RigidBodySet,
+        events: &dyn EventHandler,
+    ) {
+        self.handle_user_changes_with_pending_events(
+            islands,
+            modified_colliders,
+            removed_colliders,
+            colliders,
+            bodies,
+            events,
+        );
+        self.flush_collision_events(bodies, colliders, events);
+    }
+
+    /// Same as [`Self::handle_user_changes`], but the collision events emitted are only delivered
+    /// by the next call to [`Self::flush_collision_events`].
+    pub(crate) fn handle_user_changes_with_pending_events(
         &mut self,
         mut islands: Option<&mut IslandManager>,
         modified_colliders: &[ColliderHandle],
@@ -269,6 +654,11 @@ impl NarrowPhase {
         // by the contact/intersection graphs when a node is removed.
         let mut prox_id_remap = HashMap::new();
         let mut contact_id_remap = HashMap::new();
+        let coalescer = CollisionEventCoalescer::new(
+            events,
+            std::mem::take(&mut self.pending_collision_events),
+        );
+        let events = &coalescer;
 
         for collider in removed_colliders {
             // NOTE: if the collider does not have any graph indices currently, there is nothing
@@ -306,6 +696,8 @@ impl NarrowPhase {
             bodies,
             events,
         );
+        self.flag_changed_colliders();
+        self.pending_collision_events = coalescer.into_pending();
     }
 
     pub(crate) fn remove_collider(
@@ -319,6 +711,16 @@ impl NarrowPhase {
         contact_id_remap: &mut HashMap<ColliderHandle, ColliderGraphIndex>,
         events: &dyn EventHandler,
     ) {
+        for (_, _, pair) in self.contact_graph.interactions_with(contact_graph_id) {
+            if pair.has_any_active_contact && self.track_contact_pair_changes {
+                self.contact_pair_changes.push(ContactPairChange {
+                    collider1: pair.collider1,
+                    collider2: pair.collider2,
+                    kind: ContactPairChangeKind::Stopped,
+                });
+            }
+        }
+
         // Wake up every body in contact with the deleted collider and generate Stopped collision events.
         if let Some(islands) = islands.as_deref_mut() {
             for (a, b, pair) in self.contact_graph.interactions_with(contact_graph_id) {
@@ -550,6 +952,14 @@ impl NarrowPhase {
                     // Also wake up the dynamic bodies that were in contact.
                     if let Some(mut ctct) = contact_pair {
                         if ctct.has_any_active_contact {
+                            if self.track_contact_pair_changes {
+                                self.contact_pair_changes.push(ContactPairChange {
+                                    collider1: ctct.collider1,
+                                    collider2: ctct.collider2,
+                                    kind: ContactPairChangeKind::Stopped,
+                                });
+                            }
+
                             if let Some(islands) = islands {
                                 if let Some(co_parent1) = &co1.parent {
                                     islands.wake_up(bodies, co_parent1.handle, true);
@@ -560,8 +970,14 @@ impl NarrowPhase {
                                 }
                             }
 
+                            let throttled = co1
+                                .contact_event_impulse_threshold()
+                                .max(co2.contact_event_impulse_threshold())
+                                > 0.0;
+
                             if (co1.flags.active_events | co2.flags.active_events)
                                 .contains(ActiveEvents::COLLISION_EVENTS)
+                                && (!throttled || ctct.start_event_emited)
                             {
                                 ctct.emit_stop_event(bodies, colliders, events);
                             }
@@ -619,33 +1035,68 @@ impl NarrowPhase {
                     );
                 }
             } else {
-                // NOTE: same code as above, but for the contact graph.
-                // TODO: refactor both pieces of code somehow?
+                let _ = self.ensure_contact_pair_exists(pair.collider1, pair.collider2);
+            }
+        }
+    }
 
-                // NOTE: the collider won't have a graph index as long
-                // as it does not interact with anything.
-                if !InteractionGraph::<(), ()>::is_graph_index_valid(gid1.contact_graph_index) {
-                    gid1.contact_graph_index = self.contact_graph.graph.add_node(pair.collider1);
+    /// Removes the seeded contact pairs that weren't added by the broad-phase.
+    ///
+    /// A pair seeded between colliders with Aabbs that don't overlap would otherwise never be
+    /// removed since the broad-phase never reports it.
+    fn remove_unconfirmed_seeds(&mut self, broad_phase_events: &[BroadPhasePairEvent]) {
+        for seed in std::mem::take(&mut self.unconfirmed_seeds) {
+            let confirmed = broad_phase_events.iter().any(|event| {
+                matches!(event, BroadPhasePairEvent::AddPair(pair)
+                    if *pair == seed || *pair == seed.swap())
+            });
+
+            if !confirmed {
+                if let (Some(gid1), Some(gid2)) = (
+                    self.graph_indices.get(seed.collider1.0),
+                    self.graph_indices.get(seed.collider2.0),
+                ) {
+                    let _ = self
+                        .contact_graph
+                        .remove_edge(gid1.contact_graph_index, gid2.contact_graph_index);
                 }
+            }
+        }
+    }
 
-                if !InteractionGraph::<(), ()>::is_graph_index_valid(gid2.contact_graph_index) {
-                    gid2.contact_graph_index = self.contact_graph.graph.add_node(pair.collider2);
-                }
+    /// Adds an empty contact pair between two colliders, unless it already exists.
+    fn ensure_contact_pair_exists(
+        &mut self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) -> TemporaryInteractionIndex {
+        let (gid1, gid2) = self.graph_indices.ensure_pair_exists(
+            collider1.0,
+            collider2.0,
+            ColliderGraphIndices::invalid(),
+        );
 
-                if self
-                    .contact_graph
-                    .graph
-                    .find_edge(gid1.contact_graph_index, gid2.contact_graph_index)
-                    .is_none()
-                {
-                    let interaction = ContactPair::new(pair.collider1, pair.collider2);
-                    let _ = self.contact_graph.add_edge(
-                        gid1.contact_graph_index,
-                        gid2.contact_graph_index,
-                        interaction,
-                    );
-                }
-            }
+        // NOTE: the collider won't have a graph index as long
+        // as it does not interact with anything.
+        if !InteractionGraph::<(), ()>::is_graph_index_valid(gid1.contact_graph_index) {
+            gid1.contact_graph_index = self.contact_graph.graph.add_node(collider1);
+        }
+
+        if !InteractionGraph::<(), ()>::is_graph_index_valid(gid2.contact_graph_index) {
+            gid2.contact_graph_index = self.contact_graph.graph.add_node(collider2);
+        }
+
+        match self
+            .contact_graph
+            .graph
+            .find_edge(gid1.contact_graph_index, gid2.contact_graph_index)
+        {
+            Some(id) => id,
+            None => self.contact_graph.add_edge(
+                gid1.contact_graph_index,
+                gid2.contact_graph_index,
+                ContactPair::new(collider1, collider2),
+            ),
         }
     }
 
@@ -657,6 +1108,12 @@ impl NarrowPhase {
         broad_phase_events: &[BroadPhasePairEvent],
         events: &dyn EventHandler,
     ) {
+        let coalescer = CollisionEventCoalescer::new(
+            events,
+            std::mem::take(&mut self.pending_collision_events),
+        );
+        let events = &coalescer;
+
         for event in broad_phase_events {
             match event {
                 BroadPhasePairEvent::AddPair(pair) => {
@@ -674,6 +1131,11 @@ impl NarrowPhase {
                 }
             }
         }
+
+        self.remove_unconfirmed_seeds(broad_phase_events);
+
+        self.flag_changed_colliders();
+        self.pending_collision_events = coalescer.into_pending();
     }
 
     pub(crate) fn compute_intersections(
@@ -688,6 +1150,11 @@ impl NarrowPhase {
             return;
         }
 
+        let coalescer = CollisionEventCoalescer::new(
+            events,
+            std::mem::take(&mut self.pending_collision_events),
+        );
+        let events = &coalescer;
         let nodes = &self.intersection_graph.graph.nodes;
         let query_dispatcher = &*self.query_dispatcher;
 
@@ -709,16 +1176,15 @@ impl NarrowPhase {
                 }
 
                 // TODO: avoid lookup into bodies.
-                let mut rb_type1 = RigidBodyType::Fixed;
-                let mut rb_type2 = RigidBodyType::Fixed;
-
-                if let Some(co_parent1) = &co1.parent {
-                    rb_type1 = bodies[co_parent1.handle].body_type;
-                }
-
-                if let Some(co_parent2) = &co2.parent {
-                    rb_type2 = bodies[co_parent2.handle].body_type;
-                }
+                let (rb_type1, rb_type2) =
+                    match (parent_body_type(bodies, co1), parent_body_type(bodies, co2)) {
+                        (Some(rb_type1), Some(rb_type2)) => (rb_type1, rb_type2),
+                        // One of the parent rigid-bodies was removed without its colliders.
+                        _ => {
+                            edge.weight.intersecting = false;
+                            break 'emit_events;
+                        }
+                    };
 
                 // Filter based on the rigid-body types.
                 if !co1.flags.active_collision_types.test(rb_type1, rb_type2)
@@ -774,11 +1240,14 @@ impl NarrowPhase {
                 }
             }
         });
+
+        self.pending_collision_events = coalescer.into_pending();
     }
 
     pub(crate) fn compute_contacts(
         &mut self,
         prediction_distance: Real,
+        persistence_distance: Real,
         bodies: &RigidBodySet,
         colliders: &ColliderSet,
         impulse_joints: &ImpulseJointSet,
@@ -787,39 +1256,55 @@ impl NarrowPhase {
         hooks: &dyn PhysicsHooks,
         events: &dyn EventHandler,
     ) {
-        if modified_colliders.is_empty() {
+        if modified_colliders.is_empty() && !self.has_pairs_to_update {
             return;
         }
 
+        self.has_pairs_to_update = false;
+        let coalescer = CollisionEventCoalescer::new(
+            events,
+            std::mem::take(&mut self.pending_collision_events),
+        );
+        let events = &coalescer;
+        let contact_pair_changes = Mutex::new(std::mem::take(&mut self.contact_pair_changes));
+        let track_changes = self.track_contact_pair_changes;
         let query_dispatcher = &*self.query_dispatcher;
 
         // TODO: don't iterate on all the edges.
         par_iter_mut!(&mut self.contact_graph.graph.edges).for_each(|edge| {
             let pair = &mut edge.weight;
             let had_any_active_contact = pair.has_any_active_contact;
+            let old_contacts_signature;
             let co1 = &colliders[pair.collider1];
             let co2 = &colliders[pair.collider2];
 
             // TODO: remove the `loop` once labels on blocks are supported.
             'emit_events: loop {
-                if !co1.changes.needs_narrow_phase_update()
+                if !pair.needs_update
+                    && !co1.changes.needs_narrow_phase_update()
                     && !co2.changes.needs_narrow_phase_update()
                 {
                     // No update needed for these colliders.
                     return;
                 }
 
-                // TODO: avoid lookup into bodies.
-                let mut rb_type1 = RigidBodyType::Fixed;
-                let mut rb_type2 = RigidBodyType::Fixed;
-
-                if let Some(co_parent1) = &co1.parent {
-                    rb_type1 = bodies[co_parent1.handle].body_type;
-                }
+                old_contacts_signature =
+                    track_changes.then(|| pair.active_contacts_signature(prediction_distance));
+                pair.needs_update = false;
+                // Seeded contacts were given for the current configuration of the colliders, so
+                // they must not be invalidated by the changes that led to this configuration.
+                let seeded = std::mem::take(&mut pair.seeded);
 
-                if let Some(co_parent2) = &co2.parent {
-                    rb_type2 = bodies[co_parent2.handle].body_type;
-                }
+                // TODO: avoid lookup into bodies.
+                let (rb_type1, rb_type2) =
+                    match (parent_body_type(bodies, co1), parent_body_type(bodies, co2)) {
+                        (Some(rb_type1), Some(rb_type2)) => (rb_type1, rb_type2),
+                        // One of the parent rigid-bodies was removed without its colliders.
+                        _ => {
+                            pair.clear();
+                            break 'emit_events;
+                        }
+                    };
 
                 // Deal with contacts disabled between bodies attached by joints.
                 if let (Some(co_parent1), Some(co_parent2)) = (&co1.parent, &co2.parent) {
@@ -883,65 +1368,150 @@ impl NarrowPhase {
                     solver_flags.remove(SolverFlags::COMPUTE_IMPULSES);
                 }
 
-                if co1.changes.contains(ColliderChanges::SHAPE)
-                    || co2.changes.contains(ColliderChanges::SHAPE)
-                {
+                let shape_changed1 = co1.changes.contains(ColliderChanges::SHAPE);
+                let shape_changed2 = co2.changes.contains(ColliderChanges::SHAPE);
+                let mut contacts_to_remap = None;
+
+                if !seeded && (shape_changed1 || shape_changed2) {
                     // The shape changed so the workspace is no longer valid.
                     pair.workspace = None;
+
+                    let remap_distance1 = co1.contact_remap_distance.filter(|_| shape_changed1);
+                    let remap_distance2 = co2.contact_remap_distance.filter(|_| shape_changed2);
+
+                    if let Some(remap_distance) = remap_distance1
+                        .into_iter()
+                        .chain(remap_distance2)
+                        .reduce(Real::max)
+                    {
+                        // Keep the old contacts aside so their contact data can be
+                        // transferred to the closest contacts computed with the new shape.
+                        // The manifolds are emptied so they don’t get updated based on
+                        // spatial coherence only (which would keep contacts lying on the old
+                        // shape).
+                        let old_contacts: Vec<_> = pair
+                            .manifolds
+                            .iter_mut()
+                            .flat_map(|m| m.points.drain(..))
+                            .collect();
+                        contacts_to_remap = Some((old_contacts, remap_distance));
+                    }
+                }
+
+                if !seeded
+                    && (co1.changes.contains(ColliderChanges::PARENT_TELEPORTED)
+                        || co2.changes.contains(ColliderChanges::PARENT_TELEPORTED))
+                {
+                    // One of the colliders was teleported so the previous contacts (and
+                    // their contact data) are no longer valid.
+                    pair.manifolds.clear();
+                    pair.workspace = None;
+                    contacts_to_remap = None;
                 }
 
                 let pos12 = co1.pos.inv_mul(&co2.pos);
-                let _ = query_dispatcher.contact_manifolds(
+                generate_contact_manifolds(
+                    query_dispatcher,
                     &pos12,
-                    &*co1.shape,
-                    &*co2.shape,
-                    prediction_distance,
+                    co1,
+                    co2,
+                    prediction_distance + persistence_distance,
                     &mut pair.manifolds,
                     &mut pair.workspace,
                 );
 
+                if let Some((old_contacts, remap_distance)) = contacts_to_remap {
+                    for manifold in &mut pair.manifolds {
+                        manifold.match_contacts_using_positions(&old_contacts, remap_distance);
+                    }
+                }
+
                 let friction = CoefficientCombineRule::combine(
                     co1.material.friction,
                     co2.material.friction,
                     co1.material.friction_combine_rule as u8,
                     co2.material.friction_combine_rule as u8,
                 );
-                let restitution = CoefficientCombineRule::combine(
-                    co1.material.restitution,
-                    co2.material.restitution,
-                    co1.material.restitution_combine_rule as u8,
-                    co2.material.restitution_combine_rule as u8,
-                );
+                #[cfg(feature = "dim3")]
+                let (friction, anisotropic_friction) = match combine_anisotropic_friction(co1, co2)
+                {
+                    Some(combined) if combined.friction_along == combined.friction_across => {
+                        (combined.friction_along, None)
+                    }
+                    Some(combined) => (
+                        combined.friction_along.max(combined.friction_across),
+                        Some(combined),
+                    ),
+                    None => (friction, None),
+                };
+                let restitution =
+                    if co1.material.absorbs_restitution || co2.material.absorbs_restitution {
+                        0.0
+                    } else {
+                        CoefficientCombineRule::combine(
+                            co1.material.restitution,
+                            co2.material.restitution,
+                            co1.material.restitution_combine_rule as u8,
+                            co2.material.restitution_combine_rule as u8,
+                        )
+                    };
+                let contact_softness = co1
+                    .material
+                    .contact_softness
+                    .combine(&co2.material.contact_softness);
+                let max_contact_impulse = co1
+                    .material
+                    .max_contact_impulse
+                    .min(co2.material.max_contact_impulse);
 
                 let zero = RigidBodyDominance(0); // The value doesn't matter, it will be MAX because of the effective groups.
                 let dominance1 = co1
                     .parent
-                    .map(|p1| bodies[p1.handle].dominance)
+                    .and_then(|p1| bodies.get(p1.handle))
+                    .map(|rb1| rb1.dominance)
                     .unwrap_or(zero);
                 let dominance2 = co2
                     .parent
-                    .map(|p2| bodies[p2.handle].dominance)
+                    .and_then(|p2| bodies.get(p2.handle))
+                    .map(|rb2| rb2.dominance)
                     .unwrap_or(zero);
 
                 pair.has_any_active_contact = false;
 
+                // Capsules resting on a heightfield vertex or edge touch several triangles at the
+                // same point. Keep only one solver contact for each of these points so they don't
+                // fight each other in the solver.
+                let mut capsule_heightfield_contacts =
+                    is_capsule_heightfield_pair(co1, co2).then(Vec::new);
+
                 for manifold in &mut pair.manifolds {
                     let world_pos1 = manifold.subshape_pos1.prepend_to(&co1.pos);
                     manifold.data.solver_contacts.clear();
                     manifold.data.rigid_body1 = co1.parent.map(|p| p.handle);
                     manifold.data.rigid_body2 = co2.parent.map(|p| p.handle);
                     manifold.data.solver_flags = solver_flags;
+                    manifold.data.contact_softness = contact_softness;
+                    manifold.data.max_contact_impulse = max_contact_impulse;
+                    #[cfg(feature = "dim3")]
+                    {
+                        manifold.data.anisotropic_friction = anisotropic_friction;
+                    }
                     manifold.data.relative_dominance = dominance1.effective_group(&rb_type1)
                         - dominance2.effective_group(&rb_type2);
                     manifold.data.normal = world_pos1 * manifold.local_n1;
 
                     // Generate solver contacts.
-                    for (contact_id, contact) in manifold.points.iter().enumerate() {
+                    for (contact_id, contact) in manifold.points.iter_mut().enumerate() {
                         assert!(
                             contact_id <= u8::MAX as usize,
                             "A contact manifold cannot contain more than 255 contacts currently."
                         );
 
+                        if persistence_distance > 0.0 && contact.data.impulse != 0.0 {
+                            // Remember this contact was resting as long as it is kept.
+                            contact.data.has_rested = true;
+                        }
+
                         if contact.dist < prediction_distance {
                             // Generate the solver contact.
                             let solver_contact = SolverContact {
@@ -952,14 +1522,65 @@ impl NarrowPhase {
                                 friction,
                                 restitution,
                                 tangent_velocity: Vector::zeros(),
-                                is_new: contact.data.impulse == 0.0,
+                                is_new: contact.data.impulse == 0.0 && !contact.data.has_rested,
                             };
 
                             manifold.data.solver_contacts.push(solver_contact);
                             pair.has_any_active_contact = true;
+                        } else {
+                            // This contact is only kept because of the persistence distance.
+                            contact.data.impulse = 0.0;
+                            contact.data.tangent_impulse = na::zero();
+                            contact.data.unclamped_impulse = 0.0;
                         }
                     }
 
+                    if let Some(kept_contacts) = &mut capsule_heightfield_contacts {
+                        remove_duplicate_solver_contacts(
+                            &manifold.data.normal,
+                            &mut manifold.data.solver_contacts,
+                            kept_contacts,
+                        );
+                    }
+
+                    // Apply the built-in one-way platform behaviors.
+                    if let Some(platform) = &co1.one_way_platform {
+                        crate::pipeline::update_oneway_platform_contacts(
+                            &manifold.local_n1,
+                            &platform.allowed_local_normal,
+                            platform.allowed_angle,
+                            &mut manifold.data.solver_contacts,
+                            &mut manifold.data.one_way_states[0],
+                        );
+                    }
+
+                    if let Some(platform) = &co2.one_way_platform {
+                        crate::pipeline::update_oneway_platform_contacts(
+                            &manifold.local_n2,
+                            &platform.allowed_local_normal,
+                            platform.allowed_angle,
+                            &mut manifold.data.solver_contacts,
+                            &mut manifold.data.one_way_states[1],
+                        );
+                    }
+
+                    // Ignore the contacts touching the back of one-sided meshes.
+                    if (co1.one_sided
+                        && crate::geometry::is_back_face_contact(
+                            &*co1.shape,
+                            manifold.subshape1,
+                            &manifold.local_n1,
+                        ))
+                        || (co2.one_sided
+                            && crate::geometry::is_back_face_contact(
+                                &*co2.shape,
+                                manifold.subshape2,
+                                &manifold.local_n2,
+                            ))
+                    {
+                        manifold.data.solver_contacts.clear();
+                    }
+
                     // Apply the user-defined contact modification.
                     if active_hooks.contains(ActiveHooks::MODIFY_SOLVER_CONTACTS) {
                         let mut modifiable_solver_contacts =
@@ -991,18 +1612,63 @@ impl NarrowPhase {
                 break 'emit_events;
             }
 
+            if let Some(old_contacts_signature) = old_contacts_signature {
+                let change_kind = match (had_any_active_contact, pair.has_any_active_contact) {
+                    (false, true) => Some(ContactPairChangeKind::Started),
+                    (true, false) => Some(ContactPairChangeKind::Stopped),
+                    (true, true)
+                        if pair.active_contacts_signature(prediction_distance)
+                            != old_contacts_signature =>
+                    {
+                        Some(ContactPairChangeKind::Modified)
+                    }
+                    _ => None,
+                };
+
+                if let Some(kind) = change_kind {
+                    contact_pair_changes
+                        .lock()
+                        .unwrap()
+                        .push(ContactPairChange {
+                            collider1: pair.collider1,
+                            collider2: pair.collider2,
+                            kind,
+                        });
+                }
+            }
+
             let active_events = co1.flags.active_events | co2.flags.active_events;
 
+            // NOTE: if the pair has an impulse threshold, its start event is only emitted after
+            //       the constraints resolution, see `NarrowPhase::emit_impulse_threshold_start_event`.
+            let throttled = co1
+                .contact_event_impulse_threshold()
+                .max(co2.contact_event_impulse_threshold())
+                > 0.0;
+
             if pair.has_any_active_contact != had_any_active_contact {
                 if active_events.contains(ActiveEvents::COLLISION_EVENTS) {
                     if pair.has_any_active_contact {
-                        pair.emit_start_event(bodies, colliders, events);
-                    } else {
+                        if !throttled {
+                            pair.emit_start_event(bodies, colliders, events);
+                        }
+                    } else if !throttled || pair.start_event_emited {
                         pair.emit_stop_event(bodies, colliders, events);
                     }
                 }
             }
         });
+
+        self.contact_pair_changes = contact_pair_changes.into_inner().unwrap();
+
+        if track_changes {
+            // NOTE: the sort is stable so the changes of a given pair remain in chronological order,
+            //       and it makes the order of the pairs deterministic with the `parallel` feature.
+            self.contact_pair_changes
+                .sort_by_key(|change| (change.collider1.0, change.collider2.0));
+            self.flag_changed_colliders();
+        }
+        self.pending_collision_events = coalescer.into_pending();
     }
 
     /// Retrieve all the interactions with at least one contact point, happening between two active bodies.
@@ -1030,29 +1696,35 @@ impl NarrowPhase {
                     .contains(SolverFlags::COMPUTE_IMPULSES)
                     && manifold.data.num_active_contacts() != 0
                 {
-                    let (active_island_id1, rb_type1, sleeping1) =
-                        if let Some(handle1) = manifold.data.rigid_body1 {
-                            let rb1 = &bodies[handle1];
-                            (
-                                rb1.ids.active_island_id,
-                                rb1.body_type,
-                                rb1.activation.sleeping,
-                            )
-                        } else {
-                            (0, RigidBodyType::Fixed, true)
-                        };
+                    let rb1 = manifold.data.rigid_body1.map(|h| bodies.get(h));
+                    let rb2 = manifold.data.rigid_body2.map(|h| bodies.get(h));
 
-                    let (active_island_id2, rb_type2, sleeping2) =
-                        if let Some(handle2) = manifold.data.rigid_body2 {
-                            let rb2 = &bodies[handle2];
-                            (
-                                rb2.ids.active_island_id,
-                                rb2.body_type,
-                                rb2.activation.sleeping,
-                            )
-                        } else {
-                            (0, RigidBodyType::Fixed, true)
-                        };
+                    // Skip the manifolds of colliders whose parent rigid-body was removed.
+                    if matches!(rb1, Some(None)) || matches!(rb2, Some(None)) {
+                        continue;
+                    }
+
+                    let (active_island_id1, rb_type1, sleeping1) = if let Some(rb1) = rb1.flatten()
+                    {
+                        (
+                            rb1.ids.active_island_id,
+                            rb1.body_type,
+                            rb1.activation.sleeping,
+                        )
+                    } else {
+                        (0, RigidBodyType::Fixed, true)
+                    };
+
+                    let (active_island_id2, rb_type2, sleeping2) = if let Some(rb2) = rb2.flatten()
+                    {
+                        (
+                            rb2.ids.active_island_id,
+                            rb2.body_type,
+                            rb2.activation.sleeping,
+                        )
+                    } else {
+                        (0, RigidBodyType::Fixed, true)
+                    };
 
                     if (rb_type1.is_dynamic() || rb_type2.is_dynamic())
                         && (!rb_type1.is_dynamic() || !sleeping1)
@@ -1077,3 +1749,778 @@ impl NarrowPhase {
         }
     }
 }
+
+/// Updates the contact manifolds between `co1` and `co2`, given the position `pos12` of `co2`
+/// relative to `co1`.
+pub(crate) fn generate_contact_manifolds(
+    query_dispatcher: &dyn PersistentQueryDispatcher<ContactManifoldData, ContactData>,
+    pos12: &Isometry<Real>,
+    co1: &Collider,
+    co2: &Collider,
+    prediction_distance: Real,
+    manifolds: &mut Vec<ContactManifold>,
+    workspace: &mut Option<ContactManifoldsWorkspace>,
+) {
+    let _ = query_dispatcher.contact_manifolds(
+        pos12,
+        &*co1.shape,
+        &*co2.shape,
+        prediction_distance,
+        manifolds,
+        workspace,
+    );
+    crate::geometry::fix_internal_edge_normals(pos12, &*co1.shape, &*co2.shape, manifolds);
+}
+
+/// The type of the rigid-body a collider is attached to, [`RigidBodyType::Fixed`] if it isn't
+/// attached to any, or `None` if its parent rigid-body no longer exists.
+fn parent_body_type(bodies: &RigidBodySet, co: &Collider) -> Option<RigidBodyType> {
+    match &co.parent {
+        Some(co_parent) => bodies.get(co_parent.handle).map(|rb| rb.body_type),
+        None => Some(RigidBodyType::Fixed),
+    }
+}
+
+/// The anisotropic friction of the contacts between `co1` and `co2`, with a world-space
+/// principal direction, or `None` if neither collider has anisotropic friction.
+#[cfg(feature = "dim3")]
+fn combine_anisotropic_friction(co1: &Collider, co2: &Collider) -> Option<AnisotropicFriction> {
+    let direction = match (
+        &co1.material.anisotropic_friction,
+        &co2.material.anisotropic_friction,
+    ) {
+        (Some(friction1), _) => co1.pos.rotation * friction1.direction,
+        (None, Some(friction2)) => co2.pos.rotation * friction2.direction,
+        (None, None) => return None,
+    };
+
+    let coefficients = |co: &Collider| {
+        co.material
+            .anisotropic_friction
+            .map(|friction| (friction.friction_along, friction.friction_across))
+            .unwrap_or((co.material.friction, co.material.friction))
+    };
+    let (along1, across1) = coefficients(co1);
+    let (along2, across2) = coefficients(co2);
+    let combine = |coeff1, coeff2| {
+        CoefficientCombineRule::combine(
+            coeff1,
+            coeff2,
+            co1.material.friction_combine_rule as u8,
+            co2.material.friction_combine_rule as u8,
+        )
+    };
+
+    Some(AnisotropicFriction::new(
+        direction,
+        combine(along1, along2),
+        combine(across1, across2),
+    ))
+}
+
+fn is_capsule_heightfield_pair(co1: &Collider, co2: &Collider) -> bool {
+    (co1.shape.as_capsule().is_some() && co2.shape.as_heightfield().is_some())
+        || (co1.shape.as_heightfield().is_some() && co2.shape.as_capsule().is_some())
+}
+
+/// Removes from `solver_contacts` the contacts located at the same point, and with the same
+/// normal, as one of the `kept_contacts` from the other manifolds of the same contact pair.
+///
+/// The remaining contacts are then added to `kept_contacts`. Because the manifolds of a contact
+/// pair are always visited in the same order, the same solver contact is kept from one step to
+/// the next, which preserves its contact data (e.g. whether it is new, and its reported impulse).
+fn remove_duplicate_solver_contacts(
+    normal: &Vector<Real>,
+    solver_contacts: &mut Vec<SolverContact>,
+    kept_contacts: &mut Vec<(Point<Real>, Vector<Real>)>,
+) {
+    const DUPLICATE_DISTANCE: Real = 1.0e-3;
+    const DUPLICATE_NORMAL_COS: Real = 0.9999;
+
+    solver_contacts.retain(|contact| {
+        let is_duplicate = kept_contacts.iter().any(|(point, kept_normal)| {
+            na::distance_squared(point, &contact.point) < DUPLICATE_DISTANCE * DUPLICATE_DISTANCE
+                && normal.dot(kept_normal) > DUPLICATE_NORMAL_COS
+        });
+
+        if !is_duplicate {
+            kept_contacts.push((contact.point, *normal));
+        }
+
+        !is_duplicate
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::{ColliderBuilder, ColliderSet, Cuboid, SharedShape};
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    fn cuboid(half_extents: Vector<Real>) -> ColliderBuilder {
+        ColliderBuilder::new(SharedShape::new(Cuboid::new(half_extents)))
+    }
+
+    #[test]
+    fn contact_connected_components_split_disjoint_stacks() {
+        let mut world = TestWorld::new();
+
+        let ground = world
+            .bodies
+            .insert(RigidBodyBuilder::fixed().translation(-Vector::y() * 0.5));
+        let mut ground_half_extents = Vector::repeat(20.0);
+        ground_half_extents.y = 0.5;
+        world
+            .colliders
+            .insert_with_parent(cuboid(ground_half_extents), ground, &mut world.bodies);
+
+        // Two stacks of two boxes, far from each other.
+        let stacks: Vec<Vec<_>> = [-5.0, 5.0]
+            .into_iter()
+            .map(|x| {
+                (0..2)
+                    .map(|i| {
+                        let translation = Vector::x() * x + Vector::y() * (0.5 + i as Real);
+                        let body = world
+                            .bodies
+                            .insert(RigidBodyBuilder::dynamic().translation(translation));
+                        world.colliders.insert_with_parent(
+                            cuboid(Vector::repeat(0.5)),
+                            body,
+                            &mut world.bodies,
+                        );
+                        body
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // A sensor overlapping the first stack, which must not connect to it.
+        let sensor = world.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * -5.0 + Vector::y())
+                .gravity_scale(0.0),
+        );
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(2.0).sensor(true),
+            sensor,
+            &mut world.bodies,
+        );
+
+        for _ in 0..10 {
+            world.step();
+        }
+
+        let components = world.narrow_phase.contact_connected_components(
+            &world.bodies,
+            &world.colliders,
+            None,
+            true,
+        );
+        assert_eq!(
+            components,
+            vec![stacks[0].clone(), stacks[1].clone(), vec![sensor]]
+        );
+
+        // The fixed ground connects both stacks.
+        let components = world.narrow_phase.contact_connected_components(
+            &world.bodies,
+            &world.colliders,
+            None,
+            false,
+        );
+        let mut grounded = vec![ground];
+        grounded.extend(stacks.concat());
+        assert_eq!(components, vec![grounded, vec![sensor]]);
+    }
+
+    #[test]
+    fn num_contacts_only_counts_the_active_contacts() {
+        use crate::pipeline::{ActiveHooks, ContactModificationContext, PhysicsHooks};
+
+        let mut world = TestWorld::new();
+        let mut ground_half_extents = Vector::repeat(20.0);
+        ground_half_extents.y = 0.5;
+        let ground = world
+            .colliders
+            .insert(cuboid(ground_half_extents).translation(-Vector::y() * 0.5));
+        let body = RigidBodyBuilder::dynamic().translation(Vector::y() * 0.49);
+        let (_, collider) = world.insert(body, cuboid(Vector::repeat(0.5)));
+        let far = world
+            .colliders
+            .insert(ColliderBuilder::ball(0.5).translation(Vector::y() * 10.0));
+
+        world.step();
+        let num_points = world
+            .narrow_phase
+            .contact_pair(ground, collider)
+            .unwrap()
+            .manifolds[0]
+            .points
+            .len();
+        assert_eq!(num_points, 1 << (crate::math::DIM - 1));
+        assert_eq!(
+            world.narrow_phase.num_contacts(ground, collider),
+            num_points
+        );
+        assert_eq!(
+            world.narrow_phase.num_contacts(collider, ground),
+            num_points
+        );
+        assert_eq!(world.narrow_phase.num_contacts(ground, far), 0);
+
+        // The contacts removed by the physics hooks aren't counted.
+        struct KeepOneContact;
+        impl PhysicsHooks for KeepOneContact {
+            fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+                context.solver_contacts.truncate(1);
+            }
+        }
+
+        world.colliders[collider].set_active_hooks(ActiveHooks::MODIFY_SOLVER_CONTACTS);
+        world.step_with(&KeepOneContact, &());
+        let pair = world.narrow_phase.contact_pair(ground, collider).unwrap();
+        assert_eq!(pair.manifolds[0].points.len(), num_points);
+        assert_eq!(world.narrow_phase.num_contacts(ground, collider), 1);
+    }
+
+    #[test]
+    fn restitution_absorbing_colliders_override_the_combine_rules() {
+        use crate::dynamics::CoefficientCombineRule;
+
+        let mut world = TestWorld::new();
+        let ground = ColliderBuilder::halfspace(Vector::y_axis())
+            .restitution(1.0)
+            .absorbs_restitution(true);
+        let ground = world.colliders.insert(ground);
+        let body = RigidBodyBuilder::dynamic().translation(Vector::y() * 0.49);
+        let ball = ColliderBuilder::ball(0.5)
+            .restitution(1.0)
+            .restitution_combine_rule(CoefficientCombineRule::Max);
+        let (_, ball) = world.insert(body, ball);
+
+        let restitution = |world: &TestWorld| {
+            let pair = world.narrow_phase.contact_pair(ground, ball).unwrap();
+            pair.manifolds[0].data.solver_contacts[0].restitution
+        };
+
+        world.step();
+        assert_eq!(restitution(&world), 0.0);
+
+        // The flag is read each time the contacts are computed.
+        world.colliders[ground].set_absorbs_restitution(false);
+        world.step();
+        assert_eq!(restitution(&world), 1.0);
+    }
+
+    #[test]
+    fn contact_normal_points_from_collider1_to_collider2() {
+        for ground_first in [true, false] {
+            let mut world = TestWorld::new();
+
+            let ground = ColliderBuilder::ball(0.5).build();
+            let ball = ColliderBuilder::ball(0.5).build();
+            let ball_body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.9)
+                .build();
+            let ball_body = world.bodies.insert(ball_body);
+
+            let (ground, ball) = if ground_first {
+                let ground = world.colliders.insert(ground);
+                (
+                    ground,
+                    world
+                        .colliders
+                        .insert_with_parent(ball, ball_body, &mut world.bodies),
+                )
+            } else {
+                let ball = world
+                    .colliders
+                    .insert_with_parent(ball, ball_body, &mut world.bodies);
+                (world.colliders.insert(ground), ball)
+            };
+
+            world.gravity = Vector::zeros();
+            world.step();
+
+            let pair = world.narrow_phase.contact_pair(ground, ball).unwrap();
+            assert!(pair.has_any_active_contact);
+
+            let dir = world.colliders[pair.collider2].translation()
+                - world.colliders[pair.collider1].translation();
+            let sign = if pair.collider1 == ground { 1.0 } else { -1.0 };
+
+            for manifold in &pair.manifolds {
+                // The normal always points from `collider1` toward `collider2`.
+                assert!(manifold.data.normal.dot(&dir) > 0.0);
+                // Expressed from the ground toward the ball, the normal is always `+y`.
+                assert!((manifold.data.normal * sign - Vector::y()).norm() < 1.0e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn compound_parts_have_separate_contact_manifolds() {
+        use crate::math::Isometry;
+
+        let mut world = TestWorld::new();
+
+        let ground = world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+        let parts = vec![
+            (Isometry::from(-Vector::x()), SharedShape::ball(0.5)),
+            (Isometry::from(Vector::x()), SharedShape::ball(0.5)),
+        ];
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * 0.49)
+            .build();
+        let body = world.bodies.insert(body);
+        let compound = ColliderBuilder::compound(parts).build();
+        let compound = world
+            .colliders
+            .insert_with_parent(compound, body, &mut world.bodies);
+
+        world.gravity = Vector::zeros();
+        world.step();
+
+        let pair = world.narrow_phase.contact_pair(ground, compound).unwrap();
+        assert_eq!(pair.manifolds.len(), 2);
+
+        let sign = if pair.collider1 == ground { 1.0 } else { -1.0 };
+        let mut touching_parts = vec![];
+
+        for manifold in &pair.manifolds {
+            let part = if pair.collider1 == ground {
+                manifold.subshape2
+            } else {
+                manifold.subshape1
+            };
+            touching_parts.push(part);
+
+            assert!((manifold.data.normal * sign - Vector::y()).norm() < 1.0e-5);
+            assert_eq!(manifold.data.solver_contacts.len(), 1);
+            // Each manifold only contains the contact with its own part.
+            let expected_x = if part == 0 { -1.0 } else { 1.0 };
+            assert!((manifold.data.solver_contacts[0].point.x - expected_x).abs() < 1.0e-3);
+        }
+
+        touching_parts.sort();
+        assert_eq!(touching_parts, vec![0, 1]);
+    }
+
+    #[test]
+    fn shape_change_preserving_contacts_remaps_nearby_contacts() {
+        for (new_radius, expect_new_contact) in [(0.505, false), (1.0, true)] {
+            let mut world = TestWorld::new();
+
+            let ground = world
+                .colliders
+                .insert(ColliderBuilder::halfspace(Vector::y_axis()).build());
+            let ball_body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .can_sleep(false)
+                .build();
+            let ball_body = world.bodies.insert(ball_body);
+            let ball = ColliderBuilder::ball(0.5).build();
+            let ball = world
+                .colliders
+                .insert_with_parent(ball, ball_body, &mut world.bodies);
+
+            for _ in 0..60 {
+                world.step();
+            }
+
+            world.colliders[ball]
+                .set_shape_preserving_contacts(SharedShape::ball(new_radius), 0.01);
+            world.narrow_phase.compute_contacts(
+                world.params.prediction_distance,
+                0.0,
+                &world.bodies,
+                &world.colliders,
+                &world.impulse_joints,
+                &world.multibody_joints,
+                &[ball],
+                &(),
+                &(),
+            );
+
+            let pair = world.narrow_phase.contact_pair(ground, ball).unwrap();
+            let solver_contacts = &pair.manifolds[0].data.solver_contacts;
+            assert!(!solver_contacts.is_empty());
+            assert!(solver_contacts
+                .iter()
+                .all(|c| c.is_new == expect_new_contact));
+        }
+    }
+
+    #[test]
+    fn seeded_and_cleared_contact_pairs() {
+        let mut world = TestWorld::new();
+
+        let ground = world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()).build());
+        let ball_body = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * 0.5)
+            .build();
+        let ball_body = world.bodies.insert(ball_body);
+        let ball = ColliderBuilder::ball(0.5).build();
+        let ball = world
+            .colliders
+            .insert_with_parent(ball, ball_body, &mut world.bodies);
+
+        for _ in 0..60 {
+            world.step();
+        }
+
+        let saved_pair = world
+            .narrow_phase
+            .contact_pair(ground, ball)
+            .unwrap()
+            .clone();
+        // Clearing the pair regenerates its contacts even if no collider moved.
+        assert!(world.narrow_phase.clear_contact_pair(ball, ground));
+        assert!(world
+            .narrow_phase
+            .contact_pair(ground, ball)
+            .unwrap()
+            .manifolds
+            .is_empty());
+        world.narrow_phase.compute_contacts(
+            world.params.prediction_distance,
+            0.0,
+            &world.bodies,
+            &world.colliders,
+            &world.impulse_joints,
+            &world.multibody_joints,
+            &[],
+            &(),
+            &(),
+        );
+        let pair = world.narrow_phase.contact_pair(ground, ball).unwrap();
+        let solver_contacts = &pair.manifolds[0].data.solver_contacts;
+        assert!(!solver_contacts.is_empty());
+        assert!(solver_contacts.iter().all(|c| c.is_new));
+
+        // Seeded contacts aren't considered new, even for colliders that were just inserted.
+        let mut new_world = TestWorld::new();
+        let ground = new_world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()).build());
+        let ball_body = RigidBodyBuilder::dynamic().position(*world.bodies[ball_body].position());
+        let ball = ColliderBuilder::ball(0.5);
+        let (_, ball) = new_world.insert(ball_body, ball);
+        assert_eq!((saved_pair.collider1, saved_pair.collider2), (ground, ball));
+        assert!(new_world
+            .narrow_phase
+            .seed_contact_pair(&new_world.colliders, saved_pair));
+        new_world.narrow_phase.compute_contacts(
+            new_world.params.prediction_distance,
+            0.0,
+            &new_world.bodies,
+            &new_world.colliders,
+            &new_world.impulse_joints,
+            &new_world.multibody_joints,
+            &[ground, ball],
+            &(),
+            &(),
+        );
+        let pair = new_world.narrow_phase.contact_pair(ground, ball).unwrap();
+        let solver_contacts = &pair.manifolds[0].data.solver_contacts;
+        assert!(!solver_contacts.is_empty());
+        assert!(solver_contacts.iter().all(|c| !c.is_new));
+    }
+
+    #[test]
+    fn seeded_contact_pairs_unknown_to_the_broad_phase_are_dropped() {
+        use crate::geometry::ContactPair;
+        use crate::math::Real;
+
+        let mut world = TestWorld::new();
+
+        let mut insert_ball = |y: Real| {
+            let body = RigidBodyBuilder::fixed().translation(Vector::y() * y);
+            world.insert(body, ColliderBuilder::ball(0.5)).1
+        };
+        let near1 = insert_ball(0.0);
+        let near2 = insert_ball(0.9);
+        let far = insert_ball(10.0);
+
+        assert!(world
+            .narrow_phase
+            .seed_contact_pair(&world.colliders, ContactPair::new(near1, near2)));
+        assert!(world
+            .narrow_phase
+            .seed_contact_pair(&world.colliders, ContactPair::new(near1, far)));
+        assert_eq!(world.narrow_phase.contact_pairs().count(), 2);
+
+        world.step();
+
+        assert!(world.narrow_phase.contact_pair(near1, near2).is_some());
+        assert!(world.narrow_phase.contact_pair(near1, far).is_none());
+        assert_eq!(world.narrow_phase.contact_pairs().count(), 1);
+    }
+
+    #[test]
+    fn contact_pair_changes_are_reported_once() {
+        use crate::geometry::{ContactPairChange, ContactPairChangeKind};
+
+        let mut world = TestWorld::new();
+
+        world.narrow_phase.enable_contact_pair_change_tracking(true);
+        let ground = world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()).build());
+        let body = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        let ball =
+            world
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut world.bodies);
+
+        let mut changes = vec![];
+        for i in 0..4 {
+            if i == 3 {
+                world
+                    .colliders
+                    .remove(ball, &mut world.islands, &mut world.bodies, true);
+            }
+
+            world.step();
+            changes.push(world.narrow_phase.contact_pair_changes().to_vec());
+        }
+
+        let pair = world.narrow_phase.contact_pairs().next();
+        assert!(pair.is_none());
+
+        let change = |kind| {
+            vec![ContactPairChange {
+                collider1: ground,
+                collider2: ball,
+                kind,
+            }]
+        };
+        assert_eq!(changes[0], change(ContactPairChangeKind::Started));
+        assert!(changes[1].is_empty());
+        assert!(changes[2].is_empty());
+        assert_eq!(changes[3], change(ContactPairChangeKind::Stopped));
+    }
+
+    #[test]
+    fn contact_pair_changes_are_only_tracked_when_enabled() {
+        use crate::geometry::{ContactPairChange, ContactPairChangeKind};
+
+        let mut world = TestWorld::new();
+
+        let ground = world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()).build());
+        let body = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        let ball =
+            world
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut world.bodies);
+        assert!(!world.narrow_phase.is_contact_pair_change_tracking_enabled());
+
+        let mut changes = vec![];
+        for i in 0..2 {
+            if i == 1 {
+                // Lift the ball so its contacts stop.
+                world.narrow_phase.enable_contact_pair_change_tracking(true);
+                world.bodies[body].set_translation(Vector::y() * 5.0, true);
+            }
+
+            world.step();
+            changes.push(world.narrow_phase.contact_pair_changes().to_vec());
+        }
+
+        assert!(changes[0].is_empty());
+        assert_eq!(
+            changes[1],
+            vec![ContactPairChange {
+                collider1: ground,
+                collider2: ball,
+                kind: ContactPairChangeKind::Stopped,
+            }]
+        );
+
+        world
+            .narrow_phase
+            .enable_contact_pair_change_tracking(false);
+        assert!(world.narrow_phase.contact_pair_changes().is_empty());
+    }
+
+    #[test]
+    fn intersection_pairs_include_watched_pairs() {
+        use crate::geometry::ActiveCollisionTypes;
+
+        let mut world = TestWorld::new();
+
+        let sensor = ColliderBuilder::ball(0.5)
+            .sensor(true)
+            .active_collision_types(ActiveCollisionTypes::all());
+        let sensor = world.colliders.insert(sensor);
+        // Overlapping Aabbs, but the balls don't touch.
+        let watched = world
+            .colliders
+            .insert(ColliderBuilder::ball(0.5).translation(Vector::x() * 0.9 + Vector::y() * 0.9));
+        let intersecting = world
+            .colliders
+            .insert(ColliderBuilder::ball(0.5).translation(-Vector::x() * 0.9));
+        let _far = world
+            .colliders
+            .insert(ColliderBuilder::ball(0.5).translation(Vector::x() * 10.0));
+
+        world.gravity = Vector::zeros();
+        world.step();
+
+        let mut pairs: Vec<_> = world
+            .narrow_phase
+            .intersection_pairs()
+            .map(|(h1, h2, intersecting)| {
+                assert!(h1 == sensor || h2 == sensor);
+                (if h1 == sensor { h2 } else { h1 }, intersecting)
+            })
+            .collect();
+        pairs.sort_by_key(|(h, _)| h.into_raw_parts());
+        assert_eq!(pairs, [(watched, false), (intersecting, true)]);
+    }
+
+    #[test]
+    fn contacts_changed_flags_are_cleared_at_each_step() {
+        let mut world = TestWorld::new();
+
+        let ground = world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()).build());
+        let ball_body = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        let ball = world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5),
+            ball_body,
+            &mut world.bodies,
+        );
+        let body = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 10.0));
+        let falling =
+            world
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut world.bodies);
+
+        let mut flags = vec![];
+        for i in 0..5 {
+            match i {
+                1 => {
+                    // Lift the ball so its contacts stop.
+                    world.narrow_phase.enable_contact_pair_change_tracking(true);
+                    world.bodies[ball_body].set_translation(Vector::y() * 5.0, true);
+                }
+                3 => world.bodies[ball_body].set_translation(Vector::y() * 0.5, true),
+                4 => {
+                    world
+                        .colliders
+                        .remove(ball, &mut world.islands, &mut world.bodies, true);
+                }
+                _ => {}
+            }
+
+            world.step();
+            flags.push([ground, ball, falling].map(|co| world.narrow_phase.contacts_changed(co)));
+        }
+
+        // Nothing is flagged without tracking. Then the contacts stopped, remained stopped,
+        // started again, and stopped because of the removal.
+        assert_eq!(flags[0], [false, false, false]);
+        assert_eq!(flags[1], [true, true, false]);
+        assert_eq!(flags[2], [false, false, false]);
+        assert_eq!(flags[3], [true, true, false]);
+        assert_eq!(flags[4], [true, false, false]);
+    }
+
+    #[test]
+    #[cfg(feature = "dim3")]
+    fn capsule_on_heightfield_vertex_stays_still() {
+        let mut world = TestWorld::new();
+
+        // The capsule rests right on top of a vertex shared by several triangles.
+        let heights = na::DMatrix::zeros(11, 11);
+        let ground = ColliderBuilder::heightfield(heights, Vector::new(10.0, 1.0, 10.0));
+        world.colliders.insert(ground.build());
+        let capsule_body = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * 0.8)
+            .can_sleep(false)
+            .build();
+        let capsule_body = world.bodies.insert(capsule_body);
+        let capsule = ColliderBuilder::capsule_y(0.5, 0.3).build();
+        world
+            .colliders
+            .insert_with_parent(capsule, capsule_body, &mut world.bodies);
+
+        let mut rest_position = None;
+
+        for i in 0..400 {
+            world.step();
+
+            if i == 100 {
+                rest_position = Some(*world.bodies[capsule_body].position());
+            }
+        }
+
+        let rest_position = rest_position.unwrap();
+        let position = world.bodies[capsule_body].position();
+        assert_eq!(position.translation, rest_position.translation);
+        assert_eq!(position.rotation, rest_position.rotation);
+    }
+
+    #[test]
+    fn colliders_with_a_dangling_parent_are_skipped() {
+        let mut world = TestWorld::new();
+
+        let ground = world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+        let sensor = world
+            .colliders
+            .insert(ColliderBuilder::ball(1.0).sensor(true));
+        let body = RigidBodyBuilder::dynamic().translation(Vector::y() * 0.4);
+        let (body, ball) = world.insert(body, ColliderBuilder::ball(0.5));
+        world.step();
+        assert!(world.narrow_phase.contact_pair(ground, ball).is_some());
+        assert_eq!(
+            world.narrow_phase.intersection_pair(sensor, ball),
+            Some(true)
+        );
+
+        // Remove the rigid-body without detaching its collider, as if the collider set used
+        // for the removal was stale.
+        world.bodies.remove(
+            body,
+            &mut world.islands,
+            &mut ColliderSet::new(),
+            &mut world.impulse_joints,
+            &mut world.multibody_joints,
+            true,
+        );
+        assert!(world.colliders[ball].parent().is_some());
+        world.step();
+
+        // Force the pairs involving the orphan collider to be updated.
+        world.colliders[ball].set_translation(Vector::y() * 0.3);
+        world.step();
+
+        let pair = world.narrow_phase.contact_pair(ground, ball).unwrap();
+        assert!(!pair.has_any_active_contact);
+        assert!(pair.manifolds.is_empty());
+        assert_eq!(
+            world.narrow_phase.intersection_pair(sensor, ball),
+            Some(false)
+        );
+    }
+}
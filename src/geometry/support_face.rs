@@ -0,0 +1,363 @@
+//! Computation of the feature (face, edge, or vertex) supporting a shape in a given direction.
+
+#[cfg(feature = "dim3")]
+use crate::geometry::Cylinder;
+use crate::geometry::{Cuboid, Shape};
+use crate::math::{Point, Real, Vector, DIM};
+use na::ComplexField;
+#[cfg(feature = "dim3")]
+use na::RealField;
+use na::Unit;
+
+/// Faces making an angle with the direction smaller than the angle made by the best-aligned
+/// face plus this tolerance are considered as aligned as the best face (about one degree).
+const ANGULAR_TOLERANCE: Real = 0.0175;
+/// The number of vertices of the polygon approximating the flat caps of cylinders.
+#[cfg(feature = "dim3")]
+const CYLINDER_CAP_SUBDIVISIONS: u32 = 16;
+
+/// The feature of a shape supporting it in a given direction.
+///
+/// All the points are expressed in the local-space of the shape.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SupportFeature {
+    /// A single vertex.
+    Vertex(Point<Real>),
+    /// An edge, given by its two endpoints.
+    Edge([Point<Real>; 2]),
+    /// A polygonal face, given by its vertices in counter-clockwise order when looking at it
+    /// from the outside of the shape.
+    #[cfg(feature = "dim3")]
+    Face(Vec<Point<Real>>),
+}
+
+impl SupportFeature {
+    /// The vertices of this feature.
+    pub fn vertices(&self) -> &[Point<Real>] {
+        match self {
+            SupportFeature::Vertex(pt) => std::slice::from_ref(pt),
+            SupportFeature::Edge(pts) => pts,
+            #[cfg(feature = "dim3")]
+            SupportFeature::Face(pts) => pts,
+        }
+    }
+}
+
+/// Computes the feature of `shape` most aligned with the given local-space `direction`.
+///
+/// This is the face with the normal most aligned with `direction`. If `direction` is equally
+/// aligned (up to a one-degree tolerance) with the normals of several adjacent faces, their
+/// shared edge or vertex is returned instead. For example, the support feature of a cuboid
+/// toward one of its corners is that corner.
+///
+/// Cuboids, convex polyhedra, convex polygons, and cylinders (with their caps approximated by
+/// regular polygons) are supported. For any other convex shape, this returns its support point.
+/// Returns `None` if `shape` isn’t convex, or if `direction` is zero.
+pub fn support_face(shape: &dyn Shape, direction: &Vector<Real>) -> Option<SupportFeature> {
+    let dir = Unit::try_new(*direction, Real::EPSILON)?;
+
+    if let Some(cuboid) = shape.as_cuboid() {
+        let (points, faces) = cuboid_faces(cuboid);
+        return Some(polyhedral_support_feature(&points, &faces, &dir));
+    }
+
+    #[cfg(feature = "dim2")]
+    if let Some(polygon) = shape.as_convex_polygon() {
+        let num_points = polygon.points().len() as u32;
+        let faces: Vec<_> = polygon
+            .normals()
+            .iter()
+            .zip(0..num_points)
+            .map(|(normal, i)| (*normal, vec![i, (i + 1) % num_points]))
+            .collect();
+        return Some(polyhedral_support_feature(polygon.points(), &faces, &dir));
+    }
+
+    #[cfg(feature = "dim3")]
+    if let Some(polyhedron) = shape.as_convex_polyhedron() {
+        let faces: Vec<_> = polyhedron
+            .faces()
+            .iter()
+            .map(|face| {
+                let first = face.first_vertex_or_edge as usize;
+                let last = first + face.num_vertices_or_edges as usize;
+                (
+                    face.normal,
+                    polyhedron.vertices_adj_to_face()[first..last].to_vec(),
+                )
+            })
+            .collect();
+        return Some(polyhedral_support_feature(
+            polyhedron.points(),
+            &faces,
+            &dir,
+        ));
+    }
+
+    #[cfg(feature = "dim3")]
+    if let Some(cylinder) = shape.as_cylinder() {
+        return Some(cylinder_support_feature(cylinder, &dir));
+    }
+
+    shape
+        .as_support_map()
+        .map(|support_map| SupportFeature::Vertex(support_map.local_support_point_toward(&dir)))
+}
+
+/// The vertices of a cuboid, and its faces given as their normal and the indices of their
+/// vertices in counter-clockwise order.
+fn cuboid_faces(cuboid: &Cuboid) -> (Vec<Point<Real>>, Vec<(Unit<Vector<Real>>, Vec<u32>)>) {
+    // The i-th bit of a vertex index is set if its i-th coordinate is positive.
+    let points = (0..1u32 << DIM)
+        .map(|id| {
+            Point::from(Vector::from_fn(|i, _| {
+                if id & (1 << i) != 0 {
+                    cuboid.half_extents[i]
+                } else {
+                    -cuboid.half_extents[i]
+                }
+            }))
+        })
+        .collect();
+    let mut faces = Vec::with_capacity(2 * DIM);
+
+    for i in 0..DIM {
+        for positive in [false, true] {
+            #[cfg(feature = "dim2")]
+            let corners = [0, 1 << ((i + 1) % 2)];
+            #[cfg(feature = "dim3")]
+            let corners = {
+                let (j, k) = ((i + 1) % 3, (i + 2) % 3);
+                [0, 1 << j, (1 << j) | (1 << k), 1 << k]
+            };
+
+            let mut ids: Vec<u32> = corners
+                .iter()
+                .map(|corner| corner | ((positive as u32) << i))
+                .collect();
+            let mut normal = Vector::zeros();
+
+            if positive {
+                normal[i] = 1.0;
+            } else {
+                normal[i] = -1.0;
+                ids.reverse();
+            }
+
+            faces.push((Unit::new_unchecked(normal), ids));
+        }
+    }
+
+    (points, faces)
+}
+
+fn polyhedral_support_feature(
+    points: &[Point<Real>],
+    faces: &[(Unit<Vector<Real>>, Vec<u32>)],
+    dir: &Unit<Vector<Real>>,
+) -> SupportFeature {
+    let angle = |normal: &Unit<Vector<Real>>| ComplexField::acos(normal.dot(dir).clamp(-1.0, 1.0));
+    let best_angle = faces
+        .iter()
+        .map(|(normal, _)| angle(normal))
+        .fold(Real::MAX, Real::min);
+    let mut aligned_faces = faces
+        .iter()
+        .filter(|(normal, _)| angle(normal) <= best_angle + ANGULAR_TOLERANCE);
+
+    // The feature is made of the vertices shared by all the aligned faces.
+    let mut common = aligned_faces
+        .next()
+        .map(|(_, ids)| ids.clone())
+        .unwrap_or_default();
+
+    for (_, ids) in aligned_faces {
+        common.retain(|id| ids.contains(id));
+    }
+
+    match common.as_slice() {
+        [] => {
+            // The aligned faces don’t share any vertex. Fallback to the support point.
+            let support_pt = points
+                .iter()
+                .max_by(|a, b| a.coords.dot(dir).total_cmp(&b.coords.dot(dir)))
+                .copied()
+                .unwrap_or_else(Point::origin);
+            SupportFeature::Vertex(support_pt)
+        }
+        [id] => SupportFeature::Vertex(points[*id as usize]),
+        [id1, id2] => SupportFeature::Edge([points[*id1 as usize], points[*id2 as usize]]),
+        #[cfg(feature = "dim3")]
+        _ => SupportFeature::Face(common.iter().map(|id| points[*id as usize]).collect()),
+        #[cfg(feature = "dim2")]
+        _ => unreachable!("the faces of 2D shapes only have two vertices"),
+    }
+}
+
+#[cfg(feature = "dim3")]
+fn cylinder_support_feature(cylinder: &Cylinder, dir: &Unit<Vector<Real>>) -> SupportFeature {
+    let radial = Vector::new(dir.x, 0.0, dir.z)
+        .try_normalize(Real::EPSILON)
+        .unwrap_or_else(Vector::x)
+        * cylinder.radius;
+    let y = cylinder.half_height.copysign(dir.y);
+    let cap_angle = ComplexField::acos(dir.y.abs().min(1.0));
+    let side_angle = Real::frac_pi_2() - cap_angle;
+
+    if (cap_angle - side_angle).abs() <= ANGULAR_TOLERANCE {
+        // The direction points toward the rim of a cap.
+        SupportFeature::Vertex(Point::new(radial.x, y, radial.z))
+    } else if side_angle < cap_angle {
+        SupportFeature::Edge([
+            Point::new(radial.x, -cylinder.half_height, radial.z),
+            Point::new(radial.x, cylinder.half_height, radial.z),
+        ])
+    } else {
+        // Turn clockwise around `y` for the top cap so its vertices are counter-clockwise when
+        // seen from the outside.
+        let turn = -dir.y.signum();
+        let start = RealField::atan2(radial.z, radial.x);
+        let vertices = (0..CYLINDER_CAP_SUBDIVISIONS)
+            .map(|i| {
+                let angle =
+                    start + turn * Real::two_pi() * i as Real / CYLINDER_CAP_SUBDIVISIONS as Real;
+                Point::new(
+                    ComplexField::cos(angle) * cylinder.radius,
+                    y,
+                    ComplexField::sin(angle) * cylinder.radius,
+                )
+            })
+            .collect();
+        SupportFeature::Face(vertices)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{support_face, SupportFeature};
+    use crate::geometry::{Ball, Cuboid, SharedShape};
+    #[cfg(feature = "dim3")]
+    use crate::math::Real;
+    use crate::math::{Isometry, Point, Vector};
+
+    #[cfg(feature = "dim3")]
+    fn assert_ccw_face(vertices: &[Point<Real>], normal: &Vector<Real>) {
+        for i in 0..vertices.len() {
+            let (a, b, c) = (
+                vertices[i],
+                vertices[(i + 1) % vertices.len()],
+                vertices[(i + 2) % vertices.len()],
+            );
+            assert!((b - a).cross(&(c - b)).dot(normal) > 0.0);
+        }
+    }
+
+    #[test]
+    fn cuboid_support_features() {
+        let cuboid = Cuboid::new(Vector::repeat(1.0));
+
+        // Toward a corner.
+        let corner = support_face(&cuboid, &Vector::repeat(1.0)).unwrap();
+        assert_eq!(
+            corner,
+            SupportFeature::Vertex(Point::from(Vector::repeat(1.0)))
+        );
+
+        // Toward a face (an edge in 2D), slightly tilted but within the angular tolerance.
+        let dir = Vector::x() + Vector::y() * 0.005;
+        let face = support_face(&cuboid, &dir).unwrap();
+        #[cfg(feature = "dim2")]
+        assert!(matches!(face, SupportFeature::Edge(_)));
+        #[cfg(feature = "dim3")]
+        {
+            assert_eq!(face.vertices().len(), 4);
+            assert_ccw_face(face.vertices(), &Vector::x());
+        }
+        assert!(face.vertices().iter().all(|pt| pt.x == 1.0));
+
+        // Toward an edge, equally aligned with two faces.
+        #[cfg(feature = "dim3")]
+        {
+            let edge = support_face(&cuboid, &Vector::new(1.0, 1.0, 0.0)).unwrap();
+            assert!(matches!(edge, SupportFeature::Edge(_)));
+            assert!(edge.vertices().iter().all(|pt| pt.x == 1.0 && pt.y == 1.0));
+        }
+    }
+
+    #[test]
+    fn convex_polyhedron_support_features() {
+        let points: Vec<_> = (0..1 << crate::math::DIM)
+            .map(|id: u32| {
+                Point::from(Vector::from_fn(
+                    |i, _| {
+                        if id & (1 << i) != 0 {
+                            1.0
+                        } else {
+                            -1.0
+                        }
+                    },
+                ))
+            })
+            .collect();
+        let hull = SharedShape::convex_hull(&points).unwrap();
+
+        let face = support_face(&*hull, &-Vector::y()).unwrap();
+        assert!(face.vertices().iter().all(|pt| pt.y == -1.0));
+        #[cfg(feature = "dim2")]
+        assert!(matches!(face, SupportFeature::Edge(_)));
+        #[cfg(feature = "dim3")]
+        {
+            assert_eq!(face.vertices().len(), 4);
+            assert_ccw_face(face.vertices(), &-Vector::y());
+        }
+
+        let corner = support_face(&*hull, &-Vector::repeat(1.0)).unwrap();
+        assert_eq!(
+            corner,
+            SupportFeature::Vertex(Point::from(-Vector::repeat(1.0)))
+        );
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn cylinder_support_features() {
+        use crate::geometry::Cylinder;
+
+        let cylinder = Cylinder::new(1.0, 0.5);
+
+        let cap = support_face(&cylinder, &Vector::y()).unwrap();
+        assert_eq!(
+            cap.vertices().len(),
+            super::CYLINDER_CAP_SUBDIVISIONS as usize
+        );
+        assert!(cap.vertices().iter().all(|pt| pt.y == 1.0));
+        assert_ccw_face(cap.vertices(), &Vector::y());
+
+        let bottom = support_face(&cylinder, &-Vector::y()).unwrap();
+        assert_ccw_face(bottom.vertices(), &-Vector::y());
+
+        let side = support_face(&cylinder, &Vector::z()).unwrap();
+        assert_eq!(
+            side,
+            SupportFeature::Edge([Point::new(0.0, -1.0, 0.5), Point::new(0.0, 1.0, 0.5)])
+        );
+
+        let rim = support_face(&cylinder, &Vector::new(1.0, 1.0, 0.0)).unwrap();
+        assert_eq!(rim, SupportFeature::Vertex(Point::new(0.5, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn other_shapes_support_features() {
+        // Other convex shapes fallback to their support point.
+        let ball = Ball::new(2.0);
+        assert_eq!(
+            support_face(&ball, &Vector::x()),
+            Some(SupportFeature::Vertex(Point::from(Vector::x() * 2.0)))
+        );
+        assert_eq!(support_face(&ball, &Vector::zeros()), None);
+
+        // Non-convex shapes don’t have any support feature.
+        let compound = SharedShape::compound(vec![(Isometry::identity(), SharedShape::ball(1.0))]);
+        assert_eq!(support_face(&*compound, &Vector::x()), None);
+    }
+}
@@ -40,6 +40,17 @@ impl CollisionPipeline {
         }
     }
 
+    /// Initializes a new collision pipeline, with room for `num_contact_pairs` contact pairs in
+    /// its internal buffers.
+    ///
+    /// These buffers grow as usual (without panicking) if there are more contact pairs.
+    pub fn with_capacity(num_contact_pairs: usize) -> CollisionPipeline {
+        CollisionPipeline {
+            broadphase_collider_pairs: Vec::with_capacity(num_contact_pairs),
+            broad_phase_events: Vec::with_capacity(num_contact_pairs),
+        }
+    }
+
     fn detect_collisions(
         &mut self,
         prediction_distance: Real,
@@ -58,8 +69,10 @@ impl CollisionPipeline {
         self.broadphase_collider_pairs.clear();
 
         broad_phase.update(
+            0.0,
             prediction_distance,
             colliders,
+            bodies,
             modified_colliders,
             removed_colliders,
             &mut self.broad_phase_events,
@@ -67,7 +80,7 @@ impl CollisionPipeline {
 
         // Update narrow-phase.
         if handle_user_changes {
-            narrow_phase.handle_user_changes(
+            narrow_phase.handle_user_changes_with_pending_events(
                 None,
                 modified_colliders,
                 removed_colliders,
@@ -80,6 +93,7 @@ impl CollisionPipeline {
         narrow_phase.register_pairs(None, colliders, bodies, &self.broad_phase_events, events);
         narrow_phase.compute_contacts(
             prediction_distance,
+            0.0,
             bodies,
             colliders,
             &ImpulseJointSet::new(),
@@ -115,6 +129,9 @@ impl CollisionPipeline {
         hooks: &dyn PhysicsHooks,
         events: &dyn EventHandler,
     ) {
+        narrow_phase.clear_contact_pair_changes();
+        broad_phase.clear_collider_changes();
+
         let modified_bodies = bodies.take_modified();
         let mut modified_colliders = colliders.take_modified();
         let mut removed_colliders = colliders.take_removed();
@@ -154,6 +171,7 @@ impl CollisionPipeline {
             events,
             true,
         );
+        narrow_phase.flush_collision_events(bodies, colliders, events);
 
         if let Some(queries) = query_pipeline {
             queries.update_incremental(colliders, &modified_colliders, &removed_colliders, true);
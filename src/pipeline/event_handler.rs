@@ -1,5 +1,7 @@
 use crate::dynamics::RigidBodySet;
-use crate::geometry::{ColliderSet, CollisionEvent, ContactForceEvent, ContactPair};
+use crate::geometry::{
+    ColliderSet, CollisionEvent, ContactForceEvent, ContactImpulseClampEvent, ContactPair,
+};
 use crate::math::Real;
 use crossbeam::channel::Sender;
 
@@ -9,6 +11,10 @@ bitflags::bitflags! {
     pub struct ActiveEvents: u32 {
         /// If set, Rapier will call `EventHandler::handle_collision_event`
         /// whenever relevant for this collider.
+        ///
+        /// This works for sensors as well as for solid colliders, in which case the events
+        /// are emitted whenever the contacts of this collider with another one start or stop
+        /// being active, without affecting these contacts.
         const COLLISION_EVENTS = 0b0001;
         /// If set, Rapier will call `EventHandler::handle_contact_force_event`
         /// whenever relevant for this collider.
@@ -25,6 +31,25 @@ impl Default for ActiveEvents {
 /// Trait implemented by structures responsible for handling events generated by the physics engine.
 ///
 /// Implementors of this trait will typically collect these events for future processing.
+///
+/// # Event ordering
+/// The events of each kind generated during a single step are delivered sorted by the handles of
+/// the colliders involved: by the smallest handle of each collider pair first, then by the
+/// largest one. This order is deterministic: it doesn't depend on the order the pairs are
+/// processed in internally, including when the `parallel` feature is enabled. Contact force and
+/// contact impulse clamp events are delivered after the constraints resolution, while collision
+/// events are delivered at the end of the step.
+///
+/// # Borrowed data
+/// The contact pairs (including their contact manifolds), rigid-bodies and colliders are passed
+/// by reference, borrowed from the physics pipeline only for the duration of each call. Reading
+/// them from the handler doesn't copy them, and the borrow checker prevents the handler from
+/// keeping these references after the call returns: copy the relevant data instead (e.g. with
+/// [`ContactForceEvent::from_contact_pair`]). Note that the pipeline still needs its own
+/// buffers to gather the events before delivering them: the collision events of each pair are
+/// coalesced in a map, which may allocate when new pairs start or stop touching. Because handlers are `Sync`, the same
+/// handler may be called from several threads when the `parallel` feature is enabled, so any state
+/// it updates must rely on atomics or locks.
 pub trait EventHandler: Send + Sync {
     /// Handle a collision event.
     ///
@@ -38,7 +63,11 @@ pub trait EventHandler: Send + Sync {
     /// * `colliders` - The set of colliders.
     /// * `contact_pair` - The current state of contacts between the two colliders. This is set ot `None`
     ///                    if at least one of the collider is a sensor (in which case no contact information
-    ///                    is ever computed).
+    ///                    is ever computed), or if the contact pair of a `CollisionEvent::Started` event
+    ///                    no longer exists (e.g. because one of the colliders was removed during the same
+    ///                    step).
+    ///
+    /// The rigid-bodies the colliders are attached to can be retrieved with [`ColliderSet::parent`].
     fn handle_collision_event(
         &self,
         bodies: &RigidBodySet,
@@ -58,6 +87,9 @@ pub trait EventHandler: Send + Sync {
     /// forces `{0.0, 1.0, 0.0}` and `{0.0, -1.0, 0.0}`, then the total force magnitude tested
     /// against the `contact_force_event_threshold` is `2.0` even if the sum of these forces is actually the
     /// zero vector.
+    ///
+    /// No event is generated if the total impulse is smaller than the
+    /// `Collider::contact_event_impulse_threshold` of any of the colliders.
     fn handle_contact_force_event(
         &self,
         dt: Real,
@@ -66,6 +98,23 @@ pub trait EventHandler: Send + Sync {
         contact_pair: &ContactPair,
         total_force_magnitude: Real,
     );
+
+    /// Handle a contact impulse clamp event.
+    ///
+    /// This event is generated, after the constraints resolution, whenever a contact point
+    /// involving a collider with a finite `Collider::max_contact_impulse` would have needed a
+    /// larger impulse than this limit. It is generated at most once per collider and per contact
+    /// pair at each timestep.
+    ///
+    /// The default implementation does nothing.
+    fn handle_contact_impulse_clamp_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        _event: ContactImpulseClampEvent,
+        _contact_pair: &ContactPair,
+    ) {
+    }
 }
 
 impl EventHandler for () {
@@ -131,3 +180,163 @@ impl EventHandler for ChannelEventCollector {
         let _ = self.contact_force_event_sender.send(result);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::{ColliderBuilder, ColliderSet};
+    use crate::math::Vector;
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn event_handlers_read_the_contact_manifolds_by_reference() {
+        use crate::dynamics::RigidBodySet;
+        use crate::geometry::{CollisionEvent, ContactPair};
+        use crate::math::Real;
+        use crate::pipeline::{ActiveEvents, EventHandler};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Counts the contact points without copying anything out of the contact pairs.
+        #[derive(Default)]
+        struct ContactPointCounter(AtomicUsize);
+
+        impl EventHandler for ContactPointCounter {
+            fn handle_collision_event(
+                &self,
+                _bodies: &RigidBodySet,
+                _colliders: &ColliderSet,
+                _event: CollisionEvent,
+                _contact_pair: Option<&ContactPair>,
+            ) {
+            }
+
+            fn handle_contact_force_event(
+                &self,
+                _dt: Real,
+                _bodies: &RigidBodySet,
+                _colliders: &ColliderSet,
+                contact_pair: &ContactPair,
+                _total_force_magnitude: Real,
+            ) {
+                let num_points = contact_pair
+                    .manifolds
+                    .iter()
+                    .map(|manifold| manifold.points.len())
+                    .sum();
+                self.0.fetch_add(num_points, Ordering::Relaxed);
+            }
+        }
+
+        let mut world = TestWorld::new();
+        let counter = ContactPointCounter::default();
+
+        world
+            .colliders
+            .insert(ColliderBuilder::ball(10.0).translation(Vector::y() * -10.0));
+        let body = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.49));
+        world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).active_events(ActiveEvents::CONTACT_FORCE_EVENTS),
+            body,
+            &mut world.bodies,
+        );
+
+        world.step_with(&(), &counter);
+
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn solid_colliders_emit_collision_events() {
+        use crate::geometry::{ColliderHandle, CollisionEvent, CollisionEventFlags};
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+        let mut world = TestWorld::new();
+        let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, _) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        // A solid door with a larger sensor detecting the world.colliders approaching it.
+        let door = world
+            .bodies
+            .insert(RigidBodyBuilder::fixed().translation(Vector::x() * 3.0));
+        let door_solid = world.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).active_events(ActiveEvents::COLLISION_EVENTS),
+            door,
+            &mut world.bodies,
+        );
+        let door_sensor = world.colliders.insert_with_parent(
+            ColliderBuilder::ball(1.5)
+                .sensor(true)
+                .active_events(ActiveEvents::COLLISION_EVENTS),
+            door,
+            &mut world.bodies,
+        );
+        let ball = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().linvel(Vector::x() * 2.0));
+        let ball_collider =
+            world
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), ball, &mut world.bodies);
+
+        let mut received = vec![];
+        world.gravity = Vector::zeros();
+        for _ in 0..120 {
+            world.step_with(&(), &events);
+            received.extend(collision_recv.try_iter());
+        }
+
+        let started = |collider: ColliderHandle, flags| {
+            received.iter().any(|event| {
+                matches!(event, CollisionEvent::Started(h1, h2, f)
+                    if *f == flags
+                        && [*h1, *h2].contains(&collider)
+                        && [*h1, *h2].contains(&ball_collider))
+            })
+        };
+        assert!(started(door_sensor, CollisionEventFlags::SENSOR));
+        assert!(started(door_solid, CollisionEventFlags::empty()));
+        // The door still blocks the ball.
+        assert!(world.bodies[ball].translation().x < 2.01);
+    }
+
+    #[test]
+    fn collision_events_resolve_the_parents_of_their_colliders() {
+        use crate::geometry::CollisionEvent;
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+        let mut world = TestWorld::new();
+        let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, _) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        let ground = world
+            .colliders
+            .insert(ColliderBuilder::ball(10.0).translation(Vector::y() * -10.0));
+        let body = RigidBodyBuilder::dynamic().translation(Vector::y() * 0.49);
+        let collider = ColliderBuilder::ball(0.5).active_events(ActiveEvents::COLLISION_EVENTS);
+        let (body, collider) = world.insert(body, collider);
+
+        world.step_with(&(), &events);
+        match collision_recv.try_recv().unwrap() {
+            CollisionEvent::Started(h1, h2, _) => {
+                let parents = [world.colliders.parent(h1), world.colliders.parent(h2)];
+                assert!(parents.contains(&None));
+                assert!(parents.contains(&Some(body)));
+            }
+            CollisionEvent::Stopped(..) => panic!("the colliders should start touching"),
+        }
+
+        // The colliders of a stop event may not exist anymore.
+        world.remove(body);
+        world.step_with(&(), &events);
+        assert!(matches!(
+            collision_recv.try_recv().unwrap(),
+            CollisionEvent::Stopped(..)
+        ));
+        assert_eq!(world.colliders.parent(collider), None);
+        assert_eq!(world.colliders.parent(ground), None);
+    }
+}
@@ -3,8 +3,12 @@
 pub use collision_pipeline::CollisionPipeline;
 pub use event_handler::{ActiveEvents, ChannelEventCollector, EventHandler};
 pub use physics_hooks::{ActiveHooks, ContactModificationContext, PairFilterContext, PhysicsHooks};
-pub use physics_pipeline::PhysicsPipeline;
-pub use query_pipeline::{QueryFilter, QueryFilterFlags, QueryPipeline, QueryPipelineMode};
+pub use physics_pipeline::{PhysicsPipeline, StepReport};
+pub use query_pipeline::{
+    LocalRayHit, QueryFilter, QueryFilterFlags, QueryPipeline, QueryPipelineMode,
+};
+
+pub(crate) use physics_hooks::update_oneway_platform_contacts;
 
 #[cfg(feature = "debug-render")]
 pub use self::debug_render_pipeline::{
@@ -19,5 +23,8 @@ mod physics_pipeline;
 mod query_pipeline;
 mod user_changes;
 
+#[cfg(test)]
+pub(crate) mod test_world;
+
 #[cfg(feature = "debug-render")]
 mod debug_render_pipeline;
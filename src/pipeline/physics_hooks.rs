@@ -56,62 +56,86 @@ impl<'a> ContactModificationContext<'a> {
     /// `PhysicsHooks::modify_solver_contacts` method at each timestep, for each
     /// contact manifold involving a one-way platform. The `self.user_data` field
     /// must not be modified from the outside of this method.
+    ///
+    /// See also `ColliderBuilder::one_way_platform` for a built-in alternative that
+    /// doesn’t require any physics hook.
     pub fn update_as_oneway_platform(
         &mut self,
         allowed_local_n1: &Vector<Real>,
         allowed_angle: Real,
     ) {
-        const CONTACT_CONFIGURATION_UNKNOWN: u32 = 0;
-        const CONTACT_CURRENTLY_ALLOWED: u32 = 1;
-        const CONTACT_CURRENTLY_FORBIDDEN: u32 = 2;
-
-        let cang = ComplexField::cos(allowed_angle);
-
-        // Test the allowed normal with the local-space contact normal that
-        // points towards the exterior of context.collider1.
-        let contact_is_ok = self.manifold.local_n1.dot(&allowed_local_n1) >= cang;
-
-        match *self.user_data {
-            CONTACT_CONFIGURATION_UNKNOWN => {
-                if contact_is_ok {
-                    // The contact is close enough to the allowed normal.
-                    *self.user_data = CONTACT_CURRENTLY_ALLOWED;
-                } else {
-                    // The contact normal isn't close enough to the allowed
-                    // normal, so remove all the contacts and mark further contacts
-                    // as forbidden.
-                    self.solver_contacts.clear();
-
-                    // NOTE: in some very rare cases `local_n1` will be
-                    // zero if the objects are exactly touching at one point.
-                    // So in this case we can't really conclude.
-                    // If the norm is non-zero, then we can tell we need to forbid
-                    // further contacts. Otherwise we have to wait for the next frame.
-                    if self.manifold.local_n1.norm_squared() > 0.1 {
-                        *self.user_data = CONTACT_CURRENTLY_FORBIDDEN;
-                    }
+        update_oneway_platform_contacts(
+            &self.manifold.local_n1,
+            allowed_local_n1,
+            allowed_angle,
+            self.solver_contacts,
+            self.user_data,
+        )
+    }
+}
+
+/// Removes the contacts that should be ignored by a one-way platform.
+///
+/// The `local_n` normal is the local-space contact normal pointing toward the
+/// exterior of the platform. The `state` is the persistent state of the one-way
+/// behavior for the contact manifold being processed.
+pub(crate) fn update_oneway_platform_contacts(
+    local_n: &Vector<Real>,
+    allowed_local_n: &Vector<Real>,
+    allowed_angle: Real,
+    solver_contacts: &mut Vec<SolverContact>,
+    state: &mut u32,
+) {
+    const CONTACT_CONFIGURATION_UNKNOWN: u32 = 0;
+    const CONTACT_CURRENTLY_ALLOWED: u32 = 1;
+    const CONTACT_CURRENTLY_FORBIDDEN: u32 = 2;
+
+    let cang = ComplexField::cos(allowed_angle);
+
+    // Test the allowed normal with the local-space contact normal that
+    // points towards the exterior of the platform.
+    let contact_is_ok = local_n.dot(allowed_local_n) >= cang;
+
+    match *state {
+        CONTACT_CONFIGURATION_UNKNOWN => {
+            if contact_is_ok {
+                // The contact is close enough to the allowed normal.
+                *state = CONTACT_CURRENTLY_ALLOWED;
+            } else {
+                // The contact normal isn't close enough to the allowed
+                // normal, so remove all the contacts and mark further contacts
+                // as forbidden.
+                solver_contacts.clear();
+
+                // NOTE: in some very rare cases `local_n` will be
+                // zero if the objects are exactly touching at one point.
+                // So in this case we can't really conclude.
+                // If the norm is non-zero, then we can tell we need to forbid
+                // further contacts. Otherwise we have to wait for the next frame.
+                if local_n.norm_squared() > 0.1 {
+                    *state = CONTACT_CURRENTLY_FORBIDDEN;
                 }
             }
-            CONTACT_CURRENTLY_FORBIDDEN => {
-                // Contacts are forbidden so we need to continue forbidding contacts
-                // until all the contacts are non-penetrating again. In that case, if
-                // the contacts are OK wrt. the contact normal, then we can mark them as allowed.
-                if contact_is_ok && self.solver_contacts.iter().all(|c| c.dist > 0.0) {
-                    *self.user_data = CONTACT_CURRENTLY_ALLOWED;
-                } else {
-                    // Discard all the contacts.
-                    self.solver_contacts.clear();
-                }
+        }
+        CONTACT_CURRENTLY_FORBIDDEN => {
+            // Contacts are forbidden so we need to continue forbidding contacts
+            // until all the contacts are non-penetrating again. In that case, if
+            // the contacts are OK wrt. the contact normal, then we can mark them as allowed.
+            if contact_is_ok && solver_contacts.iter().all(|c| c.dist > 0.0) {
+                *state = CONTACT_CURRENTLY_ALLOWED;
+            } else {
+                // Discard all the contacts.
+                solver_contacts.clear();
             }
-            CONTACT_CURRENTLY_ALLOWED => {
-                // We allow all the contacts right now. The configuration becomes
-                // uncertain again when the contact manifold no longer contains any contact.
-                if self.solver_contacts.is_empty() {
-                    *self.user_data = CONTACT_CONFIGURATION_UNKNOWN;
-                }
+        }
+        CONTACT_CURRENTLY_ALLOWED => {
+            // We allow all the contacts right now. The configuration becomes
+            // uncertain again when the contact manifold no longer contains any contact.
+            if solver_contacts.is_empty() {
+                *state = CONTACT_CONFIGURATION_UNKNOWN;
             }
-            _ => unreachable!(),
         }
+        _ => unreachable!(),
     }
 }
 
@@ -229,6 +253,11 @@ pub trait PhysicsHooks: Send + Sync {
     ///   coefficient depending of the features in contacts.
     /// - Simulating one-way platforms depending on the contact normal.
     ///
+    /// The `friction` and `restitution` of each solver contact are initialized with the combined
+    /// coefficients of the colliders. Overriding them here (e.g. to simulate wet surfaces) affects
+    /// the constraints solver at the current timestep. They are recomputed at each timestep, so
+    /// the override has to be applied again at each timestep.
+    ///
     /// Each contact manifold is given a `u32` user-defined data that is persistent between
     /// timesteps (as long as the contact manifold exists). This user-defined data is initialized
     /// as 0 and can be modified in `context.user_data`.
@@ -248,3 +277,88 @@ impl PhysicsHooks for () {
 
     fn modify_solver_contacts(&self, _: &mut ContactModificationContext) {}
 }
+
+#[cfg(test)]
+mod test {
+    use super::{ActiveHooks, ContactModificationContext, PhysicsHooks};
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn contact_modification_overrides_friction() {
+        struct FrictionOverride(Option<Real>);
+        impl PhysicsHooks for FrictionOverride {
+            fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+                if let Some(friction) = self.0 {
+                    for contact in context.solver_contacts.iter_mut() {
+                        contact.friction = friction;
+                    }
+                }
+            }
+        }
+
+        fn slide(hooks: &dyn PhysicsHooks) -> Real {
+            let mut world = TestWorld::new();
+
+            world
+                .colliders
+                .insert(ColliderBuilder::halfspace(Vector::y_axis()).friction(1.0));
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .linvel(Vector::x() * 5.0)
+                .lock_rotations();
+            let body = world.bodies.insert(body);
+            let ball = ColliderBuilder::ball(0.5)
+                .friction(1.0)
+                .active_hooks(ActiveHooks::MODIFY_SOLVER_CONTACTS);
+            world
+                .colliders
+                .insert_with_parent(ball, body, &mut world.bodies);
+
+            for _ in 0..10 {
+                world.step_with(hooks, &());
+            }
+
+            world.bodies[body].linvel().x
+        }
+
+        let default_speed = slide(&());
+        assert!(default_speed < 4.0);
+        assert_eq!(slide(&FrictionOverride(None)), default_speed);
+        assert!((slide(&FrictionOverride(Some(0.0))) - 5.0).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn one_way_platform_lets_bodies_through_from_below() {
+        let mut world = TestWorld::new();
+
+        let platform = ColliderBuilder::ball(0.5)
+            .one_way_platform(Vector::y(), 0.1)
+            .build();
+        world.colliders.insert(platform);
+
+        let ball_body = RigidBodyBuilder::dynamic()
+            .translation(-Vector::y() * 2.0)
+            .linvel(Vector::y() * 10.0)
+            .build();
+        let ball_body = world.bodies.insert(ball_body);
+        let ball = ColliderBuilder::ball(0.5).build();
+        world
+            .colliders
+            .insert_with_parent(ball, ball_body, &mut world.bodies);
+
+        let mut max_height = world.bodies[ball_body].translation().y;
+
+        for _ in 0..240 {
+            world.step();
+            max_height = max_height.max(world.bodies[ball_body].translation().y);
+        }
+
+        // The ball jumped through the platform from below…
+        assert!(max_height > 2.0);
+        // …and landed on top of it.
+        assert!((world.bodies[ball_body].translation().y - 1.0).abs() < 0.1);
+    }
+}
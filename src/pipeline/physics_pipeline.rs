@@ -1,22 +1,60 @@
 //! Physics pipeline structures.
 
-use crate::counters::Counters;
+use crate::counters::{Counters, PhysicsTimings};
 #[cfg(not(feature = "parallel"))]
 use crate::dynamics::IslandSolver;
+#[cfg(all(feature = "debug-solver-residuals", not(feature = "parallel")))]
+use crate::dynamics::SolverResiduals;
 use crate::dynamics::{
-    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
-    RigidBodyChanges, RigidBodyHandle, RigidBodyPosition, RigidBodyType,
+    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, LockedAxes,
+    MultibodyJointSet, RigidBodyChanges, RigidBodyHandle, RigidBodyPosition, RigidBodyType,
+    RigidBodyVelocity,
 };
 #[cfg(feature = "parallel")]
 use crate::dynamics::{JointGraphEdge, ParallelIslandSolver as IslandSolver};
 use crate::geometry::{
     BroadPhase, BroadPhasePairEvent, ColliderChanges, ColliderHandle, ColliderPair,
-    ContactManifoldIndex, NarrowPhase, TemporaryInteractionIndex,
+    ContactImpulseClampEvent, ContactManifold, ContactManifoldExt, ContactManifoldIndex,
+    NarrowPhase, TemporaryInteractionIndex,
 };
-use crate::math::{Real, Vector};
-use crate::pipeline::{EventHandler, PhysicsHooks, QueryPipeline};
+use crate::math::{AngVector, Isometry, Real, Vector};
+use crate::pipeline::{ActiveEvents, EventHandler, PhysicsHooks, QueryPipeline};
+use crate::utils::WCross;
+use num::Zero;
 use {crate::dynamics::RigidBodySet, crate::geometry::ColliderSet};
 
+/// A summary of the work done by the last timestep, returned by
+/// [`PhysicsPipeline::step_with_report`].
+///
+/// The constraints solver always runs the number of iterations set by the
+/// [`IntegrationParameters`] (possibly increased for some islands by
+/// [`RigidBody::set_min_solver_iterations`](crate::dynamics::RigidBody::set_min_solver_iterations)),
+/// so there is no iteration cap to report. Joints never break
+/// on their own either (they are only removed by the user), so there is no broken joint
+/// count to report.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StepReport {
+    /// The number of substeps run by the timestep. This is larger than one only if some
+    /// rigid-bodies with CCD enabled needed substepping
+    /// (see [`IntegrationParameters::max_ccd_substeps`]).
+    pub num_substeps: usize,
+    /// The number of active islands during the last substep.
+    pub num_active_islands: usize,
+    /// The number of islands actually solved during the last substep. This is smaller than
+    /// `num_active_islands` if some islands were deferred because of
+    /// [`IntegrationParameters::max_solved_islands`].
+    pub num_solved_islands: usize,
+    /// The number of contact manifolds given to the constraints solver, summed over all the
+    /// substeps.
+    pub num_contact_manifolds: usize,
+    /// The number of contact points given to the constraints solver, summed over all the
+    /// substeps.
+    pub num_solver_contacts: usize,
+    /// The number of impulse joints given to the constraints solver, summed over all the
+    /// substeps.
+    pub num_impulse_joints: usize,
+}
+
 /// The physics pipeline, responsible for stepping the whole physics simulation.
 ///
 /// This structure only contains temporary data buffers. It can be dropped and replaced by a fresh
@@ -37,6 +75,19 @@ pub struct PhysicsPipeline {
     broadphase_collider_pairs: Vec<ColliderPair>,
     broad_phase_events: Vec<BroadPhasePairEvent>,
     solvers: Vec<IslandSolver>,
+    solved_islands: Vec<bool>,
+    // The active contact manifolds, always empty outside of a step. This is only kept to reuse
+    // its allocation.
+    manifolds: Vec<&'static mut ContactManifold>,
+    // The buffer given back to the collider set for recording the modified colliders, to reuse
+    // its allocation.
+    modified_colliders: Vec<ColliderHandle>,
+    // The deferred bodies, with the number of consecutive steps they were deferred.
+    deferred_bodies: Vec<(RigidBodyHandle, u32)>,
+    /// The gravity used by the last call to [`PhysicsPipeline::step`], used to detect changes.
+    previous_gravity: Option<Vector<Real>>,
+    gravity_scale: Real,
+    step_report: StepReport,
 }
 
 impl Default for PhysicsPipeline {
@@ -57,14 +108,117 @@ impl PhysicsPipeline {
         PhysicsPipeline {
             counters: Counters::new(true),
             solvers: vec![],
+            solved_islands: vec![],
+            manifolds: vec![],
+            modified_colliders: vec![],
+            deferred_bodies: vec![],
             contact_pair_indices: vec![],
             manifold_indices: vec![],
             joint_constraint_indices: vec![],
             broadphase_collider_pairs: vec![],
             broad_phase_events: vec![],
+            previous_gravity: None,
+            gravity_scale: 1.0,
+            step_report: StepReport::default(),
+        }
+    }
+
+    /// Initializes a new physics pipeline, with room for `num_contact_pairs` contact pairs in
+    /// its internal buffers.
+    ///
+    /// This avoids reallocating these buffers during the first steps of a simulation with up to
+    /// `num_contact_pairs` contact pairs. They grow as usual (without panicking) if there are more.
+    /// The constraints solver buffers are sized for a single island containing all these contacts.
+    /// See also [`RigidBodySet::with_capacity`], [`ColliderSet::with_capacity`],
+    /// [`IslandManager::with_capacity`], [`NarrowPhase::with_capacity`], and
+    /// [`BroadPhase::with_capacity`] to size the other structures of the simulation.
+    pub fn with_capacity(num_contact_pairs: usize) -> PhysicsPipeline {
+        PhysicsPipeline {
+            solvers: vec![IslandSolver::with_capacity(num_contact_pairs)],
+            manifolds: Vec::with_capacity(num_contact_pairs),
+            manifold_indices: vec![Vec::with_capacity(num_contact_pairs)],
+            contact_pair_indices: Vec::with_capacity(num_contact_pairs),
+            broadphase_collider_pairs: Vec::with_capacity(num_contact_pairs),
+            broad_phase_events: Vec::with_capacity(num_contact_pairs),
+            ..PhysicsPipeline::new()
         }
     }
 
+    /// The dynamic rigid-bodies that weren't simulated during the last call to
+    /// [`PhysicsPipeline::step`] because their island exceeded
+    /// [`IntegrationParameters::max_solved_islands`].
+    pub fn deferred_bodies(&self) -> impl ExactSizeIterator<Item = RigidBodyHandle> + '_ {
+        self.deferred_bodies.iter().map(|(handle, _)| *handle)
+    }
+
+    /// The multiplier applied to the gravity given to [`PhysicsPipeline::step`] (default: `1.0`).
+    pub fn gravity_scale(&self) -> Real {
+        self.gravity_scale
+    }
+
+    /// Sets the multiplier applied to the gravity given to [`PhysicsPipeline::step`].
+    ///
+    /// This scales the gravity of all the rigid-bodies, on top of their own gravity scale. Just
+    /// like changing the gravity itself, changing this wakes up, at the next step, the sleeping
+    /// dynamic rigid-bodies with a non-zero gravity scale. This doesn’t affect the forces applied
+    /// by a [`GravityField`](crate::control::GravityField).
+    pub fn set_gravity_scale(&mut self, scale: Real) {
+        self.gravity_scale = scale;
+    }
+
+    /// The time spent by each phase of the last call to [`PhysicsPipeline::step`].
+    ///
+    /// The timings are only measured if the `profiler` feature is enabled and if
+    /// `self.counters` is enabled. Otherwise they are all zero.
+    pub fn timings(&self) -> PhysicsTimings {
+        self.counters.timings()
+    }
+
+    /// The contact manifolds packed into each SIMD group by the constraints solver of the given
+    /// island during the last step.
+    ///
+    /// Each element is the index of a contact manifold in the list of active contact manifolds
+    /// given to the constraints solver. Contacts involving only one dynamic body are listed
+    /// after the others. Contacts that couldn't be packed into a full SIMD group aren't listed.
+    #[cfg(all(
+        feature = "simd-is-enabled",
+        feature = "debug-simd-groups",
+        not(feature = "parallel")
+    ))]
+    pub fn contact_simd_groups(&self, island_id: usize) -> Vec<[usize; crate::math::SIMD_WIDTH]> {
+        self.solvers
+            .get(island_id)
+            .map(|solver| solver.contact_simd_groups())
+            .unwrap_or_default()
+    }
+
+    /// The impulse joints packed into each SIMD group by the constraints solver of the given
+    /// island during the last step.
+    ///
+    /// Each element is the index of a joint in the interaction graph of the impulse joint set.
+    /// Joints attached to only one dynamic body are listed after the others. Joints that couldn't
+    /// be packed into a full SIMD group aren't listed.
+    #[cfg(all(
+        feature = "simd-is-enabled",
+        feature = "debug-simd-groups",
+        not(feature = "parallel")
+    ))]
+    pub fn joint_simd_groups(&self, island_id: usize) -> Vec<[usize; crate::math::SIMD_WIDTH]> {
+        self.solvers
+            .get(island_id)
+            .map(|solver| solver.joint_simd_groups())
+            .unwrap_or_default()
+    }
+
+    /// The convergence diagnostics of the constraints solver of the given island during the
+    /// last time it was solved.
+    ///
+    /// Returns `None` if no island with this index was ever solved.
+    #[cfg(all(feature = "debug-solver-residuals", not(feature = "parallel")))]
+    pub fn solver_residuals(&self, island_id: usize) -> Option<&SolverResiduals> {
+        self.solvers.get(island_id).map(|solver| solver.residuals())
+    }
+
     fn clear_modified_colliders(
         &mut self,
         colliders: &mut ColliderSet,
@@ -89,6 +243,55 @@ impl PhysicsPipeline {
         }
     }
 
+    /// Applies the modifications made by the user to the rigid-bodies and colliders since the
+    /// last step, and returns the modified colliders, removed colliders, and modified bodies.
+    ///
+    /// The collider set records the next modified colliders into `modified_colliders_buffer`.
+    fn apply_user_changes(
+        modified_colliders_buffer: Vec<ColliderHandle>,
+        islands: &mut IslandManager,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+    ) -> (
+        Vec<ColliderHandle>,
+        Vec<ColliderHandle>,
+        Vec<RigidBodyHandle>,
+    ) {
+        let mut modified_colliders = colliders.take_modified_with_buffer(modified_colliders_buffer);
+        let mut removed_colliders = colliders.take_removed();
+
+        super::user_changes::handle_user_changes_to_colliders(
+            bodies,
+            colliders,
+            &modified_colliders[..],
+        );
+
+        let modified_bodies = bodies.take_modified();
+        super::user_changes::handle_user_changes_to_rigid_bodies(
+            Some(islands),
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            &modified_bodies,
+            &mut modified_colliders,
+        );
+
+        // Disabled colliders are treated as if they were removed.
+        // NOTE: this must be called here, after handle_user_changes_to_rigid_bodies to take into
+        //       account colliders disabled because of their parent rigid-body.
+        removed_colliders.extend(
+            modified_colliders
+                .iter()
+                .copied()
+                .filter(|h| colliders.get(*h).map(|c| !c.is_enabled()).unwrap_or(false)),
+        );
+
+        (modified_colliders, removed_colliders, modified_bodies)
+    }
+
     fn detect_collisions(
         &mut self,
         integration_parameters: &IntegrationParameters,
@@ -112,8 +315,10 @@ impl PhysicsPipeline {
         self.broad_phase_events.clear();
         self.broadphase_collider_pairs.clear();
         broad_phase.update(
-            integration_parameters.prediction_distance,
+            integration_parameters.dt,
+            integration_parameters.effective_broad_phase_margin(),
             colliders,
+            bodies,
             modified_colliders,
             removed_colliders,
             &mut self.broad_phase_events,
@@ -124,7 +329,7 @@ impl PhysicsPipeline {
 
         // Update narrow-phase.
         if handle_user_changes {
-            narrow_phase.handle_user_changes(
+            narrow_phase.handle_user_changes_with_pending_events(
                 Some(islands),
                 modified_colliders,
                 removed_colliders,
@@ -142,6 +347,7 @@ impl PhysicsPipeline {
         );
         narrow_phase.compute_contacts(
             integration_parameters.prediction_distance,
+            integration_parameters.contact_persistence_distance,
             bodies,
             colliders,
             impulse_joints,
@@ -156,6 +362,155 @@ impl PhysicsPipeline {
         self.counters.stages.collision_detection_time.pause();
     }
 
+    /// Computes the angular impulses discarded by the rotation locking of the active bodies,
+    /// from the contact impulses computed by the solver and the user torques.
+    fn update_locked_angular_impulses(
+        dt: Real,
+        islands: &IslandManager,
+        bodies: &mut RigidBodySet,
+        manifolds: &[&mut ContactManifold],
+    ) {
+        for handle in islands.active_dynamic_bodies() {
+            let rb = bodies.index_mut_internal(*handle);
+            rb.locked_angular_impulse = if rb.mprops.flags.intersects(LockedAxes::ROTATION_LOCKED) {
+                rb.forces.user_torque * dt
+            } else {
+                AngVector::zero()
+            };
+        }
+
+        for manifold in manifolds {
+            // NOTE: the impulses are applied by the solver on the second body, and their
+            //       opposite on the first one, unless it dominates the other body.
+            let bodies_and_signs = [
+                (
+                    manifold.data.rigid_body1,
+                    -1.0,
+                    manifold.data.relative_dominance <= 0,
+                ),
+                (
+                    manifold.data.rigid_body2,
+                    1.0,
+                    manifold.data.relative_dominance >= 0,
+                ),
+            ];
+
+            for (handle, sign, affected) in bodies_and_signs {
+                let rb = match handle.and_then(|h| bodies.get_mut_internal(h)) {
+                    Some(rb)
+                        if affected
+                            && rb.is_dynamic()
+                            && rb.mprops.flags.intersects(LockedAxes::ROTATION_LOCKED) =>
+                    {
+                        rb
+                    }
+                    _ => continue,
+                };
+
+                for solver_contact in &manifold.data.solver_contacts {
+                    let contact = &manifold.points[solver_contact.contact_id as usize];
+                    let impulse = (manifold.data.normal * contact.data.impulse
+                        + manifold.friction_impulse(contact))
+                        * sign;
+                    rb.locked_angular_impulse +=
+                        (solver_contact.point - rb.mprops.world_com).gcross(impulse);
+                }
+            }
+        }
+
+        // Only keep the components along the locked axes.
+        for handle in islands.active_dynamic_bodies() {
+            let rb = bodies.index_mut_internal(*handle);
+            let locked = rb.is_rotation_locked();
+
+            #[cfg(feature = "dim2")]
+            if !locked {
+                rb.locked_angular_impulse = 0.0;
+            }
+
+            #[cfg(feature = "dim3")]
+            for i in 0..3 {
+                if !locked[i] {
+                    rb.locked_angular_impulse[i] = 0.0;
+                }
+            }
+        }
+    }
+
+    /// Selects the islands simulated at this timestep if there are more than
+    /// `max_solved_islands`, and freezes the bodies of the deferred islands.
+    fn select_solved_islands(
+        &mut self,
+        max_solved_islands: usize,
+        islands: &IslandManager,
+        bodies: &mut RigidBodySet,
+    ) {
+        let num_islands = islands.num_islands();
+        self.solved_islands.clear();
+        self.solved_islands
+            .resize(num_islands, num_islands <= max_solved_islands);
+
+        if num_islands <= max_solved_islands {
+            self.deferred_bodies.clear();
+            return;
+        }
+
+        // The islands deferred for the largest number of steps first, then the most
+        // energetic ones, so every island is eventually simulated.
+        let previously_deferred: std::collections::HashMap<_, _> =
+            self.deferred_bodies.drain(..).collect();
+        let mut priorities: Vec<_> = (0..num_islands)
+            .map(|island_id| {
+                let island = islands.active_island(island_id);
+                let deferred_steps = island
+                    .iter()
+                    .filter_map(|h| previously_deferred.get(h).copied())
+                    .max()
+                    .unwrap_or(0);
+                let energy: Real = island.iter().map(|h| bodies[*h].kinetic_energy()).sum();
+                (island_id, deferred_steps, energy)
+            })
+            .collect();
+        priorities.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.total_cmp(&a.2)).then(a.0.cmp(&b.0)));
+
+        for (island_id, _, _) in &priorities[..max_solved_islands] {
+            self.solved_islands[*island_id] = true;
+        }
+
+        for (island_id, deferred_steps, _) in &priorities[max_solved_islands..] {
+            for handle in islands.active_island(*island_id) {
+                // Make sure neither the final position update nor CCD moves these bodies.
+                let rb = bodies.index_mut_internal(*handle);
+                rb.pos.next_position = rb.pos.position;
+                rb.integrated_vels = RigidBodyVelocity::zero();
+                self.deferred_bodies.push((*handle, deferred_steps + 1));
+            }
+        }
+    }
+
+    /// The integration parameters used to solve the given island, taking into account the
+    /// minimum number of solver iterations of its rigid-bodies.
+    fn island_integration_parameters(
+        integration_parameters: &IntegrationParameters,
+        islands: &IslandManager,
+        bodies: &RigidBodySet,
+        island_id: usize,
+    ) -> IntegrationParameters {
+        let min_iterations = islands
+            .active_island(island_id)
+            .iter()
+            .map(|handle| bodies[*handle].min_solver_iterations)
+            .max()
+            .unwrap_or(0);
+
+        IntegrationParameters {
+            max_velocity_iterations: integration_parameters
+                .max_velocity_iterations
+                .max(min_iterations),
+            ..*integration_parameters
+        }
+    }
+
     fn build_islands_and_solve_velocity_constraints(
         &mut self,
         gravity: &Vector<Real>,
@@ -177,6 +532,7 @@ impl PhysicsPipeline {
             impulse_joints,
             multibody_joints,
             integration_parameters.min_island_size,
+            integration_parameters.sleep_hysteresis,
         );
         self.counters.stages.island_construction_time.pause();
 
@@ -190,7 +546,7 @@ impl PhysicsPipeline {
                 .resize(islands.num_islands(), Vec::new());
         }
 
-        let mut manifolds = Vec::new();
+        let mut manifolds: Vec<&mut ContactManifold> = std::mem::take(&mut self.manifolds);
         narrow_phase.select_active_contacts(
             islands,
             bodies,
@@ -204,6 +560,13 @@ impl PhysicsPipeline {
             &mut self.joint_constraint_indices,
         );
 
+        // Only the active bodies are affected by gravity and integrated. The other ones, e.g.
+        // sleeping bodies, don’t cost anything here.
+        self.counters.solver.nskipped_bodies = bodies
+            .len()
+            .saturating_sub(islands.active_dynamic_bodies().len())
+            .saturating_sub(islands.active_kinematic_bodies().len());
+
         self.counters.stages.update_time.resume();
         for handle in islands.active_dynamic_bodies() {
             let rb = bodies.index_mut_internal(*handle);
@@ -211,9 +574,30 @@ impl PhysicsPipeline {
             let effective_mass = rb.mprops.effective_mass();
             rb.forces
                 .compute_effective_force_and_torque(&gravity, &effective_mass);
+
+            if let Some(target) = &rb.position_target {
+                target.apply(
+                    integration_parameters.dt,
+                    &rb.pos,
+                    &rb.vels,
+                    &rb.mprops,
+                    &mut rb.forces,
+                );
+            }
         }
 
         for multibody in &mut multibody_joints.multibodies {
+            // The links of a multibody are all part of the same island, so they all sleep at
+            // the same time.
+            let is_sleeping = multibody.1.links().all(|link| {
+                let rb = &bodies[link.rigid_body];
+                rb.is_fixed() || rb.is_sleeping()
+            });
+
+            if is_sleeping {
+                continue;
+            }
+
             multibody
                 .1
                 .update_dynamics(integration_parameters.dt, bodies);
@@ -221,6 +605,37 @@ impl PhysicsPipeline {
         }
         self.counters.stages.update_time.pause();
 
+        self.select_solved_islands(integration_parameters.max_solved_islands, islands, bodies);
+
+        // NOTE: the constraint impulses are reset once applied, so they only affect the first
+        //       substep. The ones of the deferred islands are kept until they are simulated.
+        for island_id in 0..islands.num_islands() {
+            if self.solved_islands[island_id] {
+                for handle in islands.active_island(island_id) {
+                    bodies
+                        .index_mut_internal(*handle)
+                        .forces
+                        .apply_constraint_impulses(integration_parameters.dt);
+                }
+            }
+        }
+
+        self.step_report.num_active_islands = islands.num_islands();
+        self.step_report.num_solved_islands = 0;
+        for island_id in 0..islands.num_islands() {
+            if self.solved_islands[island_id] {
+                let manifold_indices = &self.manifold_indices[island_id];
+                self.step_report.num_solved_islands += 1;
+                self.step_report.num_contact_manifolds += manifold_indices.len();
+                self.step_report.num_solver_contacts += manifold_indices
+                    .iter()
+                    .map(|i| manifolds[*i].data.solver_contacts.len())
+                    .sum::<usize>();
+                self.step_report.num_impulse_joints +=
+                    self.joint_constraint_indices[island_id].len();
+            }
+        }
+
         self.counters.stages.solver_time.resume();
         if self.solvers.len() < islands.num_islands() {
             self.solvers
@@ -232,10 +647,20 @@ impl PhysicsPipeline {
             enable_flush_to_zero!();
 
             for island_id in 0..islands.num_islands() {
+                if !self.solved_islands[island_id] {
+                    continue;
+                }
+
+                let island_params = Self::island_integration_parameters(
+                    integration_parameters,
+                    islands,
+                    bodies,
+                    island_id,
+                );
                 self.solvers[island_id].init_and_solve(
                     island_id,
                     &mut self.counters,
-                    integration_parameters,
+                    &island_params,
                     islands,
                     bodies,
                     &mut manifolds[..],
@@ -254,6 +679,17 @@ impl PhysicsPipeline {
             use std::sync::atomic::Ordering;
 
             let num_islands = islands.num_islands();
+            let island_params: Vec<_> = (0..num_islands)
+                .map(|island_id| {
+                    Self::island_integration_parameters(
+                        integration_parameters,
+                        islands,
+                        bodies,
+                        island_id,
+                    )
+                })
+                .collect();
+            let island_params = &island_params[..];
             let solvers = &mut self.solvers[..num_islands];
             let bodies = &std::sync::atomic::AtomicPtr::new(bodies as *mut _);
             let manifolds = &std::sync::atomic::AtomicPtr::new(&mut manifolds as *mut _);
@@ -262,6 +698,7 @@ impl PhysicsPipeline {
             let multibody_joints = &std::sync::atomic::AtomicPtr::new(multibody_joints as *mut _);
             let manifold_indices = &self.manifold_indices[..];
             let joint_constraint_indices = &self.joint_constraint_indices[..];
+            let solved_islands = &self.solved_islands[..];
 
             rayon::scope(|scope| {
                 enable_flush_to_zero!();
@@ -269,6 +706,7 @@ impl PhysicsPipeline {
                 solvers
                     .par_iter_mut()
                     .enumerate()
+                    .filter(|(island_id, _)| solved_islands[*island_id])
                     .for_each(|(island_id, solver)| {
                         let bodies: &mut RigidBodySet =
                             unsafe { std::mem::transmute(bodies.load(Ordering::Relaxed)) };
@@ -284,7 +722,7 @@ impl PhysicsPipeline {
                             scope,
                             island_id,
                             islands,
-                            integration_parameters,
+                            &island_params[island_id],
                             bodies,
                             manifolds,
                             &manifold_indices[island_id],
@@ -296,7 +734,33 @@ impl PhysicsPipeline {
             });
         }
 
-        // Generate contact force events if needed.
+        Self::update_locked_angular_impulses(
+            integration_parameters.dt,
+            islands,
+            bodies,
+            &manifolds,
+        );
+
+        // Keep the manifolds buffer for the next step. Once emptied, collecting it in-place only
+        // changes the lifetime of the references it can hold, not its allocation.
+        manifolds.clear();
+        self.manifolds = manifolds.into_iter().map(|_| unreachable!()).collect();
+
+        // Generate contact force, contact impulse clamp, and impulse-throttled collision start
+        // events if needed. The contact pairs
+        // are sorted by collider handles so that the order of these events doesn't depend on
+        // the order the contact pairs are stored in.
+        self.contact_pair_indices.retain(|pair_id| {
+            let pair = narrow_phase.contact_pair_at_index(*pair_id);
+            colliders[pair.collider1].may_emit_solver_events()
+                || colliders[pair.collider2].may_emit_solver_events()
+        });
+        self.contact_pair_indices.sort_by_key(|pair_id| {
+            let pair = narrow_phase.contact_pair_at_index(*pair_id);
+            let (h1, h2) = (pair.collider1.0, pair.collider2.0);
+            (h1.min(h2), h1.max(h2))
+        });
+
         let inv_dt = crate::utils::inv(integration_parameters.dt);
         for pair_id in self.contact_pair_indices.drain(..) {
             let pair = narrow_phase.contact_pair_at_index(pair_id);
@@ -305,9 +769,19 @@ impl PhysicsPipeline {
             let threshold = co1
                 .effective_contact_force_event_threshold()
                 .min(co2.effective_contact_force_event_threshold());
-
-            if threshold < Real::MAX {
-                let total_magnitude = pair.total_impulse_magnitude() * inv_dt;
+            let impulse_threshold = co1
+                .contact_event_impulse_threshold()
+                .max(co2.contact_event_impulse_threshold());
+            let total_impulse = pair.total_impulse_magnitude();
+            let emit_start_event = impulse_threshold > 0.0
+                && total_impulse >= impulse_threshold
+                && pair.has_any_active_contact
+                && !pair.start_event_emited
+                && (co1.flags.active_events | co2.flags.active_events)
+                    .contains(ActiveEvents::COLLISION_EVENTS);
+
+            if threshold < Real::MAX && total_impulse >= impulse_threshold {
+                let total_magnitude = total_impulse * inv_dt;
 
                 // NOTE: the strict inequality is important here, so we don’t
                 //       trigger an event if the force is 0.0 and the threshold is 0.0.
@@ -321,6 +795,40 @@ impl PhysicsPipeline {
                     );
                 }
             }
+
+            if co1.material.max_contact_impulse < Real::MAX
+                || co2.material.max_contact_impulse < Real::MAX
+            {
+                let unclamped_impulse = pair
+                    .manifolds
+                    .iter()
+                    .flat_map(|m| {
+                        m.data
+                            .solver_contacts
+                            .iter()
+                            .map(|c| m.points[c.contact_id as usize].data.unclamped_impulse)
+                    })
+                    .fold(0.0, Real::max);
+
+                for (collider, co, other_collider) in [
+                    (pair.collider1, co1, pair.collider2),
+                    (pair.collider2, co2, pair.collider1),
+                ] {
+                    if unclamped_impulse > co.material.max_contact_impulse {
+                        let event = ContactImpulseClampEvent {
+                            collider,
+                            other_collider,
+                            unclamped_impulse,
+                            max_contact_impulse: co.material.max_contact_impulse,
+                        };
+                        events.handle_contact_impulse_clamp_event(bodies, colliders, event, pair);
+                    }
+                }
+            }
+
+            if emit_start_event {
+                narrow_phase.emit_impulse_threshold_start_event(pair_id);
+            }
         }
 
         self.counters.stages.solver_time.pause();
@@ -400,13 +908,16 @@ impl PhysicsPipeline {
         }
     }
 
-    /// Executes one timestep of the physics simulation.
+    /// Runs the collision detection only, without integrating velocities or moving anything.
     ///
-    /// This is the same as `self.step_generic`, except that it is specialized
-    /// to work with `RigidBodySet` and `ColliderSet`.
-    pub fn step(
+    /// The modifications made by the user to the rigid-bodies and colliders are applied, then the
+    /// broad-phase and narrow-phase are updated so that the contact and intersection data of the
+    /// `narrow_phase` (e.g. [`NarrowPhase::contacts_with`]) reflect the current collider positions.
+    /// Collision events are reported to `events` the same way as with [`PhysicsPipeline::step`].
+    ///
+    /// This is useful to detect overlaps while the simulation is paused, e.g., in an editor.
+    pub fn detect_collisions_only(
         &mut self,
-        gravity: &Vector<Real>,
         integration_parameters: &IntegrationParameters,
         islands: &mut IslandManager,
         broad_phase: &mut BroadPhase,
@@ -415,62 +926,24 @@ impl PhysicsPipeline {
         colliders: &mut ColliderSet,
         impulse_joints: &mut ImpulseJointSet,
         multibody_joints: &mut MultibodyJointSet,
-        ccd_solver: &mut CCDSolver,
-        mut query_pipeline: Option<&mut QueryPipeline>,
+        query_pipeline: Option<&mut QueryPipeline>,
         hooks: &dyn PhysicsHooks,
         events: &dyn EventHandler,
     ) {
         self.counters.reset();
         self.counters.step_started();
+        narrow_phase.clear_contact_pair_changes();
+        broad_phase.clear_collider_changes();
 
-        // Apply some of delayed wake-ups.
-        for handle in impulse_joints
-            .to_wake_up
-            .drain(..)
-            .chain(multibody_joints.to_wake_up.drain(..))
-        {
-            islands.wake_up(bodies, handle, true);
-        }
-
-        // Apply modifications.
-        let mut modified_colliders = colliders.take_modified();
-        let mut removed_colliders = colliders.take_removed();
-
-        super::user_changes::handle_user_changes_to_colliders(
-            bodies,
-            colliders,
-            &modified_colliders[..],
-        );
-
-        let mut modified_bodies = bodies.take_modified();
-        super::user_changes::handle_user_changes_to_rigid_bodies(
-            Some(islands),
-            bodies,
-            colliders,
-            impulse_joints,
-            multibody_joints,
-            &modified_bodies,
-            &mut modified_colliders,
-        );
-
-        // Disabled colliders are treated as if they were removed.
-        // NOTE: this must be called here, after handle_user_changes_to_rigid_bodies to take into
-        //       account colliders disabled because of their parent rigid-body.
-        removed_colliders.extend(
-            modified_colliders
-                .iter()
-                .copied()
-                .filter(|h| colliders.get(*h).map(|c| !c.is_enabled()).unwrap_or(false)),
-        );
-
-        // TODO: do this only on user-change.
-        // TODO: do we want some kind of automatic inverse kinematics?
-        for multibody in &mut multibody_joints.multibodies {
-            multibody.1.update_root_type(bodies);
-            // FIXME: what should we do here? We should not
-            //        rely on the next state here.
-            multibody.1.forward_kinematics(bodies, true);
-        }
+        let (mut modified_colliders, removed_colliders, mut modified_bodies) =
+            Self::apply_user_changes(
+                std::mem::take(&mut self.modified_colliders),
+                islands,
+                bodies,
+                colliders,
+                impulse_joints,
+                multibody_joints,
+            );
 
         self.detect_collisions(
             integration_parameters,
@@ -488,36 +961,191 @@ impl PhysicsPipeline {
             true,
         );
 
-        if let Some(queries) = query_pipeline.as_deref_mut() {
-            queries.update_incremental(colliders, &modified_colliders, &removed_colliders, false);
+        if let Some(queries) = query_pipeline {
+            queries.update_incremental(colliders, &modified_colliders, &removed_colliders, true);
         }
 
         self.clear_modified_colliders(colliders, &mut modified_colliders);
+        self.modified_colliders = modified_colliders;
         self.clear_modified_bodies(bodies, &mut modified_bodies);
-        removed_colliders.clear();
+        narrow_phase.flush_collision_events(bodies, colliders, events);
 
-        let mut remaining_time = integration_parameters.dt;
-        let mut integration_parameters = *integration_parameters;
+        self.counters.step_completed();
+    }
 
-        let (ccd_is_enabled, mut remaining_substeps) =
-            if integration_parameters.max_ccd_substeps == 0 {
-                (false, 1)
-            } else {
-                (true, integration_parameters.max_ccd_substeps)
-            };
+    /// Executes one timestep of the physics simulation, exactly like [`PhysicsPipeline::step`],
+    /// and returns a summary of the work it did.
+    ///
+    /// Collecting this summary only involves counting elements already computed by the step,
+    /// so this doesn't affect the simulation.
+    pub fn step_with_report(
+        &mut self,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        islands: &mut IslandManager,
+        broad_phase: &mut BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+        query_pipeline: Option<&mut QueryPipeline>,
+        hooks: &dyn PhysicsHooks,
+        events: &dyn EventHandler,
+    ) -> StepReport {
+        self.step(
+            gravity,
+            integration_parameters,
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+            query_pipeline,
+            hooks,
+            events,
+        );
+        self.step_report
+    }
 
-        while remaining_substeps > 0 {
-            // If there are more than one CCD substep, we need to split
-            // the timestep into multiple intervals. First, estimate the
-            // size of the time slice we will integrate for this substep.
-            //
-            // Note that we must do this now, before the constrains resolution
+    /// Executes one timestep of the physics simulation.
+    ///
+    /// This is the same as `self.step_generic`, except that it is specialized
+    /// to work with `RigidBodySet` and `ColliderSet`.
+    ///
+    /// The `gravity` may be changed freely between two calls. If it differs from the gravity
+    /// given to the previous call, all the sleeping dynamic rigid-bodies with a non-zero
+    /// gravity scale are woken up. Localized gravity (e.g. around a planet) can be applied
+    /// on top of it with a [`GravityField`](crate::control::GravityField). The `gravity` is
+    /// multiplied by [`PhysicsPipeline::gravity_scale`].
+    ///
+    /// Collision events are reported to `events` at the end of the step, with at most one event
+    /// per collider pair: a pair that starts and stops touching (or the opposite) within the
+    /// same step doesn't generate any collision event.
+    pub fn step(
+        &mut self,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        islands: &mut IslandManager,
+        broad_phase: &mut BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+        mut query_pipeline: Option<&mut QueryPipeline>,
+        hooks: &dyn PhysicsHooks,
+        events: &dyn EventHandler,
+    ) {
+        self.counters.reset();
+        self.counters.step_started();
+        narrow_phase.clear_contact_pair_changes();
+        broad_phase.clear_collider_changes();
+        self.step_report = StepReport::default();
+        let gravity = &(*gravity * self.gravity_scale);
+
+        // Reset the position corrections computed by the solver at the previous step.
+        for handle in islands.active_dynamic_bodies() {
+            if let Some(rb) = bodies.get_mut_internal(*handle) {
+                rb.position_correction = Isometry::identity();
+            }
+        }
+
+        // Apply some of delayed wake-ups.
+        for handle in impulse_joints
+            .to_wake_up
+            .drain(..)
+            .chain(multibody_joints.to_wake_up.drain(..))
+        {
+            islands.wake_up(bodies, handle, true);
+        }
+
+        // Wake-up the sleeping bodies affected by gravity if it changed since the last step.
+        if self.previous_gravity.map(|g| g != *gravity) == Some(true) {
+            let to_wake_up: Vec<_> = bodies
+                .iter()
+                .filter(|(_, rb)| rb.is_dynamic() && rb.is_sleeping() && rb.gravity_scale() != 0.0)
+                .map(|(h, _)| h)
+                .collect();
+
+            for handle in to_wake_up {
+                islands.wake_up(bodies, handle, true);
+            }
+        }
+        self.previous_gravity = Some(*gravity);
+
+        // Apply modifications.
+        let (mut modified_colliders, mut removed_colliders, mut modified_bodies) =
+            Self::apply_user_changes(
+                std::mem::take(&mut self.modified_colliders),
+                islands,
+                bodies,
+                colliders,
+                impulse_joints,
+                multibody_joints,
+            );
+
+        // TODO: do this only on user-change.
+        // TODO: do we want some kind of automatic inverse kinematics?
+        for multibody in &mut multibody_joints.multibodies {
+            multibody.1.update_root_type(bodies);
+            // FIXME: what should we do here? We should not
+            //        rely on the next state here.
+            multibody.1.forward_kinematics(bodies, true);
+        }
+
+        self.detect_collisions(
+            integration_parameters,
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            &modified_colliders,
+            &removed_colliders,
+            hooks,
+            events,
+            true,
+        );
+
+        if let Some(queries) = query_pipeline.as_deref_mut() {
+            queries.update_incremental(colliders, &modified_colliders, &removed_colliders, false);
+        }
+
+        self.clear_modified_colliders(colliders, &mut modified_colliders);
+        self.clear_modified_bodies(bodies, &mut modified_bodies);
+        removed_colliders.clear();
+
+        let mut remaining_time = integration_parameters.dt;
+        let mut integration_parameters = *integration_parameters;
+
+        let (ccd_is_enabled, mut remaining_substeps) =
+            if integration_parameters.max_ccd_substeps == 0 {
+                (false, 1)
+            } else {
+                (true, integration_parameters.max_ccd_substeps)
+            };
+
+        while remaining_substeps > 0 {
+            // If there are more than one CCD substep, we need to split
+            // the timestep into multiple intervals. First, estimate the
+            // size of the time slice we will integrate for this substep.
+            //
+            // Note that we must do this now, before the constrains resolution
             // because we need to use the correct timestep length for the
             // integration of external forces.
             //
             // If there is only one or zero CCD substep, there is no need
             // to split the timetsep interval. So we can just skip this part.
             if ccd_is_enabled && remaining_substeps > 1 {
+                self.counters.ccd_resumed();
                 // NOTE: Take forces into account when updating the bodies CCD activation flags
                 //       these forces have not been integrated to the body's velocity yet.
                 let ccd_active =
@@ -533,6 +1161,7 @@ impl PhysicsPipeline {
                 } else {
                     None
                 };
+                self.counters.ccd_completed();
 
                 if let Some(toi) = first_impact {
                     let original_interval = remaining_time / (remaining_substeps as Real);
@@ -565,6 +1194,7 @@ impl PhysicsPipeline {
             }
 
             self.counters.ccd.num_substeps += 1;
+            self.step_report.num_substeps += 1;
 
             self.interpolate_kinematic_velocities(&integration_parameters, islands, bodies);
             self.build_islands_and_solve_velocity_constraints(
@@ -581,6 +1211,7 @@ impl PhysicsPipeline {
 
             // If CCD is enabled, execute the CCD motion clamping.
             if ccd_is_enabled {
+                self.counters.ccd_resumed();
                 // NOTE: don't the forces into account when updating the CCD active flags because
                 //       they have already been integrated into the velocities by the solver.
                 let ccd_active = ccd_solver.update_ccd_active_flags(
@@ -600,6 +1231,7 @@ impl PhysicsPipeline {
                         events,
                     );
                 }
+                self.counters.ccd_completed();
             }
 
             self.advance_to_final_positions(islands, bodies, colliders, &mut modified_colliders);
@@ -632,6 +1264,8 @@ impl PhysicsPipeline {
             self.clear_modified_colliders(colliders, &mut modified_colliders);
         }
 
+        self.modified_colliders = modified_colliders;
+
         // Finally, make sure we update the world mass-properties of the rigid-bodies
         // that moved. Otherwise, users may end up applying forces wrt. an outdated
         // center of mass.
@@ -643,8 +1277,70 @@ impl PhysicsPipeline {
             rb.mprops.update_world_mass_properties(&rb.pos.position);
         }
 
+        // Report the net collision events of this step, once all the substeps are done.
+        narrow_phase.flush_collision_events(bodies, colliders, events);
+
         self.counters.step_completed();
     }
+
+    /// Executes timesteps until all the dynamic rigid-bodies are sleeping, or `max_steps`
+    /// timesteps have been executed.
+    ///
+    /// This calls [`PhysicsPipeline::step`] repeatedly with the same arguments, so the contacts
+    /// persist from one step to the next exactly as with a regular simulation loop. This is
+    /// useful to bake a stable initial state for a scene, e.g., letting a pile of objects come to
+    /// rest before taking a snapshot.
+    ///
+    /// A body only falls asleep once it remained almost still for a while (see
+    /// [`RigidBodyActivation`](crate::dynamics::RigidBodyActivation)), so at least a couple of
+    /// seconds of simulated time are needed for a scene to settle. Bodies that can't sleep (see
+    /// [`RigidBodyBuilder::can_sleep`](crate::dynamics::RigidBodyBuilder::can_sleep)) prevent
+    /// this from converging.
+    ///
+    /// Returns the number of timesteps executed until all the dynamic bodies were sleeping, or
+    /// `None` if some were still awake after `max_steps` timesteps. At least one timestep is
+    /// executed if `max_steps` isn’t zero.
+    pub fn settle(
+        &mut self,
+        max_steps: usize,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        islands: &mut IslandManager,
+        broad_phase: &mut BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+        mut query_pipeline: Option<&mut QueryPipeline>,
+        hooks: &dyn PhysicsHooks,
+        events: &dyn EventHandler,
+    ) -> Option<usize> {
+        for num_steps in 1..=max_steps {
+            self.step(
+                gravity,
+                integration_parameters,
+                islands,
+                broad_phase,
+                narrow_phase,
+                bodies,
+                colliders,
+                impulse_joints,
+                multibody_joints,
+                ccd_solver,
+                query_pipeline.as_deref_mut(),
+                hooks,
+                events,
+            );
+
+            if islands.active_dynamic_bodies().is_empty() {
+                return Some(num_steps);
+            }
+        }
+
+        None
+    }
 }
 
 #[cfg(test)]
@@ -655,6 +1351,7 @@ mod test {
     };
     use crate::geometry::{BroadPhase, ColliderBuilder, ColliderSet, NarrowPhase};
     use crate::math::Vector;
+    use crate::pipeline::test_world::TestWorld;
     use crate::pipeline::PhysicsPipeline;
     use crate::prelude::MultibodyJointSet;
 
@@ -855,4 +1552,1384 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn settle_steps_until_all_bodies_sleep() {
+        let mut world = TestWorld::new();
+
+        world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+        let body = RigidBodyBuilder::dynamic().translation(Vector::y()).build();
+        let body = world.bodies.insert(body);
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut world.bodies);
+
+        let settle = |world: &mut TestWorld, max_steps| {
+            world.pipeline.settle(
+                max_steps,
+                &world.gravity,
+                &world.params,
+                &mut world.islands,
+                &mut world.broad_phase,
+                &mut world.narrow_phase,
+                &mut world.bodies,
+                &mut world.colliders,
+                &mut world.impulse_joints,
+                &mut world.multibody_joints,
+                &mut world.ccd_solver,
+                None,
+                &(),
+                &(),
+            )
+        };
+
+        // The ball is still falling after ten steps.
+        assert_eq!(settle(&mut world, 10), None);
+        assert!(!world.bodies[body].is_sleeping());
+
+        let num_steps = settle(&mut world, 1000).unwrap();
+        assert!(num_steps > 0 && num_steps < 1000);
+        assert!(world.bodies[body].is_sleeping());
+        assert!((world.bodies[body].translation().y - 0.5).abs() < 1.0e-2);
+    }
+
+    #[test]
+    fn sleeping_bodies_are_skipped_and_fall_once_woken_up() {
+        let mut world = TestWorld::new();
+
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * 10.0)
+            .sleeping(true)
+            .build();
+        let body = world.bodies.insert(body);
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut world.bodies);
+
+        let step = |world: &mut TestWorld| {
+            world.step();
+            world.pipeline.counters.solver.nskipped_bodies
+        };
+
+        for _ in 0..10 {
+            assert_eq!(step(&mut world), 1);
+            assert!(world.bodies[body].is_sleeping());
+            assert_eq!(world.bodies[body].linvel().norm(), 0.0);
+            assert_eq!(world.bodies[body].translation().y, 10.0);
+        }
+
+        world.islands.wake_up(&mut world.bodies, body, true);
+        assert_eq!(step(&mut world), 0);
+        assert!(world.bodies[body].linvel().y < 0.0);
+    }
+
+    #[test]
+    fn friction_impulses_oppose_sliding() {
+        use crate::geometry::ContactManifoldExt;
+
+        for ground_first in [true, false] {
+            let mut world = TestWorld::new();
+
+            let ground = ColliderBuilder::halfspace(Vector::y_axis()).build();
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .linvel(Vector::x() * 2.0)
+                .lock_rotations()
+                .build();
+            let body = world.bodies.insert(body);
+            let ball = ColliderBuilder::ball(0.5).build();
+
+            let (ground, ball) = if ground_first {
+                let ground = world.colliders.insert(ground);
+                (
+                    ground,
+                    world
+                        .colliders
+                        .insert_with_parent(ball, body, &mut world.bodies),
+                )
+            } else {
+                let ball = world
+                    .colliders
+                    .insert_with_parent(ball, body, &mut world.bodies);
+                (world.colliders.insert(ground), ball)
+            };
+
+            for _ in 0..2 {
+                world.step();
+            }
+
+            let pair = world.narrow_phase.contact_pair(ground, ball).unwrap();
+            // The impulses are given for `collider2`, flip them if it is the ground.
+            let sign = if pair.collider2 == ball { 1.0 } else { -1.0 };
+            let mut total_friction = Vector::zeros();
+
+            for manifold in &pair.manifolds {
+                for tangent in &manifold.data.tangents {
+                    assert!(tangent.dot(&manifold.data.normal).abs() < 1.0e-5);
+                }
+
+                for contact in &manifold.points {
+                    total_friction += manifold.friction_impulse(contact) * sign;
+                }
+            }
+
+            // The friction slows down the sliding ball.
+            assert!(total_friction.x < 0.0);
+            assert!(total_friction.y.abs() < 1.0e-5 * total_friction.x.abs());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "dim3")]
+    fn cone_friction_slides_straight_downhill() {
+        use crate::dynamics::FrictionModel;
+        use crate::math::{Real, Rotation};
+
+        assert_eq!(
+            IntegrationParameters::default().friction_model,
+            FrictionModel::Cone
+        );
+
+        // The slope is slightly steeper than the friction limit, for several downhill directions.
+        let azimuths: [Real; 4] = [0.0, 0.3, 0.6, 0.785];
+
+        for azimuth in azimuths {
+            let mut world = TestWorld::new();
+
+            let (slope, friction): (Real, Real) = (0.55, 0.6);
+            let downhill = Vector::new(azimuth.cos(), 0.0, azimuth.sin());
+            let normal = Vector::y() * slope.cos() + downhill * slope.sin();
+            let ground = ColliderBuilder::halfspace(na::Unit::new_normalize(normal))
+                .friction(friction)
+                .build();
+            world.colliders.insert(ground);
+
+            let body = RigidBodyBuilder::dynamic()
+                .translation(normal * 0.5)
+                .rotation(
+                    Rotation::rotation_between(&Vector::y(), &normal)
+                        .unwrap()
+                        .scaled_axis(),
+                )
+                .lock_rotations()
+                .build();
+            let body = world.bodies.insert(body);
+            let ball = ColliderBuilder::ball(0.5).friction(friction).build();
+            world
+                .colliders
+                .insert_with_parent(ball, body, &mut world.bodies);
+
+            for _ in 0..60 {
+                world.step();
+            }
+
+            let linvel = *world.bodies[body].linvel();
+            let slope_dir = (-Vector::y() + normal * normal.y).normalize();
+            assert!(linvel.norm() > 0.05);
+            assert!(linvel.normalize().dot(&slope_dir) > 0.999);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "dim3")]
+    fn anisotropic_friction_rotates_with_the_body() {
+        use crate::dynamics::CoefficientCombineRule;
+        use crate::math::Real;
+
+        fn slide(collider: ColliderBuilder, angle: Real) -> (Vector<Real>, Vector<Real>) {
+            let mut world = TestWorld::new();
+
+            world
+                .colliders
+                .insert(ColliderBuilder::halfspace(Vector::y_axis()).friction(0.5));
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .rotation(Vector::y() * angle)
+                .linvel(Vector::new(2.0, 0.0, 2.0))
+                .lock_rotations()
+                .build();
+            let body = world.bodies.insert(body);
+            world
+                .colliders
+                .insert_with_parent(collider, body, &mut world.bodies);
+
+            for _ in 0..20 {
+                world.step();
+            }
+
+            (
+                *world.bodies[body].translation(),
+                *world.bodies[body].linvel(),
+            )
+        }
+
+        let cuboid = || {
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5)
+                .friction_combine_rule(CoefficientCombineRule::Min)
+        };
+
+        // Almost no friction along the local `x` axis of the collider.
+        let (_, linvel) = slide(cuboid().anisotropic_friction(Vector::x(), 0.0, 1.0), 0.0);
+        assert!(linvel.x > 1.99 && linvel.z < 1.0);
+        let (_, linvel) = slide(
+            cuboid().anisotropic_friction(Vector::x(), 0.0, 1.0),
+            std::f32::consts::FRAC_PI_2 as Real,
+        );
+        assert!(linvel.z > 1.99 && linvel.x < 1.0);
+
+        // Equal coefficients are the same as isotropic friction.
+        assert_eq!(
+            slide(cuboid().anisotropic_friction(Vector::x(), 0.3, 0.3), 0.0),
+            slide(cuboid().friction(0.3), 0.0)
+        );
+    }
+
+    #[test]
+    fn collision_detection_only_doesnt_move_bodies() {
+        let mut world = TestWorld::new();
+
+        let ground = world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()).build());
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * 0.25)
+            .build();
+        let body = world.bodies.insert(body);
+        let ball = ColliderBuilder::ball(0.5).build();
+        let ball = world
+            .colliders
+            .insert_with_parent(ball, body, &mut world.bodies);
+
+        for _ in 0..2 {
+            world.pipeline.detect_collisions_only(
+                &IntegrationParameters::default(),
+                &mut world.islands,
+                &mut world.broad_phase,
+                &mut world.narrow_phase,
+                &mut world.bodies,
+                &mut world.colliders,
+                &mut world.impulse_joints,
+                &mut world.multibody_joints,
+                None,
+                &(),
+                &(),
+            );
+        }
+
+        assert_eq!(world.bodies[body].translation(), &(Vector::y() * 0.25));
+        assert_eq!(world.bodies[body].linvel(), &Vector::zeros());
+        assert!(
+            world
+                .narrow_phase
+                .contact_pair(ground, ball)
+                .unwrap()
+                .has_any_active_contact
+        );
+        assert_eq!(world.narrow_phase.contacts_with(ball).count(), 1);
+    }
+
+    #[test]
+    fn events_are_sorted_by_collider_handles() {
+        use crate::geometry::ColliderHandle;
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+        let mut world = TestWorld::new();
+
+        let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, contact_force_recv) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        let insert_ball = |world: &mut TestWorld, x: usize| {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * (x as crate::math::Real * 2.0) + Vector::y() * 0.5);
+            let ball = ColliderBuilder::ball(0.5)
+                .active_events(ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS);
+            world.insert(body, ball).0
+        };
+        let balls: Vec<_> = (0..6).map(|x| insert_ball(&mut world, x)).collect();
+
+        // Free some slots so the handles of the balls inserted last are smaller than the
+        // handles of some balls inserted first.
+        for body in &balls[..3] {
+            world.remove(*body);
+        }
+        for x in 6..9 {
+            insert_ball(&mut world, x);
+        }
+        world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()).build());
+
+        let sorted_key = |h1: ColliderHandle, h2: ColliderHandle| (h1.0.min(h2.0), h1.0.max(h2.0));
+
+        for _ in 0..2 {
+            world.step_with(&(), &events);
+
+            let collision_keys: Vec<_> = collision_recv
+                .try_iter()
+                .map(|e| sorted_key(e.collider1(), e.collider2()))
+                .collect();
+            let force_keys: Vec<_> = contact_force_recv
+                .try_iter()
+                .map(|e| sorted_key(e.collider1, e.collider2))
+                .collect();
+            assert!(collision_keys.windows(2).all(|w| w[0] < w[1]));
+            assert!(force_keys.windows(2).all(|w| w[0] < w[1]));
+            assert_eq!(force_keys.len(), 6);
+        }
+    }
+
+    #[test]
+    fn contact_impulses_are_clamped_to_the_max_contact_impulse() {
+        use crate::geometry::{ContactImpulseClampEvent, ContactPair};
+        use crate::pipeline::EventHandler;
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct ClampEvents(Mutex<Vec<ContactImpulseClampEvent>>);
+
+        impl EventHandler for ClampEvents {
+            fn handle_collision_event(
+                &self,
+                _: &RigidBodySet,
+                _: &ColliderSet,
+                _: crate::geometry::CollisionEvent,
+                _: Option<&ContactPair>,
+            ) {
+            }
+
+            fn handle_contact_force_event(
+                &self,
+                _: crate::math::Real,
+                _: &RigidBodySet,
+                _: &ColliderSet,
+                _: &ContactPair,
+                _: crate::math::Real,
+            ) {
+            }
+
+            fn handle_contact_impulse_clamp_event(
+                &self,
+                _: &RigidBodySet,
+                _: &ColliderSet,
+                event: ContactImpulseClampEvent,
+                _: &ContactPair,
+            ) {
+                self.0.lock().unwrap().push(event);
+            }
+        }
+
+        let mut world = TestWorld::new();
+        let events = ClampEvents::default();
+
+        let max_impulse = 0.01;
+        let glass = ColliderBuilder::halfspace(Vector::y_axis())
+            .max_contact_impulse(max_impulse)
+            .build();
+        let glass = world.colliders.insert(glass);
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * 0.5)
+            .build();
+        let body = world.bodies.insert(body);
+        let ball =
+            world
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut world.bodies);
+
+        for _ in 0..2 {
+            world.step_with(&(), &events);
+        }
+
+        let pair = world.narrow_phase.contact_pair(glass, ball).unwrap();
+        assert!(pair.has_any_active_contact);
+        for manifold in &pair.manifolds {
+            for contact in &manifold.points {
+                assert!(contact.data.impulse <= max_impulse);
+            }
+        }
+
+        let events = events.0.into_inner().unwrap();
+        assert!(!events.is_empty());
+        for event in events {
+            assert_eq!(event.collider, glass);
+            assert_eq!(event.other_collider, ball);
+            assert_eq!(event.max_contact_impulse, max_impulse);
+            assert!(event.unclamped_impulse > max_impulse);
+        }
+    }
+
+    #[test]
+    fn exceeding_capacity_hints_grows_the_buffers() {
+        use crate::math::Real;
+
+        let mut pipeline = PhysicsPipeline::with_capacity(1);
+        let mut broad_phase = BroadPhase::new();
+        let mut narrow_phase = NarrowPhase::with_capacity(1, 1);
+        let mut bodies = RigidBodySet::with_capacity(1);
+        let mut colliders = ColliderSet::with_capacity(1);
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut islands = IslandManager::with_capacity(1);
+
+        colliders.insert(ColliderBuilder::halfspace(Vector::y_axis()).build());
+        let handles: Vec<_> = (0..3)
+            .map(|i| {
+                let body = RigidBodyBuilder::dynamic().translation(Vector::y() * (0.5 + i as Real));
+                let body = bodies.insert(body);
+                colliders.insert_with_parent(ColliderBuilder::ball(0.5), body, &mut bodies);
+                body
+            })
+            .collect();
+
+        for _ in 0..10 {
+            pipeline.step(
+                &(Vector::y() * -9.81),
+                &IntegrationParameters::default(),
+                &mut islands,
+                &mut broad_phase,
+                &mut narrow_phase,
+                &mut bodies,
+                &mut colliders,
+                &mut impulse_joints,
+                &mut multibody_joints,
+                &mut CCDSolver::new(),
+                None,
+                &(),
+                &(),
+            );
+        }
+
+        assert_eq!(islands.active_dynamic_bodies().len(), handles.len());
+        let num_touching_pairs = narrow_phase
+            .contact_pairs()
+            .filter(|pair| pair.has_any_active_contact)
+            .count();
+        assert_eq!(num_touching_pairs, 3);
+        for (i, handle) in handles.into_iter().enumerate() {
+            assert!((bodies[handle].translation().y - (0.5 + i as Real)).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn cached_contact_impulses_do_not_warmstart_the_solver() {
+        let mut results = vec![];
+
+        for zero_cached_impulses in [false, true] {
+            let mut world = TestWorld::new();
+
+            let ground = world
+                .colliders
+                .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+            let ball_body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .linvel(Vector::x() * 2.0)
+                .can_sleep(false)
+                .build();
+            let ball_body = world.bodies.insert(ball_body);
+            let ball = world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.5),
+                ball_body,
+                &mut world.bodies,
+            );
+
+            for _ in 0..20 {
+                world.step();
+
+                if zero_cached_impulses {
+                    let mut pair = world
+                        .narrow_phase
+                        .contact_pair(ground, ball)
+                        .unwrap()
+                        .clone();
+                    assert!(pair.has_any_active_contact);
+                    for manifold in &mut pair.manifolds {
+                        for point in &mut manifold.points {
+                            point.data.impulse = 0.0;
+                            point.data.tangent_impulse = na::zero();
+                        }
+                    }
+                    assert!(world.narrow_phase.seed_contact_pair(&world.colliders, pair));
+                }
+            }
+
+            results.push((
+                *world.bodies[ball_body].position(),
+                *world.bodies[ball_body].linvel(),
+            ));
+        }
+
+        // Every step starts from zero impulses, so the cached ones have no influence.
+        assert_eq!(results[0], results[1]);
+    }
+
+    #[test]
+    fn persisting_contacts_are_not_new_after_brief_separations() {
+        let mut rebounds = vec![];
+
+        for persistence_distance in [0.0, 0.05] {
+            let mut world = TestWorld::new();
+            let params = IntegrationParameters {
+                contact_persistence_distance: persistence_distance,
+                ..IntegrationParameters::default()
+            };
+
+            let ground = world
+                .colliders
+                .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+            let ball_body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .can_sleep(false)
+                .build();
+            let ball_body = world.bodies.insert(ball_body);
+            let ball = ColliderBuilder::ball(0.5).restitution(0.8);
+            let ball = world
+                .colliders
+                .insert_with_parent(ball, ball_body, &mut world.bodies);
+
+            let mut max_rebound: crate::math::Real = 0.0;
+            world.params = params;
+            for i in 0..60 {
+                if i == 20 {
+                    // A small kick separates the ball from the ground for a few steps.
+                    world.bodies[ball_body].set_linvel(Vector::y() * 0.5, true);
+                }
+
+                world.step();
+
+                let pair = world.narrow_phase.contact_pair(ground, ball);
+                let height = world.bodies[ball_body].translation().y - 0.5;
+                if height > params.prediction_distance {
+                    // Separated contacts are never active, nor apply any impulse.
+                    assert!(pair.map(|p| !p.has_any_active_contact).unwrap_or(true));
+                    assert!(pair.map(|p| p.total_impulse_magnitude()).unwrap_or(0.0) == 0.0);
+                    let has_points = pair.map(|p| p.manifolds.iter().any(|m| !m.points.is_empty()));
+                    assert_eq!(has_points, Some(persistence_distance > 0.0));
+                }
+
+                if i > 22 {
+                    max_rebound = max_rebound.max(world.bodies[ball_body].linvel().y);
+                }
+            }
+
+            rebounds.push(max_rebound);
+        }
+
+        // Without persistence, the ball bounces as if it was hitting the ground for the first time.
+        assert!(rebounds[0] > 0.1);
+        assert!(rebounds[1] < 0.05);
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "simd-is-enabled",
+        feature = "debug-simd-groups",
+        not(feature = "parallel")
+    ))]
+    fn simd_groups_reflect_the_solver_packing() {
+        use crate::math::SIMD_WIDTH;
+
+        let mut world = TestWorld::new();
+
+        world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+        for i in 0..SIMD_WIDTH + 1 {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * (i as crate::math::Real * 2.0) + Vector::y() * 0.5);
+            let body = world.bodies.insert(body);
+            world
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut world.bodies);
+        }
+
+        world.step();
+
+        // One full group of ground contacts, the remaining contact isn't packed.
+        let groups = world.pipeline.contact_simd_groups(0);
+        assert_eq!(groups.len(), 1);
+        let mut group = groups[0];
+        group.sort();
+        group.windows(2).for_each(|w| assert!(w[0] < w[1]));
+        assert!(group.iter().all(|i| *i <= SIMD_WIDTH));
+        assert!(world.pipeline.joint_simd_groups(0).is_empty());
+    }
+
+    #[test]
+    fn deferred_islands_are_simulated_in_turn() {
+        let mut world = TestWorld::new();
+        let params = IntegrationParameters {
+            min_island_size: 1,
+            max_solved_islands: 1,
+            ..IntegrationParameters::default()
+        };
+
+        let handles: Vec<_> = (1..4)
+            .map(|i| {
+                let speed = i as crate::math::Real;
+                let body = RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * speed * 3.0)
+                    .linvel(Vector::y() * speed);
+                let handle = world.bodies.insert(body);
+                world.colliders.insert_with_parent(
+                    ColliderBuilder::ball(0.5),
+                    handle,
+                    &mut world.bodies,
+                );
+                handle
+            })
+            .collect();
+
+        // The most energetic island is simulated first, then the deferred ones in turn.
+        world.params = params;
+        for expected in handles.iter().rev() {
+            let positions: Vec<_> = handles
+                .iter()
+                .map(|h| *world.bodies[*h].position())
+                .collect();
+            let velocities: Vec<_> = handles.iter().map(|h| *world.bodies[*h].linvel()).collect();
+
+            world.step();
+
+            assert_eq!(world.pipeline.deferred_bodies().len(), 2);
+            for (i, handle) in handles.iter().enumerate() {
+                let moved = *world.bodies[*handle].position() != positions[i];
+                let accelerated = *world.bodies[*handle].linvel() != velocities[i];
+                assert_eq!(moved, handle == expected);
+                assert_eq!(accelerated, handle == expected);
+                assert_eq!(
+                    world.pipeline.deferred_bodies().any(|h| h == *handle),
+                    !moved
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn pipeline_gravity_scale_composes_with_body_gravity_scales() {
+        let mut world = TestWorld::new();
+        let params = world.params;
+
+        let body = RigidBodyBuilder::dynamic().gravity_scale(2.0);
+        let body = world.bodies.insert(body);
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut world.bodies);
+        world.bodies[body].sleep();
+
+        world.gravity = Vector::y() * -10.0;
+        world.step();
+        assert!(world.bodies[body].is_sleeping());
+
+        // Changing the scale wakes the body up, and multiplies its own gravity scale.
+        world.pipeline.set_gravity_scale(0.25);
+        world.step();
+        assert!(!world.bodies[body].is_sleeping());
+        assert!((world.bodies[body].linvel().y + 10.0 * 2.0 * 0.25 * params.dt).abs() < 1.0e-5);
+    }
+
+    #[test]
+    #[cfg(all(feature = "debug-solver-residuals", not(feature = "parallel")))]
+    fn solver_residuals_are_recorded_for_each_iteration() {
+        let mut world = TestWorld::new();
+        let params = world.params;
+
+        world
+            .colliders
+            .insert(ColliderBuilder::ball(10.0).translation(Vector::y() * -10.0));
+        for i in 0..5 {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * (0.5 + i as crate::math::Real * 0.99));
+            let body = world.bodies.insert(body);
+            world
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut world.bodies);
+        }
+
+        assert!(world.pipeline.solver_residuals(0).is_none());
+
+        world.step();
+
+        let residuals = world.pipeline.solver_residuals(0).unwrap();
+        // The friction-only iterations are run after the interleaved ones.
+        assert_eq!(
+            residuals.velocity.len(),
+            params
+                .max_velocity_iterations
+                .max(params.max_velocity_friction_iterations)
+        );
+        assert_eq!(
+            residuals.stabilization.len(),
+            params.max_stabilization_iterations
+        );
+        // The velocity resolution converges.
+        assert!(residuals.velocity[0] > 0.0);
+        assert!(residuals.velocity.last().unwrap() < &residuals.velocity[0]);
+    }
+
+    #[test]
+    fn step_reports_dont_affect_the_simulation() {
+        use crate::dynamics::FixedJointBuilder;
+        use crate::math::Point;
+        use crate::pipeline::StepReport;
+
+        let build_world = || {
+            let mut world = TestWorld::new();
+
+            world
+                .colliders
+                .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+            let mut handles = vec![];
+            for i in 0..3 {
+                let body = RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * (i as crate::math::Real * 3.0) + Vector::y() * 0.4);
+                let body = world.bodies.insert(body);
+                world.colliders.insert_with_parent(
+                    ColliderBuilder::ball(0.5),
+                    body,
+                    &mut world.bodies,
+                );
+                handles.push(body);
+            }
+            let joint = FixedJointBuilder::new().local_anchor1(Point::from(Vector::x() * 3.0));
+            world
+                .impulse_joints
+                .insert(handles[0], handles[1], joint, true);
+            // Don't merge the small islands.
+            world.params.min_island_size = 1;
+            world
+        };
+
+        let mut world1 = build_world();
+        let mut world2 = build_world();
+
+        for _ in 0..10 {
+            world1.step();
+            let report = world2.pipeline.step_with_report(
+                &world2.gravity,
+                &world2.params,
+                &mut world2.islands,
+                &mut world2.broad_phase,
+                &mut world2.narrow_phase,
+                &mut world2.bodies,
+                &mut world2.colliders,
+                &mut world2.impulse_joints,
+                &mut world2.multibody_joints,
+                &mut world2.ccd_solver,
+                None,
+                &(),
+                &(),
+            );
+
+            // Two islands: the jointed balls, and the free ball, each touching the ground.
+            assert_eq!(
+                report,
+                StepReport {
+                    num_substeps: 1,
+                    num_active_islands: 2,
+                    num_solved_islands: 2,
+                    num_contact_manifolds: 3,
+                    num_solver_contacts: 3,
+                    num_impulse_joints: 1,
+                }
+            );
+        }
+
+        for ((_, rb1), (_, rb2)) in world1.bodies.iter().zip(world2.bodies.iter()) {
+            assert_eq!(rb1.position(), rb2.position());
+            assert_eq!(rb1.linvel(), rb2.linvel());
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "debug-solver-residuals", not(feature = "parallel")))]
+    fn min_solver_iterations_only_affect_their_island() {
+        let mut world = TestWorld::new();
+        let params = IntegrationParameters {
+            min_island_size: 1,
+            ..IntegrationParameters::default()
+        };
+
+        // Two stacks far apart, only the second one requiring more iterations.
+        let mut tops = vec![];
+        for (k, min_iterations) in [0, 20].into_iter().enumerate() {
+            let x = k as crate::math::Real * 100.0;
+            world.colliders.insert(
+                ColliderBuilder::ball(10.0).translation(Vector::x() * x + Vector::y() * -10.0),
+            );
+            for i in 0..3 {
+                let body = RigidBodyBuilder::dynamic()
+                    .translation(
+                        Vector::x() * x + Vector::y() * (0.5 + i as crate::math::Real * 0.99),
+                    )
+                    .min_solver_iterations(min_iterations);
+                let body = world.bodies.insert(body);
+                world.colliders.insert_with_parent(
+                    ColliderBuilder::ball(0.5),
+                    body,
+                    &mut world.bodies,
+                );
+                tops.push(body);
+            }
+        }
+
+        world.params = params;
+        world.step();
+
+        let default_iterations = params
+            .max_velocity_iterations
+            .max(params.max_velocity_friction_iterations);
+        let island1 = world.bodies[tops[0]].ids.active_island_id;
+        let island2 = world.bodies[tops[3]].ids.active_island_id;
+        assert_ne!(island1, island2);
+        assert_eq!(
+            world
+                .pipeline
+                .solver_residuals(island1)
+                .unwrap()
+                .velocity
+                .len(),
+            default_iterations
+        );
+        assert_eq!(
+            world
+                .pipeline
+                .solver_residuals(island2)
+                .unwrap()
+                .velocity
+                .len(),
+            20
+        );
+    }
+
+    #[test]
+    fn min_solver_iterations_match_more_global_iterations_for_their_island() {
+        use crate::math::Real;
+
+        // Two stacks far apart, with the given minimum solver iterations.
+        let run = |min_iterations: [usize; 2], max_velocity_iterations| {
+            let mut world = TestWorld::new();
+            let params = IntegrationParameters {
+                min_island_size: 1,
+                max_velocity_iterations,
+                ..IntegrationParameters::default()
+            };
+
+            for (k, min_iterations) in min_iterations.into_iter().enumerate() {
+                let x = k as Real * 100.0;
+                world.colliders.insert(
+                    ColliderBuilder::ball(10.0).translation(Vector::x() * x + Vector::y() * -10.0),
+                );
+                for i in 0..3 {
+                    let body = RigidBodyBuilder::dynamic()
+                        .translation(Vector::x() * x + Vector::y() * (0.5 + i as Real * 0.99))
+                        .min_solver_iterations(min_iterations);
+                    let body = world.bodies.insert(body);
+                    world.colliders.insert_with_parent(
+                        ColliderBuilder::ball(0.5),
+                        body,
+                        &mut world.bodies,
+                    );
+                }
+            }
+
+            world.params = params;
+            for _ in 0..10 {
+                world.step();
+            }
+
+            let velocities: Vec<_> = world
+                .bodies
+                .iter()
+                .map(|(_, body)| *body.linvel())
+                .collect();
+            [velocities[..3].to_vec(), velocities[3..].to_vec()]
+        };
+
+        let default_iterations = IntegrationParameters::default().max_velocity_iterations;
+        let [stack1, stack2] = run([0, 20], default_iterations);
+        let [default_stack1, default_stack2] = run([0, 0], default_iterations);
+        let [_, stack2_with_more_iterations] = run([0, 0], 20);
+
+        // Only the island of the second stack is solved with more iterations.
+        assert_eq!(stack1, default_stack1);
+        assert_eq!(stack2, stack2_with_more_iterations);
+        assert_ne!(stack2, default_stack2);
+    }
+
+    #[test]
+    #[cfg(all(feature = "enhanced-determinism", feature = "dim3", feature = "f32"))]
+    fn enhanced_determinism_matches_the_golden_trajectory() {
+        use crate::dynamics::{RevoluteJointBuilder, RigidBodyHandle};
+        use crate::math::Point;
+
+        let mut world = TestWorld::new();
+
+        world
+            .colliders
+            .insert(ColliderBuilder::cuboid(10.0, 0.1, 10.0));
+        let cube = RigidBodyBuilder::dynamic()
+            .translation(Vector::new(0.0, 2.0, 0.0))
+            .rotation(Vector::new(0.3, 0.2, 0.1))
+            .angvel(Vector::new(1.0, -2.0, 0.5));
+        let cube = world.bodies.insert(cube);
+        world.colliders.insert_with_parent(
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+            cube,
+            &mut world.bodies,
+        );
+
+        // A limited pendulum exercising the trigonometry of the multibody joints.
+        let anchor = world
+            .bodies
+            .insert(RigidBodyBuilder::fixed().translation(Vector::new(5.0, 3.0, 0.0)));
+        let bob = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::new(6.0, 3.0, 0.0)));
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.25), bob, &mut world.bodies);
+        let joint = RevoluteJointBuilder::new(Vector::z_axis())
+            .local_anchor2(Point::new(-1.0, 0.0, 0.0))
+            .limits([-1.0, 0.5]);
+        world.multibody_joints.insert(anchor, bob, joint, true);
+
+        for _ in 0..120 {
+            world.step();
+        }
+
+        let bits = |body: RigidBodyHandle| {
+            let pos = world.bodies[body].position();
+            [
+                pos.translation.x,
+                pos.translation.y,
+                pos.translation.z,
+                pos.rotation.i,
+                pos.rotation.j,
+                pos.rotation.k,
+                pos.rotation.w,
+            ]
+            .map(|x| x.to_bits())
+        };
+
+        // NOTE: these were recorded with the `enhanced-determinism` feature enabled, and must be
+        //       reproduced bit-for-bit on every platform.
+        assert_eq!(
+            bits(cube),
+            [0xbdfcb96e, 0x3f192c72, 0xbda27aad, 0x3ef31c84, 0xbf062448, 0x3f0620e7, 0x3ef31c6c]
+        );
+        assert_eq!(
+            bits(bob),
+            [0x40b14040, 0x400a18a3, 0x33800000, 0x00000000, 0xb2000000, 0xbef5c9de, 0x3f6092ab]
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "enhanced-determinism", feature = "dim3", feature = "f32"))]
+    fn enhanced_determinism_matches_with_and_without_parallel() {
+        use crate::dynamics::{MultibodyJointHandle, SphericalJointBuilder};
+        use crate::math::Point;
+
+        let simulate = |sort_parallel_constraints| {
+            let mut world = TestWorld::new();
+
+            world
+                .colliders
+                .insert(ColliderBuilder::cuboid(20.0, 0.1, 20.0));
+
+            // A pyramid of boxes, solved with many contacts per body.
+            for layer in 0..5 {
+                for i in 0..5 - layer {
+                    let x = i as f32 * 1.05 + layer as f32 * 0.525;
+                    let y = 0.6 + layer as f32 * 1.01;
+                    let body = world
+                        .bodies
+                        .insert(RigidBodyBuilder::dynamic().translation(Vector::new(x, y, 0.0)));
+                    world.colliders.insert_with_parent(
+                        ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+                        body,
+                        &mut world.bodies,
+                    );
+                }
+            }
+
+            // A chain of impulse joints, in a separate island.
+            let mut parent = world
+                .bodies
+                .insert(RigidBodyBuilder::fixed().translation(Vector::new(-5.0, 6.0, 0.0)));
+            for i in 1..6 {
+                let link =
+                    world
+                        .bodies
+                        .insert(RigidBodyBuilder::dynamic().translation(Vector::new(
+                            -5.0 + i as f32,
+                            6.0,
+                            0.0,
+                        )));
+                world.colliders.insert_with_parent(
+                    ColliderBuilder::ball(0.25),
+                    link,
+                    &mut world.bodies,
+                );
+                let joint = SphericalJointBuilder::new().local_anchor2(Point::new(-1.0, 0.0, 0.0));
+                world.impulse_joints.insert(parent, link, joint, true);
+                parent = link;
+            }
+
+            // A chain of multibody joints, touching the pyramid.
+            let mut parent = world
+                .bodies
+                .insert(RigidBodyBuilder::fixed().translation(Vector::new(1.0, 7.0, 0.0)));
+            for i in 1..4 {
+                let link =
+                    world
+                        .bodies
+                        .insert(RigidBodyBuilder::dynamic().translation(Vector::new(
+                            1.0 + i as f32 * 0.6,
+                            7.0,
+                            0.0,
+                        )));
+                world.colliders.insert_with_parent(
+                    ColliderBuilder::ball(0.25),
+                    link,
+                    &mut world.bodies,
+                );
+                let joint = SphericalJointBuilder::new().local_anchor2(Point::new(-0.6, 0.0, 0.0));
+                let handle: Option<MultibodyJointHandle> =
+                    world.multibody_joints.insert(parent, link, joint, true);
+                assert!(handle.is_some());
+                parent = link;
+            }
+
+            let params = IntegrationParameters {
+                sort_parallel_constraints,
+                ..IntegrationParameters::default()
+            };
+
+            world.params = params;
+            for _ in 0..120 {
+                world.step();
+            }
+
+            // FNV-1a hash of the bits of the positions and velocities of every body.
+            let mut hash = 0xcbf29ce484222325u64;
+            for (_, body) in world.bodies.iter() {
+                let pos = body.position();
+                let values = [
+                    pos.translation.x,
+                    pos.translation.y,
+                    pos.translation.z,
+                    pos.rotation.i,
+                    pos.rotation.j,
+                    pos.rotation.k,
+                    pos.rotation.w,
+                    body.linvel().x,
+                    body.linvel().y,
+                    body.linvel().z,
+                    body.angvel().x,
+                    body.angvel().y,
+                    body.angvel().z,
+                ];
+                for value in values {
+                    hash = (hash ^ value.to_bits() as u64).wrapping_mul(0x100000001b3);
+                }
+            }
+
+            hash
+        };
+
+        // NOTE: these were recorded without the `parallel` feature, and must be reproduced
+        //       bit-for-bit with it.
+        assert_eq!(simulate(false), 0x961e90d1587881a8);
+        assert_eq!(simulate(true), 0x2c14a381928b095c);
+    }
+
+    #[test]
+    fn identical_runs_give_identical_results() {
+        use crate::math::Real;
+
+        let run = || {
+            let mut world = TestWorld::new();
+            let params = IntegrationParameters {
+                sort_parallel_constraints: true,
+                ..IntegrationParameters::default()
+            };
+
+            world
+                .colliders
+                .insert(ColliderBuilder::halfspace(Vector::y_axis()));
+
+            // A pile of balls, with many contacts between them.
+            for layer in 0..6 {
+                for i in 0..6 {
+                    let translation = Vector::x() * (i as Real * 0.9 + layer as Real * 0.3)
+                        + Vector::y() * (0.5 + layer as Real * 0.95);
+                    let body = world
+                        .bodies
+                        .insert(RigidBodyBuilder::dynamic().translation(translation));
+                    world.colliders.insert_with_parent(
+                        ColliderBuilder::ball(0.5),
+                        body,
+                        &mut world.bodies,
+                    );
+                }
+            }
+
+            world.params = params;
+            for _ in 0..100 {
+                world.step();
+            }
+
+            world
+                .bodies
+                .iter()
+                .map(|(_, body)| {
+                    #[cfg(feature = "dim2")]
+                    let angvel = body.angvel();
+                    #[cfg(feature = "dim3")]
+                    let angvel = *body.angvel();
+                    (*body.position(), *body.linvel(), angvel)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Without any change to the inputs, two runs must match bit-for-bit.
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    #[cfg(any(feature = "parallel", feature = "enhanced-determinism"))]
+    fn sorted_constraints_dont_depend_on_the_joint_insertion_order() {
+        use crate::dynamics::RevoluteJointBuilder;
+        use crate::math::{Point, Real};
+
+        let run = |sort_parallel_constraints: bool, reverse_joints: bool| {
+            let mut world = TestWorld::new();
+            let params = IntegrationParameters {
+                sort_parallel_constraints,
+                ..IntegrationParameters::default()
+            };
+
+            // A net of world.bodies hanging from its corner, with several joints per body.
+            let n = 4;
+            let mut handles = vec![];
+            for i in 0..n {
+                for j in 0..n {
+                    let body = if i == 0 && j == 0 {
+                        RigidBodyBuilder::fixed()
+                    } else {
+                        RigidBodyBuilder::dynamic()
+                    };
+                    let translation = Vector::x() * i as Real - Vector::y() * j as Real;
+                    let handle = world.bodies.insert(body.translation(translation));
+                    world.colliders.insert_with_parent(
+                        ColliderBuilder::ball(0.25),
+                        handle,
+                        &mut world.bodies,
+                    );
+                    handles.push(handle);
+                }
+            }
+
+            let mut joints = vec![];
+            for i in 0..n {
+                for j in 0..n {
+                    if i + 1 < n {
+                        joints.push((i * n + j, (i + 1) * n + j, -Vector::x()));
+                    }
+                    if j + 1 < n {
+                        joints.push((i * n + j, i * n + j + 1, Vector::y()));
+                    }
+                }
+            }
+            if reverse_joints {
+                joints.reverse();
+            }
+
+            for (body1, body2, anchor2) in joints {
+                #[cfg(feature = "dim2")]
+                let joint = RevoluteJointBuilder::new();
+                #[cfg(feature = "dim3")]
+                let joint = RevoluteJointBuilder::new(Vector::z_axis());
+                let joint = joint.local_anchor2(Point::from(anchor2));
+                world
+                    .impulse_joints
+                    .insert(handles[body1], handles[body2], joint, true);
+            }
+
+            world.params = params;
+            for _ in 0..50 {
+                world.step();
+            }
+
+            world
+                .bodies
+                .iter()
+                .map(|(_, body)| {
+                    #[cfg(feature = "dim2")]
+                    let angvel = body.angvel();
+                    #[cfg(feature = "dim3")]
+                    let angvel = *body.angvel();
+                    (*body.position(), *body.linvel(), angvel)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The constraints are solved in an order depending on the order of the joints, unless
+        // they are sorted.
+        assert!(run(false, false) != run(false, true));
+        assert!(run(true, false) == run(true, true));
+    }
+
+    #[test]
+    fn kinematic_paddles_impart_their_velocity_to_bouncing_bodies() {
+        use crate::math::Real;
+
+        // Returns the velocity of a ball hitting a paddle moving along `y` at `paddle_vel`.
+        let bounce = |position_based: bool, paddle_vel: Real| {
+            let mut world = TestWorld::new();
+            let params = world.params;
+
+            let paddle = if position_based {
+                RigidBodyBuilder::kinematic_position_based()
+            } else {
+                RigidBodyBuilder::kinematic_velocity_based().linvel(Vector::y() * paddle_vel)
+            };
+            let paddle = world.bodies.insert(paddle);
+            let paddle_co = ColliderBuilder::ball(1.0).restitution(1.0);
+            world
+                .colliders
+                .insert_with_parent(paddle_co, paddle, &mut world.bodies);
+            let ball = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 3.0)
+                .linvel(Vector::y() * -5.0);
+            let ball = world.bodies.insert(ball);
+            let ball_co = ColliderBuilder::ball(0.5).restitution(1.0);
+            world
+                .colliders
+                .insert_with_parent(ball_co, ball, &mut world.bodies);
+
+            world.gravity = Vector::zeros();
+            for _ in 0..30 {
+                if position_based {
+                    let next =
+                        world.bodies[paddle].translation() + Vector::y() * paddle_vel * params.dt;
+                    world.bodies[paddle].set_next_kinematic_translation(next);
+                }
+
+                world.step();
+            }
+
+            world.bodies[ball].linvel().y
+        };
+
+        // A still paddle doesn’t inject any energy.
+        assert!((bounce(false, 0.0) - 5.0).abs() < 1.0e-3);
+        assert!((bounce(true, 0.0) - 5.0).abs() < 1.0e-3);
+        // A moving paddle adds twice its velocity with a restitution of 1.
+        assert!((bounce(false, 5.0) - 15.0).abs() < 1.0e-3);
+        assert!((bounce(true, 5.0) - 15.0).abs() < 1.0e-3);
+    }
+
+    #[test]
+    fn constraint_impulses_are_resolved_with_the_contacts() {
+        let mut world = TestWorld::new();
+
+        world
+            .colliders
+            .insert(ColliderBuilder::halfspace(Vector::y_axis()).restitution(1.0));
+        let resting = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        let co = ColliderBuilder::ball(0.5).restitution(1.0);
+        world
+            .colliders
+            .insert_with_parent(co, resting, &mut world.bodies);
+        let free = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 10.0));
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), free, &mut world.bodies);
+        let free_mass = world.bodies[free].mass();
+
+        world.gravity = Vector::zeros();
+        world.step();
+
+        // The impulses are accumulated, and applied only once.
+        world.bodies[free].add_constraint_impulse(Vector::x() * free_mass, true);
+        world.bodies[free].add_constraint_impulse(Vector::x() * free_mass, true);
+        assert_eq!(world.bodies[free].linvel(), &Vector::zeros());
+        world.step();
+        assert!((world.bodies[free].linvel().x - 2.0).abs() < 1.0e-5);
+        world.step();
+        assert!((world.bodies[free].linvel().x - 2.0).abs() < 1.0e-5);
+
+        // The ground pushes back against the impulse in the same timestep, without any bounce.
+        for _ in 0..10 {
+            world.bodies[resting].add_constraint_impulse(Vector::y() * -10.0, true);
+            world.step();
+            assert!(world.bodies[resting].linvel().norm() < 1.0e-3);
+        }
+    }
+
+    #[test]
+    fn constraint_impulses_of_deferred_islands_are_kept() {
+        let mut world = TestWorld::new();
+        world.gravity = Vector::zeros();
+        world.params.min_island_size = 1;
+        world.params.max_solved_islands = 1;
+
+        let moving = RigidBodyBuilder::dynamic().linvel(Vector::y());
+        world.insert(moving, ColliderBuilder::ball(0.5));
+        let still = RigidBodyBuilder::dynamic().translation(Vector::x() * 3.0);
+        let (still, _) = world.insert(still, ColliderBuilder::ball(0.5));
+        let mass = world.bodies[still].mass();
+
+        // The most energetic island is simulated first, so the impulse is applied once the
+        // island of `still` is simulated at the next timestep.
+        world.bodies[still].add_constraint_impulse(Vector::x() * mass, true);
+        world.step();
+        assert!(world.pipeline.deferred_bodies().any(|h| h == still));
+        assert_eq!(world.bodies[still].linvel(), &Vector::zeros());
+        world.step();
+        assert!(!world.pipeline.deferred_bodies().any(|h| h == still));
+        assert!((world.bodies[still].linvel().x - 1.0).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn ccd_only_prevents_tunneling_between_compatible_ccd_groups() {
+        use crate::geometry::{Group, InteractionGroups};
+
+        let mut world = TestWorld::new();
+        world.gravity = Vector::zeros();
+
+        // A thin wall, and two bullets crossing it in a single timestep.
+        let walls = InteractionGroups::new(Group::GROUP_1, Group::GROUP_1);
+        #[cfg(feature = "dim2")]
+        let wall = ColliderBuilder::cuboid(0.05, 20.0);
+        #[cfg(feature = "dim3")]
+        let wall = ColliderBuilder::cuboid(0.05, 20.0, 20.0);
+        world
+            .colliders
+            .insert(wall.translation(Vector::x() * 5.0).ccd_groups(walls));
+
+        let mut bullets = vec![];
+        for (y, ccd_groups) in [
+            (0.0, InteractionGroups::all()),
+            (10.0, InteractionGroups::new(Group::GROUP_2, Group::GROUP_2)),
+        ] {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * y)
+                .linvel(Vector::x() * 600.0)
+                .ccd_enabled(true);
+            let collider = ColliderBuilder::ball(0.1).ccd_groups(ccd_groups);
+            bullets.push(world.insert(body, collider).0);
+        }
+
+        world.step();
+        assert!(world.bodies[bullets[0]].translation().x < 5.0);
+        assert!(world.bodies[bullets[1]].translation().x > 5.0);
+    }
 }
@@ -1,10 +1,14 @@
-use crate::dynamics::RigidBodyHandle;
+use crate::dynamics::{RigidBodyHandle, RigidBodyType};
 use crate::geometry::{
-    Aabb, Collider, ColliderHandle, InteractionGroups, PointProjection, Qbvh, Ray, RayIntersection,
+    Aabb, Collider, ColliderHandle, ContactManifold, InteractionGroups, NarrowPhase,
+    PointProjection, Qbvh, Ray, RayIntersection,
 };
-use crate::math::{Isometry, Point, Real, Vector};
+use crate::math::{Isometry, Point, Real, SimdBool, SimdReal, Vector, SIMD_WIDTH};
 use crate::{dynamics::RigidBodySet, geometry::ColliderSet};
-use parry::partitioning::{QbvhDataGenerator, QbvhUpdateWorkspace};
+use parry::bounding_volume::{BoundingVolume, SimdAabb};
+use parry::partitioning::{
+    QbvhDataGenerator, QbvhUpdateWorkspace, SimdBestFirstVisitStatus, SimdBestFirstVisitor,
+};
 use parry::query::details::{
     IntersectionCompositeShapeShapeBestFirstVisitor,
     NonlinearTOICompositeShapeShapeBestFirstVisitor, PointCompositeShapeProjBestFirstVisitor,
@@ -15,11 +19,37 @@ use parry::query::details::{
 use parry::query::visitors::{
     BoundingVolumeIntersectionsVisitor, PointIntersectionsVisitor, RayIntersectionsVisitor,
 };
-use parry::query::{DefaultQueryDispatcher, NonlinearRigidMotion, QueryDispatcher, TOI};
-use parry::shape::{FeatureId, Shape, TypedSimdCompositeShape};
-use parry::utils::DefaultStorage;
+use parry::query::{
+    Contact, DefaultQueryDispatcher, NonlinearRigidMotion, QueryDispatcher, SimdRay, TOIStatus,
+    Unsupported, TOI,
+};
+use parry::shape::{FeatureId, Shape, SimdCompositeShape, TypedSimdCompositeShape};
+use parry::utils::{DefaultStorage, IsometryOpt};
+use simba::simd::{SimdBool as _, SimdPartialOrd, SimdValue};
 use std::sync::Arc;
 
+/// Maximum number of conservative advancement steps of the inflated shape-casts.
+const INFLATED_CAST_MAX_ITERATIONS: usize = 100;
+/// Distance from the inflated shape under which an inflated shape-cast is considered converged.
+const INFLATED_CAST_TOLERANCE: Real = 1.0e-5;
+
+/// The intersection between a ray and a collider, expressed in the local-space of the collider.
+///
+/// See [`QueryPipeline::cast_ray_and_get_local_hit`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LocalRayHit {
+    /// The hit point, in the local-space of the collider.
+    pub point: Point<Real>,
+    /// The normal of the collider's surface at the hit point, in the local-space of the collider.
+    pub normal: Vector<Real>,
+    /// The time of impact of the ray with the collider.
+    pub toi: Real,
+    /// The index of the hit part, if the collider is a compound shape.
+    pub subshape: Option<u32>,
+    /// The feature of the collider's shape (or of the hit part of a compound shape) that was hit.
+    pub feature: FeatureId,
+}
+
 /// A pipeline for performing queries on all the colliders of a scene.
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone)]
@@ -48,9 +78,9 @@ bitflags::bitflags! {
     pub struct QueryFilterFlags: u32 {
         /// Exclude from the query any collider attached to a fixed rigid-body and colliders with no rigid-body attached.
         const EXCLUDE_FIXED = 1 << 1;
-        /// Exclude from the query any collider attached to a dynamic rigid-body.
-        const EXCLUDE_KINEMATIC = 1 << 2;
         /// Exclude from the query any collider attached to a kinematic rigid-body.
+        const EXCLUDE_KINEMATIC = 1 << 2;
+        /// Exclude from the query any collider attached to a dynamic rigid-body.
         const EXCLUDE_DYNAMIC = 1 << 3;
         /// Exclude from the query any collider that is a sensor.
         const EXCLUDE_SENSORS = 1 << 4;
@@ -63,6 +93,8 @@ bitflags::bitflags! {
         /// Exclude all colliders attached to a non-fixed rigid-body
         /// (this will not exclude colliders not attached to any rigid-body).
         const ONLY_FIXED = Self::EXCLUDE_DYNAMIC.bits | Self::EXCLUDE_KINEMATIC.bits;
+        /// All the flags filtering colliders based on the type of their parent rigid-body.
+        const BODY_TYPE_FLAGS = Self::EXCLUDE_FIXED.bits | Self::EXCLUDE_KINEMATIC.bits | Self::EXCLUDE_DYNAMIC.bits;
     }
 }
 
@@ -82,23 +114,48 @@ impl QueryFilterFlags {
             return false;
         }
 
-        if self.contains(QueryFilterFlags::EXCLUDE_FIXED) && collider.parent.is_none() {
-            return false;
+        if !self.intersects(QueryFilterFlags::BODY_TYPE_FLAGS) {
+            // No need to look at the parent rigid-body.
+            return true;
         }
 
-        if let Some(parent) = collider.parent.and_then(|p| bodies.get(p.handle)) {
-            let parent_type = parent.body_type();
+        // Colliders without parent (or with a parent that no longer exists) behave
+        // like colliders attached to a fixed rigid-body.
+        let parent_type = collider
+            .parent
+            .and_then(|p| bodies.get(p.handle))
+            .map(|rb| rb.body_type())
+            .unwrap_or(RigidBodyType::Fixed);
+
+        !((self.contains(QueryFilterFlags::EXCLUDE_FIXED) && parent_type.is_fixed())
+            || (self.contains(QueryFilterFlags::EXCLUDE_KINEMATIC) && parent_type.is_kinematic())
+            || (self.contains(QueryFilterFlags::EXCLUDE_DYNAMIC) && parent_type.is_dynamic()))
+    }
 
-            if (self.contains(QueryFilterFlags::EXCLUDE_FIXED) && parent_type.is_fixed())
-                || (self.contains(QueryFilterFlags::EXCLUDE_KINEMATIC)
-                    && parent_type.is_kinematic())
-                || (self.contains(QueryFilterFlags::EXCLUDE_DYNAMIC) && parent_type.is_dynamic())
-            {
-                return false;
-            }
+    /// The flags excluding every collider that isn’t attached to a rigid-body with the given type.
+    ///
+    /// Colliders without any parent rigid-body are considered to be attached to a fixed rigid-body.
+    pub fn only_body_type(body_type: RigidBodyType) -> Self {
+        if body_type.is_dynamic() {
+            QueryFilterFlags::ONLY_DYNAMIC
+        } else if body_type.is_kinematic() {
+            QueryFilterFlags::ONLY_KINEMATIC
+        } else {
+            QueryFilterFlags::ONLY_FIXED
         }
+    }
 
-        true
+    /// The flags excluding every collider attached to a rigid-body with the given type.
+    ///
+    /// Colliders without any parent rigid-body are considered to be attached to a fixed rigid-body.
+    pub fn exclude_body_type(body_type: RigidBodyType) -> Self {
+        if body_type.is_dynamic() {
+            QueryFilterFlags::EXCLUDE_DYNAMIC
+        } else if body_type.is_kinematic() {
+            QueryFilterFlags::EXCLUDE_KINEMATIC
+        } else {
+            QueryFilterFlags::EXCLUDE_FIXED
+        }
     }
 }
 
@@ -164,12 +221,12 @@ impl<'a> QueryFilter<'a> {
         QueryFilterFlags::EXCLUDE_FIXED.into()
     }
 
-    /// Exclude from the query any collider attached to a dynamic rigid-body.
+    /// Exclude from the query any collider attached to a kinematic rigid-body.
     pub fn exclude_kinematic() -> Self {
         QueryFilterFlags::EXCLUDE_KINEMATIC.into()
     }
 
-    /// Exclude from the query any collider attached to a kinematic rigid-body.
+    /// Exclude from the query any collider attached to a dynamic rigid-body.
     pub fn exclude_dynamic() -> Self {
         QueryFilterFlags::EXCLUDE_DYNAMIC.into()
     }
@@ -190,6 +247,15 @@ impl<'a> QueryFilter<'a> {
         QueryFilterFlags::ONLY_FIXED.into()
     }
 
+    /// Exclude from the query any collider attached to a rigid-body with the given type.
+    ///
+    /// Colliders without any parent rigid-body are considered to be attached to a fixed rigid-body.
+    /// This can be called several times to exclude multiple rigid-body types.
+    pub fn exclude_body_type(mut self, body_type: RigidBodyType) -> Self {
+        self.flags |= QueryFilterFlags::exclude_body_type(body_type);
+        self
+    }
+
     /// Exclude from the query any collider that is a sensor.
     pub fn exclude_sensors(mut self) -> Self {
         self.flags |= QueryFilterFlags::EXCLUDE_SENSORS;
@@ -340,19 +406,40 @@ impl QueryPipeline {
 
         for modified in modified_colliders {
             // Check that the collider still exists as it may have been removed.
-            if colliders.contains(*modified) {
-                self.qbvh.pre_update_or_insert(*modified);
+            // Disabled colliders are taken out of the tree, just like with a full `update`.
+            match colliders.get(*modified) {
+                Some(co) if co.is_enabled() => self.qbvh.pre_update_or_insert(*modified),
+                Some(_) => {
+                    let _ = self.qbvh.remove(*modified);
+                }
+                None => {}
             }
         }
 
         if refit_and_rebalance {
             let _ = self.qbvh.refit(0.0, &mut self.workspace, |handle| {
-                colliders[*handle].compute_aabb()
+                // The collider may have been removed without being reported as such.
+                colliders
+                    .get(*handle)
+                    .map(|co| co.compute_aabb())
+                    .unwrap_or_else(Aabb::new_invalid)
             });
             self.qbvh.rebalance(0.0, &mut self.workspace);
         }
     }
 
+    /// The AABB enclosing every enabled collider of the scene, as of the last update of
+    /// this query pipeline.
+    ///
+    /// This is the root AABB of the acceleration structure so it is free to read. It is
+    /// conservative: it is slightly dilated, and it may stay larger than necessary after colliders
+    /// shrink, move inward, or are removed, until the next full [`QueryPipeline::update`].
+    /// The AABB is invalid (its `mins` are greater than its `maxs`) if the pipeline was never
+    /// updated or contains no collider.
+    pub fn scene_aabb(&self) -> Aabb {
+        *self.qbvh.root_aabb()
+    }
+
     /// Update the acceleration structure on the query pipeline.
     pub fn update(&mut self, bodies: &RigidBodySet, colliders: &ColliderSet) {
         self.update_with_mode(bodies, colliders, QueryPipelineMode::CurrentPosition)
@@ -386,8 +473,13 @@ impl QueryPipeline {
                     }
                     QueryPipelineMode::SweepTestWithNextPosition => {
                         for (h, co) in self.colliders.iter_enabled() {
-                            if let Some(co_parent) = co.parent {
-                                let rb_next_pos = &self.bodies[co_parent.handle].pos.next_position;
+                            // NOTE: the parent may not exist anymore if it was removed since
+                            //       the last timestep.
+                            if let Some((co_parent, rb)) = co
+                                .parent
+                                .and_then(|p| Some((p, self.bodies.get(p.handle)?)))
+                            {
+                                let rb_next_pos = &rb.pos.next_position;
                                 let next_position = rb_next_pos * co_parent.pos_wrt_parent;
                                 f(h, co.shape.compute_swept_aabb(&co.pos, &next_position))
                             } else {
@@ -397,8 +489,10 @@ impl QueryPipeline {
                     }
                     QueryPipelineMode::SweepTestWithPredictedPosition { dt } => {
                         for (h, co) in self.colliders.iter_enabled() {
-                            if let Some(co_parent) = co.parent {
-                                let rb = &self.bodies[co_parent.handle];
+                            if let Some((co_parent, rb)) = co
+                                .parent
+                                .and_then(|p| Some((p, self.bodies.get(p.handle)?)))
+                            {
                                 let predicted_pos = rb.pos.integrate_forces_and_velocities(
                                     dt, &rb.forces, &rb.vels, &rb.mprops,
                                 );
@@ -460,6 +554,13 @@ impl QueryPipeline {
     ///            it starts inside of a shape. If this `false` then the ray will hit the shape's boundary
     ///            even if its starts inside of it.
     /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
+    ///
+    /// For a ray starting outside of the hit shape, the returned normal is the unit outward normal
+    /// of the shape’s surface at the hit point (including for a ray grazing a ball, a capsule, or a
+    /// shape with round corners, even as part of a compound shape, which hits it at the tangent
+    /// point). For triangle meshes and heightfields, this is the normal of the hit triangle,
+    /// oriented toward the side of the triangle the ray comes from. If `solid` is `true` and the
+    /// ray starts inside of the shape, the time of impact is zero and the normal isn't meaningful.
     pub fn cast_ray_and_get_normal(
         &self,
         bodies: &RigidBodySet,
@@ -470,16 +571,64 @@ impl QueryPipeline {
         filter: QueryFilter,
     ) -> Option<(ColliderHandle, RayIntersection)> {
         let pipeline_shape = self.as_composite_shape(bodies, colliders, filter);
-        let mut visitor = RayCompositeShapeToiAndNormalBestFirstVisitor::new(
-            &pipeline_shape,
-            ray,
-            max_toi,
-            solid,
-        );
+        let mut visitor =
+            RayToiAndNormalBestFirstVisitor::new(&pipeline_shape, ray, max_toi, solid);
 
         self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
     }
 
+    /// Find the closest intersection between a ray and a set of collider, expressed in the
+    /// local-space of the hit collider.
+    ///
+    /// This is the same as [`QueryPipeline::cast_ray_and_get_normal`], except that the hit point
+    /// and normal are expressed in the local-space of the hit collider, so they remain attached
+    /// to it as it moves (e.g. to place a decal). The world-space hit point at any later time is
+    /// given by `collider.position() * hit.point`. If the collider is a compound shape, the hit
+    /// point is relative to the compound itself (not to the hit part), and the index of the hit
+    /// part is given by `hit.subshape`.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `ray`: the ray to cast.
+    /// * `max_toi`: the maximum time-of-impact that can be reported by this cast.
+    /// * `solid`: if this is `true` an impact at time 0.0 (i.e. at the ray origin) is returned if
+    ///            it starts inside of a shape.
+    /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
+    pub fn cast_ray_and_get_local_hit(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+        filter: QueryFilter,
+    ) -> Option<(ColliderHandle, LocalRayHit)> {
+        let (handle, hit) =
+            self.cast_ray_and_get_normal(bodies, colliders, ray, max_toi, solid, filter)?;
+        let co = &colliders[handle];
+        let local_ray = ray.inverse_transform_by(&co.pos);
+        let mut local_hit = LocalRayHit {
+            point: local_ray.point_at(hit.toi),
+            normal: co.pos.inverse_transform_vector(&hit.normal),
+            toi: hit.toi,
+            subshape: None,
+            feature: hit.feature,
+        };
+
+        if let Some(compound) = co.shape.as_compound() {
+            // Cast again to retrieve the index of the hit part.
+            let mut visitor =
+                RayToiAndNormalBestFirstVisitor::new(compound, &local_ray, max_toi, solid);
+
+            if let Some((_, (part, part_hit))) = compound.qbvh().traverse_best_first(&mut visitor) {
+                local_hit.subshape = Some(part);
+                local_hit.feature = part_hit.feature;
+            }
+        }
+
+        Some((handle, local_hit))
+    }
+
     /// Find the all intersections between a ray and a set of collider and passes them to a callback.
     ///
     /// # Parameters
@@ -507,9 +656,8 @@ impl QueryPipeline {
         let mut leaf_callback = &mut |handle: &ColliderHandle| {
             if let Some(co) = colliders.get(*handle) {
                 if filter.test(bodies, *handle, co) {
-                    if let Some(hit) = co
-                        .shape
-                        .cast_ray_and_get_normal(&co.pos, ray, max_toi, solid)
+                    if let Some(hit) =
+                        cast_ray_and_get_normal(&*co.shape, &co.pos, ray, max_toi, solid)
                     {
                         return callback(*handle, hit);
                     }
@@ -579,6 +727,37 @@ impl QueryPipeline {
             .map(|h| (h.1 .1, h.1 .0))
     }
 
+    /// Find the projection of a point on the boundary of the closest collider.
+    ///
+    /// Unlike [`QueryPipeline::project_point`] with `solid` set to `true`, the point is always
+    /// projected on the collider’s boundary, even if it is located inside of the collider. The
+    /// returned [`PointProjection::is_inside`] indicates if the point was inside of the collider.
+    ///
+    /// Returns the handle of the closest collider, the projection of the point on its boundary, and
+    /// a boolean indicating if the projected point is located at a distance smaller or equal to
+    /// `max_dist` from `point`.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `point` - The point to project.
+    /// * `max_dist` - The maximum distance between `point` and its projection for the projection
+    ///   to be flagged as being within range.
+    /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
+    pub fn project_point_on_surface(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        point: &Point<Real>,
+        max_dist: Real,
+        filter: QueryFilter,
+    ) -> Option<(ColliderHandle, PointProjection, bool)> {
+        self.project_point(bodies, colliders, point, false, filter)
+            .map(|(handle, proj)| {
+                let within_range = na::distance(point, &proj.point) <= max_dist;
+                (handle, proj, within_range)
+            })
+    }
+
     /// Find all the colliders containing the given point.
     ///
     /// # Parameters
@@ -654,6 +833,10 @@ impl QueryPipeline {
     /// point (the ray origin). In the resulting `TOI`, witness and normal 1 refer to the world
     /// collider, and are in world space.
     ///
+    /// The surface properties of the hit collider (friction, restitution) can be read from
+    /// `colliders[handle].material()`, where `handle` is the returned collider handle. Note
+    /// that all the parts of a compound shape share the material of the collider they are part of.
+    ///
     /// # Parameters
     /// * `colliders` - The set of colliders taking part in this pipeline.
     /// * `shape_pos` - The initial position of the shape to cast.
@@ -689,10 +872,114 @@ impl QueryPipeline {
         self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
     }
 
+    /// Casts a shape inflated by a margin at a constant linear velocity and retrieve the first
+    /// collider it hits.
+    ///
+    /// This is the same as [`QueryPipeline::cast_shape`], except that the impact happens as soon
+    /// as the distance between `shape` and a collider becomes smaller than `margin`. This is
+    /// equivalent to casting `shape` dilated by a ball of radius `margin` (i.e. inflated by
+    /// `margin` in every direction) and is useful to detect near-misses. In the resulting `TOI`,
+    /// the witness points are on the surfaces of the collider and of the non-inflated `shape`.
+    /// A zero `margin` gives the same result as `cast_shape`.
+    ///
+    /// If `shape` starts at a distance smaller than `margin` from a collider, the returned `TOI`
+    /// has a `toi` of zero and a `Penetrating` status, unless `stop_at_penetration` is `false` and
+    /// `shape_vel` is moving `shape` away from the collider.
+    ///
+    /// Heightfields are tested triangle by triangle (or segment by segment in 2D). Returns
+    /// `Err(Unsupported)` if the distance between `shape` and one of the colliders it may hit
+    /// can't be computed by the query dispatcher of this pipeline, instead of ignoring that
+    /// collider.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `shape_pos` - The initial position of the shape to cast.
+    /// * `shape_vel` - The constant velocity of the shape to cast (i.e. the cast direction).
+    /// * `shape` - The shape to cast.
+    /// * `margin` - The non-negative distance by which `shape` is inflated.
+    /// * `max_toi` - The maximum time-of-impact that can be reported by this cast.
+    /// * `stop_at_penetration` - If set to `false`, the shape-cast ignores colliders closer than
+    ///   `margin` at its starting point if its trajectory is moving it away from them.
+    /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
+    pub fn cast_inflated_shape(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        shape_pos: &Isometry<Real>,
+        shape_vel: &Vector<Real>,
+        shape: &dyn Shape,
+        margin: Real,
+        max_toi: Real,
+        stop_at_penetration: bool,
+        filter: QueryFilter,
+    ) -> Result<Option<(ColliderHandle, TOI)>, Unsupported> {
+        if margin <= 0.0 {
+            return Ok(self.cast_shape(
+                bodies,
+                colliders,
+                shape_pos,
+                shape_vel,
+                shape,
+                max_toi,
+                stop_at_penetration,
+                filter,
+            ));
+        }
+
+        let dispatcher = &*self.query_dispatcher;
+        let start_aabb = shape.compute_aabb(shape_pos);
+        let end_pos = Isometry::from(shape_vel * max_toi) * shape_pos;
+        let swept_aabb = start_aabb
+            .merged(&shape.compute_aabb(&end_pos))
+            .loosened(margin);
+        let mut result: Option<(ColliderHandle, TOI)> = None;
+        let mut error = None;
+
+        let mut leaf_callback = &mut |handle: &ColliderHandle| {
+            if let Some(co) = colliders.get(*handle) {
+                if filter.test(bodies, *handle, co) {
+                    let local_swept_aabb = swept_aabb.transform_by(&co.pos.inverse());
+
+                    map_heightfield_parts(&*co.shape, &local_swept_aabb, &mut |part| {
+                        let max_toi = result.map(|r| r.1.toi).unwrap_or(max_toi);
+
+                        match cast_inflated_shape_on_shape(
+                            dispatcher,
+                            &co.pos,
+                            part,
+                            shape_pos,
+                            shape_vel,
+                            shape,
+                            margin,
+                            max_toi,
+                            stop_at_penetration,
+                        ) {
+                            Ok(Some(toi)) => {
+                                if result.map(|r| toi.toi < r.1.toi).unwrap_or(true) {
+                                    result = Some((*handle, toi));
+                                }
+                            }
+                            Ok(None) => {}
+                            Err(e) => error = Some(e),
+                        }
+                    });
+                }
+            }
+
+            error.is_none()
+        };
+
+        let mut visitor = BoundingVolumeIntersectionsVisitor::new(&swept_aabb, &mut leaf_callback);
+        self.qbvh.traverse_depth_first(&mut visitor);
+
+        error.map_or(Ok(result), Err)
+    }
+
     /// Casts a shape with an arbitrary continuous motion and retrieve the first collider it hits.
     ///
     /// In the resulting `TOI`, witness and normal 1 refer to the world collider, and are in world
-    /// space.
+    /// space. The surface properties of the hit collider can be read from the
+    /// `material` of the collider with the returned handle.
     ///
     /// # Parameters
     /// * `colliders` - The set of colliders taking part in this pipeline.
@@ -734,6 +1021,245 @@ impl QueryPipeline {
         self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
     }
 
+    /// Predicts if two rigid-bodies will collide within `max_toi`, assuming they keep moving with
+    /// their current linear and angular velocities.
+    ///
+    /// This computes the nonlinear time of impact between each collider of `body1` and each
+    /// collider of `body2`, and returns the handles of the colliders hitting first, as well as
+    /// their time of impact. Forces, gravity, and the other bodies aren’t taken into account.
+    /// Returns `None` if one of the bodies doesn’t exist, or if they don’t collide within `max_toi`.
+    /// If the bodies are already intersecting, the returned `toi` is zero.
+    ///
+    /// In the resulting `TOI`, witness and normal 1 refer to the collider of `body1`, witness and
+    /// normal 2 to the collider of `body2`, and are all expressed in world-space at the time of
+    /// impact. The colliders don’t have to be part of this query pipeline.
+    pub fn predict_body_collision(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        max_toi: Real,
+    ) -> Option<(ColliderHandle, ColliderHandle, TOI)> {
+        let rb1 = bodies.get(body1)?;
+        let rb2 = bodies.get(body2)?;
+        let body_motion = |rb: &crate::dynamics::RigidBody| {
+            NonlinearRigidMotion::new(
+                *rb.position(),
+                rb.mass_properties().local_mprops.local_com,
+                *rb.linvel(),
+                rb.vels.angvel,
+            )
+        };
+        let collider_motion = |body_motion: &NonlinearRigidMotion, co: &Collider| {
+            body_motion.prepend(co.parent.map(|p| p.pos_wrt_parent).unwrap_or(co.pos.0))
+        };
+        let (motion1, motion2) = (body_motion(rb1), body_motion(rb2));
+        let mut result: Option<(ColliderHandle, ColliderHandle, TOI)> = None;
+
+        for handle1 in rb1.colliders() {
+            let co1 = &colliders[*handle1];
+            let motion_c1 = collider_motion(&motion1, co1);
+
+            for handle2 in rb2.colliders() {
+                let co2 = &colliders[*handle2];
+                let motion_c2 = collider_motion(&motion2, co2);
+                let max_toi = result.map(|r| r.2.toi).unwrap_or(max_toi);
+
+                if let Ok(Some(toi)) = self.query_dispatcher.nonlinear_time_of_impact(
+                    &motion_c1,
+                    &*co1.shape,
+                    &motion_c2,
+                    &*co2.shape,
+                    0.0,
+                    max_toi,
+                    true,
+                ) {
+                    if toi.toi <= max_toi {
+                        result = Some((*handle1, *handle2, toi));
+                    }
+                }
+            }
+        }
+
+        result.map(|(handle1, handle2, mut toi)| {
+            let pos1 = collider_motion(&motion1, &colliders[handle1]).position_at_time(toi.toi);
+            let pos2 = collider_motion(&motion2, &colliders[handle2]).position_at_time(toi.toi);
+            toi.witness1 = pos1 * toi.witness1;
+            toi.witness2 = pos2 * toi.witness2;
+            toi.normal1 = pos1 * toi.normal1;
+            toi.normal2 = pos2 * toi.normal2;
+            (handle1, handle2, toi)
+        })
+    }
+
+    /// Computes the signed distance between two colliders, and their closest points.
+    ///
+    /// The distance is negative if the colliders are penetrating, in which case the witness
+    /// points are the deepest points of each collider inside of the other one. The result is
+    /// exact for convex shapes. For composite shapes (compounds, triangle meshes, polylines),
+    /// this is the contact between their closest pair of sub-shapes.
+    ///
+    /// In the resulting `Contact`, `point1` and `normal1` refer to `collider1` while `point2` and
+    /// `normal2` refer to `collider2`. All are expressed in world-space. Returns `None` if one
+    /// of the colliders doesn’t exist or if their pair of shapes isn’t supported by the query
+    /// dispatcher. The colliders don’t have to be part of this query pipeline.
+    pub fn collider_distance(
+        &self,
+        colliders: &ColliderSet,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) -> Option<Contact> {
+        let co1 = colliders.get(collider1)?;
+        let co2 = colliders.get(collider2)?;
+        let pos12 = co1.pos.inv_mul(&co2.pos);
+        let mut contact = self
+            .query_dispatcher
+            .contact(&pos12, &*co1.shape, &*co2.shape, Real::MAX)
+            .ok()??;
+        contact.transform_by_mut(&co1.pos, &co2.pos);
+        Some(contact)
+    }
+
+    /// Computes the contact manifolds a collider would have with the other colliders of the
+    /// scene if it was moved to the given position, without modifying anything.
+    ///
+    /// The manifolds are generated by the same code as the ones computed by the narrow-phase
+    /// during a step. The first collider of each manifold is the moved collider, and
+    /// `manifold.data.normal` is set to the world-space contact normal. The other contact data
+    /// (e.g. the solver contacts and the impulses) are left to their default value.
+    /// Only the colliders with at least one contact point are reported.
+    ///
+    /// Just like the narrow-phase, sensors, colliders attached to the same rigid-body as the
+    /// moved collider, and colliders with incompatible collision groups are ignored. The contact
+    /// pair filtering hooks and `ActiveCollisionTypes` aren't taken into account.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `narrow_phase` - The narrow-phase whose query dispatcher generates the manifolds.
+    /// * `collider` - The collider to move. It doesn’t have to be part of this pipeline.
+    /// * `position` - The candidate world-space position of the collider.
+    /// * `prediction_distance` - Contact points with a distance larger than this aren’t generated.
+    /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
+    pub fn contact_manifolds_at_position(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        narrow_phase: &NarrowPhase,
+        collider: ColliderHandle,
+        position: &Isometry<Real>,
+        prediction_distance: Real,
+        filter: QueryFilter,
+    ) -> Vec<(ColliderHandle, Vec<ContactManifold>)> {
+        let mut result = vec![];
+        let co1 = match colliders.get(collider) {
+            Some(co1) if !co1.is_sensor() => co1,
+            _ => return result,
+        };
+        let parent1 = co1.parent.map(|p| p.handle);
+        let aabb = co1
+            .shape
+            .compute_aabb(position)
+            .loosened(prediction_distance);
+
+        self.colliders_with_aabb_intersecting_aabb(&aabb, |handle| {
+            let co2 = match colliders.get(*handle) {
+                Some(co2) => co2,
+                None => return true,
+            };
+
+            if *handle == collider
+                || co2.is_sensor()
+                || (parent1.is_some() && parent1 == co2.parent.map(|p| p.handle))
+                || !co1.flags.collision_groups.test(co2.flags.collision_groups)
+                || !filter.test(bodies, *handle, co2)
+            {
+                return true;
+            }
+
+            let mut manifolds = vec![];
+            crate::geometry::generate_contact_manifolds(
+                narrow_phase.query_dispatcher(),
+                &position.inv_mul(&co2.pos),
+                co1,
+                co2,
+                prediction_distance,
+                &mut manifolds,
+                &mut None,
+            );
+            manifolds.retain(|manifold| !manifold.points.is_empty());
+
+            if !manifolds.is_empty() {
+                for manifold in &mut manifolds {
+                    manifold.data.normal =
+                        manifold.subshape_pos1.prepend_to(position) * manifold.local_n1;
+                }
+
+                result.push((*handle, manifolds));
+            }
+
+            true
+        });
+
+        result
+    }
+
+    /// Computes the deepest contact between the given shape and the colliders of the scene.
+    ///
+    /// Only contacts with a distance smaller than `prediction` are taken into account. If the shape
+    /// is penetrating at least one collider, the returned contact is the one with the largest
+    /// penetration depth (i.e. the most negative `Contact::dist`).
+    ///
+    /// In the resulting `Contact`, `point1` and `normal1` refer to the given shape while `point2` and
+    /// `normal2` refer to the collider. All are expressed in world-space. If the contact is
+    /// penetrating, translating the shape by `normal2 * -contact.dist` resolves the penetration
+    /// with this collider.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `shape_pos` - The position of the shape to test.
+    /// * `shape` - The shape to test.
+    /// * `prediction` - The maximum distance between the shape and a collider for a contact to be
+    ///   reported.
+    /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
+    pub fn contact_with_shape(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        shape_pos: &Isometry<Real>,
+        shape: &dyn Shape,
+        prediction: Real,
+        filter: QueryFilter,
+    ) -> Option<(ColliderHandle, Contact)> {
+        let dispatcher = &*self.query_dispatcher;
+        let mut result: Option<(ColliderHandle, Contact)> = None;
+
+        let mut leaf_callback = &mut |handle: &ColliderHandle| {
+            if let Some(co) = colliders.get(*handle) {
+                if filter.test(bodies, *handle, co) {
+                    let pos12 = shape_pos.inv_mul(&co.pos);
+
+                    if let Ok(Some(mut contact)) =
+                        dispatcher.contact(&pos12, shape, &*co.shape, prediction)
+                    {
+                        if result.map(|r| contact.dist < r.1.dist).unwrap_or(true) {
+                            contact.transform_by_mut(shape_pos, &co.pos);
+                            result = Some((*handle, contact));
+                        }
+                    }
+                }
+            }
+
+            true
+        };
+
+        let shape_aabb = shape.compute_aabb(shape_pos).loosened(prediction.max(0.0));
+        let mut visitor = BoundingVolumeIntersectionsVisitor::new(&shape_aabb, &mut leaf_callback);
+        self.qbvh.traverse_depth_first(&mut visitor);
+
+        result
+    }
+
     /// Retrieve all the colliders intersecting the given shape.
     ///
     /// # Parameters
@@ -774,4 +1300,1116 @@ impl QueryPipeline {
 
         self.qbvh.traverse_depth_first(&mut visitor);
     }
+
+    /// Retrieve all the colliders intersecting the given shape inflated by a margin.
+    ///
+    /// A collider is reported if its distance to `shape` is smaller than or equal to `margin`.
+    /// This is equivalent to testing the intersection with `shape` dilated by a ball of radius
+    /// `margin` (i.e. inflated by `margin` in every direction), and is useful to detect
+    /// near-contacts. A zero `margin` gives the same result as
+    /// [`QueryPipeline::intersections_with_shape`].
+    ///
+    /// Heightfields are tested triangle by triangle (or segment by segment in 2D). Returns
+    /// `Err(Unsupported)`, and stops reporting colliders, as soon as the distance between `shape`
+    /// and a collider can't be computed by the query dispatcher of this pipeline.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `shape_pos` - The position of the shape to test.
+    /// * `shape` - The shape to test.
+    /// * `margin` - The non-negative distance by which `shape` is inflated.
+    /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
+    /// * `callback` - A function called with the handles of each collider intersecting the
+    ///   inflated `shape`.
+    pub fn intersections_with_inflated_shape(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        shape_pos: &Isometry<Real>,
+        shape: &dyn Shape,
+        margin: Real,
+        filter: QueryFilter,
+        mut callback: impl FnMut(ColliderHandle) -> bool,
+    ) -> Result<(), Unsupported> {
+        if margin <= 0.0 {
+            self.intersections_with_shape(bodies, colliders, shape_pos, shape, filter, callback);
+            return Ok(());
+        }
+
+        let dispatcher = &*self.query_dispatcher;
+        let inv_shape_pos = shape_pos.inverse();
+        let mut error = None;
+
+        let mut leaf_callback = &mut |handle: &ColliderHandle| {
+            if let Some(co) = colliders.get(*handle) {
+                if filter.test(bodies, *handle, co) {
+                    let pos12 = inv_shape_pos * co.pos.as_ref();
+                    let local_aabb = shape.compute_aabb(&pos12.inverse()).loosened(margin);
+                    let mut intersects = false;
+
+                    map_heightfield_parts(&*co.shape, &local_aabb, &mut |part| {
+                        if !intersects && error.is_none() {
+                            match dispatcher.distance(&pos12, shape, part) {
+                                Ok(dist) => intersects = dist <= margin,
+                                Err(e) => error = Some(e),
+                            }
+                        }
+                    });
+
+                    if error.is_some() {
+                        return false;
+                    }
+
+                    if intersects {
+                        return callback(*handle);
+                    }
+                }
+            }
+
+            true
+        };
+
+        let shape_aabb = shape.compute_aabb(shape_pos).loosened(margin);
+        let mut visitor = BoundingVolumeIntersectionsVisitor::new(&shape_aabb, &mut leaf_callback);
+
+        self.qbvh.traverse_depth_first(&mut visitor);
+
+        error.map_or(Ok(()), Err)
+    }
+}
+
+/// Calls `f` with the triangles (or segments in 2D) of `shape` intersecting `local_aabb` if
+/// `shape` is a heightfield, or with `shape` itself otherwise.
+///
+/// The query dispatcher can't compute distances to heightfields, but it can to their parts.
+fn map_heightfield_parts(shape: &dyn Shape, local_aabb: &Aabb, f: &mut dyn FnMut(&dyn Shape)) {
+    if let Some(heightfield) = shape.as_heightfield() {
+        heightfield.map_elements_in_local_aabb(local_aabb, &mut |_, part| f(part));
+    } else {
+        f(shape)
+    }
+}
+
+/// Computes the time when `shape2`, moving at the constant velocity `vel2`, gets closer than
+/// `margin` to `shape1`, using conservative advancement.
+///
+/// The witness point and normal 1 of the result are in world-space, while the witness point and
+/// normal 2 are in the local-space of `shape2`, as for the results of
+/// [`QueryPipeline::cast_shape`].
+fn cast_inflated_shape_on_shape(
+    dispatcher: &dyn QueryDispatcher,
+    pos1: &Isometry<Real>,
+    shape1: &dyn Shape,
+    pos2: &Isometry<Real>,
+    vel2: &Vector<Real>,
+    shape2: &dyn Shape,
+    margin: Real,
+    max_toi: Real,
+    stop_at_penetration: bool,
+) -> Result<Option<TOI>, Unsupported> {
+    let speed = vel2.norm();
+    let mut toi: Real = 0.0;
+
+    for _ in 0..INFLATED_CAST_MAX_ITERATIONS {
+        let pos12 = pos1.inv_mul(&(Isometry::from(vel2 * toi) * pos2));
+        let dist = dispatcher.distance(&pos12, shape1, shape2)?;
+
+        if dist <= margin + INFLATED_CAST_TOLERANCE {
+            // The prediction is large enough for the contact to be found even if the contact
+            // and distance queries disagree slightly.
+            let prediction = 2.0 * (margin + INFLATED_CAST_TOLERANCE);
+
+            if let Some(contact) = dispatcher.contact(&pos12, shape1, shape2, prediction)? {
+                let normal1 = pos1 * contact.normal1;
+                let status = if toi == 0.0 && dist < margin {
+                    // Moving away from the collider we start close to.
+                    if !stop_at_penetration && vel2.dot(&normal1) > 0.0 {
+                        return Ok(None);
+                    }
+
+                    TOIStatus::Penetrating
+                } else {
+                    TOIStatus::Converged
+                };
+
+                return Ok(Some(TOI {
+                    toi,
+                    witness1: pos1 * contact.point1,
+                    witness2: contact.point2,
+                    normal1,
+                    normal2: contact.normal2,
+                    status,
+                }));
+            }
+        }
+
+        if speed == 0.0 {
+            return Ok(None);
+        }
+
+        // The shapes can’t get closer than `dist - margin` while `shape2` travels this distance.
+        toi += (dist - margin).max(INFLATED_CAST_TOLERANCE) / speed;
+
+        if toi > max_toi {
+            return Ok(None);
+        }
+    }
+
+    Ok(None)
+}
+
+/// A visitor for casting a ray on the colliders of a query pipeline, or on the parts of a compound
+/// shape.
+///
+/// This is the same as parry's `RayCompositeShapeToiAndNormalBestFirstVisitor`, except that each
+/// part is hit with [`cast_ray_and_get_normal`], so the result doesn't need to be recomputed.
+struct RayToiAndNormalBestFirstVisitor<'a, S> {
+    shape: &'a S,
+    ray: &'a Ray,
+    simd_ray: SimdRay,
+    max_toi: Real,
+    solid: bool,
+}
+
+impl<'a, S> RayToiAndNormalBestFirstVisitor<'a, S> {
+    fn new(shape: &'a S, ray: &'a Ray, max_toi: Real, solid: bool) -> Self {
+        Self {
+            shape,
+            ray,
+            simd_ray: SimdRay::splat(*ray),
+            max_toi,
+            solid,
+        }
+    }
+}
+
+impl<'a, S> SimdBestFirstVisitor<S::PartId, SimdAabb> for RayToiAndNormalBestFirstVisitor<'a, S>
+where
+    S: TypedSimdCompositeShape<PartShape = dyn Shape, QbvhStorage = DefaultStorage>,
+{
+    type Result = (S::PartId, RayIntersection);
+
+    fn visit(
+        &mut self,
+        best: Real,
+        aabb: &SimdAabb,
+        data: Option<[Option<&S::PartId>; SIMD_WIDTH]>,
+    ) -> SimdBestFirstVisitStatus<Self::Result> {
+        let (hit, toi) = aabb.cast_local_ray(&self.simd_ray, SimdReal::splat(self.max_toi));
+
+        if let Some(data) = data {
+            let mut weights = [0.0; SIMD_WIDTH];
+            let mut mask = [false; SIMD_WIDTH];
+            let mut results = [None; SIMD_WIDTH];
+
+            let better_toi = toi.simd_lt(SimdReal::splat(best));
+            let bitmask = (hit & better_toi).bitmask();
+
+            for ii in 0..SIMD_WIDTH {
+                if let Some(part_id) = data[ii].filter(|_| (bitmask & (1 << ii)) != 0) {
+                    self.shape
+                        .map_typed_part_at(*part_id, |part_pos, part_shape| {
+                            let part_pos = part_pos.copied().unwrap_or_else(Isometry::identity);
+
+                            if let Some(result) = cast_ray_and_get_normal(
+                                part_shape,
+                                &part_pos,
+                                self.ray,
+                                self.max_toi,
+                                self.solid,
+                            ) {
+                                results[ii] = Some((*part_id, result));
+                                mask[ii] = true;
+                                weights[ii] = result.toi;
+                            }
+                        });
+                }
+            }
+
+            SimdBestFirstVisitStatus::MaybeContinue {
+                weights: SimdReal::from(weights),
+                mask: SimdBool::from(mask),
+                results,
+            }
+        } else {
+            SimdBestFirstVisitStatus::MaybeContinue {
+                weights: toi,
+                mask: hit,
+                results: [None; SIMD_WIDTH],
+            }
+        }
+    }
+}
+
+/// Casts a ray on a shape.
+///
+/// Unlike `Shape::cast_ray_and_get_normal`, the feature-id of a ray hitting a polyline identifies
+/// the polyline segment that was hit, instead of the feature of this segment. Rays hitting a ball
+/// are computed with [`cast_local_ray_and_get_normal_on_ball`], and rays hitting a capsule or a
+/// shape with round corners with [`cast_local_ray_and_get_normal_on_round_shape`], including
+/// when they are parts of a compound shape.
+fn cast_ray_and_get_normal(
+    shape: &dyn Shape,
+    pos: &Isometry<Real>,
+    ray: &Ray,
+    max_toi: Real,
+    solid: bool,
+) -> Option<RayIntersection> {
+    if let Some(polyline) = shape.as_polyline() {
+        let local_ray = ray.inverse_transform_by(pos);
+        let mut visitor = RayCompositeShapeToiAndNormalBestFirstVisitor::new(
+            polyline, &local_ray, max_toi, solid,
+        );
+
+        SimdCompositeShape::qbvh(polyline)
+            .traverse_best_first(&mut visitor)
+            .map(|(_, (segment, hit))| RayIntersection {
+                feature: polyline.segment_feature_to_polyline_feature(segment, hit.feature),
+                ..hit.transform_by(pos)
+            })
+    } else if let Some(compound) = shape.as_compound() {
+        let local_ray = ray.inverse_transform_by(pos);
+        let mut visitor =
+            RayToiAndNormalBestFirstVisitor::new(compound, &local_ray, max_toi, solid);
+
+        compound
+            .qbvh()
+            .traverse_best_first(&mut visitor)
+            .map(|(_, (_, hit))| hit.transform_by(pos))
+    } else if let Some(ball) = shape.as_ball() {
+        let local_ray = ray.inverse_transform_by(pos);
+        cast_local_ray_and_get_normal_on_ball(ball.radius, &local_ray, max_toi, solid)
+            .map(|hit| hit.transform_by(pos))
+    } else if let Some((inner_shape, border_radius)) = round_shape_parts(shape) {
+        let local_ray = ray.inverse_transform_by(pos);
+        cast_local_ray_and_get_normal_on_round_shape(
+            shape,
+            inner_shape,
+            border_radius,
+            &local_ray,
+            max_toi,
+            solid,
+        )
+        .map(|hit| hit.transform_by(pos))
+    } else {
+        shape.cast_ray_and_get_normal(pos, ray, max_toi, solid)
+    }
+}
+
+/// The inner shape and border radius of a capsule or of a shape with round corners.
+fn round_shape_parts(shape: &dyn Shape) -> Option<(&dyn Shape, Real)> {
+    if let Some(capsule) = shape.as_capsule() {
+        return Some((&capsule.segment, capsule.radius));
+    }
+    if let Some(round) = shape.as_round_cuboid() {
+        return Some((&round.inner_shape, round.border_radius));
+    }
+    if let Some(round) = shape.as_round_triangle() {
+        return Some((&round.inner_shape, round.border_radius));
+    }
+    #[cfg(feature = "dim2")]
+    if let Some(round) = shape.as_round_convex_polygon() {
+        return Some((&round.inner_shape, round.border_radius));
+    }
+    #[cfg(feature = "dim3")]
+    if let Some(round) = shape.as_round_cylinder() {
+        return Some((&round.inner_shape, round.border_radius));
+    }
+    #[cfg(feature = "dim3")]
+    if let Some(round) = shape.as_round_cone() {
+        return Some((&round.inner_shape, round.border_radius));
+    }
+    #[cfg(feature = "dim3")]
+    if let Some(round) = shape.as_round_convex_polyhedron() {
+        return Some((&round.inner_shape, round.border_radius));
+    }
+
+    None
+}
+
+/// Casts a ray on a capsule or a shape with round corners, given its inner shape and border
+/// radius.
+///
+/// `Shape::cast_local_ray_and_get_normal` only computes the hit on such shapes up to the
+/// tolerance of the GJK algorithm, which is noticeable on the normal of grazing rays. Here, the
+/// hit point is moved along the ray with Newton iterations until it lies at `border_radius` from
+/// the inner shape, and the normal is the direction from the closest point of the inner shape to
+/// the hit point, i.e., the exact normal of the surface at the hit point.
+fn cast_local_ray_and_get_normal_on_round_shape(
+    shape: &dyn Shape,
+    inner_shape: &dyn Shape,
+    border_radius: Real,
+    ray: &Ray,
+    max_toi: Real,
+    solid: bool,
+) -> Option<RayIntersection> {
+    const MAX_ITERATIONS: usize = 20;
+    const TOLERANCE: Real = 1.0e-6;
+
+    let mut hit = shape.cast_local_ray_and_get_normal(ray, max_toi, solid)?;
+    let dir_norm = ray.dir.norm();
+
+    if (solid && hit.toi == 0.0) || dir_norm == 0.0 {
+        // The ray starts inside of the shape.
+        return Some(hit);
+    }
+
+    // Parry's normal is reversed if the ray starts inside of the shape.
+    let entering = hit.normal.dot(&ray.dir) < 0.0;
+
+    for _ in 0..MAX_ITERATIONS {
+        let point = ray.point_at(hit.toi);
+        let proj = inner_shape.project_local_point(&point, true);
+        let dpt = point - proj.point;
+        let dist = dpt.norm();
+
+        if proj.is_inside || dist == 0.0 {
+            break;
+        }
+
+        let normal = dpt / dist;
+        hit.normal = if entering { normal } else { -normal };
+
+        let gap = dist - border_radius;
+        if gap.abs() <= TOLERANCE * border_radius.max(1.0) {
+            break;
+        }
+
+        // The distance to the inner shape is convex along the ray, so Newton iterations don't
+        // step over the hit as long as the distance varies in the expected direction. Otherwise,
+        // step back to the surface by at most the penetration depth.
+        let slope = ray.dir.dot(&normal);
+        let step = if (entering && slope < 0.0) || (!entering && slope > 0.0) {
+            -gap / slope
+        } else if gap < 0.0 {
+            let depth_toi = -gap / dir_norm;
+            if entering {
+                -depth_toi
+            } else {
+                depth_toi
+            }
+        } else {
+            break;
+        };
+
+        hit.toi = (hit.toi + step).max(0.0);
+    }
+
+    (hit.toi <= max_toi).then(|| hit)
+}
+
+/// Casts a ray on a ball centered at the origin.
+///
+/// Unlike `Ball::cast_local_ray_and_get_normal`, this doesn't suffer from catastrophic
+/// cancellation when the ray origin is far from the ball: a ray grazing the ball hits it at the
+/// tangent point, with the surface normal at this point. A ray starting on the surface of the
+/// ball is considered outside of it, and gets the outward normal.
+fn cast_local_ray_and_get_normal_on_ball(
+    radius: Real,
+    ray: &Ray,
+    max_toi: Real,
+    solid: bool,
+) -> Option<RayIntersection> {
+    let sq_dir = ray.dir.norm_squared();
+    let sq_dist_to_surface = ray.origin.coords.norm_squared() - radius * radius;
+
+    if sq_dir == 0.0 {
+        return (sq_dist_to_surface <= 0.0)
+            .then(|| RayIntersection::new(0.0, Vector::zeros(), FeatureId::Face(0)));
+    }
+
+    // Work with the point of the ray line closest to the center, to avoid subtracting
+    // large, nearly equal, numbers.
+    let closest_toi = -ray.origin.coords.dot(&ray.dir) / sq_dir;
+    let closest = ray.point_at(closest_toi);
+    let sq_half_chord = radius * radius - closest.coords.norm_squared();
+
+    if sq_half_chord < 0.0 {
+        return None;
+    }
+
+    let half_chord_toi = (sq_half_chord / sq_dir).sqrt();
+    let inside = sq_dist_to_surface < 0.0;
+    let toi = if !inside {
+        let toi = closest_toi - half_chord_toi;
+
+        if toi < 0.0 && sq_dist_to_surface > 0.0 {
+            // The ball is behind the ray.
+            return None;
+        }
+
+        toi.max(0.0)
+    } else if solid {
+        0.0
+    } else {
+        closest_toi + half_chord_toi
+    };
+
+    if toi > max_toi {
+        return None;
+    }
+
+    let hit = closest + ray.dir * (toi - closest_toi);
+    let normal = hit.coords.try_normalize(0.0).unwrap_or_else(Vector::zeros);
+    let normal = if inside { -normal } else { normal };
+    Some(RayIntersection::new(toi, normal, FeatureId::Face(0)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::QueryPipeline;
+    use crate::dynamics::{RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{ColliderBuilder, ColliderSet, Ray, RayIntersection, SharedShape};
+    use crate::math::{AngVector, Isometry, Point, Real, Vector};
+    use crate::pipeline::QueryFilter;
+    use parry::query::{TOIStatus, Unsupported};
+    use parry::shape::{Ball, FeatureId};
+
+    fn cast_ray(collider: ColliderBuilder, ray: &Ray) -> Option<RayIntersection> {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        colliders.insert(collider);
+
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&bodies, &colliders);
+        query_pipeline
+            .cast_ray_and_get_normal(
+                &bodies,
+                &colliders,
+                ray,
+                Real::MAX,
+                true,
+                QueryFilter::default(),
+            )
+            .map(|(_, hit)| hit)
+    }
+
+    #[test]
+    fn ray_cast_on_closed_polyline_reports_the_hit_segment() {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        // A counterclockwise square, with the segment `i` starting at the vertex `i`.
+        let vertices = vec![
+            Point::from(-Vector::x() - Vector::y()),
+            Point::from(Vector::x() - Vector::y()),
+            Point::from(Vector::x() + Vector::y()),
+            Point::from(Vector::y() - Vector::x()),
+        ];
+        colliders.insert(ColliderBuilder::closed_polyline(vertices).unwrap().build());
+
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&bodies, &colliders);
+
+        for (segment, dir) in [
+            (0, -Vector::y()),
+            (1, Vector::x()),
+            (2, Vector::y()),
+            (3, -Vector::x()),
+        ] {
+            let ray = Ray::new(Point::origin(), dir);
+            let (_, hit) = query_pipeline
+                .cast_ray_and_get_normal(
+                    &bodies,
+                    &colliders,
+                    &ray,
+                    10.0,
+                    true,
+                    QueryFilter::default(),
+                )
+                .unwrap();
+
+            assert!((hit.toi - 1.0).abs() < 1.0e-5);
+            #[cfg(feature = "dim2")]
+            assert_eq!(hit.feature, FeatureId::Face(segment));
+            #[cfg(feature = "dim3")]
+            assert_eq!(hit.feature, FeatureId::Edge(segment));
+        }
+    }
+
+    #[test]
+    fn grazing_rays_hit_round_shapes_at_their_tangent_point() {
+        // A ray going down, grazing the right side of a unit circle centered at the origin.
+        let x = 0.9999;
+        let ray = Ray::new(
+            Point::from(Vector::x() * x + Vector::y() * 100.0),
+            -Vector::y(),
+        );
+        let part_pos = Isometry::new(Vector::y() * 2.0, na::zero::<AngVector<Real>>());
+
+        #[cfg(feature = "dim2")]
+        let round_cuboid = ColliderBuilder::round_cuboid(0.5, 0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let round_cuboid = ColliderBuilder::round_cuboid(0.5, 0.5, 0.5, 0.5);
+
+        // The collider, and the center and radius of its round part hit by the ray.
+        let cases = [
+            (ColliderBuilder::ball(1.0), Vector::zeros(), 1.0),
+            (
+                ColliderBuilder::compound(vec![(part_pos, SharedShape::ball(1.0))]),
+                Vector::y() * 2.0,
+                1.0,
+            ),
+            (ColliderBuilder::capsule_y(1.0, 1.0), Vector::y(), 1.0),
+            (round_cuboid, Vector::repeat(0.5), 0.5),
+        ];
+
+        for (collider, center, radius) in cases {
+            let hit = cast_ray(collider, &ray).unwrap();
+            let dx = x - center.x;
+            let expected_point =
+                Vector::x() * x + Vector::y() * (center.y + (radius * radius - dx * dx).sqrt());
+            let expected_normal =
+                (expected_point - Vector::y() * center.y - Vector::x() * center.x) / radius;
+
+            assert!((ray.point_at(hit.toi).coords - expected_point).norm() < 1.0e-3);
+            assert!((hit.normal - expected_normal).norm() < 1.0e-3);
+            assert!((hit.normal.norm() - 1.0).abs() < 1.0e-5);
+        }
+    }
+
+    #[test]
+    fn rays_hit_heightfields_on_the_side_they_come_from() {
+        // A slope rising by 1 over 4 along the X axis, at the height 0.5 at the origin.
+        #[cfg(feature = "dim2")]
+        let heightfield = ColliderBuilder::heightfield(
+            na::DVector::from_vec(vec![0.0, 1.0]),
+            Vector::new(4.0, 1.0),
+        );
+        #[cfg(feature = "dim3")]
+        let heightfield = ColliderBuilder::heightfield(
+            na::DMatrix::from_row_slice(2, 2, &[0.0, 1.0, 0.0, 1.0]),
+            Vector::new(4.0, 1.0, 4.0),
+        );
+        let mut slope_normal = Vector::y() * 4.0 - Vector::x();
+        slope_normal.normalize_mut();
+
+        for dir in [-Vector::y(), Vector::y()] {
+            let ray = Ray::new(Point::from(Vector::x() * 0.1 - dir * 5.0), dir);
+            let hit = cast_ray(heightfield.clone(), &ray).unwrap();
+            let height = 0.5 + 0.1 / 4.0;
+            assert!((hit.toi - (5.0 + height * dir.y)).abs() < 1.0e-4);
+            assert!((hit.normal + slope_normal * dir.y).norm() < 1.0e-5);
+        }
+    }
+
+    #[test]
+    fn local_ray_hits_follow_rotated_and_translated_colliders() {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        // Rotated by 90 degrees, so the local X axis points up.
+        #[cfg(feature = "dim2")]
+        let rotation = std::f32::consts::FRAC_PI_2 as Real;
+        #[cfg(feature = "dim3")]
+        let rotation = Vector::z() * std::f32::consts::FRAC_PI_2 as Real;
+        let position = Isometry::new(Vector::x() * 3.0 + Vector::y() * 2.0, rotation);
+        let part_pos = Isometry::new(Vector::x() * 4.0, na::zero::<AngVector<Real>>());
+        let parts = vec![
+            (Isometry::identity(), SharedShape::ball(0.5)),
+            (part_pos, SharedShape::ball(0.5)),
+        ];
+        let handle = colliders.insert(ColliderBuilder::compound(parts).position(position));
+
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&bodies, &colliders);
+
+        // Going down on the second part, at the world-space position (3, 6).
+        let ray = Ray::new(
+            Point::from(Vector::x() * 3.0 + Vector::y() * 10.0),
+            -Vector::y(),
+        );
+        let (hit_handle, hit) = query_pipeline
+            .cast_ray_and_get_local_hit(
+                &bodies,
+                &colliders,
+                &ray,
+                Real::MAX,
+                true,
+                QueryFilter::default(),
+            )
+            .unwrap();
+
+        assert_eq!(hit_handle, handle);
+        assert_eq!(hit.subshape, Some(1));
+        assert!((hit.toi - 3.5).abs() < 1.0e-5);
+        assert!((hit.point - Point::from(Vector::x() * 4.5)).norm() < 1.0e-5);
+        assert!((hit.normal - Vector::x()).norm() < 1.0e-5);
+
+        // Back to world-space, the hit matches the one of `cast_ray_and_get_normal`.
+        let world_point = position * hit.point;
+        let world_normal = position * hit.normal;
+        assert!((world_point - ray.point_at(hit.toi)).norm() < 1.0e-5);
+        assert!((world_normal - Vector::y()).norm() < 1.0e-5);
+
+        // The local hit stays attached to the collider as it moves.
+        let new_position = Isometry::new(-Vector::y(), na::zero::<AngVector<Real>>());
+        colliders[handle].set_position(new_position);
+        let moved_point = colliders[handle].position() * hit.point;
+        assert!((moved_point - Point::from(Vector::x() * 4.5 - Vector::y())).norm() < 1.0e-5);
+    }
+
+    #[test]
+    fn inflated_shape_queries_test_heightfields_and_report_unsupported_shapes() {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        // A flat ground at the height 0.
+        #[cfg(feature = "dim2")]
+        let heightfield = ColliderBuilder::heightfield(
+            na::DVector::from_vec(vec![0.0, 0.0, 0.0]),
+            Vector::new(10.0, 1.0),
+        );
+        #[cfg(feature = "dim3")]
+        let heightfield =
+            ColliderBuilder::heightfield(na::DMatrix::zeros(3, 3), Vector::new(10.0, 1.0, 10.0));
+        let handle = colliders.insert(heightfield.clone());
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&bodies, &colliders);
+
+        let ball = Ball::new(0.5);
+        let margin = 0.25;
+        #[cfg(feature = "dim3")]
+        let ball_at = |height: Real| Isometry::translation(0.1, height, 0.1);
+        #[cfg(feature = "dim2")]
+        let ball_at = |height: Real| Isometry::translation(0.1, height);
+
+        let (hit_handle, toi) = query_pipeline
+            .cast_inflated_shape(
+                &bodies,
+                &colliders,
+                &ball_at(2.0),
+                &-Vector::y(),
+                &ball,
+                margin,
+                Real::MAX,
+                true,
+                QueryFilter::default(),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(hit_handle, handle);
+        assert!((toi.toi - 1.25).abs() < 1.0e-4);
+        assert_eq!(toi.status, TOIStatus::Converged);
+
+        for (height, expected) in [(0.7, true), (0.8, false)] {
+            let mut hits = vec![];
+            query_pipeline
+                .intersections_with_inflated_shape(
+                    &bodies,
+                    &colliders,
+                    &ball_at(height),
+                    &ball,
+                    margin,
+                    QueryFilter::default(),
+                    |handle| {
+                        hits.push(handle);
+                        true
+                    },
+                )
+                .unwrap();
+            assert_eq!(hits.is_empty(), !expected);
+        }
+
+        // The distance between two heightfields can't be computed.
+        let heightfield = heightfield.build();
+        #[cfg(feature = "dim3")]
+        let query_pos = Isometry::translation(0.0, 0.1, 0.0);
+        #[cfg(feature = "dim2")]
+        let query_pos = Isometry::translation(0.0, 0.1);
+        assert_eq!(
+            query_pipeline
+                .cast_inflated_shape(
+                    &bodies,
+                    &colliders,
+                    &query_pos,
+                    &-Vector::y(),
+                    heightfield.shape(),
+                    margin,
+                    Real::MAX,
+                    true,
+                    QueryFilter::default(),
+                )
+                .err(),
+            Some(Unsupported)
+        );
+        assert_eq!(
+            query_pipeline.intersections_with_inflated_shape(
+                &bodies,
+                &colliders,
+                &query_pos,
+                heightfield.shape(),
+                margin,
+                QueryFilter::default(),
+                |_| panic!("the query isn't supported"),
+            ),
+            Err(Unsupported)
+        );
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn rays_hit_trimeshes_on_the_side_they_come_from() {
+        let trimesh = ColliderBuilder::trimesh(
+            vec![
+                Point::new(-5.0, 0.0, -5.0),
+                Point::new(-5.0, 0.0, 5.0),
+                Point::new(5.0, 0.0, 0.0),
+            ],
+            vec![[0, 1, 2]],
+        );
+
+        let dirs: [Vector<Real>; 3] = [-Vector::y(), Vector::y(), Vector::new(0.3, -1.0, 0.2)];
+
+        for dir in dirs {
+            let ray = Ray::new(Point::new(0.1, -dir.y.signum() * 5.0, 0.1), dir);
+            let hit = cast_ray(trimesh.clone(), &ray).unwrap();
+            assert!((hit.toi - 5.0).abs() < 1.0e-5);
+            assert_eq!(hit.normal, -Vector::y() * dir.y.signum());
+        }
+    }
+
+    #[test]
+    fn collider_distance_handles_penetrations_and_compounds() {
+        let mut colliders = ColliderSet::new();
+        let ball1 = colliders.insert(ColliderBuilder::ball(0.5));
+        let ball2 = colliders.insert(ColliderBuilder::ball(0.5).translation(Vector::x() * 3.0));
+        let compound = SharedShape::compound(vec![
+            (Isometry::from(Vector::x() * -10.0), SharedShape::ball(0.5)),
+            (Isometry::from(Vector::x()), SharedShape::ball(0.5)),
+        ]);
+        let compound = colliders.insert(
+            ColliderBuilder::new(compound).translation(Vector::x() * 5.0 + Vector::y() * 0.5),
+        );
+        let queries = QueryPipeline::new();
+
+        let contact = queries.collider_distance(&colliders, ball1, ball2).unwrap();
+        assert!((contact.dist - 2.0).abs() < 1.0e-5);
+        assert!((contact.point1 - Point::from(Vector::x() * 0.5)).norm() < 1.0e-5);
+        assert!((contact.point2 - Point::from(Vector::x() * 2.5)).norm() < 1.0e-5);
+
+        // The closest ball of the compound is the one at x = -5.
+        let contact = queries
+            .collider_distance(&colliders, compound, ball1)
+            .unwrap();
+        let expected = (Vector::<Real>::x() * 5.0 + Vector::y() * 0.5).norm() - 1.0;
+        assert!((contact.dist - expected).abs() < 1.0e-5);
+
+        colliders[ball2].set_translation(Vector::x() * 0.8);
+        let contact = queries.collider_distance(&colliders, ball2, ball1).unwrap();
+        assert!((contact.dist + 0.2).abs() < 1.0e-5);
+        assert!((contact.normal1.into_inner() + Vector::x()).norm() < 1.0e-5);
+    }
+
+    #[test]
+    fn scene_aabb_bounds_the_enabled_colliders() {
+        use crate::pipeline::test_world::TestWorld;
+
+        let mut world = TestWorld::new();
+        world.gravity = Vector::zeros();
+        let mut query_pipeline = QueryPipeline::new();
+
+        assert!(query_pipeline.scene_aabb().mins.x > query_pipeline.scene_aabb().maxs.x);
+
+        world
+            .colliders
+            .insert(ColliderBuilder::ball(0.5).translation(Vector::y() * 5.0));
+        world.colliders.insert(
+            ColliderBuilder::ball(0.5)
+                .translation(Vector::x() * -100.0)
+                .enabled(false),
+        );
+        let body = RigidBodyBuilder::dynamic().linvel(Vector::x() * 60.0);
+        let (moving, _) = world.insert(body, ColliderBuilder::ball(0.5));
+
+        let step = |world: &mut TestWorld, query_pipeline: &mut QueryPipeline| {
+            world.pipeline.step(
+                &world.gravity,
+                &world.params,
+                &mut world.islands,
+                &mut world.broad_phase,
+                &mut world.narrow_phase,
+                &mut world.bodies,
+                &mut world.colliders,
+                &mut world.impulse_joints,
+                &mut world.multibody_joints,
+                &mut world.ccd_solver,
+                Some(query_pipeline),
+                &(),
+                &(),
+            )
+        };
+
+        step(&mut world, &mut query_pipeline);
+        // The disabled collider is excluded.
+        let aabb = query_pipeline.scene_aabb();
+        assert!(aabb.mins.x <= -0.5 && aabb.mins.x > -1.0);
+        assert!(aabb.maxs.y >= 5.5 && aabb.maxs.y < 6.0);
+
+        // The AABB follows the colliders moving out of it.
+        for _ in 0..20 {
+            step(&mut world, &mut query_pipeline);
+        }
+        let moving_x = world.bodies[moving].translation().x;
+        assert!(moving_x > 20.0);
+        assert!(query_pipeline.scene_aabb().maxs.x >= moving_x + 0.5);
+    }
+
+    #[test]
+    fn contact_manifolds_at_position_match_the_narrow_phase() {
+        use crate::geometry::{ActiveCollisionTypes, BroadPhase, NarrowPhase};
+        use crate::pipeline::CollisionPipeline;
+
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut broad_phase = BroadPhase::new();
+        let mut narrow_phase = NarrowPhase::new();
+        let mut queries = QueryPipeline::new();
+        let prediction_distance = 0.002;
+
+        let ground = colliders.insert(ColliderBuilder::ball(10.0).translation(Vector::y() * -10.0));
+        colliders.insert(ColliderBuilder::ball(1.0).sensor(true));
+        let mover = colliders.insert(
+            ColliderBuilder::ball(0.5)
+                .translation(Vector::x() * 20.0)
+                .active_collision_types(ActiveCollisionTypes::all()),
+        );
+        queries.update(&bodies, &colliders);
+
+        let position = Isometry::from(Vector::y() * 0.4);
+        let result = queries.contact_manifolds_at_position(
+            &bodies,
+            &colliders,
+            &narrow_phase,
+            mover,
+            &position,
+            prediction_distance,
+            QueryFilter::default(),
+        );
+
+        // The sensor is ignored.
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].0, ground);
+        let manifold = &result[0].1[0];
+        assert!((manifold.data.normal + Vector::y()).norm() < 1.0e-5);
+        assert!((manifold.points[0].dist + 0.1).abs() < 1.0e-5);
+        // Nothing was modified.
+        assert_eq!(
+            *colliders[mover].position(),
+            Isometry::from(Vector::x() * 20.0)
+        );
+
+        // Actually moving the collider generates the same contacts.
+        colliders[mover].set_position(position);
+        CollisionPipeline::new().step(
+            prediction_distance,
+            &mut broad_phase,
+            &mut narrow_phase,
+            &mut bodies,
+            &mut colliders,
+            None,
+            &(),
+            &(),
+        );
+        let pair = narrow_phase.contact_pair(mover, ground).unwrap();
+        assert_eq!(pair.manifolds.len(), 1);
+        assert_eq!(pair.manifolds[0].points.len(), manifold.points.len());
+        assert!((pair.manifolds[0].points[0].dist - manifold.points[0].dist).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn predicted_body_collisions_account_for_rotations() {
+        use crate::math::{Point, Real};
+        use crate::pipeline::QueryPipeline;
+
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let query_pipeline = QueryPipeline::new();
+
+        // Two balls attached at one unit from the center of mass of a spinning body.
+        #[cfg(feature = "dim2")]
+        let angvel = std::f32::consts::FRAC_PI_2 as Real;
+        #[cfg(feature = "dim3")]
+        let angvel = Vector::z() * std::f32::consts::FRAC_PI_2 as Real;
+        let spinning = bodies.insert(RigidBodyBuilder::dynamic().angvel(angvel));
+        let ball = ColliderBuilder::ball(0.25).translation(Vector::x());
+        let spinning_ball = colliders.insert_with_parent(ball, spinning, &mut bodies);
+        let ball = ColliderBuilder::ball(0.25).translation(-Vector::x());
+        colliders.insert_with_parent(ball, spinning, &mut bodies);
+
+        let target = bodies.insert(RigidBodyBuilder::fixed().translation(Vector::y()));
+        let target_ball =
+            colliders.insert_with_parent(ColliderBuilder::ball(0.25), target, &mut bodies);
+        let moving_away = bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 3.0)
+                .linvel(Vector::x()),
+        );
+        colliders.insert_with_parent(ColliderBuilder::ball(0.25), moving_away, &mut bodies);
+
+        let (collider1, collider2, toi) = query_pipeline
+            .predict_body_collision(&bodies, &colliders, spinning, target, 1.0)
+            .unwrap();
+        // The balls touch when the angle between them, seen from the spinning body's center,
+        // is `2 * asin(0.25)`.
+        let expected_toi = (std::f32::consts::FRAC_PI_2 as Real - 2.0 * (0.25 as Real).asin())
+            / std::f32::consts::FRAC_PI_2 as Real;
+        assert_eq!((collider1, collider2), (spinning_ball, target_ball));
+        assert!((toi.toi - expected_toi).abs() < 1.0e-2);
+        assert!(((toi.witness2 - Point::from(Vector::y())).norm() - 0.25).abs() < 1.0e-2);
+
+        assert!(query_pipeline
+            .predict_body_collision(&bodies, &colliders, spinning, target, 0.5)
+            .is_none());
+        assert!(query_pipeline
+            .predict_body_collision(&bodies, &colliders, spinning, moving_away, 10.0)
+            .is_none());
+    }
+
+    #[test]
+    fn query_filters_select_colliders_by_parent_body_type() {
+        use crate::dynamics::RigidBodyType;
+        use crate::pipeline::QueryFilterFlags;
+
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut handles = vec![];
+        for (i, body) in [
+            Some(RigidBodyBuilder::dynamic()),
+            Some(RigidBodyBuilder::kinematic_position_based()),
+            None,
+            Some(RigidBodyBuilder::fixed()),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let collider = ColliderBuilder::ball(0.5);
+            let translation = Vector::x() * (2.0 + i as Real * 2.0);
+            let handle = match body {
+                Some(body) => {
+                    let body = bodies.insert(body.translation(translation));
+                    colliders.insert_with_parent(collider, body, &mut bodies)
+                }
+                None => colliders.insert(collider.translation(translation)),
+            };
+            handles.push(handle);
+        }
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&bodies, &colliders);
+
+        let first_hit = |origin: Real, filter: QueryFilter| {
+            let ray = Ray::new(Point::from(Vector::x() * origin), Vector::x());
+            query_pipeline
+                .cast_ray(&bodies, &colliders, &ray, Real::MAX, true, filter)
+                .map(|hit| hit.0)
+        };
+
+        assert_eq!(first_hit(0.0, QueryFilter::default()), Some(handles[0]));
+        let filter = QueryFilter::default().exclude_body_type(RigidBodyType::Dynamic);
+        assert_eq!(first_hit(0.0, filter), Some(handles[1]));
+        // The collider without parent is considered attached to a fixed rigid-body.
+        let filter = filter.exclude_body_type(RigidBodyType::KinematicPositionBased);
+        assert_eq!(first_hit(0.0, filter), Some(handles[2]));
+        let filter = QueryFilterFlags::only_body_type(RigidBodyType::Fixed).into();
+        assert_eq!(first_hit(0.0, filter), Some(handles[2]));
+        let filter = QueryFilterFlags::only_body_type(RigidBodyType::KinematicVelocityBased);
+        assert_eq!(first_hit(0.0, filter.into()), Some(handles[1]));
+        let filter = QueryFilter::default().exclude_body_type(RigidBodyType::Fixed);
+        assert_eq!(first_hit(5.0, filter), None);
+    }
+
+    #[test]
+    fn points_are_projected_on_the_surface_even_from_inside() {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let handle = colliders.insert(ColliderBuilder::ball(1.0));
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&bodies, &colliders);
+
+        for (x, max_dist, expected_inside, expected_within_range) in [
+            (0.5, 1.0, true, true),
+            (0.5, 0.25, true, false),
+            (3.0, 2.5, false, true),
+            (3.0, 1.5, false, false),
+        ] {
+            let point = Point::from(Vector::x() * x);
+            let (hit_handle, proj, within_range) = query_pipeline
+                .project_point_on_surface(
+                    &bodies,
+                    &colliders,
+                    &point,
+                    max_dist,
+                    QueryFilter::default(),
+                )
+                .unwrap();
+            assert_eq!(hit_handle, handle);
+            assert!((proj.point - Point::from(Vector::x())).norm() < 1.0e-5);
+            assert_eq!(proj.is_inside, expected_inside);
+            assert_eq!(within_range, expected_within_range);
+        }
+
+        let point = Point::from(Vector::x() * 0.5);
+        assert!(query_pipeline
+            .project_point_on_surface(
+                &bodies,
+                &colliders,
+                &point,
+                1.0,
+                QueryFilter::exclude_fixed(),
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn shape_contacts_report_the_deepest_penetration() {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let penetrated = colliders.insert(ColliderBuilder::ball(1.0));
+        colliders.insert(ColliderBuilder::ball(1.0).translation(Vector::x() * 5.0));
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&bodies, &colliders);
+
+        let ball = Ball::new(0.5);
+        let shape_pos = Isometry::from(Vector::x() * 1.3);
+        // Both colliders are within the prediction distance, but only the first one is
+        // penetrated.
+        let (handle, contact) = query_pipeline
+            .contact_with_shape(
+                &bodies,
+                &colliders,
+                &shape_pos,
+                &ball,
+                3.0,
+                QueryFilter::default(),
+            )
+            .unwrap();
+        assert_eq!(handle, penetrated);
+        assert!((contact.dist + 0.2).abs() < 1.0e-5);
+        assert!((contact.point2 - Point::from(Vector::x())).norm() < 1.0e-5);
+        assert!((contact.normal2.into_inner() - Vector::x()).norm() < 1.0e-5);
+
+        // Translating the shape along the contact normal resolves the penetration.
+        let shape_pos =
+            Isometry::from(Vector::x() * 1.3 - contact.normal2.into_inner() * contact.dist);
+        let (_, contact) = query_pipeline
+            .contact_with_shape(
+                &bodies,
+                &colliders,
+                &shape_pos,
+                &ball,
+                0.1,
+                QueryFilter::default(),
+            )
+            .unwrap();
+        assert!(contact.dist.abs() < 1.0e-5);
+
+        let shape_pos = Isometry::from(Vector::x() * 2.6);
+        assert!(query_pipeline
+            .contact_with_shape(
+                &bodies,
+                &colliders,
+                &shape_pos,
+                &ball,
+                0.1,
+                QueryFilter::default(),
+            )
+            .is_none());
+    }
 }
@@ -0,0 +1,93 @@
+//! A physics world gathering everything needed to step a `PhysicsPipeline` in tests.
+
+use crate::dynamics::{
+    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet, RigidBody,
+    RigidBodyHandle, RigidBodySet,
+};
+use crate::geometry::{BroadPhase, Collider, ColliderHandle, ColliderSet, NarrowPhase};
+use crate::math::{Real, Vector};
+use crate::pipeline::{EventHandler, PhysicsHooks, PhysicsPipeline};
+
+/// Every set and structure needed to step a [`PhysicsPipeline`].
+pub(crate) struct TestWorld {
+    pub gravity: Vector<Real>,
+    pub params: IntegrationParameters,
+    pub pipeline: PhysicsPipeline,
+    pub islands: IslandManager,
+    pub broad_phase: BroadPhase,
+    pub narrow_phase: NarrowPhase,
+    pub bodies: RigidBodySet,
+    pub colliders: ColliderSet,
+    pub impulse_joints: ImpulseJointSet,
+    pub multibody_joints: MultibodyJointSet,
+    pub ccd_solver: CCDSolver,
+}
+
+impl TestWorld {
+    /// An empty world, with a gravity of `-9.81` along the `y` axis and the default integration
+    /// parameters.
+    pub fn new() -> Self {
+        Self {
+            gravity: Vector::y() * -9.81,
+            params: IntegrationParameters::default(),
+            pipeline: PhysicsPipeline::new(),
+            islands: IslandManager::new(),
+            broad_phase: BroadPhase::new(),
+            narrow_phase: NarrowPhase::new(),
+            bodies: RigidBodySet::new(),
+            colliders: ColliderSet::new(),
+            impulse_joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            ccd_solver: CCDSolver::new(),
+        }
+    }
+
+    /// Inserts a rigid-body, and a collider attached to it.
+    pub fn insert(
+        &mut self,
+        body: impl Into<RigidBody>,
+        collider: impl Into<Collider>,
+    ) -> (RigidBodyHandle, ColliderHandle) {
+        let body = self.bodies.insert(body);
+        let collider = self
+            .colliders
+            .insert_with_parent(collider, body, &mut self.bodies);
+        (body, collider)
+    }
+
+    /// Removes a rigid-body, with its colliders and joints.
+    pub fn remove(&mut self, body: RigidBodyHandle) -> Option<RigidBody> {
+        self.bodies.remove(
+            body,
+            &mut self.islands,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            true,
+        )
+    }
+
+    /// Executes one timestep.
+    pub fn step(&mut self) {
+        self.step_with(&(), &());
+    }
+
+    /// Executes one timestep with the given physics hooks and event handler.
+    pub fn step_with(&mut self, hooks: &dyn PhysicsHooks, events: &dyn EventHandler) {
+        self.pipeline.step(
+            &self.gravity,
+            &self.params,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd_solver,
+            None,
+            hooks,
+            events,
+        );
+    }
+}
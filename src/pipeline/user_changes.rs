@@ -161,6 +161,27 @@ pub(crate) fn handle_user_changes_to_rigid_bodies(
                 }
             }
 
+            if changes.contains(RigidBodyChanges::TELEPORTED) {
+                // Invalidate the contacts of the rigid-body’s colliders so they
+                // don’t survive the teleportation.
+                for handle in rb.colliders.0.iter() {
+                    // NOTE: we can’t just use `colliders.get_mut_internal_with_modification_tracking`
+                    // here because that would modify the `modified_colliders` inside of the `ColliderSet`
+                    // instead of the one passed to this method.
+                    let co = colliders.index_mut_internal(*handle);
+                    if !co.changes.contains(ColliderChanges::MODIFIED) {
+                        modified_colliders.push(*handle);
+                    }
+
+                    co.changes |= ColliderChanges::MODIFIED | ColliderChanges::PARENT_TELEPORTED;
+                }
+
+                // Reset the impulses of the attached impulse joints.
+                impulse_joints.map_attached_joints_mut(*handle, |_, _, _, joint| {
+                    joint.impulses = na::zero();
+                });
+            }
+
             if changes.contains(RigidBodyChanges::ENABLED_OR_DISABLED) {
                 // Propagate the rigid-body’s enabled/disable status to its colliders.
                 for handle in rb.colliders.0.iter() {
@@ -752,6 +752,18 @@ impl Drop for DisableFloatingPointExceptionsFlags {
     }
 }
 
+/// Packs two `u64` into a single `u128`, e.g., to be stored as a collider or rigid-body user-data.
+///
+/// The first element is stored in the least significant bits.
+pub fn pack_u64s(data: [u64; 2]) -> u128 {
+    data[0] as u128 | ((data[1] as u128) << 64)
+}
+
+/// Unpacks the two `u64` that were packed into an `u128` with [`pack_u64s`].
+pub fn unpack_u64s(data: u128) -> [u64; 2] {
+    [data as u64, (data >> 64) as u64]
+}
+
 pub(crate) fn select_other<T: PartialEq>(pair: (T, T), elt: T) -> T {
     if pair.0 == elt {
         pair.1